@@ -0,0 +1,195 @@
+//! Benchmarks of the loading and categorization hot paths
+//!
+//! Runs on synthetic `BEF` files written to a temporary directory, so no
+//! registry data is needed. Each case runs once to warm up and then a fixed
+//! number of times, reporting the minimum, median and maximum time.
+//!
+//! ```text
+//! cargo bench --bench hot_paths [name filter]
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arrow::array::{
+    ArrayRef, Date32Array, Float64Array, Int32Array, StringArray, Time32SecondArray,
+};
+use arrow::datatypes::{DataType, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::NaiveDate;
+use par_reader::algorithm::health::scd::{ScdConfig, apply_scd_algorithm};
+use par_reader::models::DiagnosisType;
+use par_reader::models::collections::ModelCollection;
+use par_reader::models::health::diagnosis::{Diagnosis, DiagnosisCollection};
+use par_reader::registry::RegisterLoader;
+use par_reader::registry::bef::BefRegistryDeserializer;
+use par_reader::registry::direct_deserializer::DirectIndividualDeserializer;
+use par_reader::registry::direct_registry_loader::DirectRegistryLoader;
+use parquet::arrow::ArrowWriter;
+
+/// Number of persons in the synthetic `BEF` directory
+const PERSONS: usize = 200_000;
+
+/// Number of files the persons are split over
+const FILES: usize = 4;
+
+/// Number of diagnoses to categorize
+const DIAGNOSES: usize = 500_000;
+
+/// Number of timed runs per case
+const ITERATIONS: usize = 10;
+
+/// Codes of the synthetic diagnoses, SCD and otherwise
+const CODES: &[&str] = &[
+    "D570", "E109", "G409", "J459", "Q210", "C910", "K500", "N185", "A099", "R109",
+];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // `cargo bench` passes `--bench`; any other argument filters the cases
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with("--"));
+    let selected = |name: &str| filter.as_ref().is_none_or(|filter| name.contains(filter));
+
+    let dir = std::env::temp_dir().join(format!("par_reader_bench_{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let schema = BefRegistryDeserializer::new().get_schema();
+    let mut batches = Vec::with_capacity(FILES);
+    for file in 0..FILES {
+        let batch = synthetic_batch(
+            &schema,
+            file * PERSONS / FILES..(file + 1) * PERSONS / FILES,
+        )?;
+        write_parquet(&dir.join(format!("bef_{file}.parquet")), &batch)?;
+        batches.push(batch);
+    }
+
+    let loader = DirectRegistryLoader::new("BEF");
+    if selected("load/full") {
+        bench("load/full", || loader.load(&dir, None))?;
+    }
+    for (name, percent) in [("load/pnr_1%", 1), ("load/pnr_50%", 50)] {
+        if selected(name) {
+            let pnrs: HashSet<String> = (0..PERSONS)
+                .filter(|person| person % 100 < percent)
+                .map(pnr)
+                .collect();
+            bench(name, || loader.load(&dir, Some(&pnrs)))?;
+        }
+    }
+
+    if selected("deserialize/individuals") {
+        let deserializer = DirectIndividualDeserializer::new("BEF");
+        bench("deserialize/individuals", || {
+            deserializer.deserialize_batch(&batches[0])
+        })?;
+    }
+
+    if selected("scd/categorize") {
+        let diagnoses = synthetic_diagnoses();
+        let config = ScdConfig::default();
+        let birth_dates = HashMap::new();
+        bench("scd/categorize", || {
+            apply_scd_algorithm(&diagnoses, &config, &birth_dates)
+        })?;
+    }
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+/// Time a case, printing the minimum, median and maximum of its runs
+fn bench<T, E>(name: &str, mut run: impl FnMut() -> Result<T, E>) -> Result<(), E> {
+    black_box(run()?);
+    let mut times: Vec<Duration> = Vec::with_capacity(ITERATIONS);
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        black_box(run()?);
+        times.push(start.elapsed());
+    }
+    times.sort();
+    println!(
+        "{name:<24} min {:>10.2?}  median {:>10.2?}  max {:>10.2?}",
+        times[0],
+        times[ITERATIONS / 2],
+        times[ITERATIONS - 1]
+    );
+    Ok(())
+}
+
+/// The PNR of a synthetic person
+fn pnr(person: usize) -> String {
+    format!("{person:010}")
+}
+
+/// Build a batch of the declared `BEF` columns for a range of persons
+fn synthetic_batch(
+    schema: &Schema,
+    persons: std::ops::Range<usize>,
+) -> Result<RecordBatch, arrow::error::ArrowError> {
+    let pnr_column = BefRegistryDeserializer::new().get_pnr_column_name();
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let values = persons.clone();
+            match field.data_type() {
+                DataType::Utf8 if Some(field.name().as_str()) == pnr_column => {
+                    Arc::new(StringArray::from_iter_values(values.map(pnr))) as ArrayRef
+                }
+                DataType::Utf8 => Arc::new(
+                    values
+                        .map(|person| Some(CODES[person % CODES.len()]))
+                        .collect::<StringArray>(),
+                ),
+                DataType::Int32 => Arc::new(
+                    values
+                        .map(|person| i32::try_from(person % 1000).ok())
+                        .collect::<Int32Array>(),
+                ),
+                DataType::Float64 => Arc::new(
+                    values
+                        .map(|person| f64::from(u16::try_from(person % 1000).unwrap_or(0)))
+                        .collect::<Float64Array>(),
+                ),
+                DataType::Date32 => Arc::new(
+                    values
+                        .map(|person| i32::try_from(person % 20_000).ok())
+                        .collect::<Date32Array>(),
+                ),
+                DataType::Time32(TimeUnit::Second) => Arc::new(
+                    values
+                        .map(|person| i32::try_from(person % 86_400).ok())
+                        .collect::<Time32SecondArray>(),
+                ),
+                other => panic!("no generator for {} of type {other}", field.name()),
+            }
+        })
+        .collect();
+    RecordBatch::try_new(Arc::new(schema.clone()), columns)
+}
+
+/// Write a batch as a Parquet file
+fn write_parquet(path: &Path, batch: &RecordBatch) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), None)?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Build diagnoses spread over a quarter as many persons
+fn synthetic_diagnoses() -> DiagnosisCollection {
+    let base = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap_or_default();
+    let mut collection = DiagnosisCollection::new();
+    for index in 0..DIAGNOSES {
+        collection.add(Diagnosis::new(
+            pnr(index / 4),
+            CODES[index % CODES.len()].to_string(),
+            DiagnosisType::Primary,
+            base.checked_add_days(chrono::Days::new((index % 7000) as u64)),
+        ));
+    }
+    collection
+}
@@ -0,0 +1,54 @@
+//! Benchmark for the single-column equality filter fast path
+//!
+//! Compares `ExpressionFilter::filter`, which takes the scalar-kernel fast path
+//! for `column == literal`, against the generic mask evaluation on synthetic
+//! data, and checks that both produce identical results.
+
+use arrow::array::{ArrayRef, Int32Array, StringArray};
+use arrow::record_batch::RecordBatch;
+use par_reader::filter::{BatchFilter, ExpressionFilter, LiteralValue, eq_filter};
+use par_reader::filter_record_batch;
+use std::sync::Arc;
+use std::time::Instant;
+
+const ROWS: usize = 1_000_000;
+const ITERATIONS: u32 = 20;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let pnrs: Vec<String> = (0..ROWS).map(|i| format!("{:010}", i % 50_000)).collect();
+    let years: Vec<i32> = (0..ROWS).map(|i| 2000 + (i % 20) as i32).collect();
+    let batch = RecordBatch::try_from_iter(vec![
+        ("PNR", Arc::new(StringArray::from(pnrs)) as ArrayRef),
+        ("YEAR", Arc::new(Int32Array::from(years)) as ArrayRef),
+    ])?;
+
+    for expr in [
+        eq_filter("PNR", LiteralValue::String(format!("{:010}", 42))),
+        eq_filter("YEAR", LiteralValue::Int(2019)),
+    ] {
+        let filter = ExpressionFilter::new(expr.clone());
+
+        let start = Instant::now();
+        let mut fast = None;
+        for _ in 0..ITERATIONS {
+            fast = Some(filter.filter(&batch)?);
+        }
+        let fast_elapsed = start.elapsed() / ITERATIONS;
+
+        let start = Instant::now();
+        let mut generic = None;
+        for _ in 0..ITERATIONS {
+            generic = Some(filter_record_batch(&batch, &filter.evaluate(&batch)?)?);
+        }
+        let generic_elapsed = start.elapsed() / ITERATIONS;
+
+        assert_eq!(fast, generic, "fast path and generic path disagree");
+        println!(
+            "{expr:?}: fast {fast_elapsed:?}, generic {generic_elapsed:?} ({:.2}x), {} rows kept",
+            generic_elapsed.as_secs_f64() / fast_elapsed.as_secs_f64(),
+            fast.map_or(0, |b| b.num_rows())
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! FromRow derive macro implementation
+//!
+//! This module contains the implementation of the FromRow derive macro,
+//! which generates typed deserialization of Parquet rows into structs.
+
+use darling::{ast, FromDeriveInput, FromField};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Receiver for the struct that derives `FromRow`
+#[derive(Debug, FromDeriveInput)]
+#[darling(supports(struct_named))]
+struct FromRowReceiver {
+    /// The struct identifier
+    ident: syn::Ident,
+    /// The struct generics
+    generics: syn::Generics,
+    /// The struct data with parsed fields
+    data: ast::Data<(), FromRowFieldReceiver>,
+}
+
+/// Receiver for the fields in the struct
+///
+/// Shares the `#[field(...)]` attribute with `RegistryTrait`, so a struct
+/// deriving both reads the same column names.
+#[derive(Debug, FromField)]
+#[darling(attributes(field))]
+struct FromRowFieldReceiver {
+    /// The field identifier
+    ident: Option<syn::Ident>,
+    /// Column name attribute
+    #[darling(default, rename = "name")]
+    column_name: Option<String>,
+    /// Field nullability attribute, accepted for compatibility with `RegistryTrait`
+    #[darling(default)]
+    #[allow(dead_code)]
+    nullable: Option<bool>,
+}
+
+/// Process the FromRow derive macro
+pub fn process_derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let receiver = match FromRowReceiver::from_derive_input(&input) {
+        Ok(receiver) => receiver,
+        Err(err) => return err.write_errors().into(),
+    };
+
+    let ast::Data::Struct(fields) = &receiver.data else {
+        unreachable!("Darling ensures this is a struct")
+    };
+
+    let field_names: Vec<_> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect();
+
+    // Default to the upper-cased field name, as the registries do
+    let column_names: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            field
+                .column_name
+                .clone()
+                .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string().to_uppercase())
+        })
+        .collect();
+
+    let struct_name = &receiver.ident;
+    let (impl_generics, ty_generics, where_clause) = receiver.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics ::par_reader::reader::FromRow for #struct_name #ty_generics #where_clause {
+            fn columns() -> &'static [&'static str] {
+                &[#(#column_names),*]
+            }
+
+            fn from_row(
+                row: &::par_reader::parquet::record::Row,
+            ) -> ::par_reader::parquet::errors::Result<Self> {
+                Ok(Self {
+                    #(#field_names: ::par_reader::reader::get_field(row, #column_names)?,)*
+                })
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
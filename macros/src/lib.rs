@@ -9,6 +9,7 @@ use proc_macro::TokenStream;
 mod utils;
 mod registry_trait;
 mod property_field_impl;
+mod from_row;
 
 // Tests
 #[cfg(test)]
@@ -18,13 +19,35 @@ mod tests;
 ///
 /// This macro generates a registry trait implementation from a struct definition.
 ///
+/// The deserializer implements `RegisterLoader`. The field marked
+/// `#[field(pnr = true)]` names the PNR column used for PNR filtering,
+/// returned by `get_pnr_column_name()`; a struct without a marked field uses
+/// its `pnr` field, if any. The loader reports the registry `name`, or
+/// `loader_name` if given, and a registry reaching PNRs through a join names
+/// its key with `join_column`.
+///
+/// The deserializer's `arrow_schema()` is generated from the field
+/// declarations, so a registry's `get_schema()` should return it rather than
+/// repeat the columns. Columns that must be read without a struct field are
+/// declared with `#[registry(extra_columns(NAME = "DataType", ...))]`, using
+/// the Arrow type names `Utf8`, `Int32`, `Int64`, `Float64`, `Date32` or
+/// `Boolean`; they follow the declared fields and are nullable.
+///
+/// The deserializer's `serialize_batch()` turns records back into a batch of
+/// the declared fields, so a batch deserialized and serialized again keeps
+/// its values.
+///
+/// The `id_field` names the identity field, by field name or source column,
+/// defaulting to `pnr`. The struct's generated `id_value()` returns its value
+/// for a row, whether that is a PNR or e.g. a `RECNUM`.
+///
 /// # Example with PNR as identifier
 ///
 /// ```rust
 /// #[derive(RegistryTrait)]
 /// #[registry(name = "VNDS", description = "Migration registry", id_field = "pnr")]
 /// struct VndsRegistry {
-///     #[field(name = "PNR")]
+///     #[field(name = "PNR", pnr = true)]
 ///     pnr: String,
 ///
 ///     #[field(name = "INDUD_KODE")]
@@ -76,4 +99,28 @@ pub fn derive_registry_trait(input: TokenStream) -> TokenStream {
 #[proc_macro_derive(PropertyField, attributes(property))]
 pub fn derive_property_field(input: TokenStream) -> TokenStream {
     property_field_impl::process_derive_property_field(input)
-}
\ No newline at end of file
+}
+
+/// FromRow derive macro
+///
+/// This macro generates typed deserialization of Parquet rows, so that files
+/// can be iterated as user structs with `ParquetReader::iter_as`. Columns are
+/// named by the `#[field(name = "...")]` attribute, defaulting to the
+/// upper-cased field name.
+///
+/// # Example
+///
+/// ```rust
+/// #[derive(FromRow)]
+/// struct Person {
+///     #[field(name = "PNR")]
+///     pnr: String,
+///
+///     #[field(name = "FOED_DAG")]
+///     birth_date: Option<NaiveDate>,
+/// }
+/// ```
+#[proc_macro_derive(FromRow, attributes(field))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    from_row::process_derive_from_row(input)
+}
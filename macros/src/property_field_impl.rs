@@ -88,10 +88,9 @@ fn generate_property_field_impl(
         let setter_code = generate_field_setter_code(field_name, field_type, &field_name_str);
         
         Some(quote! {
-            // Setter implementation
+            // Setter implementation, which returns once the value is set
             if property == #property_name {
                 #setter_code
-                return;
             }
         })
     }).collect::<Vec<_>>();
@@ -104,11 +103,12 @@ fn generate_property_field_impl(
                 // Try to set the value in the appropriate field
                 #(#property_field_setters)*
                 
-                // If not handled above, store it in the properties map
-                // This assumes the struct has a properties field or method
-                if let Some(props) = &mut self.properties {
-                    props.insert(property.to_string(), value);
-                }
+                // If not handled above, e.g. for a value of another type than
+                // the field of its name, store it in the properties map
+                // This assumes the struct has a properties field
+                self.properties
+                    .get_or_insert_with(std::collections::HashMap::new)
+                    .insert(property.to_string(), value);
             }
         }
     }
@@ -137,40 +137,48 @@ fn generate_field_setter_code(field_name: &syn::Ident, field_type: &Type, _field
                 if let Some(v) = value.downcast_ref::<Option<String>>() {
                     println!("Setting Optional string value to Individual: field={}, value={:?}", stringify!(#field_name), v);
                     self.#field_name = v.clone();
+                    return;
                 } else if let Some(v) = value.downcast_ref::<String>() {
                     // Also try non-Option String for flexibility
                     println!("Setting string value to Optional<String> field Individual: field={}, value={}", stringify!(#field_name), v);
                     self.#field_name = Some(v.clone());
+                    return;
                 }
             },
             "NaiveDate" => quote! {
                 if let Some(v) = value.downcast_ref::<Option<chrono::NaiveDate>>() {
                     println!("Setting Optional date value to Individual: field={}, value={:?}", stringify!(#field_name), v);
                     self.#field_name = *v;
+                    return;
                 } else if let Some(v) = value.downcast_ref::<chrono::NaiveDate>() {
                     // Also try non-Option Date for flexibility
                     println!("Setting date value to Optional<NaiveDate> field: field={}", stringify!(#field_name));
                     self.#field_name = Some(*v);
+                    return;
                 }
             },
             "i32" => quote! {
                 if let Some(v) = value.downcast_ref::<Option<i32>>() {
                     println!("Setting Optional i32 value to Individual: field={}, value={:?}", stringify!(#field_name), v);
                     self.#field_name = *v;
+                    return;
                 } else if let Some(v) = value.downcast_ref::<i32>() {
                     // Also try non-Option i32 for flexibility
                     println!("Setting i32 value to Optional<i32> field: field={}", stringify!(#field_name));
                     self.#field_name = Some(*v);
+                    return;
                 }
             },
             "f64" => quote! {
                 if let Some(v) = value.downcast_ref::<Option<f64>>() {
                     println!("Setting Optional f64 value to Individual: field={}, value={:?}", stringify!(#field_name), v);
                     self.#field_name = *v;
+                    return;
                 } else if let Some(v) = value.downcast_ref::<f64>() {
                     // Also try non-Option f64 for flexibility
                     println!("Setting f64 value to Optional<f64> field: field={}", stringify!(#field_name));
                     self.#field_name = Some(*v);
+                    return;
                 }
             },
             // For Vec types
@@ -181,11 +189,13 @@ fn generate_field_setter_code(field_name: &syn::Ident, field_type: &Type, _field
                     "String" => quote! {
                         if let Some(v) = value.downcast_ref::<Option<Vec<String>>>() {
                             self.#field_name = v.clone();
+                            return;
                         }
                     },
                     "NaiveDate" => quote! {
                         if let Some(v) = value.downcast_ref::<Option<Vec<chrono::NaiveDate>>>() {
                             self.#field_name = v.clone();
+                            return;
                         }
                     },
                     _ => quote! {
@@ -205,11 +215,13 @@ fn generate_field_setter_code(field_name: &syn::Ident, field_type: &Type, _field
                 if let Some(v) = value.downcast_ref::<String>() {
                     println!("Setting string value to Individual: field={}, value={}", stringify!(#field_name), v);
                     self.#field_name = v.clone();
+                    return;
                 } else if let Some(v) = value.downcast_ref::<Option<String>>() {
                     // Try to extract String from Option<String>
                     if let Some(inner_val) = v {
                         println!("Setting string from Option<String> to Individual: field={}, value={}", stringify!(#field_name), inner_val);
                         self.#field_name = inner_val.clone();
+                        return;
                     }
                 }
             },
@@ -217,11 +229,13 @@ fn generate_field_setter_code(field_name: &syn::Ident, field_type: &Type, _field
                 if let Some(v) = value.downcast_ref::<i32>() {
                     println!("Setting i32 value to Individual: field={}, value={}", stringify!(#field_name), v);
                     self.#field_name = *v;
+                    return;
                 } else if let Some(v) = value.downcast_ref::<Option<i32>>() {
                     // Try to extract i32 from Option<i32>
                     if let Some(inner_val) = v {
                         println!("Setting i32 from Option<i32> to Individual: field={}, value={}", stringify!(#field_name), inner_val);
                         self.#field_name = *inner_val;
+                        return;
                     }
                 }
             },
@@ -229,11 +243,13 @@ fn generate_field_setter_code(field_name: &syn::Ident, field_type: &Type, _field
                 if let Some(v) = value.downcast_ref::<f64>() {
                     println!("Setting f64 value to Individual: field={}, value={}", stringify!(#field_name), v);
                     self.#field_name = *v;
+                    return;
                 } else if let Some(v) = value.downcast_ref::<Option<f64>>() {
                     // Try to extract f64 from Option<f64>
                     if let Some(inner_val) = v {
                         println!("Setting f64 from Option<f64> to Individual: field={}, value={}", stringify!(#field_name), inner_val);
                         self.#field_name = *inner_val;
+                        return;
                     }
                 }
             },
@@ -241,11 +257,13 @@ fn generate_field_setter_code(field_name: &syn::Ident, field_type: &Type, _field
                 if let Some(v) = value.downcast_ref::<chrono::NaiveDate>() {
                     println!("Setting date value to Individual: field={}, value={}", stringify!(#field_name), v);
                     self.#field_name = *v;
+                    return;
                 } else if let Some(v) = value.downcast_ref::<Option<chrono::NaiveDate>>() {
                     // Try to extract NaiveDate from Option<NaiveDate>
                     if let Some(inner_val) = v {
                         println!("Setting date from Option<NaiveDate> to Individual: field={}, value={}", stringify!(#field_name), inner_val);
                         self.#field_name = *inner_val;
+                        return;
                     }
                 }
             },
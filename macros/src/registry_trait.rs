@@ -3,10 +3,10 @@
 //! This module contains the implementation of the RegistryTrait derive macro,
 //! which is used to generate code for registry trait implementations.
 
-use darling::{ast, FromDeriveInput, FromField};
+use darling::{ast, ast::NestedMeta, FromDeriveInput, FromField, FromMeta};
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
-use syn::{parse_macro_input, DeriveInput, Type};
+use syn::{parse_macro_input, DeriveInput};
 
 use crate::utils;
 
@@ -24,6 +24,15 @@ struct RegistryTraitReceiver {
     /// Identifier field type (pnr, `record_number`, or `dw_ek_kontakt`)
     #[darling(default)]
     id_field: Option<String>,
+    /// Columns read in addition to the declared fields
+    #[darling(default)]
+    extra_columns: ExtraColumns,
+    /// Name the generated `RegisterLoader` reports, defaulting to `name`
+    #[darling(default)]
+    loader_name: Option<String>,
+    /// Column joined on to reach PNRs, for a registry without a PNR column
+    #[darling(default)]
+    join_column: Option<String>,
     /// The struct data with parsed fields
     data: ast::Data<(), RegistryFieldReceiver>,
 }
@@ -44,6 +53,60 @@ struct RegistryFieldReceiver {
     #[darling(default)]
     #[allow(dead_code)]
     nullable: Option<bool>,
+    /// Marks the column holding the PNR of the person a row describes
+    #[darling(default)]
+    pnr: bool,
+}
+
+/// Arrow data types an extra column can be declared with
+const EXTRA_COLUMN_TYPES: &[&str] = &["Utf8", "Int32", "Int64", "Float64", "Date32", "Boolean"];
+
+/// Columns without a struct field, from `#[registry(extra_columns(NAME = "DataType", ...))]`
+///
+/// Extra columns are part of the registry schema, and so are read from the
+/// source files, but are not deserialized. They are always nullable.
+#[derive(Debug, Default)]
+struct ExtraColumns(Vec<(String, syn::Ident)>);
+
+impl FromMeta for ExtraColumns {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let mut errors = darling::Error::accumulator();
+        let mut columns = Vec::with_capacity(items.len());
+
+        for item in items {
+            let NestedMeta::Meta(syn::Meta::NameValue(name_value)) = item else {
+                errors.push(
+                    darling::Error::custom("expected `COLUMN = \"DataType\"`").with_span(item),
+                );
+                continue;
+            };
+            let Some(column) = name_value.path.get_ident() else {
+                errors.push(
+                    darling::Error::custom("expected a column name").with_span(&name_value.path),
+                );
+                continue;
+            };
+            let Some(data_type) = errors.handle(String::from_expr(&name_value.value)) else {
+                continue;
+            };
+            if !EXTRA_COLUMN_TYPES.contains(&data_type.as_str()) {
+                errors.push(
+                    darling::Error::custom(format!(
+                        "unsupported data type `{data_type}`, expected one of {}",
+                        EXTRA_COLUMN_TYPES.join(", ")
+                    ))
+                    .with_span(&name_value.value),
+                );
+                continue;
+            }
+            columns.push((
+                column.to_string(),
+                syn::Ident::new(&data_type, proc_macro2::Span::call_site()),
+            ));
+        }
+
+        errors.finish_with(Self(columns))
+    }
 }
 
 /// Process the RegistryTrait derive macro
@@ -79,14 +142,88 @@ pub fn process_derive_registry_trait(input: TokenStream) -> TokenStream {
         unreachable!("Darling ensures this is a struct")
     };
 
+    // At most one field can be the PNR column
+    let pnr_fields: Vec<_> = fields.iter().filter(|field| field.pnr).collect();
+    if let [_, duplicate, ..] = pnr_fields.as_slice() {
+        return syn::Error::new_spanned(
+            duplicate.ident.as_ref().unwrap(),
+            "only one field can be marked `#[field(pnr = true)]`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // An extra column must not repeat a declared field
+    for (column, _) in &receiver.extra_columns.0 {
+        if fields
+            .iter()
+            .any(|field| source_column_name(field) == *column)
+        {
+            return syn::Error::new_spanned(
+                struct_name,
+                format!("extra column `{column}` is already declared by a field"),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    // The id field is named by its field name or its source column
+    let Some(id) = fields.iter().find(|field| {
+        field.ident.as_ref().is_some_and(|ident| *ident == id_field)
+            || source_column_name(field) == id_field
+    }) else {
+        return syn::Error::new_spanned(
+            struct_name,
+            format!("`id_field = \"{id_field}\"` does not name a field"),
+        )
+        .to_compile_error()
+        .into();
+    };
+    let id_ident = id.ident.as_ref().unwrap();
+    let id_value = if utils::is_option_type(&id.ty) {
+        quote! { self.#id_ident.as_deref() }
+    } else {
+        quote! { Some(self.#id_ident.as_str()) }
+    };
+    let id_value = quote! { #id_value.filter(|value| !value.is_empty()) };
+
+    // The deserializer checks rows for an id by the property its column is
+    // stored under, whatever the field is called
+    let id_kind = match source_column_name(id).as_str() {
+        "RECNUM" => "record_number".to_string(),
+        "DW_EK_KONTAKT" => "dw_ek_kontakt".to_string(),
+        _ => id_field.clone(),
+    };
+
     // Generate the trait implementation
-    let expanded = generate_registry_impl(
+    let mut expanded = generate_registry_impl(
         &receiver.ident,
         &registry_name,
         &registry_desc,
         &id_field,
+        &id_kind,
         fields,
+        &receiver.extra_columns,
     );
+    expanded.extend(generate_register_loader_impl(
+        &receiver,
+        &registry_name,
+        fields,
+    ));
+    expanded.extend(quote! {
+        impl #struct_name {
+            /// Get the identity value of this row
+            ///
+            /// The value of the field named by `id_field`, e.g. the PNR or the
+            /// `RECNUM`, so rows can be keyed without knowing which it is.
+            /// An empty value is treated as missing.
+            #[must_use]
+            pub fn id_value(&self) -> Option<&str> {
+                #id_value
+            }
+        }
+    });
 
     // Convert back to proc_macro::TokenStream
     TokenStream::from(expanded)
@@ -98,15 +235,19 @@ fn generate_registry_impl(
     registry_name: &str,
     registry_desc: &str,
     id_field: &str,
+    id_kind: &str,
     fields: &ast::Fields<RegistryFieldReceiver>,
+    extra_columns: &ExtraColumns,
 ) -> proc_macro2::TokenStream {
     let deserializer_name = format_ident!("{}Deserializer", struct_name);
+    let (extra_names, extra_types): (Vec<_>, Vec<_>) = extra_columns.0.iter().cloned().unzip();
 
     // Extract field names for use in impl blocks
     let field_names: Vec<_> = fields
         .iter()
         .map(|field| field.ident.as_ref().unwrap())
         .collect();
+    let field_types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
 
     // Generate field mappings
     let field_mappings = fields.iter().map(|field| {
@@ -115,10 +256,7 @@ fn generate_registry_impl(
         let field_type = &field.ty;
 
         // Extract field attributes
-        let source_name = field
-            .field_name
-            .clone()
-            .unwrap_or_else(|| field_name.to_string().to_uppercase());
+        let source_name = source_column_name(field);
 
         // Determine the target property name for ID fields
         // We need to make sure it's mapped to the standardized name expected by the trait deserializer
@@ -165,131 +303,22 @@ fn generate_registry_impl(
     // Update the unused variable to avoid warnings
     let _has_id_field = has_id_field;
 
-    // Prepare field extraction for properties
+    // Prepare field extraction for properties: each field is read back from
+    // the property of its name, be it a dedicated field or in the map
     let field_extraction_statements = fields.iter().filter_map(|field| {
         let field_name = field.ident.as_ref().unwrap();
         let field_name_str = field_name.to_string();
+        let field_type = &field.ty;
 
         // Skip ID field extraction as it's handled separately
         if field_name_str == id_field {
             None
         } else {
-            // Extract field type information before generating code
-            let is_option = utils::is_option_type(&field.ty);
-
-            // For non-ID fields, generate property extraction code based on the field type
-            // First get the field type as a string for matching
-            let field_type = if let Type::Path(type_path) = &field.ty {
-                if let Some(segment) = type_path.path.segments.first() {
-                    segment.ident.to_string()
-                } else {
-                    "Unknown".to_string()
-                }
-            } else {
-                "Unknown".to_string()
-            };
-
-            // For Option types, extract the inner type
-            let inner_type = if field_type == "Option" {
-                if let Type::Path(type_path) = &field.ty {
-                    if let Some(segment) = type_path.path.segments.first() {
-                        if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
-                            if let Some(syn::GenericArgument::Type(Type::Path(inner_path))) = args.args.first() {
-                                if let Some(inner_segment) = inner_path.path.segments.first() {
-                                    inner_segment.ident.to_string()
-                                } else {
-                                    "Unknown".to_string()
-                                }
-                            } else {
-                                "Unknown".to_string()
-                            }
-                        } else {
-                            "Unknown".to_string()
-                        }
-                    } else {
-                        "Unknown".to_string()
-                    }
-                } else {
-                    "Unknown".to_string()
-                }
-            } else {
-                field_type
-            };
-
-            // Now match on the type for code generation
-            match inner_type.as_str() {
-                // For String fields
-                "String" => {
-                    if is_option {
-                        // For Option<String> fields
-                        Some(quote! {
-                            // Extract Option<String> property - try both field name and source field name
-                            if let Some(props) = individual.properties() {
-                                // Debug logging has been removed
-
-                                // Try both actual field name and stringified field name
-                                let property_value = props.get(#field_name_str)
-                                    .or_else(|| props.get(stringify!(#field_name)));
-
-                                if let Some(value) = property_value {
-                                    if let Some(string_val) = value.downcast_ref::<Option<String>>() {
-                                        // Debug logging has been removed
-                                        instance.#field_name = string_val.clone();
-                                    }
-                                }
-                            }
-                        })
-                    } else {
-                        // For String fields (non-Option)
-                        Some(quote! {
-                            // Extract String property - try both field name and source field name
-                            if let Some(props) = individual.properties() {
-                                // Try both actual field name and stringified field name
-                                let property_value = props.get(#field_name_str)
-                                    .or_else(|| props.get(stringify!(#field_name)));
-
-                                if let Some(value) = property_value {
-                                    if let Some(string_val) = value.downcast_ref::<String>() {
-                                        instance.#field_name = string_val.clone();
-                                    }
-                                }
-                            }
-                        })
-                    }
-                },
-                // For Date fields
-                "Date" | "NaiveDate" => {
-                    Some(quote! {
-                        // Extract Option<NaiveDate> property - try both field name and source field name
-                        if let Some(props) = individual.properties() {
-                            // Debug logging has been removed
-
-                            // Try both actual field name and stringified field name
-                            let property_value = props.get(#field_name_str)
-                                .or_else(|| props.get(stringify!(#field_name)));
-
-                            if let Some(value) = property_value {
-                                if let Some(date_val) = value.downcast_ref::<Option<chrono::NaiveDate>>() {
-                                    // Debug logging has been removed
-                                    instance.#field_name = *date_val;
-                                }
-                            }
-                        }
-                    })
-                },
-                // For Integer and other field types - add more as needed
-                _ => {
-                    // Default extraction for other types
-                    Some(quote! {
-                        // Extract generic property
-                        if let Some(props) = individual.properties() {
-                            if let Some(_value) = props.get(#field_name_str) {
-                                // No specific handling for this type yet
-                            }
-                        }
-                    })
+            Some(quote! {
+                if let Some(value) = individual.property_value::<#field_type>(#field_name_str) {
+                    instance.#field_name = value;
                 }
-            }
+            })
         }
     }).collect::<Vec<_>>();
 
@@ -391,6 +420,7 @@ fn generate_registry_impl(
         #[derive(Debug)]
         pub struct #deserializer_name {
             inner: std::sync::Arc<dyn crate::registry::trait_deserializer::RegistryDeserializer>,
+            arrow_schema: arrow::datatypes::SchemaRef,
         }
 
         // Implement the registry type trait for our struct to make types compatible
@@ -402,6 +432,7 @@ fn generate_registry_impl(
             pub fn new() -> Self {
                 // Create the schema
                 let schema = Self::create_schema();
+                let arrow_schema = Self::create_arrow_schema(&schema);
 
                 // Create the deserializer implementation
                 let inner = std::sync::Arc::new(
@@ -409,11 +440,11 @@ fn generate_registry_impl(
                         #registry_name,
                         #registry_desc,
                         schema,
-                        Some(#id_field)
+                        Some(#id_kind)
                     )
                 );
 
-                Self { inner }
+                Self { inner, arrow_schema }
             }
 
             /// Create the schema definition for this registry
@@ -430,6 +461,40 @@ fn generate_registry_impl(
                 )
             }
 
+            /// Create the Arrow schema of this registry
+            ///
+            /// The declared fields in order, followed by the extra columns.
+            fn create_arrow_schema(
+                schema: &crate::schema::RegistrySchema,
+            ) -> arrow::datatypes::SchemaRef {
+                let extra_columns = [
+                    #(arrow::datatypes::Field::new(#extra_names, arrow::datatypes::DataType::#extra_types, true)),*
+                ];
+                let fields: Vec<arrow::datatypes::FieldRef> = schema
+                    .arrow_schema()
+                    .fields()
+                    .iter()
+                    .cloned()
+                    .chain(extra_columns.into_iter().map(std::sync::Arc::new))
+                    .collect();
+                std::sync::Arc::new(arrow::datatypes::Schema::new(fields))
+            }
+
+            /// Get the Arrow schema of this registry
+            ///
+            /// Generated from the field declarations and any
+            /// `#[registry(extra_columns(...))]`.
+            #[must_use]
+            pub fn arrow_schema(&self) -> arrow::datatypes::SchemaRef {
+                self.arrow_schema.clone()
+            }
+
+            /// Get the declared `(source, target, type)` field map of this registry
+            #[must_use]
+            pub fn field_map(&self) -> &[(String, String, crate::schema::field_def::FieldType)] {
+                self.inner.field_map()
+            }
+
             /// Deserialize a record batch using this deserializer
             pub fn deserialize_batch(&self, batch: &arrow::record_batch::RecordBatch)
                 -> crate::error::Result<Vec<#struct_name>> {
@@ -449,6 +514,30 @@ fn generate_registry_impl(
                     .map(|individual| #struct_name::from(individual));
                 Ok(result)
             }
+
+            /// Serialize records to a record batch of the declared fields
+            ///
+            /// The inverse of `deserialize_batch`: the columns are those of
+            /// `arrow_schema()` without the extra columns, with `None` as null.
+            pub fn serialize_batch(&self, records: &[#struct_name])
+                -> crate::error::Result<arrow::record_batch::RecordBatch> {
+                let columns: Vec<arrow::array::ArrayRef> = vec![
+                    #(<#field_types as crate::schema::field_def::IntoArrowArray>::into_array(
+                        records.iter().map(|record| record.#field_names.clone()).collect()
+                    )),*
+                ];
+                let fields: Vec<arrow::datatypes::FieldRef> = self
+                    .arrow_schema
+                    .fields()
+                    .iter()
+                    .take(columns.len())
+                    .cloned()
+                    .collect();
+                Ok(arrow::record_batch::RecordBatch::try_new(
+                    std::sync::Arc::new(arrow::datatypes::Schema::new(fields)),
+                    columns,
+                )?)
+            }
         }
 
         // From implementation
@@ -471,6 +560,70 @@ fn generate_registry_impl(
     }
 }
 
+/// Generate the `RegisterLoader` implementation of the deserializer
+///
+/// The PNR column is the field marked `pnr = true`, or else a field named
+/// `pnr`, so it cannot drift from the struct.
+fn generate_register_loader_impl(
+    receiver: &RegistryTraitReceiver,
+    registry_name: &str,
+    fields: &ast::Fields<RegistryFieldReceiver>,
+) -> proc_macro2::TokenStream {
+    let deserializer_name = format_ident!("{}Deserializer", receiver.ident);
+    let loader_name = receiver.loader_name.as_deref().unwrap_or(registry_name);
+
+    let pnr_column = fields
+        .iter()
+        .find(|field| field.pnr)
+        .or_else(|| {
+            fields
+                .iter()
+                .find(|field| field.ident.as_ref().is_some_and(|ident| ident == "pnr"))
+        })
+        .map(source_column_name);
+    let pnr_column = match pnr_column {
+        Some(column) => quote! { Some(#column) },
+        None => quote! { None },
+    };
+    let join_column = receiver.join_column.as_ref().map(|column| {
+        quote! {
+            /// Returns the column joined on to reach PNRs
+            fn get_join_column_name(&self) -> Option<&'static str> {
+                Some(#column)
+            }
+        }
+    });
+
+    quote! {
+        impl crate::registry::RegisterLoader for #deserializer_name {
+            /// Get the name of the register
+            fn get_register_name(&self) -> &'static str {
+                #loader_name
+            }
+
+            /// Get the schema for this register, generated from the registry struct
+            fn get_schema(&self) -> crate::SchemaRef {
+                self.arrow_schema()
+            }
+
+            /// Returns the column name containing the PNR, as marked on the registry struct
+            fn get_pnr_column_name(&self) -> Option<&'static str> {
+                #pnr_column
+            }
+
+            #join_column
+        }
+    }
+}
+
+/// Get the source column name of a field, defaulting to the upper-cased field name
+fn source_column_name(field: &RegistryFieldReceiver) -> String {
+    field
+        .field_name
+        .clone()
+        .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string().to_uppercase())
+}
+
 /// Helper to check if a struct has a specific field
 fn has_field_named(fields: &ast::Fields<RegistryFieldReceiver>, name: &str) -> bool {
     fields
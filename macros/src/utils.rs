@@ -88,6 +88,8 @@ pub fn extract_inner_type_info(ty: &Type) -> (TokenStream, TokenStream, TokenStr
                 );
             } else if ident == "NaiveDate" || ident == "Date" {
                 return (quote! { Date }, quote! { date }, quote! { date_setter });
+            } else if ident == "NaiveTime" {
+                return (quote! { Time }, quote! { time }, quote! { time_setter });
             } else if ident == "bool" {
                 return (
                     quote! { Boolean },
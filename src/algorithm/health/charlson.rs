@@ -0,0 +1,399 @@
+//! Charlson Comorbidity Index
+//!
+//! This module implements the Charlson Comorbidity Index using the ICD-10
+//! coding algorithm of Quan et al. (2005) and the original Charlson weights.
+//! Codes are expected without the Danish `D` prefix, as elsewhere in the
+//! health algorithms.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::algorithm::health::classifier::{ClassifierConfig, CodeGroupTable, DiseaseClassifier};
+use crate::models::health::diagnosis::DiagnosisCollection;
+
+/// Charlson comorbidity groups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CharlsonComorbidity {
+    /// Myocardial infarction
+    MyocardialInfarction,
+    /// Congestive heart failure
+    CongestiveHeartFailure,
+    /// Peripheral vascular disease
+    PeripheralVascularDisease,
+    /// Cerebrovascular disease
+    CerebrovascularDisease,
+    /// Dementia
+    Dementia,
+    /// Chronic pulmonary disease
+    ChronicPulmonaryDisease,
+    /// Rheumatic disease
+    RheumaticDisease,
+    /// Peptic ulcer disease
+    PepticUlcerDisease,
+    /// Mild liver disease
+    MildLiverDisease,
+    /// Diabetes without chronic complications
+    Diabetes,
+    /// Diabetes with chronic complications
+    DiabetesWithComplications,
+    /// Hemiplegia or paraplegia
+    HemiplegiaParaplegia,
+    /// Renal disease
+    RenalDisease,
+    /// Any malignancy, including lymphoma and leukemia
+    Malignancy,
+    /// Moderate or severe liver disease
+    ModerateSevereLiverDisease,
+    /// Metastatic solid tumor
+    MetastaticSolidTumor,
+    /// AIDS/HIV
+    AidsHiv,
+}
+
+impl CharlsonComorbidity {
+    /// Get the Charlson weight of this comorbidity
+    #[must_use]
+    pub const fn weight(self) -> u32 {
+        match self {
+            Self::DiabetesWithComplications
+            | Self::HemiplegiaParaplegia
+            | Self::RenalDisease
+            | Self::Malignancy => 2,
+            Self::ModerateSevereLiverDisease => 3,
+            Self::MetastaticSolidTumor | Self::AidsHiv => 6,
+            _ => 1,
+        }
+    }
+
+    /// Get the milder comorbidity that this one supersedes, if any
+    ///
+    /// When both are present only the more severe form is counted.
+    #[must_use]
+    pub const fn supersedes(self) -> Option<Self> {
+        match self {
+            Self::DiabetesWithComplications => Some(Self::Diabetes),
+            Self::ModerateSevereLiverDisease => Some(Self::MildLiverDisease),
+            Self::MetastaticSolidTumor => Some(Self::Malignancy),
+            _ => None,
+        }
+    }
+}
+
+/// Build the Charlson code table (Quan et al. 2005)
+fn charlson_code_table() -> CodeGroupTable<CharlsonComorbidity> {
+    use CharlsonComorbidity::{
+        AidsHiv, CerebrovascularDisease, ChronicPulmonaryDisease, CongestiveHeartFailure, Dementia,
+        Diabetes, DiabetesWithComplications, HemiplegiaParaplegia, Malignancy,
+        MetastaticSolidTumor, MildLiverDisease, ModerateSevereLiverDisease, MyocardialInfarction,
+        PepticUlcerDisease, PeripheralVascularDisease, RenalDisease, RheumaticDisease,
+    };
+
+    let groups: [(CharlsonComorbidity, &[&str]); 17] = [
+        (MyocardialInfarction, &["I21", "I22", "I252"]),
+        (
+            CongestiveHeartFailure,
+            &[
+                "I099", "I110", "I130", "I132", "I255", "I420", "I425", "I426", "I427", "I428",
+                "I429", "I43", "I50", "P290",
+            ],
+        ),
+        (
+            PeripheralVascularDisease,
+            &[
+                "I70", "I71", "I731", "I738", "I739", "I771", "I790", "I792", "K551", "K558",
+                "K559", "Z958", "Z959",
+            ],
+        ),
+        (
+            CerebrovascularDisease,
+            &[
+                "G45", "G46", "H340", "I60", "I61", "I62", "I63", "I64", "I65", "I66", "I67",
+                "I68", "I69",
+            ],
+        ),
+        (
+            Dementia,
+            &["F00", "F01", "F02", "F03", "F051", "G30", "G311"],
+        ),
+        (
+            ChronicPulmonaryDisease,
+            &[
+                "I278", "I279", "J40", "J41", "J42", "J43", "J44", "J45", "J46", "J47", "J60",
+                "J61", "J62", "J63", "J64", "J65", "J66", "J67", "J684", "J701", "J703",
+            ],
+        ),
+        (
+            RheumaticDisease,
+            &[
+                "M05", "M06", "M315", "M32", "M33", "M34", "M351", "M353", "M360",
+            ],
+        ),
+        (PepticUlcerDisease, &["K25", "K26", "K27", "K28"]),
+        (
+            MildLiverDisease,
+            &[
+                "B18", "K700", "K701", "K702", "K703", "K709", "K713", "K714", "K715", "K717",
+                "K73", "K74", "K760", "K762", "K763", "K764", "K768", "K769", "Z944",
+            ],
+        ),
+        (
+            Diabetes,
+            &[
+                "E100", "E101", "E106", "E108", "E109", "E110", "E111", "E116", "E118", "E119",
+                "E120", "E121", "E126", "E128", "E129", "E130", "E131", "E136", "E138", "E139",
+                "E140", "E141", "E146", "E148", "E149",
+            ],
+        ),
+        (
+            DiabetesWithComplications,
+            &[
+                "E102", "E103", "E104", "E105", "E107", "E112", "E113", "E114", "E115", "E117",
+                "E122", "E123", "E124", "E125", "E127", "E132", "E133", "E134", "E135", "E137",
+                "E142", "E143", "E144", "E145", "E147",
+            ],
+        ),
+        (
+            HemiplegiaParaplegia,
+            &[
+                "G041", "G114", "G801", "G802", "G81", "G82", "G830", "G831", "G832", "G833",
+                "G834", "G839",
+            ],
+        ),
+        (
+            RenalDisease,
+            &[
+                "I120", "I131", "N032", "N033", "N034", "N035", "N036", "N037", "N052", "N053",
+                "N054", "N055", "N056", "N057", "N18", "N19", "N250", "Z490", "Z491", "Z492",
+                "Z940", "Z992",
+            ],
+        ),
+        (
+            Malignancy,
+            &[
+                "C00", "C01", "C02", "C03", "C04", "C05", "C06", "C07", "C08", "C09", "C10", "C11",
+                "C12", "C13", "C14", "C15", "C16", "C17", "C18", "C19", "C20", "C21", "C22", "C23",
+                "C24", "C25", "C26", "C30", "C31", "C32", "C33", "C34", "C37", "C38", "C39", "C40",
+                "C41", "C43", "C45", "C46", "C47", "C48", "C49", "C50", "C51", "C52", "C53", "C54",
+                "C55", "C56", "C57", "C58", "C60", "C61", "C62", "C63", "C64", "C65", "C66", "C67",
+                "C68", "C69", "C70", "C71", "C72", "C73", "C74", "C75", "C76", "C81", "C82", "C83",
+                "C84", "C85", "C88", "C90", "C91", "C92", "C93", "C94", "C95", "C96", "C97",
+            ],
+        ),
+        (
+            ModerateSevereLiverDisease,
+            &[
+                "I850", "I859", "I864", "I982", "K704", "K711", "K721", "K729", "K765", "K766",
+                "K767",
+            ],
+        ),
+        (MetastaticSolidTumor, &["C77", "C78", "C79", "C80"]),
+        (AidsHiv, &["B20", "B21", "B22", "B24"]),
+    ];
+
+    let mut table = CodeGroupTable::new();
+    for (comorbidity, prefixes) in groups {
+        table.insert_all(prefixes, comorbidity, f64::from(comorbidity.weight()));
+    }
+    table
+}
+
+/// Configuration for the Charlson Comorbidity Index
+#[derive(Debug, Clone, Default)]
+pub struct CharlsonConfig {
+    /// Date at which the index is assessed; diagnoses after it are ignored.
+    /// When unset, all diagnoses are used.
+    pub index_date: Option<NaiveDate>,
+    /// Only use diagnoses at most this many days before the index date
+    pub lookback_days: Option<i64>,
+    /// Add the Charlson age points (one per decade from age 50, at most 4)
+    pub age_adjusted: bool,
+    /// Birth dates by PNR, required for the age-adjusted variant
+    pub birth_dates: HashMap<String, NaiveDate>,
+}
+
+/// Charlson Comorbidity Index of one individual
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharlsonResult {
+    /// Individual's PNR
+    pub pnr: String,
+    /// Comorbidities counted towards the score, after applying the hierarchy
+    pub comorbidities: Vec<CharlsonComorbidity>,
+    /// Sum of the comorbidity weights
+    pub comorbidity_score: u32,
+    /// Age points; zero unless age adjustment is enabled
+    pub age_score: u32,
+    /// Total score (comorbidity score plus age points)
+    pub score: u32,
+}
+
+/// Charlson age points for an age in whole years
+#[must_use]
+pub const fn charlson_age_points(age_years: i64) -> u32 {
+    match age_years {
+        ..50 => 0,
+        50..60 => 1,
+        60..70 => 2,
+        70..80 => 3,
+        _ => 4,
+    }
+}
+
+/// Compute the Charlson Comorbidity Index for every individual in a collection
+///
+/// Each comorbidity counts once regardless of how many diagnoses indicate it,
+/// and the severe form of liver disease, diabetes or cancer replaces the mild
+/// form. For the age-adjusted variant, age is taken at the index date or, if
+/// unset, at the individual's latest dated diagnosis.
+///
+/// # Arguments
+/// * `diagnoses` - The diagnoses to score
+/// * `config` - Index date, lookback window and age adjustment
+///
+/// # Returns
+/// Charlson results by PNR, for every individual with diagnoses
+#[must_use]
+pub fn compute_charlson(
+    diagnoses: &DiagnosisCollection,
+    config: &CharlsonConfig,
+) -> HashMap<String, CharlsonResult> {
+    let start_date = config
+        .index_date
+        .zip(config.lookback_days)
+        .map(|(index_date, days)| index_date - chrono::Duration::days(days));
+    let classifier = DiseaseClassifier::new(
+        charlson_code_table(),
+        ClassifierConfig {
+            start_date,
+            end_date: config.index_date,
+            ..ClassifierConfig::default()
+        },
+    );
+
+    classifier
+        .classify(diagnoses, &HashMap::new())
+        .into_iter()
+        .map(|(pnr, classification)| {
+            let present: Vec<CharlsonComorbidity> =
+                classification.groups.iter().map(|m| m.group).collect();
+            let mut comorbidities: Vec<CharlsonComorbidity> = present
+                .iter()
+                .copied()
+                .filter(|c| !present.iter().any(|other| other.supersedes() == Some(*c)))
+                .collect();
+            comorbidities.sort();
+
+            let comorbidity_score = comorbidities.iter().map(|c| c.weight()).sum();
+            let age_score = if config.age_adjusted {
+                let assessed_at = config.index_date.or_else(|| {
+                    diagnoses
+                        .get_diagnoses(&pnr)
+                        .iter()
+                        .filter_map(|d| d.diagnosis_date)
+                        .max()
+                });
+                config
+                    .birth_dates
+                    .get(&pnr)
+                    .zip(assessed_at)
+                    .map_or(0, |(birth_date, date)| {
+                        charlson_age_points((date - *birth_date).num_days() / 365)
+                    })
+            } else {
+                0
+            };
+
+            let result = CharlsonResult {
+                pnr: pnr.clone(),
+                comorbidities,
+                comorbidity_score,
+                age_score,
+                score: comorbidity_score + age_score,
+            };
+            (pnr, result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::collections::ModelCollection;
+    use crate::models::core::types::DiagnosisType;
+    use crate::models::health::diagnosis::Diagnosis;
+
+    fn collection(entries: &[(&str, &str, NaiveDate)]) -> DiagnosisCollection {
+        let mut diagnoses = DiagnosisCollection::new();
+        for (pnr, code, date) in entries {
+            diagnoses.add(Diagnosis::new(
+                (*pnr).to_string(),
+                (*code).to_string(),
+                DiagnosisType::Primary,
+                Some(*date),
+            ));
+        }
+        diagnoses
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_compute_charlson_hand_computed() {
+        let diagnoses = collection(&[
+            // MI (1) + CHF (1) + complicated diabetes (2), which supersedes
+            // uncomplicated diabetes: 4
+            ("a", "I219", date(2015, 3, 1)),
+            ("a", "I500", date(2016, 3, 1)),
+            ("a", "E109", date(2016, 5, 1)),
+            ("a", "E112", date(2017, 5, 1)),
+            // Metastatic tumor (6) supersedes malignancy; AIDS (6): 12
+            ("b", "C509", date(2018, 1, 1)),
+            ("b", "C787", date(2018, 6, 1)),
+            ("b", "B20", date(2018, 7, 1)),
+            // No Charlson conditions
+            ("c", "S720", date(2018, 1, 1)),
+        ]);
+
+        let results = compute_charlson(&diagnoses, &CharlsonConfig::default());
+        assert_eq!(
+            results["a"].comorbidities,
+            vec![
+                CharlsonComorbidity::MyocardialInfarction,
+                CharlsonComorbidity::CongestiveHeartFailure,
+                CharlsonComorbidity::DiabetesWithComplications,
+            ]
+        );
+        assert_eq!(results["a"].score, 4);
+        assert_eq!(results["b"].score, 12);
+        assert_eq!(results["c"].score, 0);
+        assert!(results["c"].comorbidities.is_empty());
+    }
+
+    #[test]
+    fn test_compute_charlson_lookback_and_age() {
+        let diagnoses = collection(&[
+            ("a", "I219", date(2010, 1, 1)),
+            ("a", "K703", date(2019, 6, 1)),
+            ("a", "K766", date(2021, 1, 1)),
+        ]);
+        let config = CharlsonConfig {
+            index_date: Some(date(2020, 1, 1)),
+            lookback_days: Some(5 * 365),
+            age_adjusted: true,
+            birth_dates: HashMap::from([("a".to_string(), date(1955, 1, 1))]),
+        };
+
+        // Only the mild liver disease falls in the window: 1, plus 2 age
+        // points for age 65 at the index date
+        let result = &compute_charlson(&diagnoses, &config)["a"];
+        assert_eq!(
+            result.comorbidities,
+            vec![CharlsonComorbidity::MildLiverDisease]
+        );
+        assert_eq!(result.comorbidity_score, 1);
+        assert_eq!(result.age_score, 2);
+        assert_eq!(result.score, 3);
+    }
+}
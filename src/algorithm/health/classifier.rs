@@ -0,0 +1,432 @@
+//! Generic disease identification from diagnosis codes
+//!
+//! This module implements a table-driven classifier that assigns diagnoses to
+//! disease groups by ICD-10 code prefix. The same machinery backs the SCD
+//! algorithm and comorbidity indices such as Charlson or Elixhauser, which
+//! differ only in their code tables and weights.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::models::health::diagnosis::{Diagnosis, DiagnosisCollection};
+
+/// Check that a diagnosis code has a valid ICD-10 structure
+///
+/// A valid code is a chapter letter followed by two digits and up to four
+/// further digits or letters, in the compact form used by the registers
+/// (e.g. `E10`, `I219`, `Q909A`). Surrounding whitespace and letter case are
+/// ignored. The code is not checked against the ICD-10 code list.
+#[must_use]
+pub fn is_valid_icd10(code: &str) -> bool {
+    let bytes = code.trim().as_bytes();
+    matches!(bytes, [letter, d1, d2, rest @ ..]
+        if letter.is_ascii_alphabetic()
+            && d1.is_ascii_digit()
+            && d2.is_ascii_digit()
+            && rest.len() <= 4
+            && rest.iter().all(u8::is_ascii_alphanumeric))
+}
+
+/// Check that a diagnosis code has a valid ICD-8 structure
+///
+/// A valid code is three digits followed by up to two further digits, as in
+/// the five-digit Danish ICD-8 (e.g. `250`, `24900`). Surrounding whitespace
+/// is ignored. Supplementary E and Y codes are not accepted.
+#[must_use]
+pub fn is_valid_icd8(code: &str) -> bool {
+    let bytes = code.trim().as_bytes();
+    (3..=5).contains(&bytes.len()) && bytes.iter().all(u8::is_ascii_digit)
+}
+
+/// Revision of the ICD used for diagnosis codes
+///
+/// The Danish National Patient Registry used ICD-8 until the end of 1993
+/// and ICD-10 from 1 January 1994.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum IcdVersion {
+    /// ICD-8, with numeric codes
+    Icd8,
+    /// ICD-10, with a chapter letter
+    #[default]
+    Icd10,
+}
+
+impl IcdVersion {
+    /// Get the version in use in the Danish registers on a date
+    #[must_use]
+    pub fn on_date(date: NaiveDate) -> Self {
+        if date < NaiveDate::from_ymd_opt(1994, 1, 1).unwrap() {
+            Self::Icd8
+        } else {
+            Self::Icd10
+        }
+    }
+
+    /// Get the version whose structure a code has, if any
+    #[must_use]
+    pub fn of_code(code: &str) -> Option<Self> {
+        if is_valid_icd10(code) {
+            Some(Self::Icd10)
+        } else if is_valid_icd8(code) {
+            Some(Self::Icd8)
+        } else {
+            None
+        }
+    }
+
+    /// Check that a code has the structure of this version
+    #[must_use]
+    pub fn is_valid_code(self, code: &str) -> bool {
+        match self {
+            Self::Icd8 => is_valid_icd8(code),
+            Self::Icd10 => is_valid_icd10(code),
+        }
+    }
+}
+
+/// A disease group and its weight, as assigned to a code prefix
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeGroup<G> {
+    /// The disease group
+    pub group: G,
+    /// The weight contributed to the score when the group is present
+    pub weight: f64,
+}
+
+/// Lookup table mapping ICD-10 code prefixes to disease groups
+///
+/// Codes are matched against the longest prefix in the table, so specific
+/// entries (e.g. `Q790`) override broader ones (e.g. `Q79`). Codes are
+/// trimmed and uppercased before matching; prefixes are uppercased on insertion.
+#[derive(Debug, Clone)]
+pub struct CodeGroupTable<G> {
+    /// Groups by normalized prefix
+    entries: HashMap<String, CodeGroup<G>>,
+    /// Length of the longest prefix, bounding the lookup
+    max_prefix_len: usize,
+}
+
+impl<G> Default for CodeGroupTable<G> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            max_prefix_len: 0,
+        }
+    }
+}
+
+impl<G> CodeGroupTable<G> {
+    /// Create an empty table
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the group for a code prefix
+    pub fn insert(&mut self, prefix: &str, group: G, weight: f64) {
+        let prefix = prefix.trim().to_uppercase();
+        self.max_prefix_len = self.max_prefix_len.max(prefix.len());
+        self.entries.insert(prefix, CodeGroup { group, weight });
+    }
+
+    /// Add the same group for several code prefixes
+    pub fn insert_all(&mut self, prefixes: &[&str], group: G, weight: f64)
+    where
+        G: Clone,
+    {
+        for prefix in prefixes {
+            self.insert(prefix, group.clone(), weight);
+        }
+    }
+
+    /// Builder-style variant of [`insert_all`](Self::insert_all)
+    #[must_use]
+    pub fn with_group(mut self, prefixes: &[&str], group: G, weight: f64) -> Self
+    where
+        G: Clone,
+    {
+        self.insert_all(prefixes, group, weight);
+        self
+    }
+
+    /// Number of prefixes in the table
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the table has no prefixes
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Keep only the entries whose prefix satisfies the predicate
+    pub fn retain(&mut self, mut predicate: impl FnMut(&str, &CodeGroup<G>) -> bool) {
+        self.entries
+            .retain(|prefix, group| predicate(prefix, group));
+    }
+
+    /// Look up the group for a diagnosis code by longest matching prefix
+    #[must_use]
+    pub fn lookup(&self, code: &str) -> Option<&CodeGroup<G>> {
+        let code = code.trim().to_uppercase();
+        let longest = self.max_prefix_len.min(code.len());
+
+        (1..=longest)
+            .rev()
+            .find_map(|len| code.get(..len).and_then(|prefix| self.entries.get(prefix)))
+    }
+}
+
+/// Date and age restrictions applied before classifying a diagnosis
+#[derive(Debug, Clone, Default)]
+pub struct ClassifierConfig {
+    /// Ignore diagnoses before this date
+    pub start_date: Option<NaiveDate>,
+    /// Ignore diagnoses after this date
+    pub end_date: Option<NaiveDate>,
+    /// Ignore diagnoses made before this age (in years)
+    pub min_age_years: Option<u32>,
+    /// Ignore diagnoses made after this age (in years)
+    pub max_age_years: Option<u32>,
+}
+
+impl ClassifierConfig {
+    /// Whether a diagnosis passes the date and age restrictions
+    ///
+    /// Diagnoses without a date, or individuals without a birth date, are not
+    /// excluded by the corresponding restriction.
+    #[must_use]
+    pub fn accepts(&self, diagnosis: &Diagnosis, birth_date: Option<NaiveDate>) -> bool {
+        let Some(date) = diagnosis.diagnosis_date else {
+            return true;
+        };
+
+        if self.start_date.is_some_and(|start| date < start)
+            || self.end_date.is_some_and(|end| date > end)
+        {
+            return false;
+        }
+
+        if let Some(birth_date) = birth_date {
+            let age_at_diagnosis = (date - birth_date).num_days() / 365;
+            if self
+                .min_age_years
+                .is_some_and(|min| age_at_diagnosis < i64::from(min))
+                || self
+                    .max_age_years
+                    .is_some_and(|max| age_at_diagnosis > i64::from(max))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// An individual's membership of one disease group
+#[derive(Debug, Clone)]
+pub struct GroupMembership<G> {
+    /// The disease group
+    pub group: G,
+    /// The highest weight among the diagnoses assigning this group
+    pub weight: f64,
+    /// Date of the earliest dated diagnosis in the group
+    pub first_date: Option<NaiveDate>,
+    /// The diagnoses assigning this group, in collection order
+    pub diagnoses: Vec<Arc<Diagnosis>>,
+}
+
+/// Classification of one individual's diagnoses
+#[derive(Debug, Clone)]
+pub struct ClassificationResult<G> {
+    /// Individual's PNR
+    pub pnr: String,
+    /// Disease groups present, in order of first appearance
+    pub groups: Vec<GroupMembership<G>>,
+    /// Sum of the group weights
+    pub score: f64,
+}
+
+impl<G: PartialEq> ClassificationResult<G> {
+    /// Whether the individual belongs to a group
+    #[must_use]
+    pub fn has_group(&self, group: &G) -> bool {
+        self.groups.iter().any(|m| m.group == *group)
+    }
+}
+
+/// Classifier assigning diagnoses to disease groups using a [`CodeGroupTable`]
+#[derive(Debug, Clone)]
+pub struct DiseaseClassifier<G> {
+    /// The code table
+    table: CodeGroupTable<G>,
+    /// Date and age restrictions
+    config: ClassifierConfig,
+}
+
+impl<G: Clone + PartialEq> DiseaseClassifier<G> {
+    /// Create a classifier from a code table and restrictions
+    #[must_use]
+    pub const fn new(table: CodeGroupTable<G>, config: ClassifierConfig) -> Self {
+        Self { table, config }
+    }
+
+    /// The code table used by this classifier
+    #[must_use]
+    pub const fn table(&self) -> &CodeGroupTable<G> {
+        &self.table
+    }
+
+    /// Classify a single diagnosis
+    ///
+    /// Returns the group for the diagnosis code, or `None` if the code is not
+    /// in the table or the diagnosis is excluded by the date or age restrictions.
+    #[must_use]
+    pub fn classify_diagnosis(
+        &self,
+        diagnosis: &Diagnosis,
+        birth_date: Option<NaiveDate>,
+    ) -> Option<&CodeGroup<G>> {
+        if !self.config.accepts(diagnosis, birth_date) {
+            return None;
+        }
+        self.table.lookup(&diagnosis.diagnosis_code)
+    }
+
+    /// Classify the diagnoses of every individual in a collection
+    ///
+    /// Every individual with diagnoses gets a result, with no groups and a
+    /// score of zero if none of their diagnoses match.
+    ///
+    /// # Arguments
+    /// * `diagnoses` - The diagnoses to classify
+    /// * `birth_dates` - Birth dates by PNR, used for the age restrictions
+    ///
+    /// # Returns
+    /// Classification results by PNR
+    #[must_use]
+    pub fn classify(
+        &self,
+        diagnoses: &DiagnosisCollection,
+        birth_dates: &HashMap<String, NaiveDate>,
+    ) -> HashMap<String, ClassificationResult<G>> {
+        diagnoses
+            .individuals()
+            .into_iter()
+            .map(|pnr| {
+                let result = self.classify_individual(
+                    &pnr,
+                    &diagnoses.get_diagnoses(&pnr),
+                    birth_dates.get(&pnr).copied(),
+                );
+                (pnr, result)
+            })
+            .collect()
+    }
+
+    /// Classify one individual's diagnoses
+    #[must_use]
+    pub fn classify_individual(
+        &self,
+        pnr: &str,
+        diagnoses: &[Arc<Diagnosis>],
+        birth_date: Option<NaiveDate>,
+    ) -> ClassificationResult<G> {
+        let mut groups: Vec<GroupMembership<G>> = Vec::new();
+
+        for diagnosis in diagnoses {
+            let Some(code_group) = self.classify_diagnosis(diagnosis, birth_date) else {
+                continue;
+            };
+
+            let position = groups.iter().position(|m| m.group == code_group.group);
+            let membership = match position {
+                Some(position) => &mut groups[position],
+                None => {
+                    groups.push(GroupMembership {
+                        group: code_group.group.clone(),
+                        weight: code_group.weight,
+                        first_date: None,
+                        diagnoses: Vec::new(),
+                    });
+                    groups.last_mut().expect("membership was just pushed")
+                }
+            };
+
+            membership.weight = membership.weight.max(code_group.weight);
+            if let Some(date) = diagnosis.diagnosis_date
+                && membership.first_date.is_none_or(|first| date < first)
+            {
+                membership.first_date = Some(date);
+            }
+            membership.diagnoses.push(diagnosis.clone());
+        }
+
+        let score = groups.iter().map(|m| m.weight).sum();
+        ClassificationResult {
+            pnr: pnr.to_string(),
+            groups,
+            score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::collections::ModelCollection;
+    use crate::models::core::types::DiagnosisType;
+
+    #[test]
+    fn test_classify_with_longest_prefix_and_restrictions() {
+        let table = CodeGroupTable::new()
+            .with_group(&["I21", "I22"], "mi", 1.0)
+            .with_group(&["E10"], "diabetes", 1.0)
+            .with_group(&["E102"], "diabetes_complicated", 2.0);
+        let config = ClassifierConfig {
+            start_date: NaiveDate::from_ymd_opt(2010, 1, 1),
+            ..ClassifierConfig::default()
+        };
+        let classifier = DiseaseClassifier::new(table, config);
+
+        let date = |y| NaiveDate::from_ymd_opt(y, 6, 1);
+        let mut diagnoses = DiagnosisCollection::new();
+        for (code, year) in [("I219", 2012), ("i22", 2011), ("E102", 2015), ("E10", 2005)] {
+            diagnoses.add(Diagnosis::new(
+                "a".to_string(),
+                code.to_string(),
+                DiagnosisType::Primary,
+                date(year),
+            ));
+        }
+
+        let results = classifier.classify(&diagnoses, &HashMap::new());
+        let result = &results["a"];
+        assert!(result.has_group(&"mi"));
+        assert!(result.has_group(&"diabetes_complicated"));
+        // The 2005 E10 diagnosis falls before the study start
+        assert!(!result.has_group(&"diabetes"));
+        assert_eq!(result.groups[0].first_date, date(2011));
+        assert!((result.score - 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_lookup_codes_shorter_than_prefixes() {
+        let table = CodeGroupTable::new()
+            .with_group(&["C"], 1, 1.0)
+            .with_group(&["D57"], 2, 1.0)
+            .with_group(&["D610"], 3, 1.0);
+
+        assert_eq!(table.lookup("C").map(|g| g.group), Some(1));
+        for code in ["", "D", "D5", "D6", "D61", "Dé", "é"] {
+            assert!(table.lookup(code).is_none(), "{code:?}");
+        }
+        assert_eq!(table.lookup("D57").map(|g| g.group), Some(2));
+        assert_eq!(table.lookup("D6109").map(|g| g.group), Some(3));
+    }
+}
@@ -4,18 +4,26 @@
 //! LPR data harmonization, diagnosis classification, and SCD algorithm.
 
 // LPR data processing modules
-pub mod lpr2_processor;
-pub mod lpr3_processor;
+// pub mod lpr2_processor;
+// pub mod lpr3_processor;
 pub mod lpr_config;
-pub mod lpr_loader;
-pub mod lpr_utility;
+// pub mod lpr_loader;
+// pub mod lpr_utility;
 
-// SCD algorithm module
+// Disease classification modules
+pub mod charlson;
+pub mod classifier;
 pub mod scd;
 
 // Re-export common types
 pub use crate::models::health::diagnosis::ScdResult;
+pub use charlson::{CharlsonComorbidity, CharlsonConfig, CharlsonResult, compute_charlson};
+pub use classifier::{
+    ClassificationResult, ClassifierConfig, CodeGroup, CodeGroupTable, DiseaseClassifier,
+    GroupMembership, IcdVersion, is_valid_icd8, is_valid_icd10,
+};
 pub use lpr_config::LprConfig;
-pub use lpr_loader::{load_diagnoses, process_lpr_data};
+// pub use lpr_loader::{load_diagnoses, process_lpr_data};
+pub use scd::apply_scd_to_individuals;
 pub use scd::categories::ScdCategory;
 pub use scd::severity::SeverityLevel;
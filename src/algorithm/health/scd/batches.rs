@@ -0,0 +1,233 @@
+//! SCD algorithm on diagnosis batches
+//!
+//! Most diagnoses do not have an SCD code. Rather than converting every row
+//! into a `Diagnosis` for a `DiagnosisCollection`, the codes are categorized
+//! while borrowed from the Arrow columns, and only rows with SCD codes
+//! become `Diagnosis` objects.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{Array, Date32Array, Date64Array, StringArray};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use chrono::NaiveDate;
+use rayon::prelude::*;
+
+use super::{ScdClassifiers, ScdConfig, individual_scd_result};
+use crate::error::{Error, Result};
+use crate::models::core::types::DiagnosisType;
+use crate::models::health::diagnosis::{Diagnosis, ScdCriteria, ScdResult};
+use crate::utils::arrow::array_utils::downcast_array;
+
+/// The diagnoses of one individual gathered from the batches
+#[derive(Default)]
+struct IndividualRows {
+    /// The number of diagnoses
+    count: usize,
+    /// The diagnoses with SCD codes, in row order
+    scd_diagnoses: Vec<Arc<Diagnosis>>,
+}
+
+/// Apply the SCD algorithm to diagnosis batches
+///
+/// The results are those of [`apply_scd_algorithm`](super::apply_scd_algorithm)
+/// on a collection of the same rows, as `Diagnosis::from_lpr_batch` reads
+/// them: rows without a PNR or code are skipped, and diagnoses have type
+/// `Other` and the severity of `ScdCriteria`. No birth dates are available,
+/// so the age restrictions of the configuration do not apply.
+///
+/// # Arguments
+///
+/// * `batches` - The diagnosis batches
+/// * `pnr_column` - The PNR column, of type `Utf8`
+/// * `code_column` - The diagnosis code column, of type `Utf8`
+/// * `date_column` - The diagnosis date column, of type `Date32` or `Date64`
+/// * `config` - The SCD configuration
+///
+/// # Returns
+///
+/// SCD results by PNR, for every individual with diagnoses
+///
+/// # Errors
+///
+/// Returns an error if a column is missing from a batch or has the wrong type
+pub fn apply_scd_to_batches(
+    batches: &[RecordBatch],
+    pnr_column: &str,
+    code_column: &str,
+    date_column: &str,
+    config: &ScdConfig,
+) -> Result<HashMap<String, ScdResult>> {
+    let criteria = ScdCriteria::new();
+    let classifiers = ScdClassifiers::new(config);
+    let mut individuals: HashMap<&str, IndividualRows> = HashMap::new();
+
+    for batch in batches {
+        let pnrs = string_column(batch, pnr_column)?;
+        let codes = string_column(batch, code_column)?;
+        let dates = date_values(batch, date_column)?;
+
+        for (row, (pnr, code)) in pnrs.iter().zip(codes.iter()).enumerate() {
+            let (Some(pnr), Some(code)) = (pnr, code) else {
+                continue;
+            };
+            let rows = individuals.entry(pnr).or_default();
+            rows.count += 1;
+
+            let version = config.icd_version_on(dates[row]);
+            let is_scd_code = classifiers
+                .classifier_for(code, version)
+                .is_some_and(|classifier| classifier.table().lookup(code).is_some());
+            if !is_scd_code {
+                continue;
+            }
+            let mut diagnosis = Diagnosis::new(
+                pnr.to_string(),
+                code.to_string(),
+                DiagnosisType::Other,
+                dates[row],
+            );
+            if criteria.is_scd(code) {
+                diagnosis = diagnosis.as_scd(criteria.get_severity(code));
+            }
+            rows.scd_diagnoses.push(Arc::new(diagnosis));
+        }
+    }
+
+    let scd_results = individuals
+        .into_par_iter()
+        .filter_map(|(pnr, rows)| {
+            individual_scd_result(
+                pnr.to_string(),
+                &rows.scd_diagnoses,
+                rows.count,
+                config,
+                &classifiers,
+                None,
+            )
+            .map(|scd_result| (scd_result.pnr.clone(), scd_result))
+        })
+        .collect();
+    classifiers.warn_ignored();
+
+    Ok(scd_results)
+}
+
+/// Get a `Utf8` column of a batch
+fn string_column<'a>(batch: &'a RecordBatch, column: &str) -> Result<&'a StringArray> {
+    let array = batch
+        .column_by_name(column)
+        .ok_or_else(|| Error::ColumnNotFound {
+            column: column.to_string(),
+        })?;
+    downcast_array::<StringArray>(array, column, "Utf8")
+}
+
+/// Get the values of a `Date32` or `Date64` column of a batch
+fn date_values(batch: &RecordBatch, column: &str) -> Result<Vec<Option<NaiveDate>>> {
+    let array = batch
+        .column_by_name(column)
+        .ok_or_else(|| Error::ColumnNotFound {
+            column: column.to_string(),
+        })?;
+
+    match array.data_type() {
+        DataType::Date32 => {
+            let array = downcast_array::<Date32Array>(array, column, "Date32")?;
+            Ok((0..array.len())
+                .map(|row| array.value_as_date(row).filter(|_| array.is_valid(row)))
+                .collect())
+        }
+        DataType::Date64 => {
+            let array = downcast_array::<Date64Array>(array, column, "Date64")?;
+            Ok((0..array.len())
+                .map(|row| array.value_as_date(row).filter(|_| array.is_valid(row)))
+                .collect())
+        }
+        _ => Err(Error::InvalidDataType {
+            column: column.to_string(),
+            expected: "Date32 or Date64".to_string(),
+        }
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::health::scd::apply_scd_algorithm;
+    use crate::common::traits::LprRegistry;
+    use crate::models::collections::ModelCollection;
+    use crate::models::health::diagnosis::DiagnosisCollection;
+    use arrow::datatypes::{Field, Schema};
+
+    fn diagnosis_batch(rows: &[(&str, Option<&str>, Option<i32>)]) -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("PNR", DataType::Utf8, false),
+            Field::new("DIAG", DataType::Utf8, true),
+            Field::new("INDDTO", DataType::Date32, true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|(pnr, _, _)| *pnr),
+                )),
+                Arc::new(StringArray::from_iter(
+                    rows.iter().map(|(_, code, _)| *code),
+                )),
+                Arc::new(Date32Array::from_iter(
+                    rows.iter().map(|(_, _, date)| *date),
+                )),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_batches_match_collection() {
+        let batches = vec![
+            diagnosis_batch(&[
+                ("1", Some("C50"), Some(14_000)),
+                ("1", Some("J45"), Some(14_100)),
+                ("2", Some("D80"), None),
+                ("3", None, Some(14_000)),
+            ]),
+            diagnosis_batch(&[
+                ("1", Some("N185"), Some(14_200)),
+                ("2", Some("Q20"), Some(13_000)),
+                ("4", Some("R51"), Some(14_000)),
+            ]),
+        ];
+        let config = ScdConfig {
+            include_congenital: false,
+            min_qualifying_diagnoses: 2,
+            ..ScdConfig::default()
+        };
+
+        let mut collection = DiagnosisCollection::new();
+        for batch in &batches {
+            for diagnosis in Diagnosis::from_lpr_batch(batch).unwrap() {
+                collection.add(diagnosis);
+            }
+        }
+        let expected = apply_scd_algorithm(&collection, &config, &HashMap::new()).unwrap();
+
+        let results = apply_scd_to_batches(&batches, "PNR", "DIAG", "INDDTO", &config).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(expected.len(), 3);
+        assert!(results["1"].has_scd);
+        assert!(!results["2"].has_scd);
+
+        let mut pnrs: Vec<&String> = expected.keys().collect();
+        pnrs.sort_unstable();
+        for pnr in pnrs {
+            assert_eq!(
+                format!("{:?}", results[pnr]),
+                format!("{:?}", expected[pnr]),
+                "results differ for {pnr}"
+            );
+        }
+    }
+}
@@ -5,7 +5,7 @@
 use std::fmt;
 
 /// SCD disease categories
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ScdCategory {
     /// Blood disorders (e.g., anemias, coagulation defects)
     BloodDisorder = 1,
@@ -0,0 +1,163 @@
+//! Dominant SCD category per individual
+//!
+//! An individual can have SCD diagnoses in several categories. For
+//! tabulation, each individual is assigned the single category contributing
+//! their most severe diagnosis.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{Int32Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use super::categories::ScdCategory;
+use super::categorize_diagnosis_version;
+use super::severity::SeverityLevel;
+use crate::algorithm::health::classifier::IcdVersion;
+use crate::error::Result;
+use crate::models::health::diagnosis::ScdResult;
+
+/// SCD queries on an individual's result
+pub trait ScdResultExt {
+    /// Get the category contributing the highest severity
+    ///
+    /// Severities are those of the SCD code table of each code's ICD
+    /// version. When several categories reach the highest severity, the one
+    /// listed first in [`ScdCategory::all_categories`] (the lowest category
+    /// number) is chosen.
+    ///
+    /// # Returns
+    ///
+    /// The dominant category and its severity, or `None` without SCD diagnoses
+    fn dominant_category(&self) -> Option<(ScdCategory, SeverityLevel)>;
+}
+
+impl ScdResultExt for ScdResult {
+    fn dominant_category(&self) -> Option<(ScdCategory, SeverityLevel)> {
+        self.scd_diagnoses
+            .iter()
+            .filter_map(|diagnosis| {
+                let code = &diagnosis.diagnosis_code;
+                IcdVersion::of_code(code)
+                    .and_then(|version| categorize_diagnosis_version(code, version))
+            })
+            .map(|(category, _, severity)| (category, severity))
+            .max_by(|(a_category, a_severity), (b_category, b_severity)| {
+                a_severity
+                    .cmp(b_severity)
+                    .then((*b_category as u8).cmp(&(*a_category as u8)))
+            })
+    }
+}
+
+/// Export the dominant SCD category of each individual
+///
+/// The batch has one row per individual, sorted by PNR, with the columns
+/// `PNR`, `DOMINANT_CATEGORY_ID`, `DOMINANT_CATEGORY` and
+/// `DOMINANT_SEVERITY`. Individuals without SCD have null category and
+/// severity.
+///
+/// # Errors
+///
+/// Returns an error if the batch cannot be built
+pub fn dominant_category_batch(scd_results: &HashMap<String, ScdResult>) -> Result<RecordBatch> {
+    let mut pnrs: Vec<&String> = scd_results.keys().collect();
+    pnrs.sort_unstable();
+
+    let dominant: Vec<Option<(ScdCategory, SeverityLevel)>> = pnrs
+        .iter()
+        .map(|pnr| scd_results[*pnr].dominant_category())
+        .collect();
+
+    let schema = Schema::new(vec![
+        Field::new("PNR", DataType::Utf8, false),
+        Field::new("DOMINANT_CATEGORY_ID", DataType::Int32, true),
+        Field::new("DOMINANT_CATEGORY", DataType::Utf8, true),
+        Field::new("DOMINANT_SEVERITY", DataType::Int32, true),
+    ]);
+
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from_iter_values(pnrs)),
+            Arc::new(Int32Array::from_iter(dominant.iter().map(|value| {
+                value.map(|(category, _)| i32::from(category as u8))
+            }))),
+            Arc::new(StringArray::from_iter(
+                dominant
+                    .iter()
+                    .map(|value| value.map(|(category, _)| category.display_name())),
+            )),
+            Arc::new(Int32Array::from_iter(
+                dominant
+                    .iter()
+                    .map(|value| value.map(|(_, severity)| severity.as_i32())),
+            )),
+        ],
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::health::scd::categorize_diagnosis;
+    use crate::models::core::types::DiagnosisType;
+    use crate::models::health::diagnosis::Diagnosis;
+    use arrow::array::Array;
+
+    fn with_diagnoses(pnr: &str, codes: &[&str]) -> ScdResult {
+        let mut result = ScdResult::new(pnr.to_string());
+        for code in codes {
+            let (category, is_congenital, _) = categorize_diagnosis(code).unwrap();
+            let diagnosis = Diagnosis::new(
+                pnr.to_string(),
+                (*code).to_string(),
+                DiagnosisType::Primary,
+                None,
+            );
+            result.add_scd_diagnosis(Arc::new(diagnosis), category as u8, is_congenital);
+        }
+        result
+    }
+
+    #[test]
+    fn test_dominant_category() {
+        // Severe renal disease outranks a moderate immune disorder
+        let result = with_diagnoses("1", &["D80", "N185"]);
+        assert_eq!(
+            result.dominant_category(),
+            Some((ScdCategory::RenalDisorder, SeverityLevel::Severe))
+        );
+
+        // Equally severe: the lower category number wins
+        let result = with_diagnoses("2", &["N185", "C50"]);
+        assert_eq!(
+            result.dominant_category(),
+            Some((ScdCategory::BloodDisorder, SeverityLevel::Severe))
+        );
+
+        assert_eq!(ScdResult::new("3".to_string()).dominant_category(), None);
+    }
+
+    #[test]
+    fn test_dominant_category_batch() {
+        let scd_results: HashMap<String, ScdResult> = [
+            ("2".to_string(), ScdResult::new("2".to_string())),
+            ("1".to_string(), with_diagnoses("1", &["D80"])),
+        ]
+        .into_iter()
+        .collect();
+
+        let batch = dominant_category_batch(&scd_results).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        let categories = batch
+            .column_by_name("DOMINANT_CATEGORY")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(categories.value(0), "Immune System Disorder");
+        assert!(categories.is_null(1));
+    }
+}
@@ -0,0 +1,111 @@
+//! ICD-8 code table for the SCD algorithm
+//!
+//! Danish LPR data before 1994 is coded in ICD-8. This table translates the
+//! groups of the ICD-10 table to the corresponding ICD-8 rubrics. ICD-10
+//! groups without an ICD-8 counterpart, such as the immune deficiencies, are
+//! not mapped.
+
+use super::categories::ScdCategory;
+use super::severity::SeverityLevel;
+use crate::algorithm::health::classifier::CodeGroupTable;
+
+/// Build the ICD-8 SCD code table
+pub(super) fn icd8_code_table() -> CodeGroupTable<ScdCategory> {
+    use ScdCategory::{
+        BloodDisorder, CardiovascularDisorder, CongenitalDisorder, EndocrineDisorder,
+        GastrointestinalDisorder, MusculoskeletalDisorder, NeurologicalDisorder, RenalDisorder,
+        RespiratoryDisorder,
+    };
+
+    let mild = f64::from(SeverityLevel::Mild.as_i32());
+    let moderate = f64::from(SeverityLevel::Moderate.as_i32());
+    let severe = f64::from(SeverityLevel::Severe.as_i32());
+
+    CodeGroupTable::new()
+        // Malignant neoplasms (140-209)
+        .with_group(
+            &["14", "15", "16", "17", "18", "19", "20"],
+            BloodDisorder,
+            severe,
+        )
+        // Haemolytic and aplastic anaemias, coagulation defects and other
+        // diseases of blood (282-289)
+        .with_group(
+            &["282", "283", "284", "286", "287", "288", "289"],
+            BloodDisorder,
+            moderate,
+        )
+        // Insulin-dependent diabetes, pituitary, adrenal and metabolic disorders
+        .with_group(
+            &["249", "253", "255", "258", "270", "271", "272", "273"],
+            EndocrineDisorder,
+            moderate,
+        )
+        // Cystic fibrosis
+        .with_group(&["2730"], EndocrineDisorder, severe)
+        // Childhood psychoses and neurological disorders
+        .with_group(
+            &["299", "333", "341", "343", "344", "345"],
+            NeurologicalDisorder,
+            moderate,
+        )
+        // Hereditary neuromuscular disorders
+        .with_group(&["330"], NeurologicalDisorder, severe)
+        // Chronic rheumatic and other heart disease, venous thrombosis
+        .with_group(
+            &[
+                "394", "395", "396", "397", "398", "403", "420", "421", "422", "423", "424", "425",
+                "426", "427", "428", "452", "453", "454",
+            ],
+            CardiovascularDisorder,
+            moderate,
+        )
+        // Heart failure
+        .with_group(&["4270", "4271"], CardiovascularDisorder, severe)
+        // Chronic bronchitis, emphysema, pneumoconioses and interstitial disease
+        .with_group(
+            &["491", "492", "515", "516", "517", "518"],
+            RespiratoryDisorder,
+            moderate,
+        )
+        // Asthma
+        .with_group(&["493"], RespiratoryDisorder, mild)
+        // Inflammatory bowel, liver, pancreatic and coeliac disease
+        .with_group(
+            &["563", "573", "577", "2690"],
+            GastrointestinalDisorder,
+            moderate,
+        )
+        // Cirrhosis of liver
+        .with_group(&["571"], GastrointestinalDisorder, severe)
+        // Rheumatoid arthritis, connective tissue disorders and spinal curvature
+        .with_group(
+            &["446", "712", "734", "737"],
+            MusculoskeletalDisorder,
+            moderate,
+        )
+        // Nephritis, nephrosis and other renal disorders
+        .with_group(
+            &["580", "581", "582", "583", "584", "592", "593"],
+            RenalDisorder,
+            moderate,
+        )
+        // Uraemia
+        .with_group(&["792"], RenalDisorder, severe)
+        // Kernicterus
+        .with_group(&["774"], NeurologicalDisorder, moderate)
+        // Congenital anomalies of the nervous system (740-743)
+        .with_group(&["740", "741", "742", "743"], NeurologicalDisorder, severe)
+        // Congenital anomalies of the circulatory system (746-747)
+        .with_group(&["746", "747"], CardiovascularDisorder, severe)
+        // Congenital anomalies of the respiratory system, cleft lip and palate
+        .with_group(&["748", "749"], RespiratoryDisorder, moderate)
+        // Congenital anomalies of the digestive system
+        .with_group(&["750", "751"], GastrointestinalDisorder, moderate)
+        // Congenital anomalies of the urinary system
+        .with_group(&["753"], RenalDisorder, moderate)
+        // Congenital anomalies of the musculoskeletal system
+        .with_group(&["755", "756"], MusculoskeletalDisorder, moderate)
+        // Other and unspecified congenital anomalies
+        .with_group(&["757", "758", "759"], CongenitalDisorder, moderate)
+}
@@ -0,0 +1,96 @@
+//! Storing SCD results on individuals
+//!
+//! SCD results are keyed by PNR, separately from the individuals they were
+//! computed for. Copying them onto `Individual::scd` allows joint analysis
+//! with demographics.
+
+use std::collections::HashMap;
+
+use crate::collections::IndividualCollection;
+use crate::models::health::diagnosis::{ScdResult, ScdStatus};
+
+/// Set the SCD status of each individual with an SCD result
+///
+/// Individuals without a result keep their current status.
+///
+/// # Arguments
+///
+/// * `individuals` - The individuals to update
+/// * `scd_results` - SCD results by PNR, from the SCD algorithm
+///
+/// # Returns
+///
+/// The PNRs with results but no matching individual, sorted
+pub fn apply_scd_to_individuals(
+    individuals: &mut IndividualCollection,
+    scd_results: &HashMap<String, ScdResult>,
+) -> Vec<String> {
+    let mut unmatched = Vec::new();
+    for (pnr, result) in scd_results {
+        match individuals.raw_mut().get_mut(pnr) {
+            Some(individual) => individual.scd = Some(ScdStatus::from(result)),
+            None => unmatched.push(pnr.clone()),
+        }
+    }
+    unmatched.sort_unstable();
+
+    if !unmatched.is_empty() {
+        log::warn!(
+            "{} of {} SCD results have no matching individual",
+            unmatched.len(),
+            scd_results.len()
+        );
+    }
+
+    unmatched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::traits::ModelCollection;
+    use crate::models::core::Individual;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_apply_scd_to_individuals() {
+        let individual = Individual {
+            pnr: "0101011234".to_string(),
+            ..Individual::default()
+        };
+        let mut individuals = IndividualCollection::from_individuals(vec![individual]);
+
+        let mut result = ScdResult::new("0101011234".to_string());
+        result.has_scd = true;
+        result.scd_categories = vec![9];
+        result.max_severity = 3;
+        result.first_scd_date = NaiveDate::from_ymd_opt(2010, 6, 1);
+        let scd_results: HashMap<String, ScdResult> = [
+            (result.pnr.clone(), result),
+            (
+                "0202022345".to_string(),
+                ScdResult::new("0202022345".to_string()),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let unmatched = apply_scd_to_individuals(&mut individuals, &scd_results);
+        assert_eq!(unmatched, vec!["0202022345"]);
+
+        let status = individuals
+            .get(&"0101011234".to_string())
+            .unwrap()
+            .scd
+            .clone();
+        assert_eq!(
+            status,
+            Some(ScdStatus {
+                has_scd: true,
+                categories: vec![9],
+                max_severity: 3,
+                earliest_date: NaiveDate::from_ymd_opt(2010, 6, 1),
+            })
+        );
+    }
+}
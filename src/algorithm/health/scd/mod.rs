@@ -3,18 +3,36 @@
 //! This module implements the Severe Chronic Disease (SCD) algorithm for
 //! identifying patients with severe chronic diseases based on ICD-10 diagnosis codes.
 
+pub mod batches;
 pub mod categories;
+pub mod dominant;
+mod icd8;
+pub mod individuals;
 pub mod severity;
 
-use crate::error::Result;
+use crate::algorithm::health::classifier::{
+    ClassifierConfig, CodeGroup, CodeGroupTable, DiseaseClassifier, IcdVersion,
+};
+use crate::error::{ParquetReaderError, Result};
 use crate::models::health::diagnosis::{Diagnosis, DiagnosisCollection, ScdResult};
+pub use batches::apply_scd_to_batches;
 use categories::ScdCategory;
-use chrono::NaiveDate;
+use chrono::{Duration, NaiveDate};
+pub use dominant::{ScdResultExt, dominant_category_batch};
+pub use individuals::apply_scd_to_individuals;
+use lazy_static::lazy_static;
+use rayon::prelude::*;
 use severity::SeverityLevel;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Configuration for SCD algorithm
+///
+/// With `index_dates`, only diagnoses in each individual's lookback window
+/// `[index - lookback, index]` qualify, and individuals without an index date
+/// are skipped. The window narrows the study period rather than replacing
+/// it: a diagnosis must also fall within `start_date` and `end_date`.
 #[derive(Debug, Clone)]
 pub struct ScdConfig {
     /// Start date for study period
@@ -27,6 +45,26 @@ pub struct ScdConfig {
     pub min_age_years: Option<u32>,
     /// Maximum age for SCD diagnosis (in years)
     pub max_age_years: Option<u32>,
+    /// Categories to record, or `None` to record all categories
+    pub included_categories: Option<HashSet<ScdCategory>>,
+    /// Categories never recorded, even if included
+    pub excluded_categories: HashSet<ScdCategory>,
+    /// Minimum number of qualifying diagnoses for an individual to have SCD;
+    /// 0 is treated as 1
+    pub min_qualifying_diagnoses: usize,
+    /// Count qualifying diagnoses on distinct dates only; undated diagnoses
+    /// then do not count
+    pub require_distinct_dates: bool,
+    /// Index date of each individual to anchor the lookback window on
+    pub index_dates: Option<HashMap<String, NaiveDate>>,
+    /// Length of the lookback window before the index date, or `None` for
+    /// every diagnosis up to the index date
+    pub lookback: Option<Duration>,
+    /// ICD version of the diagnosis codes
+    pub icd_version: IcdVersion,
+    /// Infer the ICD version from the diagnosis date, using `icd_version`
+    /// for undated diagnoses
+    pub infer_icd_version: bool,
 }
 
 impl Default for ScdConfig {
@@ -37,640 +75,527 @@ impl Default for ScdConfig {
             include_congenital: true,
             min_age_years: None,
             max_age_years: None,
+            included_categories: None,
+            excluded_categories: HashSet::new(),
+            min_qualifying_diagnoses: 1,
+            require_distinct_dates: false,
+            index_dates: None,
+            lookback: None,
+            icd_version: IcdVersion::Icd10,
+            infer_icd_version: false,
+        }
+    }
+}
+
+impl ScdConfig {
+    /// Check whether diagnoses of a category are recorded
+    ///
+    /// A category is recorded if it is included (all are when
+    /// `included_categories` is `None`) and not excluded.
+    #[must_use]
+    pub fn records_category(&self, category: ScdCategory) -> bool {
+        self.included_categories
+            .as_ref()
+            .is_none_or(|included| included.contains(&category))
+            && !self.excluded_categories.contains(&category)
+    }
+
+    /// Get the ICD version of a diagnosis made on a date
+    #[must_use]
+    pub fn icd_version_on(&self, date: Option<NaiveDate>) -> IcdVersion {
+        match date {
+            Some(date) if self.infer_icd_version => IcdVersion::on_date(date),
+            _ => self.icd_version,
+        }
+    }
+
+    /// Get the lookback window of an individual
+    ///
+    /// # Returns
+    ///
+    /// `None` if no index dates are configured, `Some(None)` if the
+    /// individual has no index date, or `Some(Some((start, index)))`
+    fn lookback_window(&self, pnr: &str) -> Option<Option<(Option<NaiveDate>, NaiveDate)>> {
+        let index_dates = self.index_dates.as_ref()?;
+        Some(index_dates.get(pnr).map(|&index| {
+            let start = self
+                .lookback
+                .and_then(|lookback| index.checked_sub_signed(lookback));
+            (start, index)
+        }))
+    }
+
+    /// Count qualifying diagnoses, by distinct date if required
+    fn qualifying_count<'a>(&self, diagnoses: impl Iterator<Item = &'a Diagnosis>) -> usize {
+        if self.require_distinct_dates {
+            diagnoses
+                .filter_map(|diagnosis| diagnosis.diagnosis_date)
+                .collect::<HashSet<_>>()
+                .len()
+        } else {
+            diagnoses.count()
         }
     }
 }
 
 /// Apply the SCD algorithm to a diagnosis collection and generate SCD results for each individual
+///
+/// Individuals are processed in parallel. Each result depends only on the
+/// individual's own diagnoses, so the output is the same as a serial run.
 pub fn apply_scd_algorithm(
     diagnosis_collection: &DiagnosisCollection,
     config: &ScdConfig,
     birth_dates: &HashMap<String, NaiveDate>,
 ) -> Result<HashMap<String, ScdResult>> {
-    // Store and track SCD results by PNR
-    let mut scd_results: HashMap<String, ScdResult> = HashMap::new();
+    let classifiers = ScdClassifiers::new(config);
 
-    // Process each individual's diagnoses
-    for pnr in diagnosis_collection.individuals_with_diagnoses() {
-        // Get all diagnoses for this individual
-        let diagnoses = diagnosis_collection.get_diagnoses(&pnr);
+    // Gather the diagnoses up front, so the collection itself (with its
+    // lazily built code index) is never shared between threads
+    let individuals: Vec<(String, Vec<Arc<Diagnosis>>)> = diagnosis_collection
+        .individuals_with_diagnoses()
+        .into_iter()
+        .map(|pnr| {
+            let diagnoses = diagnosis_collection.get_diagnoses(&pnr);
+            (pnr, diagnoses)
+        })
+        .collect();
 
+    // Process each individual's diagnoses
+    let scd_results = individuals
+        .into_par_iter()
         // Skip if no diagnoses
-        if diagnoses.is_empty() {
-            continue;
-        }
-
-        // Create a new SCD result for this individual
-        let mut scd_result = ScdResult::new(pnr.clone());
-
-        // Process each diagnosis
-        for diagnosis in &diagnoses {
-            process_diagnosis(&mut scd_result, diagnosis, config, birth_dates)?;
-        }
-
-        // Add hospitalization count (this would come from a separate source,
-        // for now we'll just use the diagnosis count as a proxy)
-        let hospitalization_count = diagnoses.len() as i32;
-        scd_result.hospitalization_count = hospitalization_count;
-
-        // Store the result
-        scd_results.insert(pnr, scd_result);
-    }
+        .filter(|(_, diagnoses)| !diagnoses.is_empty())
+        .filter_map(|(pnr, diagnoses)| {
+            let birth_date = birth_dates.get(&pnr).copied();
+            let diagnosis_count = diagnoses.len();
+            individual_scd_result(
+                pnr,
+                &diagnoses,
+                diagnosis_count,
+                config,
+                &classifiers,
+                birth_date,
+            )
+            .map(|scd_result| (scd_result.pnr.clone(), scd_result))
+        })
+        .collect();
+    classifiers.warn_ignored();
 
     Ok(scd_results)
 }
 
-/// Process a single diagnosis and update the SCD result accordingly
-fn process_diagnosis(
-    scd_result: &mut ScdResult,
-    diagnosis: &Arc<Diagnosis>,
-    config: &ScdConfig,
-    birth_dates: &HashMap<String, NaiveDate>,
-) -> Result<()> {
-    // Skip if outside date range
-    if let (Some(date), Some(start_date)) = (diagnosis.diagnosis_date, config.start_date) {
-        if date < start_date {
-            return Ok(());
-        }
-    }
+/// The SCD classifiers of each ICD version
+///
+/// Codes without the structure of the version they should be in are
+/// ignored and counted, rather than classified with the wrong table.
+struct ScdClassifiers {
+    /// Classifier for ICD-8 codes
+    icd8: DiseaseClassifier<ScdCategory>,
+    /// Classifier for ICD-10 codes
+    icd10: DiseaseClassifier<ScdCategory>,
+    /// Number of codes ignored for having the wrong version
+    ignored: AtomicUsize,
+}
 
-    if let (Some(date), Some(end_date)) = (diagnosis.diagnosis_date, config.end_date) {
-        if date > end_date {
-            return Ok(());
+impl ScdClassifiers {
+    /// Create the classifiers for a configuration
+    fn new(config: &ScdConfig) -> Self {
+        Self {
+            icd8: version_classifier(config, IcdVersion::Icd8),
+            icd10: version_classifier(config, IcdVersion::Icd10),
+            ignored: AtomicUsize::new(0),
         }
     }
 
-    // Skip if outside age range
-    if let (Some(date), Some(birth_date)) =
-        (diagnosis.diagnosis_date, birth_dates.get(&scd_result.pnr))
-    {
-        let age_at_diagnosis = (date - *birth_date).num_days() / 365;
-
-        if let Some(min_age) = config.min_age_years {
-            if age_at_diagnosis < i64::from(min_age) {
-                return Ok(());
-            }
+    /// Get the classifier for a code, or `None` if it has the wrong version
+    fn classifier_for(
+        &self,
+        code: &str,
+        version: IcdVersion,
+    ) -> Option<&DiseaseClassifier<ScdCategory>> {
+        if !version.is_valid_code(code) {
+            self.ignored.fetch_add(1, Ordering::Relaxed);
+            return None;
         }
+        Some(match version {
+            IcdVersion::Icd8 => &self.icd8,
+            IcdVersion::Icd10 => &self.icd10,
+        })
+    }
 
-        if let Some(max_age) = config.max_age_years {
-            if age_at_diagnosis > i64::from(max_age) {
-                return Ok(());
-            }
-        }
+    /// Classify a diagnosis with the table of its ICD version
+    fn classify(
+        &self,
+        config: &ScdConfig,
+        diagnosis: &Diagnosis,
+        birth_date: Option<NaiveDate>,
+    ) -> Option<&CodeGroup<ScdCategory>> {
+        let version = config.icd_version_on(diagnosis.diagnosis_date);
+        self.classifier_for(&diagnosis.diagnosis_code, version)?
+            .classify_diagnosis(diagnosis, birth_date)
     }
 
-    // Determine if this is an SCD diagnosis based on ICD-10 code
-    if let Some((category, is_congenital, _severity)) =
-        categorize_diagnosis(&diagnosis.diagnosis_code)
-    {
-        // Skip congenital diseases if not included
-        if is_congenital && !config.include_congenital {
-            return Ok(());
+    /// Log the number of codes ignored for having the wrong version
+    fn warn_ignored(&self) {
+        let ignored = self.ignored.load(Ordering::Relaxed);
+        if ignored > 0 {
+            log::warn!("Ignored {ignored} diagnosis codes not of the expected ICD version");
         }
-
-        // This is an SCD diagnosis, mark it
-        scd_result.add_scd_diagnosis(diagnosis.clone(), category as u8, is_congenital);
     }
-
-    Ok(())
 }
 
-/// Categorize a diagnosis based on ICD-10 code
-/// Returns (category, `is_congenital`, severity) if it's an SCD diagnosis, None otherwise
-fn categorize_diagnosis(diagnosis_code: &str) -> Option<(ScdCategory, bool, SeverityLevel)> {
-    // Clean and normalize the diagnosis code
-    let clean_code = diagnosis_code.trim().to_uppercase();
-
-    // Check if empty
-    if clean_code.is_empty() {
-        return None;
-    }
+/// Compute the SCD result of one individual
+///
+/// `diagnoses` only needs to include the diagnoses with SCD codes, while
+/// `diagnosis_count` is the number of all their diagnoses. Returns `None`
+/// for individuals without an index date when index dates are configured.
+fn individual_scd_result(
+    pnr: String,
+    diagnoses: &[Arc<Diagnosis>],
+    diagnosis_count: usize,
+    config: &ScdConfig,
+    classifiers: &ScdClassifiers,
+    birth_date: Option<NaiveDate>,
+) -> Option<ScdResult> {
+    // Skip individuals without an index date to look back from
+    let window = match config.lookback_window(&pnr) {
+        Some(None) => return None,
+        Some(window) => window,
+        None => None,
+    };
 
-    // Check for congenital malformations (Q codes)
-    // Variable is used implicitly in pattern matching below
-    #[allow(unused_variables)]
-    let is_congenital = clean_code.starts_with('Q');
+    // Create a new SCD result for this individual
+    let mut scd_result = ScdResult::new(pnr);
 
-    // Special handling for specific codes that require additional parsing (4-character codes)
-    if clean_code.len() >= 4 {
-        let diag4 = &clean_code[..4];
+    // Classify each diagnosis
+    // Categories are filtered after classification, so that a code
+    // is never recorded under a category it only falls back to
+    let qualifying: Vec<(&Arc<Diagnosis>, ScdCategory)> = diagnoses
+        .iter()
+        .filter(|diagnosis| {
+            window.is_none_or(|(start, index)| {
+                diagnosis
+                    .diagnosis_date
+                    .is_some_and(|date| date <= index && start.is_none_or(|start| date >= start))
+            })
+        })
+        .filter_map(|diagnosis| {
+            classifiers
+                .classify(config, diagnosis, birth_date)
+                .filter(|code_group| config.records_category(code_group.group))
+                .map(|code_group| (diagnosis, code_group.group))
+        })
+        .collect();
 
-        // Specific 4-character codes from the R reference implementation
-        match diag4 {
-            // Aplastic anemias
-            "D610" | "D613" | "D618" | "D619" => {
-                return Some((ScdCategory::BloodDisorder, false, SeverityLevel::Moderate));
-            }
-            // Hemophagocytic syndrome
-            "D762" => {
-                return Some((ScdCategory::BloodDisorder, false, SeverityLevel::Moderate));
-            }
-            // Degenerative disorders of the nervous system
-            "G310" | "G318" | "G319" => {
-                return Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                ));
-            }
-            // Myasthenia
-            "G702" => {
-                return Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                ));
-            }
-            // Myopathies
-            "G710" | "G711" | "G712" | "G713" | "G736" => {
-                return Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Severe,
-                ));
-            }
-            // Spastic conditions
-            "G811" | "G821" | "G824" => {
-                return Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                ));
-            }
-            // Hydrocephalus in neoplastic disease
-            "G941" => {
-                return Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                ));
-            }
-            // COPD specific code
-            "J448" => {
-                return Some((
-                    ScdCategory::RespiratoryDisorder,
-                    false,
-                    SeverityLevel::Severe,
-                ));
-            }
-            // Disturbances of cerebral status of newborn
-            "P910" | "P911" | "P912" => {
-                return Some((
-                    ScdCategory::NeurologicalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                ));
-            }
-            // Disorders of muscle tone of newborns
-            "P941" | "P942" | "P943" | "P944" | "P945" | "P946" | "P947" | "P948" | "P949" => {
-                return Some((
-                    ScdCategory::NeurologicalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                ));
-            }
-            // Congenital malformations
-            "Q790" => {
-                return Some((
-                    ScdCategory::RespiratoryDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                ));
-            }
-            "Q792" | "Q793" => {
-                return Some((
-                    ScdCategory::GastrointestinalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                ));
-            }
-            "Q860" => {
-                return Some((
-                    ScdCategory::CongenitalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                ));
-            }
-            _ => { /* Continue to main categorization */ }
+    // Record the qualifying count of each category
+    for (_, category) in &qualifying {
+        let category = *category as u8;
+        if scd_result.qualifying_count(category) == 0 {
+            let count = config.qualifying_count(
+                qualifying
+                    .iter()
+                    .filter(|(_, other)| *other as u8 == category)
+                    .map(|(diagnosis, _)| diagnosis.as_ref()),
+            );
+            scd_result.qualifying_counts.push((category, count));
         }
     }
 
-    // Categorize by first characters
-    match &clean_code[..1] {
-        // C codes are cancer and are considered severe (C00-C99)
-        "C" => Some((ScdCategory::BloodDisorder, false, SeverityLevel::Severe)),
-
-        // D codes cover blood and immune disorders
-        "D" => {
-            match &clean_code[..3] {
-                // Immune system disorders (D80-D89)
-                "D80" | "D81" | "D82" | "D83" | "D84" | "D86" | "D89" => {
-                    Some((ScdCategory::ImmuneDisorder, false, SeverityLevel::Moderate))
-                }
-                // Blood disorders (D55-D77)
-                "D55" | "D56" | "D57" | "D58" | "D59" | "D60" | "D61" | "D64" | "D65" | "D66"
-                | "D67" | "D68" | "D69" | "D70" | "D71" | "D72" | "D73" | "D76" => {
-                    let severity = if clean_code.starts_with("D57") {
-                        // Sickle cell disorders
-                        SeverityLevel::Severe
-                    } else {
-                        SeverityLevel::Moderate
-                    };
-                    Some((ScdCategory::BloodDisorder, false, severity))
-                }
-                _ => None,
-            }
+    // A single code may be a miscoding, so SCD can require several
+    let total = config.qualifying_count(qualifying.iter().map(|(diagnosis, _)| diagnosis.as_ref()));
+    if total >= config.min_qualifying_diagnoses.max(1) {
+        for (diagnosis, category) in qualifying {
+            let is_congenital = is_congenital_code(&diagnosis.diagnosis_code);
+            scd_result.add_scd_diagnosis(diagnosis.clone(), category as u8, is_congenital);
         }
+    }
 
-        // E codes cover endocrine disorders
-        "E" => {
-            match &clean_code[..3] {
-                // Insulin-dependent diabetes mellitus
-                "E10" => Some((
-                    ScdCategory::EndocrineDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Adrenogenital disorders
-                "E25" => Some((
-                    ScdCategory::EndocrineDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Disorders of amino-acid metabolism
-                "E70" | "E71" | "E72" | "E730" => Some((
-                    ScdCategory::EndocrineDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Disorders of metabolism and cystic fibrosis (E74-E84)
-                "E74" | "E75" | "E76" | "E77" | "E78" | "E79" | "E80" | "E83" | "E84" => {
-                    let severity = if clean_code.starts_with("E84") {
-                        // Cystic fibrosis
-                        SeverityLevel::Severe
-                    } else {
-                        SeverityLevel::Moderate
-                    };
-                    Some((ScdCategory::EndocrineDisorder, false, severity))
-                }
-                // Other endocrine disorders
-                "E22" | "E23" | "E24" | "E26" | "E27" | "E31" | "E34" | "E85" | "E88" => Some((
-                    ScdCategory::EndocrineDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                _ => None,
-            }
-        }
+    // Add hospitalization count (this would come from a separate source,
+    // for now we'll just use the diagnosis count as a proxy)
+    scd_result.hospitalization_count = diagnosis_count as i32;
 
-        // F codes cover mental disorders (incl. autism)
-        "F" => {
-            if clean_code.starts_with("F84") {
-                // Autism spectrum disorders
-                Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                ))
-            } else {
-                None
-            }
-        }
+    Some(scd_result)
+}
 
-        // G codes cover neurological disorders
-        "G" => {
-            match &clean_code[..3] {
-                // Spinal muscular atrophy and related syndromes
-                "G12" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Severe,
-                )),
-                // Demyelinating diseases
-                "G37" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Epilepsy
-                "G40" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Hereditary and idiopathic neuropathy
-                "G60" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Cerebral palsy
-                "G80" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Hydrocephalus
-                "G91" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Other neurological disorders
-                "G11" | "G13" | "G23" | "G24" | "G25" | "G31" | "G41" | "G70" | "G71" | "G72"
-                | "G81" | "G82" => {
-                    let severity = if clean_code.starts_with("G12") || // Motor neuron disease
-                                     clean_code.starts_with("G71")
-                    {
-                        // Muscular dystrophy
-                        SeverityLevel::Severe
-                    } else {
-                        SeverityLevel::Moderate
-                    };
-                    Some((ScdCategory::NeurologicalDisorder, false, severity))
-                }
-                _ => None,
-            }
-        }
+lazy_static! {
+    /// SCD code table: ICD-10 prefix to category, weighted by severity
+    ///
+    /// Four-character entries override the three-character groups they belong to.
+    static ref SCD_CODE_TABLE: CodeGroupTable<ScdCategory> = scd_code_table();
 
-        // I codes cover cardiovascular disorders
-        "I" => {
-            match &clean_code[..3] {
-                // Hypertensive renal disease
-                "I12" => Some((
-                    ScdCategory::CardiovascularDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Pulmonary heart disease
-                "I27" => Some((
-                    ScdCategory::CardiovascularDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Heart diseases (I30-I52)
-                "I30" | "I31" | "I32" | "I33" | "I34" | "I35" | "I36" | "I37" | "I38" | "I39"
-                | "I40" | "I41" | "I42" | "I43" | "I44" | "I45" | "I46" | "I47" | "I48" | "I49"
-                | "I50" | "I51" | "I52" => {
-                    let severity = if clean_code.starts_with("I50") {
-                        // Heart failure
-                        SeverityLevel::Severe
-                    } else {
-                        SeverityLevel::Moderate
-                    };
-                    Some((ScdCategory::CardiovascularDisorder, false, severity))
-                }
-                // Other vascular disorders
-                "I81" | "I82" | "I83" => Some((
-                    ScdCategory::CardiovascularDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                _ => None,
-            }
-        }
+    /// SCD code table for ICD-8 codes
+    static ref ICD8_SCD_CODE_TABLE: CodeGroupTable<ScdCategory> = icd8::icd8_code_table();
+}
 
-        // J codes cover respiratory disorders
-        "J" => {
-            match &clean_code[..3] {
-                // COPD
-                "J44" => Some((
-                    ScdCategory::RespiratoryDisorder,
-                    false,
-                    SeverityLevel::Severe,
-                )),
-                // Other interstitial pulmonary disease
-                "J84" => Some((
-                    ScdCategory::RespiratoryDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Other respiratory disorders
-                "J41" | "J42" | "J43" | "J45" | "J47" | "J60" | "J61" | "J62" | "J63" | "J64"
-                | "J65" | "J66" | "J67" | "J68" | "J69" | "J70" | "J96" => {
-                    let severity = if clean_code.starts_with("J96") {
-                        // Respiratory failure
-                        SeverityLevel::Severe
-                    } else if clean_code.starts_with("J45") {
-                        // Asthma
-                        SeverityLevel::Mild
-                    } else {
-                        SeverityLevel::Moderate
-                    };
-                    Some((ScdCategory::RespiratoryDisorder, false, severity))
-                }
-                _ => None,
-            }
-        }
+/// Get the SCD code table of an ICD version
+fn version_code_table(version: IcdVersion) -> &'static CodeGroupTable<ScdCategory> {
+    match version {
+        IcdVersion::Icd8 => &ICD8_SCD_CODE_TABLE,
+        IcdVersion::Icd10 => &SCD_CODE_TABLE,
+    }
+}
 
-        // K codes cover gastrointestinal disorders
-        "K" => {
-            match &clean_code[..3] {
-                // Gastro-esophageal reflux disease
-                "K21" => Some((
-                    ScdCategory::GastrointestinalDisorder,
-                    false,
-                    SeverityLevel::Mild,
-                )),
-                // Crohn's disease and ulcerative colitis
-                "K50" | "K51" => Some((
-                    ScdCategory::GastrointestinalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Diseases of the liver (K70-K77)
-                "K70" | "K71" | "K72" | "K73" | "K74" | "K75" | "K76" | "K77" => {
-                    let severity = if clean_code.starts_with("K74") {
-                        // Fibrosis/cirrhosis of liver
-                        SeverityLevel::Severe
-                    } else {
-                        SeverityLevel::Moderate
-                    };
-                    Some((ScdCategory::GastrointestinalDisorder, false, severity))
-                }
-                // Intestinal malabsorption
-                "K90" => Some((
-                    ScdCategory::GastrointestinalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                // Other gastrointestinal disorders
-                "K86" | "K87" => Some((
-                    ScdCategory::GastrointestinalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                _ => None,
-            }
-        }
+/// Build the SCD code table from the reference categorization
+fn scd_code_table() -> CodeGroupTable<ScdCategory> {
+    use ScdCategory::{
+        BloodDisorder, CardiovascularDisorder, CongenitalDisorder, EndocrineDisorder,
+        GastrointestinalDisorder, ImmuneDisorder, MusculoskeletalDisorder, NeurologicalDisorder,
+        RenalDisorder, RespiratoryDisorder,
+    };
 
-        // M codes cover musculoskeletal disorders
-        "M" => {
-            match &clean_code[..3] {
-                // Systematic involvement of connective tissue (M30-M35)
-                "M30" | "M31" | "M32" | "M33" | "M34" | "M35" => {
-                    let severity = if clean_code.starts_with("M32") || // Systemic lupus erythematosus
-                                     clean_code.starts_with("M34")
-                    {
-                        // Systemic sclerosis
-                        SeverityLevel::Severe
-                    } else {
-                        SeverityLevel::Moderate
-                    };
-                    Some((ScdCategory::MusculoskeletalDisorder, false, severity))
-                }
-                // Other musculoskeletal disorders
-                "M05" | "M06" | "M07" | "M08" | "M09" | "M40" | "M41" | "M42" | "M43" | "M45"
-                | "M46" => Some((
-                    ScdCategory::MusculoskeletalDisorder,
-                    false,
-                    SeverityLevel::Moderate,
-                )),
-                _ => None,
-            }
-        }
+    let mild = f64::from(SeverityLevel::Mild.as_i32());
+    let moderate = f64::from(SeverityLevel::Moderate.as_i32());
+    let severe = f64::from(SeverityLevel::Severe.as_i32());
 
-        // N codes cover renal disorders
-        "N" => {
-            match &clean_code[..3] {
-                // Nephritic syndrome (N03-N05)
-                "N03" | "N04" | "N05" => {
-                    Some((ScdCategory::RenalDisorder, false, SeverityLevel::Moderate))
-                }
-                // Hereditary nephropathy
-                "N07" => Some((ScdCategory::RenalDisorder, false, SeverityLevel::Moderate)),
-                // Obstructive and reflux uropathy
-                "N13" => Some((ScdCategory::RenalDisorder, false, SeverityLevel::Moderate)),
-                // Chronic kidney disease (N18-N19, N25-N27)
-                "N18" | "N19" | "N25" | "N26" | "N27" => {
-                    let severity = if clean_code.starts_with("N18") || clean_code.starts_with("N19")
-                    {
-                        // Kidney failure
-                        // Check for specific stages of CKD
-                        if clean_code.len() > 3
-                            && clean_code.chars().nth(3).unwrap().is_ascii_digit()
-                        {
-                            let stage = clean_code.chars().nth(3).unwrap().to_digit(10).unwrap();
-                            if stage >= 4 {
-                                // CKD stage 4-5
-                                SeverityLevel::Severe
-                            } else {
-                                SeverityLevel::Moderate
-                            }
-                        } else {
-                            SeverityLevel::Moderate
-                        }
-                    } else {
-                        SeverityLevel::Moderate
-                    };
-                    Some((ScdCategory::RenalDisorder, false, severity))
-                }
-                // Other renal disorders
-                "N01" | "N02" | "N06" | "N08" | "N11" | "N12" | "N14" | "N15" | "N16" | "N20"
-                | "N21" | "N22" | "N23" | "N24" | "N28" | "N29" => {
-                    Some((ScdCategory::RenalDisorder, false, SeverityLevel::Moderate))
-                }
-                _ => None,
-            }
-        }
+    CodeGroupTable::new()
+        // Cancer (C00-C99)
+        .with_group(&["C"], BloodDisorder, severe)
+        // Immune system disorders (D80-D89)
+        .with_group(
+            &["D80", "D81", "D82", "D83", "D84", "D86", "D89"],
+            ImmuneDisorder,
+            moderate,
+        )
+        // Blood disorders (D55-D77), including aplastic anemias and
+        // hemophagocytic syndrome
+        .with_group(
+            &[
+                "D55", "D56", "D58", "D59", "D60", "D61", "D64", "D65", "D66", "D67", "D68", "D69",
+                "D70", "D71", "D72", "D73", "D76", "D610", "D613", "D618", "D619", "D762",
+            ],
+            BloodDisorder,
+            moderate,
+        )
+        // Sickle cell disorders
+        .with_group(&["D57"], BloodDisorder, severe)
+        // Diabetes, adrenogenital and other endocrine and metabolic disorders
+        .with_group(
+            &[
+                "E10", "E22", "E23", "E24", "E25", "E26", "E27", "E31", "E34", "E70", "E71", "E72",
+                "E730", "E74", "E75", "E76", "E77", "E78", "E79", "E80", "E83", "E85", "E88",
+            ],
+            EndocrineDisorder,
+            moderate,
+        )
+        // Cystic fibrosis
+        .with_group(&["E84"], EndocrineDisorder, severe)
+        // Autism spectrum disorders and neurological disorders
+        .with_group(
+            &[
+                "F84", "G11", "G13", "G23", "G24", "G25", "G31", "G37", "G40", "G41", "G60", "G70",
+                "G72", "G80", "G81", "G82", "G91", "G310", "G318", "G319", "G702", "G811", "G821",
+                "G824", "G941",
+            ],
+            NeurologicalDisorder,
+            moderate,
+        )
+        // Spinal muscular atrophy, muscular dystrophy and myopathies
+        .with_group(
+            &["G12", "G71", "G710", "G711", "G712", "G713", "G736"],
+            NeurologicalDisorder,
+            severe,
+        )
+        // Cardiovascular disorders
+        .with_group(
+            &[
+                "I12", "I27", "I30", "I31", "I32", "I33", "I34", "I35", "I36", "I37", "I38", "I39",
+                "I40", "I41", "I42", "I43", "I44", "I45", "I46", "I47", "I48", "I49", "I51", "I52",
+                "I81", "I82", "I83",
+            ],
+            CardiovascularDisorder,
+            moderate,
+        )
+        // Heart failure
+        .with_group(&["I50"], CardiovascularDisorder, severe)
+        // Respiratory disorders
+        .with_group(
+            &[
+                "J41", "J42", "J43", "J47", "J60", "J61", "J62", "J63", "J64", "J65", "J66", "J67",
+                "J68", "J69", "J70", "J84",
+            ],
+            RespiratoryDisorder,
+            moderate,
+        )
+        // COPD and respiratory failure
+        .with_group(&["J44", "J448", "J96"], RespiratoryDisorder, severe)
+        // Asthma
+        .with_group(&["J45"], RespiratoryDisorder, mild)
+        // Gastro-esophageal reflux disease
+        .with_group(&["K21"], GastrointestinalDisorder, mild)
+        // Inflammatory bowel, liver, pancreatic and malabsorption disorders
+        .with_group(
+            &[
+                "K50", "K51", "K70", "K71", "K72", "K73", "K75", "K76", "K77", "K86", "K87", "K90",
+            ],
+            GastrointestinalDisorder,
+            moderate,
+        )
+        // Fibrosis and cirrhosis of liver
+        .with_group(&["K74"], GastrointestinalDisorder, severe)
+        // Connective tissue and other musculoskeletal disorders
+        .with_group(
+            &[
+                "M05", "M06", "M07", "M08", "M09", "M30", "M31", "M33", "M35", "M40", "M41", "M42",
+                "M43", "M45", "M46",
+            ],
+            MusculoskeletalDisorder,
+            moderate,
+        )
+        // Systemic lupus erythematosus and systemic sclerosis
+        .with_group(&["M32", "M34"], MusculoskeletalDisorder, severe)
+        // Renal disorders
+        .with_group(
+            &[
+                "N01", "N02", "N03", "N04", "N05", "N06", "N07", "N08", "N11", "N12", "N13", "N14",
+                "N15", "N16", "N18", "N19", "N20", "N21", "N22", "N23", "N24", "N25", "N26", "N27",
+                "N28", "N29",
+            ],
+            RenalDisorder,
+            moderate,
+        )
+        // Chronic kidney disease stage 4-5
+        .with_group(
+            &[
+                "N184", "N185", "N186", "N187", "N188", "N189", "N194", "N195", "N196", "N197",
+                "N198", "N199",
+            ],
+            RenalDisorder,
+            severe,
+        )
+        // Perinatal respiratory disease
+        .with_group(&["P27"], RespiratoryDisorder, moderate)
+        // Kernicterus, cerebral status and muscle tone disorders of newborns
+        .with_group(&["P57", "P91", "P94"], NeurologicalDisorder, moderate)
+        // Congenital malformations of the nervous system (Q01-Q07)
+        .with_group(
+            &["Q01", "Q02", "Q03", "Q04", "Q05", "Q06", "Q07"],
+            NeurologicalDisorder,
+            severe,
+        )
+        // Congenital malformations of the circulatory system (Q20-Q28)
+        .with_group(
+            &[
+                "Q20", "Q21", "Q22", "Q23", "Q24", "Q25", "Q26", "Q27", "Q28",
+            ],
+            CardiovascularDisorder,
+            severe,
+        )
+        // Congenital malformations of the respiratory system, cleft lip and palate
+        .with_group(
+            &[
+                "Q30", "Q31", "Q32", "Q33", "Q34", "Q35", "Q36", "Q37", "Q790",
+            ],
+            RespiratoryDisorder,
+            moderate,
+        )
+        // Congenital malformations of the alimentary tract
+        .with_group(
+            &[
+                "Q38", "Q39", "Q40", "Q41", "Q42", "Q43", "Q44", "Q45", "Q792", "Q793",
+            ],
+            GastrointestinalDisorder,
+            moderate,
+        )
+        // Congenital malformations of the urinary system (Q60-Q64)
+        .with_group(
+            &["Q60", "Q61", "Q62", "Q63", "Q64"],
+            RenalDisorder,
+            moderate,
+        )
+        // Musculoskeletal congenital malformations
+        .with_group(&["Q77", "Q78", "Q79"], MusculoskeletalDisorder, moderate)
+        // Congenital malformation syndromes and other congenital malformations
+        .with_group(
+            &[
+                "Q80", "Q81", "Q82", "Q83", "Q84", "Q85", "Q86", "Q87", "Q88", "Q89", "Q860",
+            ],
+            CongenitalDisorder,
+            moderate,
+        )
+        // Chromosomal abnormalities (Q90-Q99)
+        .with_group(
+            &[
+                "Q90", "Q91", "Q92", "Q93", "Q94", "Q95", "Q96", "Q97", "Q98", "Q99",
+            ],
+            CongenitalDisorder,
+            severe,
+        )
+}
 
-        // P codes cover perinatal conditions
-        "P" => {
-            match &clean_code[..3] {
-                // Chronic respiratory disease originating in the perinatal period
-                "P27" => Some((
-                    ScdCategory::RespiratoryDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                // Kernicterus
-                "P57" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                // Disturbances of cerebral status of newborn
-                "P91" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                // Disorders of muscle tone of newborns
-                "P94" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                _ => None,
-            }
-        }
+/// Whether an SCD code denotes a congenital or perinatal condition
+///
+/// These are the P and Q chapters of ICD-10 and rubrics 740-779 of ICD-8.
+fn is_congenital_code(code: &str) -> bool {
+    let code = code.trim().to_uppercase();
+    code.starts_with('P')
+        || code.starts_with('Q')
+        || code
+            .get(..3)
+            .and_then(|rubric| rubric.parse::<u16>().ok())
+            .is_some_and(|rubric| (740..=779).contains(&rubric))
+}
 
-        // Q codes cover congenital malformations
-        "Q" => {
-            match &clean_code[..3] {
-                // Congenital malformations of the nervous system (Q01-Q07)
-                "Q01" | "Q02" | "Q03" | "Q04" | "Q05" | "Q06" | "Q07" => Some((
-                    ScdCategory::NeurologicalDisorder,
-                    true,
-                    SeverityLevel::Severe,
-                )),
-                // Congenital malformations of the circulatory system (Q20-Q26)
-                "Q20" | "Q21" | "Q22" | "Q23" | "Q24" | "Q25" | "Q26" | "Q27" | "Q28" => Some((
-                    ScdCategory::CardiovascularDisorder,
-                    true,
-                    SeverityLevel::Severe,
-                )),
-                // Congenital malformations of respiratory system (Q30-Q33)
-                "Q30" | "Q31" | "Q32" | "Q33" => Some((
-                    ScdCategory::RespiratoryDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                // Other congenital malformations of respiratory system, cleft lip and cleft palate (Q34-Q37)
-                "Q34" | "Q35" | "Q36" | "Q37" => Some((
-                    ScdCategory::RespiratoryDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                // Congenital malformations of upper alimentary tract and intestines (Q38-Q45)
-                "Q38" | "Q39" | "Q40" | "Q41" | "Q42" | "Q43" | "Q44" | "Q45" => Some((
-                    ScdCategory::GastrointestinalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                // Congenital malformations of the urinary system (Q60-Q64)
-                "Q60" | "Q61" | "Q62" | "Q63" | "Q64" => {
-                    Some((ScdCategory::RenalDisorder, true, SeverityLevel::Moderate))
-                }
-                // Musculoskeletal congenital
-                "Q77" | "Q78" | "Q79" => Some((
-                    ScdCategory::MusculoskeletalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                // Congenital malformation syndromes
-                "Q87" => Some((
-                    ScdCategory::CongenitalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                // Chromosomal abnormalities (Q90-Q99)
-                "Q90" | "Q91" | "Q92" | "Q93" | "Q94" | "Q95" | "Q96" | "Q97" | "Q98" | "Q99" => {
-                    Some((ScdCategory::CongenitalDisorder, true, SeverityLevel::Severe))
-                }
-                // Other congenital
-                "Q80" | "Q81" | "Q82" | "Q83" | "Q84" | "Q85" | "Q86" | "Q88" | "Q89" => Some((
-                    ScdCategory::CongenitalDisorder,
-                    true,
-                    SeverityLevel::Moderate,
-                )),
-                _ => None,
-            }
-        }
+/// Create the SCD classifier for a configuration
+///
+/// The classifier uses the table of the configured `icd_version`.
+/// Congenital and perinatal codes are removed from the table when the
+/// configuration excludes them.
+#[must_use]
+pub fn scd_classifier(config: &ScdConfig) -> DiseaseClassifier<ScdCategory> {
+    version_classifier(config, config.icd_version)
+}
+
+/// Create the SCD classifier of an ICD version for a configuration
+fn version_classifier(config: &ScdConfig, version: IcdVersion) -> DiseaseClassifier<ScdCategory> {
+    let mut table = version_code_table(version).clone();
+    if !config.include_congenital {
+        table.retain(|prefix, _| !is_congenital_code(prefix));
+    }
+
+    DiseaseClassifier::new(
+        table,
+        ClassifierConfig {
+            start_date: config.start_date,
+            end_date: config.end_date,
+            min_age_years: config.min_age_years,
+            max_age_years: config.max_age_years,
+        },
+    )
+}
+
+/// Categorize a diagnosis based on ICD-10 code
+/// Returns (category, `is_congenital`, severity) if it's an SCD diagnosis, None otherwise.
+/// Codes without a valid ICD-10 structure (see [`is_valid_icd10`]) are never SCD.
+///
+/// [`is_valid_icd10`]: crate::algorithm::health::classifier::is_valid_icd10
+#[must_use]
+pub fn categorize_diagnosis(diagnosis_code: &str) -> Option<(ScdCategory, bool, SeverityLevel)> {
+    categorize_diagnosis_version(diagnosis_code, IcdVersion::Icd10)
+}
 
-        // Not an SCD diagnosis
-        _ => None,
+/// Categorize a diagnosis code of a given ICD version
+///
+/// See [`categorize_diagnosis`]; codes without the structure of `version`
+/// are never SCD.
+#[must_use]
+pub fn categorize_diagnosis_version(
+    diagnosis_code: &str,
+    version: IcdVersion,
+) -> Option<(ScdCategory, bool, SeverityLevel)> {
+    // Reject malformed codes up front rather than relying on the table
+    if !version.is_valid_code(diagnosis_code) {
+        return None;
     }
+
+    version_code_table(version)
+        .lookup(diagnosis_code)
+        .map(|code_group| {
+            (
+                code_group.group,
+                is_congenital_code(diagnosis_code),
+                SeverityLevel::from_i32(code_group.weight as i32),
+            )
+        })
 }
 
 /// Get all individuals with SCD from the results
@@ -709,18 +634,613 @@ pub fn get_individuals_by_severity(
         .collect()
 }
 
+/// SCD-positive individuals together with the number screened
+///
+/// Returned by the `summarize_scd*` functions, so that prevalences can be
+/// computed against the individuals the algorithm actually evaluated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScdSummary {
+    /// The number of individuals with SCD results
+    pub total_screened: usize,
+    /// The PNRs of the selected SCD-positive individuals, sorted
+    pub pnrs: Vec<String>,
+    /// The number of selected individuals in each SCD category
+    pub category_counts: HashMap<ScdCategory, usize>,
+}
+
+impl ScdSummary {
+    /// Get the fraction of screened individuals that were selected
+    #[must_use]
+    pub fn prevalence(&self) -> f64 {
+        if self.total_screened == 0 {
+            0.0
+        } else {
+            self.pnrs.len() as f64 / self.total_screened as f64
+        }
+    }
+}
+
+/// Summarize the SCD results matching a predicate
+fn summarize_scd_results(
+    scd_results: &HashMap<String, ScdResult>,
+    predicate: impl Fn(&ScdResult) -> bool,
+) -> Result<ScdSummary> {
+    if scd_results.is_empty() {
+        return Err(ParquetReaderError::InvalidOperation(
+            "No SCD results to summarize; apply the SCD algorithm first".to_string(),
+        )
+        .into());
+    }
+
+    let mut summary = ScdSummary {
+        total_screened: scd_results.len(),
+        ..ScdSummary::default()
+    };
+    for (pnr, result) in scd_results {
+        if !(result.has_scd && predicate(result)) {
+            continue;
+        }
+        summary.pnrs.push(pnr.clone());
+        for &category in &result.scd_categories {
+            *summary
+                .category_counts
+                .entry(ScdCategory::from_u8(category))
+                .or_default() += 1;
+        }
+    }
+    summary.pnrs.sort_unstable();
+
+    Ok(summary)
+}
+
+/// Summarize all individuals with SCD
+///
+/// Unlike [`get_individuals_with_scd`], this reports the number of
+/// individuals screened and the count in each category.
+///
+/// # Errors
+///
+/// Returns an error if `scd_results` is empty, which usually means the
+/// algorithm was not run
+pub fn summarize_scd(scd_results: &HashMap<String, ScdResult>) -> Result<ScdSummary> {
+    summarize_scd_results(scd_results, |_| true)
+}
+
+/// Summarize the individuals with a specific SCD category
+///
+/// See [`get_individuals_by_category`]; the category counts include the
+/// other categories these individuals have.
+///
+/// # Errors
+///
+/// Returns an error if `scd_results` is empty
+pub fn summarize_scd_by_category(
+    scd_results: &HashMap<String, ScdResult>,
+    category: ScdCategory,
+) -> Result<ScdSummary> {
+    summarize_scd_results(scd_results, |result| {
+        result.scd_categories.contains(&(category as u8))
+    })
+}
+
+/// Summarize the individuals with SCD of at least a severity level
+///
+/// See [`get_individuals_by_severity`].
+///
+/// # Errors
+///
+/// Returns an error if `scd_results` is empty
+pub fn summarize_scd_by_severity(
+    scd_results: &HashMap<String, ScdResult>,
+    min_severity: SeverityLevel,
+) -> Result<ScdSummary> {
+    summarize_scd_results(scd_results, |result| {
+        result.max_severity >= min_severity as i32
+    })
+}
+
+/// SCD individuals by the origin of their qualifying diagnoses
+///
+/// Supports the usual stratification of severe chronic disease into
+/// congenital and acquired conditions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScdOriginSummary {
+    /// The number of individuals with SCD
+    pub total_scd: usize,
+    /// Individuals whose SCD diagnoses are all congenital or perinatal
+    pub congenital_only: usize,
+    /// Individuals whose SCD diagnoses are all acquired
+    pub acquired_only: usize,
+    /// Individuals with both congenital and acquired SCD diagnoses
+    pub both: usize,
+}
+
+/// Count the SCD individuals with congenital, acquired, or mixed SCD
+///
+/// Each individual is classified by their qualifying diagnoses, using the
+/// same P and Q chapter rule as the congenital flag of [`ScdResult`].
+/// Individuals without SCD are not counted.
+#[must_use]
+pub fn scd_summary(scd_results: &HashMap<String, ScdResult>) -> ScdOriginSummary {
+    let mut summary = ScdOriginSummary::default();
+    for result in scd_results.values().filter(|result| result.has_scd) {
+        let (congenital, acquired): (Vec<_>, Vec<_>) = result
+            .scd_diagnoses
+            .iter()
+            .partition(|diagnosis| is_congenital_code(&diagnosis.diagnosis_code));
+
+        summary.total_scd += 1;
+        match (congenital.is_empty(), acquired.is_empty()) {
+            (false, true) => summary.congenital_only += 1,
+            (true, false) => summary.acquired_only += 1,
+            (false, false) => summary.both += 1,
+            // Flagged without recorded diagnoses; the origin is unknown
+            (true, true) => {}
+        }
+    }
+    summary
+}
+
 /// Extension trait for `DiagnosisCollection`
 pub trait DiagnosisCollectionExt {
-    /// Get all individuals who have any diagnoses
+    /// Get all individuals who have any diagnoses or an SCD result
+    ///
+    /// See [`DiagnosisCollection::all_individuals`]; each individual is
+    /// listed once.
     fn individuals_with_diagnoses(&self) -> Vec<String>;
 }
 
 impl DiagnosisCollectionExt for DiagnosisCollection {
     fn individuals_with_diagnoses(&self) -> Vec<String> {
-        // We'll implement this when we update the DiagnosisCollection
-        // For now, return a combination of individuals with and without SCD
-        let mut individuals = self.individuals_with_scd();
-        individuals.extend(self.individuals_without_scd());
-        individuals
+        self.all_individuals().into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::health::classifier::is_valid_icd10;
+
+    #[test]
+    fn test_categorize_diagnosis_table() {
+        assert_eq!(
+            categorize_diagnosis("c50"),
+            Some((ScdCategory::BloodDisorder, false, SeverityLevel::Severe))
+        );
+        // Four-character entries override their three-character group
+        assert_eq!(
+            categorize_diagnosis("Q790"),
+            Some((
+                ScdCategory::RespiratoryDisorder,
+                true,
+                SeverityLevel::Moderate
+            ))
+        );
+        assert_eq!(
+            categorize_diagnosis("Q791"),
+            Some((
+                ScdCategory::MusculoskeletalDisorder,
+                true,
+                SeverityLevel::Moderate
+            ))
+        );
+        assert_eq!(
+            categorize_diagnosis("N185"),
+            Some((ScdCategory::RenalDisorder, false, SeverityLevel::Severe))
+        );
+        assert_eq!(
+            categorize_diagnosis("N183"),
+            Some((ScdCategory::RenalDisorder, false, SeverityLevel::Moderate))
+        );
+        assert_eq!(
+            categorize_diagnosis("J459"),
+            Some((ScdCategory::RespiratoryDisorder, false, SeverityLevel::Mild))
+        );
+        assert_eq!(categorize_diagnosis("F32"), None);
+        assert_eq!(categorize_diagnosis(""), None);
+    }
+
+    #[test]
+    fn test_categorize_malformed_codes() {
+        for code in [
+            "", " ", "C", "c5", "123", "ZZ", "ZZZ", "1C50", "C5X", "E10.9", "Q9090AXY", "ÆØÅ",
+            "Cé50", "E1é0", "\u{0}",
+        ] {
+            assert!(!is_valid_icd10(code), "{code:?} should be invalid");
+            assert_eq!(categorize_diagnosis(code), None, "{code:?}");
+        }
+        assert!(is_valid_icd10(" q909a "));
+    }
+
+    #[test]
+    fn test_categorize_short_codes() {
+        // Length 1 and 2 codes are shorter than every three-character group
+        for code in ["D", "E", "G", "Q", "D5", "E1", "N1", "Q9"] {
+            assert_eq!(categorize_diagnosis(code), None, "{code:?}");
+            assert!(SCD_CODE_TABLE.lookup(code).is_none(), "{code:?}");
+        }
+        // Length 3 codes match their group but not the four-character entries
+        assert_eq!(
+            categorize_diagnosis("D61"),
+            Some((ScdCategory::BloodDisorder, false, SeverityLevel::Moderate))
+        );
+        assert_eq!(
+            categorize_diagnosis("N18"),
+            Some((ScdCategory::RenalDisorder, false, SeverityLevel::Moderate))
+        );
+        assert_eq!(categorize_diagnosis("G73"), None);
+    }
+
+    #[test]
+    fn test_categorize_random_inputs_never_panic() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let alphabet: Vec<char> = "CDEGIJKMNPQZ0123456789 .-éÆ\u{0}".chars().collect();
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..10_000 {
+            let len = rng.random_range(0..8);
+            let code: String = (0..len)
+                .map(|_| alphabet[rng.random_range(0..alphabet.len())])
+                .collect();
+            let result = categorize_diagnosis(&code);
+            if !is_valid_icd10(&code) {
+                assert_eq!(result, None, "{code:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_scd_classifier_excludes_congenital() {
+        let config = ScdConfig {
+            include_congenital: false,
+            ..ScdConfig::default()
+        };
+        let table = scd_classifier(&config).table().clone();
+        assert!(table.lookup("Q900").is_none());
+        assert!(table.lookup("P270").is_none());
+        assert!(table.lookup("E840").is_some());
+    }
+
+    #[test]
+    fn test_summarize_scd_reports_denominator() {
+        assert!(summarize_scd(&HashMap::new()).is_err());
+
+        let mut positive = ScdResult::new("0101011234".to_string());
+        positive.has_scd = true;
+        positive.scd_categories = vec![
+            ScdCategory::BloodDisorder as u8,
+            ScdCategory::RenalDisorder as u8,
+        ];
+        positive.max_severity = SeverityLevel::Severe as i32;
+        let scd_results: HashMap<String, ScdResult> = [
+            (positive.pnr.clone(), positive),
+            (
+                "0202022345".to_string(),
+                ScdResult::new("0202022345".to_string()),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let summary = summarize_scd(&scd_results).unwrap();
+        assert_eq!(summary.total_screened, 2);
+        assert_eq!(summary.pnrs, vec!["0101011234"]);
+        assert_eq!(summary.category_counts[&ScdCategory::RenalDisorder], 1);
+        assert!((summary.prevalence() - 0.5).abs() < f64::EPSILON);
+
+        let summary = summarize_scd_by_category(&scd_results, ScdCategory::BloodDisorder).unwrap();
+        assert_eq!(summary.pnrs.len(), 1);
+        let summary = summarize_scd_by_category(&scd_results, ScdCategory::ImmuneDisorder).unwrap();
+        assert!(summary.pnrs.is_empty());
+        assert_eq!(summary.total_screened, 2);
+    }
+
+    #[test]
+    fn test_scd_summary_by_origin() {
+        use crate::models::core::types::DiagnosisType;
+
+        let with_codes = |pnr: &str, codes: &[&str]| {
+            let mut result = ScdResult::new(pnr.to_string());
+            for code in codes {
+                let (category, is_congenital, _) = categorize_diagnosis(code).unwrap();
+                let diagnosis = Diagnosis::new(
+                    pnr.to_string(),
+                    (*code).to_string(),
+                    DiagnosisType::Primary,
+                    None,
+                );
+                result.add_scd_diagnosis(Arc::new(diagnosis), category as u8, is_congenital);
+            }
+            (pnr.to_string(), result)
+        };
+        let scd_results: HashMap<String, ScdResult> = [
+            with_codes("1", &["Q20"]),
+            with_codes("2", &["N185", "C50"]),
+            with_codes("3", &["Q20", "D80"]),
+            with_codes("4", &[]),
+        ]
+        .into_iter()
+        .collect();
+
+        let summary = scd_summary(&scd_results);
+        assert_eq!(
+            summary,
+            ScdOriginSummary {
+                total_scd: 3,
+                congenital_only: 1,
+                acquired_only: 1,
+                both: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_icd_version_selects_table() {
+        use crate::models::collections::ModelCollection;
+        use crate::models::core::types::DiagnosisType;
+
+        assert_eq!(
+            categorize_diagnosis_version("24900", IcdVersion::Icd8),
+            Some((
+                ScdCategory::EndocrineDisorder,
+                false,
+                SeverityLevel::Moderate
+            ))
+        );
+        assert_eq!(
+            categorize_diagnosis_version("74699", IcdVersion::Icd8),
+            Some((
+                ScdCategory::CardiovascularDisorder,
+                true,
+                SeverityLevel::Severe
+            ))
+        );
+        assert_eq!(categorize_diagnosis_version("E10", IcdVersion::Icd8), None);
+        assert_eq!(categorize_diagnosis("24900"), None);
+
+        let date = |y| NaiveDate::from_ymd_opt(y, 6, 1);
+        let mut collection = DiagnosisCollection::new();
+        for (code, year) in [
+            ("24900", 1990),
+            ("E10", 1992),
+            ("G40", 2000),
+            ("34509", 2001),
+        ] {
+            collection.add(Diagnosis::new(
+                "1".to_string(),
+                code.to_string(),
+                DiagnosisType::Primary,
+                date(year),
+            ));
+        }
+
+        // Only the ICD-10 codes are classified by default
+        let results =
+            apply_scd_algorithm(&collection, &ScdConfig::default(), &HashMap::new()).unwrap();
+        assert_eq!(results["1"].scd_diagnoses.len(), 2);
+
+        // Inferring the version keeps the codes matching their date
+        let config = ScdConfig {
+            infer_icd_version: true,
+            ..ScdConfig::default()
+        };
+        let results = apply_scd_algorithm(&collection, &config, &HashMap::new()).unwrap();
+        let codes: Vec<&str> = results["1"]
+            .scd_diagnoses
+            .iter()
+            .map(|diagnosis| diagnosis.diagnosis_code.as_str())
+            .collect();
+        assert_eq!(codes, vec!["24900", "G40"]);
+    }
+
+    #[test]
+    fn test_all_individuals_counts_each_once() {
+        use crate::models::collections::ModelCollection;
+        use crate::models::core::types::DiagnosisType;
+
+        let mut collection = DiagnosisCollection::new();
+        for (pnr, code) in [("1", "C50"), ("1", "J45"), ("2", "R51")] {
+            collection.add(Diagnosis::new(
+                pnr.to_string(),
+                code.to_string(),
+                DiagnosisType::Primary,
+                None,
+            ));
+        }
+        let results =
+            apply_scd_algorithm(&collection, &ScdConfig::default(), &HashMap::new()).unwrap();
+        for result in results.into_values() {
+            collection.add_scd_result(result);
+        }
+        collection.add_scd_result(ScdResult::new("3".to_string()));
+
+        let all = collection.all_individuals();
+        assert_eq!(all.len(), 3);
+        assert_eq!(
+            collection.individuals_with_scd().len() + collection.individuals_without_scd().len(),
+            all.len()
+        );
+
+        let mut listed = collection.individuals_with_diagnoses();
+        listed.sort_unstable();
+        assert_eq!(listed, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_category_inclusion_and_exclusion() {
+        let config = ScdConfig::default();
+        assert!(config.records_category(ScdCategory::CongenitalDisorder));
+
+        let config = ScdConfig {
+            included_categories: Some(
+                [
+                    ScdCategory::NeurologicalDisorder,
+                    ScdCategory::CongenitalDisorder,
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            excluded_categories: [ScdCategory::CongenitalDisorder].into_iter().collect(),
+            ..ScdConfig::default()
+        };
+        assert!(config.records_category(ScdCategory::NeurologicalDisorder));
+        // Exclusion is subtracted from the included categories
+        assert!(!config.records_category(ScdCategory::CongenitalDisorder));
+        assert!(!config.records_category(ScdCategory::BloodDisorder));
+
+        // Exclusion alone removes a category from all others
+        let config = ScdConfig {
+            excluded_categories: [ScdCategory::BloodDisorder].into_iter().collect(),
+            ..ScdConfig::default()
+        };
+        assert!(!config.records_category(ScdCategory::BloodDisorder));
+        assert!(config.records_category(ScdCategory::RenalDisorder));
+    }
+
+    #[test]
+    fn test_apply_scd_algorithm_filters_categories() {
+        use crate::models::collections::ModelCollection;
+        use crate::models::core::types::DiagnosisType;
+        use crate::models::health::diagnosis::Diagnosis;
+
+        let mut diagnoses = DiagnosisCollection::new();
+        for (pnr, code) in [("1", "C50"), ("1", "N185"), ("2", "C50")] {
+            diagnoses.add(Diagnosis::new(
+                pnr.to_string(),
+                code.to_string(),
+                DiagnosisType::Primary,
+                None,
+            ));
+        }
+        let config = ScdConfig {
+            excluded_categories: [ScdCategory::BloodDisorder].into_iter().collect(),
+            ..ScdConfig::default()
+        };
+
+        let results = apply_scd_algorithm(&diagnoses, &config, &HashMap::new()).unwrap();
+        assert_eq!(
+            results["1"].scd_categories,
+            vec![ScdCategory::RenalDisorder as u8]
+        );
+        assert!(!results["2"].has_scd);
+    }
+
+    #[test]
+    fn test_min_qualifying_diagnoses() {
+        use crate::models::collections::ModelCollection;
+        use crate::models::core::types::DiagnosisType;
+
+        let date = |day| NaiveDate::from_ymd_opt(2010, 1, day);
+        let mut diagnoses = DiagnosisCollection::new();
+        for (pnr, code, day) in [
+            ("1", "N185", Some(1)),
+            ("1", "N185", Some(1)),
+            ("2", "N185", Some(1)),
+            ("2", "C50", Some(5)),
+            ("3", "N185", None),
+        ] {
+            diagnoses.add(Diagnosis::new(
+                pnr.to_string(),
+                code.to_string(),
+                DiagnosisType::Primary,
+                day.and_then(date),
+            ));
+        }
+
+        let config = ScdConfig {
+            min_qualifying_diagnoses: 2,
+            ..ScdConfig::default()
+        };
+        let results = apply_scd_algorithm(&diagnoses, &config, &HashMap::new()).unwrap();
+        assert!(results["1"].has_scd);
+        assert!(results["2"].has_scd);
+        assert!(!results["3"].has_scd);
+        assert_eq!(
+            results["1"].qualifying_count(ScdCategory::RenalDisorder as u8),
+            2
+        );
+
+        // Two contacts on the same day count once
+        let config = ScdConfig {
+            require_distinct_dates: true,
+            ..config
+        };
+        let results = apply_scd_algorithm(&diagnoses, &config, &HashMap::new()).unwrap();
+        assert!(!results["1"].has_scd);
+        assert_eq!(
+            results["1"].qualifying_count(ScdCategory::RenalDisorder as u8),
+            1
+        );
+        assert!(results["2"].has_scd);
+        assert_eq!(results["2"].scd_diagnoses.len(), 2);
+    }
+
+    #[test]
+    fn test_lookback_from_index_date() {
+        use crate::models::collections::ModelCollection;
+        use crate::models::core::types::DiagnosisType;
+
+        let date = |year| NaiveDate::from_ymd_opt(year, 6, 1).unwrap();
+        let mut diagnoses = DiagnosisCollection::new();
+        for (pnr, year) in [("1", 2008), ("2", 2002), ("3", 2008)] {
+            diagnoses.add(Diagnosis::new(
+                pnr.to_string(),
+                "N185".to_string(),
+                DiagnosisType::Primary,
+                Some(date(year)),
+            ));
+        }
+
+        let config = ScdConfig {
+            index_dates: Some(
+                [("1".to_string(), date(2010)), ("2".to_string(), date(2010))]
+                    .into_iter()
+                    .collect(),
+            ),
+            lookback: Some(Duration::days(5 * 365)),
+            ..ScdConfig::default()
+        };
+        let results = apply_scd_algorithm(&diagnoses, &config, &HashMap::new()).unwrap();
+        assert!(results["1"].has_scd);
+        // Diagnosed before the lookback window
+        assert!(!results["2"].has_scd);
+        // No index date
+        assert!(!results.contains_key("3"));
+    }
+
+    /// The parallel SCD algorithm gives the same results as a single thread
+    #[test]
+    fn test_parallel_scd_matches_serial() {
+        use crate::models::collections::ModelCollection;
+        use crate::models::core::types::DiagnosisType;
+
+        const INDIVIDUALS: usize = 2_000;
+        let codes = ["N185", "C50", "D80", "J459", "E10", "Z00"];
+        let mut diagnoses = DiagnosisCollection::new();
+        for i in 0..INDIVIDUALS * 3 {
+            diagnoses.add(Diagnosis::new(
+                format!("{:010}", i % INDIVIDUALS),
+                codes[i % codes.len()].to_string(),
+                DiagnosisType::Primary,
+                NaiveDate::from_ymd_opt(2000 + (i % 20) as i32, 1, 1),
+            ));
+        }
+        let config = ScdConfig::default();
+        let birth_dates = HashMap::new();
+
+        let serial_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let serial = serial_pool
+            .install(|| apply_scd_algorithm(&diagnoses, &config, &birth_dates))
+            .unwrap();
+        let parallel = apply_scd_algorithm(&diagnoses, &config, &birth_dates).unwrap();
+
+        assert_eq!(serial.len(), parallel.len());
+        for (pnr, result) in &serial {
+            assert_eq!(format!("{result:?}"), format!("{:?}", parallel[pnr]));
+        }
     }
 }
@@ -4,6 +4,6 @@
 //! of research study workflows, including population generation,
 //! health data processing, case-control matching, and analysis.
 
-// pub mod health;
+pub mod health;
 // pub mod matching;
 // pub mod population;
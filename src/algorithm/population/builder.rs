@@ -129,16 +129,26 @@ impl Population {
             progress += 1;
         }
 
-        // Step 5: Add diagnosis data from LPR
+        // Step 5: Add diagnosis data from LPR, with the PNRs of its
+        // admissions (LPR2) or contacts (LPR3)
         if let Some(lpr_path) = registry_paths.get("lpr") {
-            log::info!(
-                "[Step {}/{}] Loading diagnosis data from LPR registry",
-                progress + 1,
-                total_steps
-            );
+            let lookup_path = registry_paths
+                .get("lpr_adm")
+                .or_else(|| registry_paths.get("lpr3_kontakter"));
+            if let Some(lookup_path) = lookup_path {
+                log::info!(
+                    "[Step {}/{}] Loading diagnosis data from LPR registry",
+                    progress + 1,
+                    total_steps
+                );
 
-            integration.add_diagnosis_data(lpr_path, pnr_filter.as_ref())?;
-            progress += 1;
+                integration.add_diagnosis_data(lpr_path, lookup_path, pnr_filter.as_ref())?;
+                progress += 1;
+            } else {
+                log::warn!(
+                    "Skipping LPR diagnoses: no lpr_adm or lpr3_kontakter path to look up their PNRs"
+                );
+            }
         }
 
         // Step 6: Add income data from IND
@@ -24,6 +24,7 @@ use crate::registry::{
 };
 
 use crate::registry::factory;
+use crate::utils::arrow::{DuplicatePolicy, build_lookup};
 
 /// Registry integration manager for combining data from multiple sources
 pub struct RegistryIntegration {
@@ -194,9 +195,14 @@ impl RegistryIntegration {
     }
 
     /// Add diagnosis data from LPR registry
+    ///
+    /// Diagnoses carry no PNR: the `DW_EK_KONTAKT` (LPR3) or `RECNUM` (LPR2)
+    /// of each is looked up in the `LPR3_KONTAKTER` or `LPR_ADM` registry at
+    /// `lookup_path`.
     pub fn add_diagnosis_data(
         &mut self,
         path: &Path,
+        lookup_path: &Path,
         pnr_filter: Option<&HashSet<String>>,
     ) -> Result<()> {
         // Determine if this is LPR2 or LPR3 based on path
@@ -207,10 +213,11 @@ impl RegistryIntegration {
             let registry = factory::registry_from_name("lpr3_diagnoser")?;
             let batches = registry.load(path, pnr_filter)?;
 
-            // Create and populate a PNR lookup for the adapter
-            // For LPR3, we'd need to map kontakt IDs to PNRs
-            // This is just a placeholder since actual implementation would depend on data structure
-            let pnr_lookup: HashMap<String, String> = HashMap::new();
+            // Map the contact IDs of the diagnoses to PNRs
+            let contacts =
+                factory::registry_from_name("lpr3_kontakter")?.load(lookup_path, pnr_filter)?;
+            let pnr_lookup =
+                build_lookup(&contacts, "DW_EK_KONTAKT", "CPR", DuplicatePolicy::Error)?;
 
             // Create the adapter with lookup
             use crate::registry::lpr::PnrLookupRegistry;
@@ -234,10 +241,10 @@ impl RegistryIntegration {
             let registry = factory::registry_from_name("lpr_diag")?;
             let batches = registry.load(path, pnr_filter)?;
 
-            // Create and populate a PNR lookup for the adapter
-            // For LPR2, we'd need to map record IDs to PNRs
-            // This is just a placeholder since actual implementation would depend on data structure
-            let pnr_lookup: HashMap<String, String> = HashMap::new();
+            // Map the record numbers of the diagnoses to PNRs
+            let admissions =
+                factory::registry_from_name("lpr_adm")?.load(lookup_path, pnr_filter)?;
+            let pnr_lookup = build_lookup(&admissions, "RECNUM", "PNR", DuplicatePolicy::Error)?;
 
             // Create the adapter with lookup
             let mut adapter = LprDiagRegister::new();
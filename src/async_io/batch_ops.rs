@@ -6,7 +6,8 @@ use std::path::Path;
 use arrow::datatypes::Schema;
 use arrow::record_batch::RecordBatch;
 use futures::TryStreamExt;
-use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+use parquet::arrow::async_reader::{ParquetRecordBatchStream, ParquetRecordBatchStreamBuilder};
+use tokio::fs::File;
 
 use super::file_ops::open_parquet_file_async;
 use crate::error::Result;
@@ -14,32 +15,20 @@ use crate::utils::{
     DEFAULT_BATCH_SIZE, get_batch_size, log_operation_complete, log_operation_start,
 };
 
-/// Read a Parquet file asynchronously into Arrow record batches
-///
-/// This function opens a Parquet file asynchronously and streams its
-/// contents without loading the entire file into memory at once.
+/// Open a Parquet file as an asynchronous stream of record batches
 ///
 /// # Arguments
 /// * `path` - Path to the Parquet file
 /// * `schema` - Optional Arrow Schema for projecting specific columns
 /// * `batch_size` - Optional batch size for reading (defaults to `DEFAULT_BATCH_SIZE`)
 ///
-/// # Returns
-/// A vector of `RecordBatch` objects
-///
 /// # Errors
-/// Returns an error if file reading fails
-///
-/// # Panics
-/// Panics if the projection mask is Some but is attempted to be unwrapped as None
-pub async fn read_parquet_async(
+/// Returns an error if the file cannot be opened or is not a valid Parquet file
+pub async fn open_batch_stream(
     path: &Path,
     schema: Option<&Schema>,
     batch_size: Option<usize>,
-) -> Result<Vec<RecordBatch>> {
-    let start = std::time::Instant::now();
-    log_operation_start("Reading parquet file asynchronously", path);
-
+) -> Result<ParquetRecordBatchStream<File>> {
     // Open file asynchronously
     let file = open_parquet_file_async(path).await?;
 
@@ -58,8 +47,8 @@ pub async fn read_parquet_async(
             builder.parquet_schema(),
         );
 
-        if has_projection {
-            builder = builder.with_projection(projection_mask.unwrap());
+        if let (true, Some(projection_mask)) = (has_projection, projection_mask) {
+            builder = builder.with_projection(projection_mask);
         }
     }
 
@@ -71,9 +60,35 @@ pub async fn read_parquet_async(
     builder = builder.with_batch_size(batch_size);
 
     // Build the stream
-    let stream = builder
+    builder
         .build()
-        .map_err(|e| anyhow::anyhow!("Failed to build parquet stream {}", e))?;
+        .map_err(|e| anyhow::anyhow!("Failed to build parquet stream {}", e))
+}
+
+/// Read a Parquet file asynchronously into Arrow record batches
+///
+/// This function opens a Parquet file asynchronously and streams its
+/// contents without loading the entire file into memory at once.
+///
+/// # Arguments
+/// * `path` - Path to the Parquet file
+/// * `schema` - Optional Arrow Schema for projecting specific columns
+/// * `batch_size` - Optional batch size for reading (defaults to `DEFAULT_BATCH_SIZE`)
+///
+/// # Returns
+/// A vector of `RecordBatch` objects
+///
+/// # Errors
+/// Returns an error if file reading fails
+pub async fn read_parquet_async(
+    path: &Path,
+    schema: Option<&Schema>,
+    batch_size: Option<usize>,
+) -> Result<Vec<RecordBatch>> {
+    let start = std::time::Instant::now();
+    log_operation_start("Reading parquet file asynchronously", path);
+
+    let stream = open_batch_stream(path, schema, batch_size).await?;
 
     // Collect results
     let batches = stream
@@ -7,6 +7,7 @@ pub mod file_ops;
 pub mod filter_ops;
 pub mod loader;
 pub mod parallel_ops;
+pub mod stream_ops;
 
 // Re-export all public items from submodules
 pub use batch_ops::*;
@@ -14,3 +15,4 @@ pub use file_ops::*;
 pub use filter_ops::*;
 pub use loader::*;
 pub use parallel_ops::*;
+pub use stream_ops::*;
@@ -0,0 +1,208 @@
+//! Async streaming over multiple Parquet files
+//! Provides lazily evaluated `Stream`s of record batches and deserialized models,
+//! so results can be consumed incrementally instead of buffered in memory
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use futures::{Stream, StreamExt, TryStreamExt, stream};
+
+use super::batch_ops::open_batch_stream;
+use crate::error::Result;
+use crate::models::core::Individual;
+use crate::registry::RegisterLoader;
+use crate::registry::direct_registry_loader::DirectRegistryLoader;
+
+/// Stream the record batches of several Parquet files in order
+///
+/// Files are opened one at a time as the stream is polled, and each file is
+/// read batch by batch, so at most one batch per file is held in memory.
+///
+/// # Arguments
+/// * `paths` - Paths to the Parquet files
+/// * `schema` - Optional Arrow Schema for projecting specific columns
+/// * `batch_size` - Optional batch size for reading (defaults to `DEFAULT_BATCH_SIZE`)
+///
+/// # Returns
+/// A stream of record batches; a file that cannot be opened yields a single
+/// error item in its place
+pub fn stream_rows(
+    paths: Vec<PathBuf>,
+    schema: Option<SchemaRef>,
+    batch_size: Option<usize>,
+) -> impl Stream<Item = Result<RecordBatch>> + Send + 'static {
+    stream::iter(paths)
+        .then(move |path| {
+            let schema = schema.clone();
+            async move {
+                let batches = open_batch_stream(&path, schema.as_deref(), batch_size).await?;
+                Ok::<_, anyhow::Error>(batches.map_err(move |e| {
+                    anyhow::anyhow!("Failed to read record batch from {}: {}", path.display(), e)
+                }))
+            }
+        })
+        .try_flatten()
+}
+
+/// Stream the individuals deserialized from several registry files
+///
+/// Batches are read with the loader's schema and deserialized as they
+/// arrive, so individuals from the first batch are available before the
+/// remaining files are read. If the loader records provenance, each
+/// individual carries its file and row.
+///
+/// # Arguments
+/// * `loader` - The registry loader used to deserialize each batch
+/// * `paths` - Paths to the registry's Parquet files
+///
+/// # Returns
+/// A stream of individuals, in file and row order
+pub fn stream_individuals(
+    loader: Arc<DirectRegistryLoader>,
+    paths: Vec<PathBuf>,
+) -> impl Stream<Item = Result<Individual>> + Send + 'static {
+    let schema = loader.get_schema();
+    stream::iter(paths)
+        .then(move |path| {
+            let loader = Arc::clone(&loader);
+            let schema = Arc::clone(&schema);
+            async move {
+                let batches = open_batch_stream(&path, Some(schema.as_ref()), None).await?;
+                let source_file: Arc<str> = Arc::from(path.display().to_string());
+                let mut first_row = 0;
+                Ok::<_, anyhow::Error>(
+                    batches
+                        .map_err(move |e| {
+                            anyhow::anyhow!(
+                                "Failed to read record batch from {}: {}",
+                                path.display(),
+                                e
+                            )
+                        })
+                        .and_then(move |batch| {
+                            let individuals =
+                                loader.deserialize_batch_from(&batch, &source_file, first_row);
+                            first_row += batch.num_rows();
+                            async move { individuals }
+                        }),
+                )
+            }
+        })
+        .try_flatten()
+        .map_ok(|individuals| stream::iter(individuals.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, StringArray};
+    use parquet::arrow::ArrowWriter;
+
+    #[tokio::test]
+    async fn test_stream_rows_across_files() {
+        let dir = std::env::temp_dir().join(format!("par_reader_stream_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut paths = Vec::new();
+        for (name, pnrs) in [("a", vec!["1", "2", "3"]), ("b", vec!["4", "5"])] {
+            let batch = RecordBatch::try_from_iter(vec![(
+                "PNR",
+                Arc::new(StringArray::from(pnrs)) as ArrayRef,
+            )])
+            .unwrap();
+            let path = dir.join(format!("{name}.parquet"));
+            let mut writer =
+                ArrowWriter::try_new(std::fs::File::create(&path).unwrap(), batch.schema(), None)
+                    .unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+            paths.push(path);
+        }
+
+        let batches: Vec<RecordBatch> = stream_rows(paths.clone(), None, Some(2))
+            .try_collect()
+            .await
+            .unwrap();
+        let sizes: Vec<usize> = batches.iter().map(RecordBatch::num_rows).collect();
+        assert_eq!(sizes, vec![2, 1, 2]);
+
+        // A missing file surfaces as an error item once it is reached
+        paths.push(dir.join("missing.parquet"));
+        let results: Vec<Result<RecordBatch>> = stream_rows(paths, None, None).collect().await;
+        assert_eq!(results.len(), 3);
+        assert!(results[2].is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stream_individuals_with_provenance() {
+        let dir = std::env::temp_dir().join(format!(
+            "par_reader_stream_provenance_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut paths = Vec::new();
+        for (name, pnrs) in [
+            ("a", vec![Some("1"), None, Some("2")]),
+            ("b", vec![Some("3")]),
+        ] {
+            let batch = RecordBatch::try_from_iter(vec![(
+                "PNR",
+                Arc::new(StringArray::from(pnrs)) as ArrayRef,
+            )])
+            .unwrap();
+            let path = dir.join(format!("{name}.parquet"));
+            let mut writer =
+                ArrowWriter::try_new(std::fs::File::create(&path).unwrap(), batch.schema(), None)
+                    .unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+            paths.push(path);
+        }
+
+        let loader = Arc::new(DirectRegistryLoader::new("BEF").with_provenance(true));
+        let individuals: Vec<Individual> = stream_individuals(loader, paths.clone())
+            .try_collect()
+            .await
+            .unwrap();
+        let rows: Vec<(String, String, usize)> = individuals
+            .iter()
+            .map(|individual| {
+                let provenance = individual.provenance.as_ref().unwrap();
+                (
+                    individual.pnr.clone(),
+                    provenance.source_file.to_string(),
+                    provenance.source_row,
+                )
+            })
+            .collect();
+        let file = |index: usize| paths[index].display().to_string();
+        assert_eq!(
+            rows,
+            vec![
+                ("1".to_string(), file(0), 0),
+                ("2".to_string(), file(0), 2),
+                ("3".to_string(), file(1), 0),
+            ]
+        );
+
+        // Without provenance nothing is recorded
+        let loader = Arc::new(DirectRegistryLoader::new("BEF"));
+        let individuals: Vec<Individual> = stream_individuals(loader, paths)
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(
+            individuals
+                .iter()
+                .all(|individual| individual.provenance.is_none())
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
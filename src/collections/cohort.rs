@@ -0,0 +1,352 @@
+//! Cohort selection with attrition reporting
+//!
+//! A study cohort is selected from a population by a sequence of inclusion
+//! and exclusion criteria, and reported as an attrition table:
+//!
+//! ```text
+//! N=10000
+//! excluded 500 for age: N=9500
+//! excluded 120 for prior diagnosis: N=9380
+//! ```
+//!
+//! [`CohortBuilder`] applies the criteria to an [`IndividualCollection`] in
+//! order and records the individuals remaining after each. An
+//! [`AttritionTable`] can also be built from the [`ExclusionLog`] of filtered
+//! registry batches.
+
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StringArray, UInt32Array, UInt64Array};
+use arrow::record_batch::RecordBatch;
+
+use crate::collections::IndividualCollection;
+use crate::common::traits::ModelCollection;
+use crate::error::Result;
+use crate::filter::core::ExclusionLog;
+use crate::filter::entity::EntityFilter;
+use crate::models::core::Individual;
+
+/// Whether a cohort step keeps or removes the individuals it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttritionStepKind {
+    /// Keep only the individuals matching the criterion
+    Inclusion,
+    /// Remove the individuals matching the criterion
+    Exclusion,
+}
+
+impl AttritionStepKind {
+    /// Get the name of the kind, as in the exported table
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Inclusion => "inclusion",
+            Self::Exclusion => "exclusion",
+        }
+    }
+}
+
+/// One step of an attrition table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttritionStep {
+    /// The criterion of the step, e.g. "age 18 to 65"
+    pub description: String,
+    /// Whether the step keeps or removes the matching individuals
+    pub kind: AttritionStepKind,
+    /// The number of individuals the step removed
+    pub excluded: u64,
+    /// The number of individuals remaining after the step
+    pub remaining: u64,
+}
+
+/// The individuals remaining after each step of a cohort selection
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttritionTable {
+    /// The number of individuals before the first step
+    pub initial: u64,
+    /// The steps, in the order they were applied
+    pub steps: Vec<AttritionStep>,
+}
+
+impl AttritionTable {
+    /// Get the number of individuals remaining after the last step
+    #[must_use]
+    pub fn remaining(&self) -> u64 {
+        self.steps
+            .last()
+            .map_or(self.initial, |step| step.remaining)
+    }
+
+    /// Export the table with one row for the initial population and one per step
+    ///
+    /// The columns are `step` (0 for the initial population), `description`,
+    /// `kind` (null for the initial population), `excluded` and `remaining`.
+    ///
+    /// # Errors
+    /// Returns an error if the record batch cannot be created
+    pub fn to_record_batch(&self) -> Result<RecordBatch> {
+        let step = UInt32Array::from_iter_values(0..=self.steps.len() as u32);
+        let description: StringArray = std::iter::once(Some("Initial population"))
+            .chain(
+                self.steps
+                    .iter()
+                    .map(|step| Some(step.description.as_str())),
+            )
+            .collect();
+        let kind: StringArray = std::iter::once(None)
+            .chain(self.steps.iter().map(|step| Some(step.kind.as_str())))
+            .collect();
+        let excluded = UInt64Array::from_iter_values(
+            std::iter::once(0).chain(self.steps.iter().map(|step| step.excluded)),
+        );
+        let remaining = UInt64Array::from_iter_values(
+            std::iter::once(self.initial).chain(self.steps.iter().map(|step| step.remaining)),
+        );
+
+        Ok(RecordBatch::try_from_iter(vec![
+            ("step", Arc::new(step) as ArrayRef),
+            ("description", Arc::new(description) as ArrayRef),
+            ("kind", Arc::new(kind) as ArrayRef),
+            ("excluded", Arc::new(excluded) as ArrayRef),
+            ("remaining", Arc::new(remaining) as ArrayRef),
+        ])?)
+    }
+}
+
+impl From<&ExclusionLog> for AttritionTable {
+    /// Turn the exclusions of each filter into exclusion steps, in order
+    fn from(log: &ExclusionLog) -> Self {
+        let mut remaining = log.rows();
+        let steps = log
+            .exclusions()
+            .iter()
+            .map(|(label, excluded)| {
+                remaining = remaining.saturating_sub(*excluded);
+                AttritionStep {
+                    description: label.clone(),
+                    kind: AttritionStepKind::Exclusion,
+                    excluded: *excluded,
+                    remaining,
+                }
+            })
+            .collect();
+        Self {
+            initial: log.rows(),
+            steps,
+        }
+    }
+}
+
+impl fmt::Display for AttritionTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "N={}", self.initial)?;
+        for step in &self.steps {
+            let verb = match step.kind {
+                AttritionStepKind::Inclusion => "not included",
+                AttritionStepKind::Exclusion => "excluded",
+            };
+            write!(
+                f,
+                "\n{verb} {} for {}: N={}",
+                step.excluded, step.description, step.remaining
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Builder of a study cohort that records its attrition
+#[derive(Debug, Clone)]
+pub struct CohortBuilder {
+    individuals: Vec<Arc<Individual>>,
+    attrition: AttritionTable,
+}
+
+impl CohortBuilder {
+    /// Start from every individual of a collection
+    #[must_use]
+    pub fn new(collection: &IndividualCollection) -> Self {
+        let individuals = collection.all();
+        Self {
+            attrition: AttritionTable {
+                initial: individuals.len() as u64,
+                steps: Vec::new(),
+            },
+            individuals,
+        }
+    }
+
+    /// Keep only the individuals matching a criterion
+    #[must_use]
+    pub fn include<F>(self, description: impl Into<String>, predicate: F) -> Self
+    where
+        F: Fn(&Individual) -> bool,
+    {
+        self.step(description.into(), AttritionStepKind::Inclusion, predicate)
+    }
+
+    /// Remove the individuals matching a criterion
+    #[must_use]
+    pub fn exclude<F>(self, description: impl Into<String>, predicate: F) -> Self
+    where
+        F: Fn(&Individual) -> bool,
+    {
+        self.step(
+            description.into(),
+            AttritionStepKind::Exclusion,
+            |individual| !predicate(individual),
+        )
+    }
+
+    /// Keep only the individuals an entity filter matches
+    #[must_use]
+    pub fn include_matching(
+        self,
+        description: impl Into<String>,
+        filter: &dyn EntityFilter<Individual>,
+    ) -> Self {
+        self.include(description, |individual| filter.matches(individual))
+    }
+
+    /// Keep the individuals passing `keep` and record the step
+    fn step<F>(mut self, description: String, kind: AttritionStepKind, keep: F) -> Self
+    where
+        F: Fn(&Individual) -> bool,
+    {
+        let before = self.individuals.len();
+        self.individuals.retain(|individual| keep(individual));
+        self.attrition.steps.push(AttritionStep {
+            description,
+            kind,
+            excluded: (before - self.individuals.len()) as u64,
+            remaining: self.individuals.len() as u64,
+        });
+        self
+    }
+
+    /// Get the number of individuals remaining
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.individuals.len()
+    }
+
+    /// Get the attrition table of the steps so far
+    #[must_use]
+    pub const fn attrition(&self) -> &AttritionTable {
+        &self.attrition
+    }
+
+    /// Finish the cohort
+    ///
+    /// # Returns
+    /// The remaining individuals and the attrition table
+    #[must_use]
+    pub fn build(self) -> (IndividualCollection, AttritionTable) {
+        let individuals = self
+            .individuals
+            .into_iter()
+            .map(Arc::unwrap_or_clone)
+            .collect();
+        (
+            IndividualCollection::from_individuals(individuals),
+            self.attrition,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_cohort_attrition() {
+        let index_date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let individuals = (0..10)
+            .map(|i| {
+                let mut individual =
+                    Individual::new(i.to_string(), NaiveDate::from_ymd_opt(1960 + 5 * i, 1, 1));
+                individual.gender = Some(if i % 2 == 0 { "M" } else { "K" }.to_string());
+                individual
+            })
+            .collect();
+        let collection = IndividualCollection::from_individuals(individuals);
+
+        let (cohort, attrition) = CohortBuilder::new(&collection)
+            .include("age 18 to 65", |individual| {
+                individual
+                    .age_in_years_at(index_date)
+                    .is_some_and(|age| (18..=65).contains(&age))
+            })
+            .exclude("male", |individual| {
+                individual.gender.as_deref() == Some("M")
+            })
+            .build();
+
+        // Born 1960 to 2005 in steps of 5: aged 60, 55, ..., 15
+        assert_eq!(cohort.count(), 4);
+        assert_eq!(attrition.initial, 10);
+        assert_eq!(
+            attrition
+                .steps
+                .iter()
+                .map(|step| (step.excluded, step.remaining))
+                .collect::<Vec<_>>(),
+            vec![(1, 9), (5, 4)]
+        );
+        assert_eq!(attrition.remaining(), 4);
+        assert_eq!(
+            attrition.to_string(),
+            "N=10\nnot included 1 for age 18 to 65: N=9\nexcluded 5 for male: N=4"
+        );
+
+        let batch = attrition.to_record_batch().unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        let remaining = batch
+            .column_by_name("remaining")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(remaining.values().to_vec(), vec![10, 9, 4]);
+    }
+
+    #[test]
+    fn test_attrition_from_exclusion_log() {
+        use crate::filter::core::{AndFilter, BatchFilter, filter_with_exclusions};
+        use crate::filter::expr::{Expr, ExpressionFilter, LiteralValue};
+        use arrow::array::Int32Array;
+
+        let expr =
+            |e: Expr| Arc::new(ExpressionFilter::new(e)) as Arc<dyn BatchFilter + Send + Sync>;
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+        )])
+        .unwrap();
+        let filter = AndFilter::new(vec![
+            expr(Expr::Gt("a".to_string(), LiteralValue::Int(1))),
+            expr(Expr::Lt("a".to_string(), LiteralValue::Int(7))),
+        ]);
+        let mut log = ExclusionLog::new();
+        filter_with_exclusions(&batch, &filter, &mut log).unwrap();
+
+        let attrition = AttritionTable::from(&log);
+        assert_eq!(attrition.initial, 10);
+        assert_eq!(
+            attrition
+                .steps
+                .iter()
+                .map(|step| (step.excluded, step.remaining))
+                .collect::<Vec<_>>(),
+            vec![(2, 8), (3, 5)]
+        );
+        assert!(
+            attrition
+                .steps
+                .iter()
+                .all(|step| step.kind == AttritionStepKind::Exclusion)
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! Cross-tabulation of categorical properties
+//!
+//! A `CrossTab` counts individuals by the combination of two categorical
+//! properties, e.g. gender by immigration type, for descriptive tables.
+//! Individuals missing a value are counted in an explicit
+//! [`MISSING_CATEGORY`] rather than dropped.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::error::Result;
+use crate::models::core::Individual;
+use crate::models::core::individual::PropertyField;
+use crate::schema::ValueLabelSet;
+
+/// Category label of individuals with a missing value
+pub const MISSING_CATEGORY: &str = "Missing";
+
+/// Label of the total row and column in exported batches
+pub const TOTAL_LABEL: &str = "Total";
+
+/// A counts matrix for two categorical properties
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossTab {
+    /// Property whose categories form the rows
+    pub row_field: PropertyField,
+    /// Property whose categories form the columns
+    pub col_field: PropertyField,
+    /// Row categories, sorted, with [`MISSING_CATEGORY`] last if present
+    pub row_labels: Vec<String>,
+    /// Column categories, sorted, with [`MISSING_CATEGORY`] last if present
+    pub col_labels: Vec<String>,
+    /// Counts indexed by row, then column
+    pub counts: Vec<Vec<usize>>,
+}
+
+impl CrossTab {
+    /// Cross-tabulate individuals by two properties
+    #[must_use]
+    pub fn from_individuals<'a>(
+        individuals: impl IntoIterator<Item = &'a Individual>,
+        row_field: PropertyField,
+        col_field: PropertyField,
+    ) -> Self {
+        let pairs: Vec<(Option<String>, Option<String>)> = individuals
+            .into_iter()
+            .map(|individual| (row_field.value(individual), col_field.value(individual)))
+            .collect();
+
+        let row_labels = categories(pairs.iter().map(|(row, _)| row.as_deref()));
+        let col_labels = categories(pairs.iter().map(|(_, col)| col.as_deref()));
+
+        let mut counts = vec![vec![0; col_labels.len()]; row_labels.len()];
+        for (row, col) in &pairs {
+            let row = label_index(&row_labels, row.as_deref());
+            let col = label_index(&col_labels, col.as_deref());
+            counts[row][col] += 1;
+        }
+
+        Self {
+            row_field,
+            col_field,
+            row_labels,
+            col_labels,
+            counts,
+        }
+    }
+
+    /// Replace category codes by their value labels
+    ///
+    /// Each property's categories are decoded with the labels of its
+    /// [`PropertyField::source_column`], e.g. `SOCIO13` code `110` becomes
+    /// "Employee, basic level". Codes without a label, and
+    /// [`MISSING_CATEGORY`], are kept. Categories keep their order. Codes
+    /// sharing a label are merged into one category.
+    #[must_use]
+    pub fn with_value_labels(mut self, labels: &ValueLabelSet) -> Self {
+        let (row_labels, row_index) = relabel(&self.row_labels, self.row_field, labels);
+        let (col_labels, col_index) = relabel(&self.col_labels, self.col_field, labels);
+
+        let mut counts = vec![vec![0; col_labels.len()]; row_labels.len()];
+        for (row, values) in self.counts.iter().enumerate() {
+            for (col, count) in values.iter().enumerate() {
+                counts[row_index[row]][col_index[col]] += count;
+            }
+        }
+
+        self.row_labels = row_labels;
+        self.col_labels = col_labels;
+        self.counts = counts;
+        self
+    }
+
+    /// Get the count for a row and column category
+    ///
+    /// # Returns
+    /// The count, or zero if either category does not occur
+    #[must_use]
+    pub fn count(&self, row: &str, col: &str) -> usize {
+        let row = self.row_labels.iter().position(|label| label == row);
+        let col = self.col_labels.iter().position(|label| label == col);
+        match (row, col) {
+            (Some(row), Some(col)) => self.counts[row][col],
+            _ => 0,
+        }
+    }
+
+    /// Get the total of each row
+    #[must_use]
+    pub fn row_totals(&self) -> Vec<usize> {
+        self.counts.iter().map(|row| row.iter().sum()).collect()
+    }
+
+    /// Get the total of each column
+    #[must_use]
+    pub fn col_totals(&self) -> Vec<usize> {
+        (0..self.col_labels.len())
+            .map(|col| self.counts.iter().map(|row| row[col]).sum())
+            .collect()
+    }
+
+    /// Get the total number of individuals
+    #[must_use]
+    pub fn grand_total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Export the table as a record batch
+    ///
+    /// The first column, named after the row property, holds the row
+    /// categories; it is followed by one count column per column category
+    /// and a [`TOTAL_LABEL`] column. The last row holds the column totals.
+    ///
+    /// # Errors
+    /// Returns an error if the batch cannot be created
+    pub fn to_record_batch(&self) -> Result<RecordBatch> {
+        let mut fields = vec![Field::new(self.row_field.name(), DataType::Utf8, false)];
+        fields.extend(
+            self.col_labels
+                .iter()
+                .map(String::as_str)
+                .chain([TOTAL_LABEL])
+                .map(|label| Field::new(label, DataType::UInt64, false)),
+        );
+        let schema = Schema::new(fields);
+
+        let labels = self
+            .row_labels
+            .iter()
+            .map(String::as_str)
+            .chain([TOTAL_LABEL]);
+        let mut columns: Vec<ArrayRef> = vec![Arc::new(StringArray::from_iter_values(labels))];
+
+        let col_totals = self.col_totals();
+        for (col, total) in col_totals.iter().enumerate() {
+            let values = self.counts.iter().map(|row| row[col]).chain([*total]);
+            columns.push(Arc::new(UInt64Array::from_iter_values(
+                values.map(|count| count as u64),
+            )));
+        }
+        let totals = self
+            .row_totals()
+            .into_iter()
+            .chain([self.grand_total()])
+            .map(|count| count as u64);
+        columns.push(Arc::new(UInt64Array::from_iter_values(totals)));
+
+        Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+    }
+}
+
+/// Decode categories with value labels, merging codes that share a label
+///
+/// # Returns
+/// The distinct decoded categories in order, and the index of each original
+/// category among them
+fn relabel(
+    categories: &[String],
+    field: PropertyField,
+    labels: &ValueLabelSet,
+) -> (Vec<String>, Vec<usize>) {
+    let mut decoded: Vec<String> = Vec::new();
+    let index = categories
+        .iter()
+        .map(|category| {
+            let label = match field.source_column() {
+                Some(column) if category != MISSING_CATEGORY => labels.decode(column, category),
+                _ => category,
+            };
+            decoded
+                .iter()
+                .position(|existing| existing == label)
+                .unwrap_or_else(|| {
+                    decoded.push(label.to_string());
+                    decoded.len() - 1
+                })
+        })
+        .collect();
+    (decoded, index)
+}
+
+/// Collect the sorted distinct categories, with missing values last
+///
+/// A value equal to [`MISSING_CATEGORY`] is counted as missing.
+fn categories<'a>(values: impl Iterator<Item = Option<&'a str>>) -> Vec<String> {
+    let mut missing = false;
+    let mut labels = BTreeSet::new();
+    for value in values {
+        match value {
+            Some(value) => {
+                labels.insert(value.to_string());
+            }
+            None => missing = true,
+        }
+    }
+
+    let mut labels: Vec<String> = labels.into_iter().collect();
+    if missing && !labels.iter().any(|label| label == MISSING_CATEGORY) {
+        labels.push(MISSING_CATEGORY.to_string());
+    }
+    labels
+}
+
+/// Find the index of a value's category in `labels`
+fn label_index(labels: &[String], value: Option<&str>) -> usize {
+    let label = value.unwrap_or(MISSING_CATEGORY);
+    labels
+        .iter()
+        .position(|candidate| candidate == label)
+        .expect("categories are collected from the same values")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crosstab_totals_and_export() {
+        let individual = |pnr: &str, gender: Option<&str>, immigration_type: Option<&str>| {
+            let mut individual = Individual::new(pnr.to_string(), None);
+            individual.gender = gender.map(str::to_string);
+            individual.immigration_type = immigration_type.map(str::to_string);
+            individual
+        };
+        let individuals = [
+            individual("1", Some("M"), Some("1")),
+            individual("2", Some("K"), Some("2")),
+            individual("3", Some("M"), Some("1")),
+            individual("4", None, Some("3")),
+        ];
+
+        let table = CrossTab::from_individuals(
+            &individuals,
+            PropertyField::Gender,
+            PropertyField::ImmigrationType,
+        );
+        assert_eq!(table.row_labels, vec!["K", "M", MISSING_CATEGORY]);
+        assert_eq!(
+            table.col_labels,
+            vec!["Danish origin", "Descendant", "Immigrant"]
+        );
+        assert_eq!(table.count("M", "Danish origin"), 2);
+        assert_eq!(table.count(MISSING_CATEGORY, "Descendant"), 1);
+        assert_eq!(table.row_totals(), vec![1, 2, 1]);
+        assert_eq!(table.col_totals(), vec![2, 1, 1]);
+        assert_eq!(table.grand_total(), 4);
+
+        let mut labels = ValueLabelSet::new();
+        labels.insert("KOEN", "K", "Female");
+        labels.insert("KOEN", "M", "Male");
+        let labelled = table.clone().with_value_labels(&labels);
+        assert_eq!(
+            labelled.row_labels,
+            vec!["Female", "Male", MISSING_CATEGORY]
+        );
+        assert_eq!(labelled.count("Male", "Danish origin"), 2);
+
+        let batch = table.to_record_batch().unwrap();
+        assert_eq!(batch.num_rows(), 4);
+        assert_eq!(batch.schema().field(0).name(), "gender");
+        let totals = batch
+            .column_by_name(TOTAL_LABEL)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(totals.values(), &[1, 2, 1, 4]);
+    }
+}
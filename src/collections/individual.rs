@@ -4,16 +4,30 @@
 //! This is the canonical implementation of `IndividualCollection` in the codebase.
 
 use crate::collections::GenericCollection;
+use crate::collections::crosstab::CrossTab;
 use crate::common::traits::{
     BatchCollection, LookupCollection, ModelCollection, TemporalCollection,
 };
 use crate::error::Result;
+use crate::filter::entity::EntityFilter;
 use crate::models::core::Individual;
+use crate::models::core::individual::PropertyField;
 use crate::models::core::traits::{ArrowSchema, HealthStatus};
 use arrow::record_batch::RecordBatch;
 use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 
+/// Number of individuals per age group, from [`IndividualCollection::age_distribution`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AgeDistribution {
+    /// Count of individuals per age bucket
+    pub counts: HashMap<Range<i64>, usize>,
+    /// Count of individuals with an unknown birth date
+    pub unknown: usize,
+}
+
 /// Specialized collection for Individual models
 #[derive(Debug, Default)]
 pub struct IndividualCollection {
@@ -75,6 +89,66 @@ impl IndividualCollection {
         })
     }
 
+    /// Count individuals per age group at an index date
+    ///
+    /// Ages are computed with [`Individual::age_in_years_at`], so individuals
+    /// who died or emigrated before the index date are still counted. An
+    /// individual is counted in every bucket containing their age; ages
+    /// outside all buckets are not counted.
+    ///
+    /// # Arguments
+    /// * `index_date` - The date at which ages are computed
+    /// * `buckets` - Half-open age ranges, e.g. `0..18` and `18..65`
+    ///
+    /// # Returns
+    /// The count per bucket (zero for empty buckets) and the number of
+    /// individuals with an unknown birth date
+    #[must_use]
+    pub fn age_distribution(
+        &self,
+        index_date: NaiveDate,
+        buckets: &[Range<i64>],
+    ) -> AgeDistribution {
+        let mut distribution = AgeDistribution {
+            counts: buckets.iter().map(|bucket| (bucket.clone(), 0)).collect(),
+            unknown: 0,
+        };
+
+        for individual in self.inner.all() {
+            let Some(age) = individual.age_in_years_at(index_date) else {
+                distribution.unknown += 1;
+                continue;
+            };
+            for bucket in buckets.iter().filter(|bucket| bucket.contains(&age)) {
+                if let Some(count) = distribution.counts.get_mut(bucket) {
+                    *count += 1;
+                }
+            }
+        }
+
+        distribution
+    }
+
+    /// Cross-tabulate the individuals by two categorical properties
+    ///
+    /// Individuals missing a value are counted in the
+    /// [`MISSING_CATEGORY`](crate::collections::crosstab::MISSING_CATEGORY) row
+    /// or column.
+    #[must_use]
+    pub fn crosstab(&self, row_field: PropertyField, col_field: PropertyField) -> CrossTab {
+        let individuals = self.inner.all();
+        CrossTab::from_individuals(individuals.iter().map(AsRef::as_ref), row_field, col_field)
+    }
+
+    /// Get the individuals matching an entity filter
+    ///
+    /// Filters are built from [`crate::filter::entity`], e.g.
+    /// `born_between(start, end).and(has_parent())`.
+    #[must_use]
+    pub fn filter_entities(&self, filter: &dyn EntityFilter<Individual>) -> Vec<Arc<Individual>> {
+        self.inner.filter(|individual| filter.matches(individual))
+    }
+
     /// Get individuals by gender
     #[must_use]
     pub fn by_gender(&self, gender: Option<String>) -> Vec<Arc<Individual>> {
@@ -216,3 +290,34 @@ impl BatchCollection<Individual> for IndividualCollection {
         Individual::to_record_batch(&individuals)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_age_distribution() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+        let collection = IndividualCollection::from_individuals(vec![
+            Individual::new("1".to_string(), date(2010, 6, 1)),
+            Individual::new("2".to_string(), date(2000, 1, 1)),
+            Individual::new("3".to_string(), date(2002, 1, 2)),
+            Individual::new("4".to_string(), None),
+            Individual::new("5".to_string(), date(1900, 1, 1)),
+        ]);
+
+        let index_date = date(2020, 1, 1).unwrap();
+        assert_eq!(
+            collection
+                .get(&"3".to_string())
+                .unwrap()
+                .age_in_years_at(index_date),
+            Some(17)
+        );
+
+        let distribution = collection.age_distribution(index_date, &[0..18, 18..65]);
+        assert_eq!(distribution.counts[&(0..18)], 2);
+        assert_eq!(distribution.counts[&(18..65)], 1);
+        assert_eq!(distribution.unknown, 1);
+    }
+}
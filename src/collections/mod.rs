@@ -8,14 +8,18 @@
 //! and specialized collections that provide domain-specific functionality.
 
 // Specialized collection modules
+pub mod cohort;
+pub mod crosstab;
 pub mod diagnosis;
 pub mod family;
 pub mod individual;
 
 // Re-export specialized collections for convenience
+pub use cohort::{AttritionStep, AttritionStepKind, AttritionTable, CohortBuilder};
+pub use crosstab::CrossTab;
 pub use diagnosis::DiagnosisCollection;
 pub use family::FamilyCollection;
-pub use individual::IndividualCollection;
+pub use individual::{AgeDistribution, IndividualCollection};
 
 use crate::common::traits::{
     BatchCollection, CacheableCollection, LookupCollection, ModelCollection, TemporalCollection,
@@ -68,6 +72,14 @@ impl<T: EntityModel> GenericCollection<T> {
         self.items.keys().cloned().collect()
     }
 
+    /// Get mutable access to a model
+    ///
+    /// A model still shared with callers of `get` or `all` is cloned first,
+    /// so those references are unaffected.
+    pub fn get_mut(&mut self, id: &T::Id) -> Option<&mut T> {
+        self.items.get_mut(id).map(Arc::make_mut)
+    }
+
     /// Remove a model from the collection
     pub fn remove(&mut self, id: &T::Id) -> Option<Arc<T>> {
         self.items.remove(id)
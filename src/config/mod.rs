@@ -1,6 +1,7 @@
 //! Configuration for `ParquetReader`.
 
 use crate::schema::DateFormatConfig;
+use crate::utils::arrow::DateRangeCheck;
 
 /// Configuration for the `ParquetReader`
 #[derive(Debug, Clone)]
@@ -21,6 +22,13 @@ pub struct ParquetReaderConfig {
     pub log_adaptations: bool,
     /// Date format configuration for string-to-date conversions
     pub date_format_config: DateFormatConfig,
+    /// Project files down to the declared schema, logging and dropping columns
+    /// that are not part of it. When disabled, extra columns are an error.
+    /// Missing required columns are an error either way.
+    pub ignore_extra_columns: bool,
+    /// Plausible date ranges checked after loading. Out-of-range dates are
+    /// logged and, if the check says so, set to null.
+    pub date_range_checks: Vec<DateRangeCheck>,
 }
 
 impl Default for ParquetReaderConfig {
@@ -34,6 +42,8 @@ impl Default for ParquetReaderConfig {
             strict_adaptation: false,
             log_adaptations: true,
             date_format_config: DateFormatConfig::default(),
+            ignore_extra_columns: true,
+            date_range_checks: Vec::new(),
         }
     }
 }
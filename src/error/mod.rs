@@ -11,6 +11,8 @@ use arrow::error::ArrowError;
 use parquet::errors::ParquetError;
 use thiserror::Error;
 
+use crate::schema::SchemaCompatibilityReport;
+
 pub mod util;
 
 /// Main error enum for the `ParquetReader` library
@@ -66,6 +68,14 @@ pub enum ParquetReaderError {
     #[error("Error: {message}")]
     Custom { message: String },
 
+    /// A directory to load contains no Parquet files
+    #[error("No Parquet files found in directory: {}", path.display())]
+    EmptyDirectory { path: PathBuf },
+
+    /// Files to load together have incompatible schemas
+    #[error("Incompatible schemas: {}", describe_issues(report))]
+    IncompatibleSchemas { report: SchemaCompatibilityReport },
+
     /// Error for when a filter excludes an entity
     #[error("Filter excluded entity: {message}")]
     FilterExcluded { message: String },
@@ -312,6 +322,10 @@ impl ParquetReaderError {
             Self::FilterExcluded { message } => Self::FilterExcluded {
                 message: format!("{message} (path: {path_str})"),
             },
+            // The directory is already the path of the error
+            Self::EmptyDirectory { path } => Self::EmptyDirectory { path },
+            // The report already names the files
+            Self::IncompatibleSchemas { report } => Self::IncompatibleSchemas { report },
             Self::Other(msg) => Self::Other(format!("{msg} (path: {path_str})")),
         }
     }
@@ -346,11 +360,29 @@ impl ParquetReaderError {
             Self::FilterExcluded { message } => Self::FilterExcluded {
                 message: format!("{ctx}: {message}"),
             },
+            // Kept as is, so callers can still match on the empty directory
+            Self::EmptyDirectory { path } => Self::EmptyDirectory { path },
+            Self::IncompatibleSchemas { report } => Self::IncompatibleSchemas { report },
             Self::Other(msg) => Self::Other(format!("{ctx}: {msg}")),
         }
     }
 }
 
+/// Describe the issues of a schema compatibility report, one per file pair
+fn describe_issues(report: &SchemaCompatibilityReport) -> String {
+    report
+        .issues
+        .iter()
+        .map(|issue| {
+            format!(
+                "{} vs {}: {}",
+                issue.file_path, issue.reference_path, issue.description
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 /// Extension traits for easy context addition to Results
 pub trait ResultExt<T> {
     /// Add context to a Result
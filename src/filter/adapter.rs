@@ -13,66 +13,92 @@ use arrow::record_batch::RecordBatch;
 
 use crate::error::{ParquetReaderError, Result};
 use crate::filter::core::{BatchFilter, filter_record_batch};
+use crate::filter::entity::EntityFilter;
+use crate::filter::expr::ExpressionFilter;
 use crate::filter::generic::{AndFilter as GenericAndFilter, BoxedFilter, Filter};
 use crate::models::Individual;
 //use crate::models::Family;
 
-/// Adapter for converting entity filters to record batch filters
+/// Adapter for applying an entity filter to record batches
+///
+/// If the filter can be translated into an expression over the registry
+/// columns (see [`EntityFilter::to_expr`]), batches are filtered with that
+/// expression, without deserializing any entities, and the adapter can be
+/// pushed down to the Parquet read. Otherwise each batch is deserialized with
+/// the extraction function and rows are kept where the entity matches.
 pub struct EntityToBatchAdapter<T> {
+    filter: Arc<dyn EntityFilter<T>>,
+    pushdown: Option<ExpressionFilter>,
     extract_fn: Arc<dyn Fn(&RecordBatch) -> Result<Vec<T>> + Send + Sync>,
 }
 
 impl<T> Debug for EntityToBatchAdapter<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("EntityToBatchAdapter")
+            .field("filter", &self.filter)
+            .field("pushdown", &self.pushdown)
             .finish_non_exhaustive()
     }
 }
 
-impl<T: Clone + 'static> EntityToBatchAdapter<T> {
-    /// Create a new adapter with an extraction function
-    pub fn new<F>(extract_fn: F) -> Self
+impl<T: 'static> EntityToBatchAdapter<T> {
+    /// Create a new adapter for an entity filter
+    ///
+    /// # Arguments
+    /// * `filter` - The entity filter to apply
+    /// * `extract_fn` - Deserializes a batch into one entity per row, used
+    ///   when the filter cannot be pushed down
+    pub fn new<F, E>(filter: F, extract_fn: E) -> Self
     where
-        F: Fn(&RecordBatch) -> Result<Vec<T>> + Send + Sync + 'static,
+        F: EntityFilter<T> + 'static,
+        E: Fn(&RecordBatch) -> Result<Vec<T>> + Send + Sync + 'static,
     {
+        let pushdown = filter.to_expr().map(ExpressionFilter::new);
         Self {
+            filter: Arc::new(filter),
+            pushdown,
             extract_fn: Arc::new(extract_fn),
         }
     }
+
+    /// Whether the filter is applied as an expression over record batches
+    #[must_use]
+    pub const fn is_pushdown(&self) -> bool {
+        self.pushdown.is_some()
+    }
 }
 
-/// Entity filter adapter implementation for `BatchFilter`
-impl<T: Clone + Send + Sync + 'static + std::fmt::Debug> BatchFilter for EntityToBatchAdapter<T>
-where
-    T: Clone + Send + Sync,
-{
+impl<T> BatchFilter for EntityToBatchAdapter<T> {
     fn filter(&self, batch: &RecordBatch) -> Result<RecordBatch> {
-        // Extract entities from the batch
+        if let Some(pushdown) = &self.pushdown {
+            return pushdown.filter(batch);
+        }
+
+        // Fall back to filtering the deserialized entities
         let entities =
             (self.extract_fn)(batch).with_context(|| "Failed to extract entities from batch")?;
-
-        // Create a mask to identify which rows to keep
-        let mut keep_rows = vec![false; batch.num_rows()];
-
-        // This is a simplified implementation that assumes 1:1 mapping
-        // between entities and batch rows. A real implementation would need
-        // to track the mapping from entities back to row indices.
-        if entities.len() == batch.num_rows() {
-            // Assume 1:1 mapping for this example
-            keep_rows = vec![true; batch.num_rows()];
+        if entities.len() != batch.num_rows() {
+            return Err(ParquetReaderError::FilterError(format!(
+                "Extracted {} entities from a batch of {} rows",
+                entities.len(),
+                batch.num_rows()
+            ))
+            .into());
         }
 
-        // Convert to arrow array
-        let mask = arrow::array::BooleanArray::from(keep_rows);
-
-        // Apply mask to create filtered batch
+        let mask: arrow::array::BooleanArray = entities
+            .iter()
+            .map(|entity| Some(self.filter.matches(entity)))
+            .collect();
         filter_record_batch(batch, &mask)
     }
 
     fn required_columns(&self) -> HashSet<String> {
-        // We'd need to know which columns are required to extract entities
-        // For now, return an empty set
-        HashSet::new()
+        // Without pushdown the columns needed depend on the extraction function
+        self.pushdown
+            .as_ref()
+            .map(BatchFilter::required_columns)
+            .unwrap_or_default()
     }
 }
 
@@ -260,6 +286,19 @@ where
     }
 }
 
+impl<P> EntityFilter<Individual> for IndividualFilter<P>
+where
+    P: Fn(&Individual) -> bool + Send + Sync,
+{
+    fn matches(&self, individual: &Individual) -> bool {
+        (self.predicate)(individual)
+    }
+
+    fn required_fields(&self) -> HashSet<String> {
+        self.required_fields.clone()
+    }
+}
+
 impl<P> Debug for IndividualFilter<P>
 where
     P: Fn(&Individual) -> bool + Send + Sync,
@@ -320,3 +359,88 @@ where
 //             .finish()
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::entity::{EntityFilterExt, born_between, gender_is, has_parent};
+    use crate::models::Gender;
+    use arrow::array::{ArrayRef, Date32Array, StringArray};
+    use chrono::NaiveDate;
+
+    fn bef_batch() -> RecordBatch {
+        RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["1", "2", "3"])) as ArrayRef,
+            ),
+            (
+                "KOEN",
+                Arc::new(StringArray::from(vec![Some("K"), Some("M"), None])) as ArrayRef,
+            ),
+            (
+                "FOED_DAG",
+                Arc::new(Date32Array::from(vec![Some(14_610), Some(3_653), None])) as ArrayRef,
+            ),
+            (
+                "MOR_ID",
+                Arc::new(StringArray::from(vec![Some("9"), None, None])) as ArrayRef,
+            ),
+            (
+                "FAR_ID",
+                Arc::new(StringArray::from(vec![None, Some("8"), None])) as ArrayRef,
+            ),
+        ])
+        .unwrap()
+    }
+
+    /// One individual per row, with the fields the filters read
+    fn extract(batch: &RecordBatch) -> Result<Vec<Individual>> {
+        let pnrs = batch
+            .column_by_name("PNR")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let genders = batch
+            .column_by_name("KOEN")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        Ok(pnrs
+            .iter()
+            .zip(genders.iter())
+            .map(|(pnr, gender)| {
+                let mut individual = Individual::new(pnr.unwrap().to_string(), None);
+                individual.gender = gender.map(str::to_string);
+                individual
+            })
+            .collect())
+    }
+
+    #[test]
+    fn test_entity_to_batch_pushdown_and_fallback() {
+        let batch = bef_batch();
+        let date = |y| NaiveDate::from_ymd_opt(y, 1, 1).unwrap();
+
+        // 2010-01-01 is day 14610, 1980-01-01 is day 3653
+        let cohort =
+            born_between(date(2000), date(2020)).or(has_parent().and(gender_is(Gender::Male)));
+        let adapter = EntityToBatchAdapter::new(cohort, extract);
+        assert!(adapter.is_pushdown());
+        assert!(adapter.required_columns().contains("FOED_DAG"));
+        assert_eq!(adapter.filter(&batch).unwrap().num_rows(), 2);
+
+        // Negation is evaluated on deserialized individuals; a missing gender passes
+        let adapter = EntityToBatchAdapter::new(gender_is(Gender::Female).not(), extract);
+        assert!(!adapter.is_pushdown());
+        let filtered = adapter.filter(&batch).unwrap();
+        let pnrs = filtered
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(pnrs.iter().flatten().collect::<Vec<_>>(), vec!["2", "3"]);
+    }
+}
@@ -4,12 +4,15 @@
 //! It defines common traits, functions, and utilities for filtering Arrow record batches.
 
 use std::any::Any;
+use std::borrow::Cow;
+use std::fmt;
 use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Context;
-use arrow::array::{ArrayRef, BooleanArray};
+use arrow::array::{Array, ArrayRef, BooleanArray, UInt32Array, UInt64Array};
 use arrow::compute::filter as arrow_filter;
+use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 
 use crate::error::Result;
@@ -64,6 +67,319 @@ pub trait BatchFilter: std::fmt::Debug {
 
     /// Returns the set of column names required by this filter
     fn required_columns(&self) -> std::collections::HashSet<String>;
+
+    /// Evaluate the filter to a boolean mask over the rows of `batch`
+    ///
+    /// The default implementation tracks row positions through [`filter`](Self::filter),
+    /// so it works for any filter that keeps the columns of its input.
+    /// Filters that compute a mask directly should override it.
+    ///
+    /// # Errors
+    /// Returns an error if filtering fails
+    fn evaluate_mask(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        mask_from_filtered_rows(self, batch)
+    }
+
+    /// A short description of the filter, naming it in an [`ExclusionLog`]
+    ///
+    /// The default implementation is the name of the filter type.
+    fn label(&self) -> String {
+        let name = std::any::type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name).to_string()
+    }
+
+    /// Evaluate the filter to a mask, recording the rows it excludes in `log`
+    ///
+    /// The default implementation attributes every row not selected to this
+    /// filter. Composite filters attribute the rows to their sub-filters
+    /// where they can.
+    ///
+    /// # Errors
+    /// Returns an error if filtering fails
+    fn evaluate_mask_with_exclusions(
+        &self,
+        batch: &RecordBatch,
+        log: &mut ExclusionLog,
+    ) -> Result<BooleanArray> {
+        let mask = self.evaluate_mask(batch)?;
+        log.record(&self.label(), (batch.num_rows() - mask.true_count()) as u64);
+        Ok(mask)
+    }
+}
+
+/// The rows excluded by each filter of a filter tree
+///
+/// Counts accumulate over the batches given to [`filter_with_exclusions`],
+/// in the order the filters were first evaluated, which gives the steps of
+/// a CONSORT-style flow diagram.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExclusionLog {
+    /// The number of rows filtered
+    rows: u64,
+    /// The label of each filter with the number of rows it excluded
+    exclusions: Vec<(String, u64)>,
+}
+
+impl ExclusionLog {
+    /// Create an empty log
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add rows excluded by a filter
+    pub fn record(&mut self, filter: &str, excluded: u64) {
+        match self
+            .exclusions
+            .iter_mut()
+            .find(|(label, _)| label == filter)
+        {
+            Some((_, count)) => *count += excluded,
+            None => self.exclusions.push((filter.to_string(), excluded)),
+        }
+    }
+
+    /// Get the number of rows filtered
+    #[must_use]
+    pub const fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// Get the number of rows no filter excluded
+    #[must_use]
+    pub fn kept(&self) -> u64 {
+        self.rows.saturating_sub(self.total_excluded())
+    }
+
+    /// Get the number of rows excluded by any filter
+    #[must_use]
+    pub fn total_excluded(&self) -> u64 {
+        self.exclusions.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Get the number of rows excluded by a filter, by its label
+    #[must_use]
+    pub fn excluded_by(&self, filter: &str) -> u64 {
+        self.exclusions
+            .iter()
+            .find(|(label, _)| label == filter)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// Get the label of each filter with the number of rows it excluded
+    #[must_use]
+    pub fn exclusions(&self) -> &[(String, u64)] {
+        &self.exclusions
+    }
+}
+
+impl fmt::Display for ExclusionLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} rows", self.rows)?;
+        for (label, count) in &self.exclusions {
+            writeln!(f, "{count} excluded by {label}")?;
+        }
+        write!(f, "{} rows kept", self.kept())
+    }
+}
+
+/// Filter a record batch, recording the excluded rows in an [`ExclusionLog`]
+///
+/// Pass the same log for every batch of a dataset to count its exclusions.
+///
+/// # Arguments
+/// * `batch` - The record batch to filter
+/// * `filter` - The filter to apply
+/// * `log` - The log to add the batch's rows and exclusions to
+///
+/// # Returns
+/// The rows selected by the filter
+///
+/// # Errors
+/// Returns an error if filtering fails
+pub fn filter_with_exclusions(
+    batch: &RecordBatch,
+    filter: &dyn BatchFilter,
+    log: &mut ExclusionLog,
+) -> Result<RecordBatch> {
+    log.rows += batch.num_rows() as u64;
+    let mask = filter.evaluate_mask_with_exclusions(batch, log)?;
+    filter_record_batch(batch, &mask)
+}
+
+/// Name of the temporary column used to track row positions through a filter
+const ROW_INDEX_COLUMN: &str = "__par_reader_row_index";
+
+/// Derive a filter's mask by tracking row positions through [`BatchFilter::filter`]
+fn mask_from_filtered_rows<F: BatchFilter + ?Sized>(
+    filter: &F,
+    batch: &RecordBatch,
+) -> Result<BooleanArray> {
+    let mut fields: Vec<Arc<Field>> = batch.schema().fields().iter().cloned().collect();
+    fields.push(Arc::new(Field::new(
+        ROW_INDEX_COLUMN,
+        DataType::UInt64,
+        false,
+    )));
+    let mut columns = batch.columns().to_vec();
+    columns.push(Arc::new(UInt64Array::from_iter_values(
+        0..batch.num_rows() as u64,
+    )));
+    let indexed = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .with_context(|| "Failed to add row index column")?;
+
+    let filtered = filter.filter(&indexed)?;
+    let kept = filtered
+        .column_by_name(ROW_INDEX_COLUMN)
+        .and_then(|col| col.as_any().downcast_ref::<UInt64Array>())
+        .ok_or_else(|| anyhow::anyhow!("Filter dropped the row index column"))?;
+
+    let mut mask = vec![false; batch.num_rows()];
+    for idx in kept.values() {
+        let idx = usize::try_from(*idx).with_context(|| "Row index out of range")?;
+        mask[idx] = true;
+    }
+    Ok(BooleanArray::from(mask))
+}
+
+/// Whether a mask selects a row; null mask entries do not
+fn is_selected(mask: &BooleanArray, row: usize) -> bool {
+    mask.is_valid(row) && mask.value(row)
+}
+
+/// Expand a set of selected row positions into a full-length mask
+fn mask_from_indices(num_rows: usize, indices: &UInt32Array) -> BooleanArray {
+    let mut mask = vec![false; num_rows];
+    for idx in indices.values() {
+        mask[*idx as usize] = true;
+    }
+    BooleanArray::from(mask)
+}
+
+/// Row positions `0..num_rows` as a `UInt32Array`
+fn all_indices(num_rows: usize) -> Result<UInt32Array> {
+    let num_rows =
+        u32::try_from(num_rows).with_context(|| "Batch too large for index-based filtering")?;
+    Ok(UInt32Array::from_iter_values(0..num_rows))
+}
+
+/// Evaluate the conjunction of filters, short-circuiting at the array level
+///
+/// The first filter is evaluated on all rows; each subsequent filter is only
+/// evaluated on the rows that are still selected, gathered with Arrow's `take`
+/// kernel. Evaluation stops as soon as no rows remain. Null mask entries count
+/// as not selected.
+///
+/// # Arguments
+/// * `batch` - The record batch to evaluate against
+/// * `filters` - The filters to combine; put the most selective first
+///
+/// # Returns
+/// A mask over the rows of `batch`, true where every filter matches
+///
+/// # Errors
+/// Returns an error if any filter fails to evaluate
+pub fn evaluate_and_mask(
+    batch: &RecordBatch,
+    filters: &[Arc<dyn BatchFilter + Send + Sync>],
+) -> Result<BooleanArray> {
+    and_mask(batch, filters, None)
+}
+
+/// Evaluate the conjunction of filters as [`evaluate_and_mask`] does,
+/// attributing the rows each filter removes from the surviving rows to it
+fn and_mask(
+    batch: &RecordBatch,
+    filters: &[Arc<dyn BatchFilter + Send + Sync>],
+    mut log: Option<&mut ExclusionLog>,
+) -> Result<BooleanArray> {
+    let mut indices = all_indices(batch.num_rows())?;
+
+    for filter in filters {
+        if indices.is_empty() {
+            // Nothing is left for the remaining filters to exclude
+            if let Some(log) = log.as_deref_mut() {
+                log.record(&filter.label(), 0);
+                continue;
+            }
+            break;
+        }
+
+        // Only evaluate on the surviving rows
+        let surviving = if indices.len() == batch.num_rows() {
+            Cow::Borrowed(batch)
+        } else {
+            Cow::Owned(
+                arrow::compute::take_record_batch(batch, &indices)
+                    .with_context(|| "Failed to gather surviving rows")?,
+            )
+        };
+        let mask = match log.as_deref_mut() {
+            Some(log) => filter.evaluate_mask_with_exclusions(&surviving, log)?,
+            None => filter.evaluate_mask(&surviving)?,
+        };
+
+        indices = indices
+            .values()
+            .iter()
+            .enumerate()
+            .filter(|(row, _)| is_selected(&mask, *row))
+            .map(|(_, idx)| *idx)
+            .collect();
+    }
+
+    Ok(mask_from_indices(batch.num_rows(), &indices))
+}
+
+/// Evaluate the disjunction of filters, short-circuiting at the array level
+///
+/// Each filter is only evaluated on the rows not yet matched by an earlier
+/// filter, and evaluation stops once every row has matched. Null mask entries
+/// count as not selected.
+///
+/// # Arguments
+/// * `batch` - The record batch to evaluate against
+/// * `filters` - The filters to combine; put the least selective first
+///
+/// # Returns
+/// A mask over the rows of `batch`, true where any filter matches
+///
+/// # Errors
+/// Returns an error if any filter fails to evaluate
+pub fn evaluate_or_mask(
+    batch: &RecordBatch,
+    filters: &[Arc<dyn BatchFilter + Send + Sync>],
+) -> Result<BooleanArray> {
+    let mut matched = vec![false; batch.num_rows()];
+    let mut remaining = all_indices(batch.num_rows())?;
+
+    for filter in filters {
+        if remaining.is_empty() {
+            break;
+        }
+
+        // Only evaluate on the rows that have not matched yet
+        let mask = if remaining.len() == batch.num_rows() {
+            filter.evaluate_mask(batch)?
+        } else {
+            let unmatched = arrow::compute::take_record_batch(batch, &remaining)
+                .with_context(|| "Failed to gather unmatched rows")?;
+            filter.evaluate_mask(&unmatched)?
+        };
+
+        let mut still_remaining = Vec::with_capacity(remaining.len());
+        for (row, idx) in remaining.values().iter().enumerate() {
+            if is_selected(&mask, row) {
+                matched[*idx as usize] = true;
+            } else {
+                still_remaining.push(*idx);
+            }
+        }
+        remaining = UInt32Array::from(still_remaining);
+    }
+
+    Ok(BooleanArray::from(matched))
 }
 
 /// A filter that always includes all rows
@@ -82,6 +398,10 @@ impl BatchFilter for IncludeAllFilter {
     fn required_columns(&self) -> std::collections::HashSet<String> {
         std::collections::HashSet::new()
     }
+
+    fn evaluate_mask(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        Ok(BooleanArray::from(vec![true; batch.num_rows()]))
+    }
 }
 
 /// A filter that excludes all rows
@@ -100,6 +420,10 @@ impl BatchFilter for ExcludeAllFilter {
     fn required_columns(&self) -> std::collections::HashSet<String> {
         std::collections::HashSet::new()
     }
+
+    fn evaluate_mask(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        Ok(BooleanArray::from(vec![false; batch.num_rows()]))
+    }
 }
 
 /// A filter that combines multiple filters with a logical AND
@@ -122,19 +446,26 @@ impl BatchFilter for AndFilter {
             return IncludeAllFilter.filter(batch);
         }
 
-        // Apply the first filter
-        let mut result_batch = self.filters[0].filter(batch)?;
+        let mask = evaluate_and_mask(batch, &self.filters)?;
+        filter_record_batch(batch, &mask)
+    }
 
-        // Apply each subsequent filter
-        for filter in &self.filters[1..] {
-            if result_batch.num_rows() == 0 {
-                return Ok(result_batch);
-            }
+    fn evaluate_mask(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        evaluate_and_mask(batch, &self.filters)
+    }
 
-            result_batch = filter.filter(&result_batch)?;
-        }
+    fn label(&self) -> String {
+        let labels: Vec<String> = self.filters.iter().map(|filter| filter.label()).collect();
+        format!("({})", labels.join(" and "))
+    }
 
-        Ok(result_batch)
+    /// Attribute each row to the first sub-filter that excludes it
+    fn evaluate_mask_with_exclusions(
+        &self,
+        batch: &RecordBatch,
+        log: &mut ExclusionLog,
+    ) -> Result<BooleanArray> {
+        and_mask(batch, &self.filters, Some(log))
     }
 
     fn required_columns(&self) -> std::collections::HashSet<String> {
@@ -166,56 +497,19 @@ impl BatchFilter for OrFilter {
             return ExcludeAllFilter.filter(batch);
         }
 
-        // Apply each filter and collect results
-        let mut all_masks = Vec::with_capacity(self.filters.len());
-
-        for filter in &self.filters {
-            // Create a mask for this filter
-            let result_batch = filter.filter(batch)?;
-
-            // If any filter accepts all rows, we're done
-            if result_batch.num_rows() == batch.num_rows() {
-                return Ok(result_batch);
-            }
-
-            // Otherwise, collect the mask
-            if result_batch.num_rows() > 0 {
-                // Create a mask representing which rows were kept
-                let row_mask = vec![false; batch.num_rows()];
-                // This would require tracking indices - simplified approach for now
-                // In a real implementation, you'd need to track which indices were kept
-                // and rebuild the full mask
-                all_masks.push(row_mask);
-            }
-        }
-
-        // Combine all masks with OR
-        // This is a simplified implementation - in practice you'd need to
-        // properly combine the masks based on indices
-        // For now, we'll process each filter separately and combine the results
+        let mask = evaluate_or_mask(batch, &self.filters)?;
+        filter_record_batch(batch, &mask)
+    }
 
-        // This is inefficient but correct - apply each filter and combine results
-        let mut filtered_batches = Vec::with_capacity(self.filters.len());
-        for filter in &self.filters {
-            let result = filter.filter(batch)?;
-            if result.num_rows() > 0 {
-                filtered_batches.push(result);
-            }
-        }
+    fn evaluate_mask(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        evaluate_or_mask(batch, &self.filters)
+    }
 
-        // Combine all filtered batches
-        if filtered_batches.is_empty() {
-            // No rows matched any filter
-            Ok(RecordBatch::new_empty(batch.schema()))
-        } else if filtered_batches.len() == 1 {
-            // Only one filter matched
-            return Ok(filtered_batches.remove(0));
-        } else {
-            // Multiple filters matched - need to concatenate and deduplicate
-            // This is complex and would require custom implementation
-            // For now, return the first non-empty batch (incomplete implementation)
-            return Ok(filtered_batches.remove(0));
-        }
+    /// A row is excluded only if every sub-filter rejects it, so exclusions
+    /// are attributed to the sub-filters together, under this label
+    fn label(&self) -> String {
+        let labels: Vec<String> = self.filters.iter().map(|filter| filter.label()).collect();
+        format!("({})", labels.join(" or "))
     }
 
     fn required_columns(&self) -> std::collections::HashSet<String> {
@@ -291,6 +585,10 @@ impl BatchFilter for NotFilter {
     fn required_columns(&self) -> std::collections::HashSet<String> {
         self.filter.required_columns()
     }
+
+    fn label(&self) -> String {
+        format!("not {}", self.filter.label())
+    }
 }
 
 /// Apply multiple filters to a batch in sequence
@@ -329,7 +627,7 @@ pub fn read_parquet_with_filter(
 ) -> Result<Vec<RecordBatch>> {
     // Read the file
     let batches = crate::utils::read_parquet::<std::collections::hash_map::RandomState>(
-        path, None, None, None, None,
+        path, None, None, None, None, None,
     )
     .with_context(|| {
         format!(
@@ -351,3 +649,140 @@ pub fn read_parquet_with_filter(
 
     Ok(filtered_batches)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::expr::{Expr, ExpressionFilter, LiteralValue};
+    use arrow::array::Int32Array;
+    use arrow::compute::{and, or};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Build a batch of random `a` and `b` values, the same for the same seed
+    fn random_batch(rows: usize, seed: u64) -> RecordBatch {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let a: Int32Array = (0..rows)
+            .map(|_| rng.random_bool(0.9).then(|| rng.random_range(0..10)))
+            .collect();
+        let b: Int32Array = (0..rows).map(|_| Some(rng.random_range(0..10))).collect();
+        RecordBatch::try_from_iter(vec![
+            ("a", Arc::new(a) as ArrayRef),
+            ("b", Arc::new(b) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    /// Evaluate every filter on all rows and combine the full masks
+    fn naive(
+        batch: &RecordBatch,
+        filters: &[Arc<dyn BatchFilter + Send + Sync>],
+        conj: bool,
+    ) -> BooleanArray {
+        // Nulls never select a row, so normalize each mask before combining
+        let full_mask = |filter: &Arc<dyn BatchFilter + Send + Sync>| -> BooleanArray {
+            let mask = filter.evaluate_mask(batch).unwrap();
+            mask.iter().map(|v| Some(v == Some(true))).collect()
+        };
+
+        let mut result = full_mask(&filters[0]);
+        for filter in &filters[1..] {
+            let mask = full_mask(filter);
+            result = if conj {
+                and(&result, &mask)
+            } else {
+                or(&result, &mask)
+            }
+            .unwrap();
+        }
+        result
+    }
+
+    #[test]
+    fn test_short_circuit_masks_match_naive_combination() {
+        let expr =
+            |e: Expr| Arc::new(ExpressionFilter::new(e)) as Arc<dyn BatchFilter + Send + Sync>;
+        let filters = vec![
+            expr(Expr::Gt("a".to_string(), LiteralValue::Int(6))),
+            expr(Expr::Lt("b".to_string(), LiteralValue::Int(5))),
+            expr(Expr::Eq("b".to_string(), LiteralValue::Int(3))),
+        ];
+
+        for seed in 0..20 {
+            let batch = random_batch(500, seed);
+            assert_eq!(
+                evaluate_and_mask(&batch, &filters).unwrap(),
+                naive(&batch, &filters, true),
+                "seed {seed}"
+            );
+            assert_eq!(
+                evaluate_or_mask(&batch, &filters).unwrap(),
+                naive(&batch, &filters, false),
+                "seed {seed}"
+            );
+
+            let or_filter = OrFilter::new(filters.clone());
+            assert_eq!(
+                or_filter.filter(&batch).unwrap(),
+                filter_record_batch(&batch, &naive(&batch, &filters, false)).unwrap(),
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_filter_with_exclusions() {
+        let expr =
+            |e: Expr| Arc::new(ExpressionFilter::new(e)) as Arc<dyn BatchFilter + Send + Sync>;
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "a",
+                Arc::new(Int32Array::from(vec![
+                    Some(1),
+                    Some(7),
+                    Some(8),
+                    None,
+                    Some(9),
+                ])) as ArrayRef,
+            ),
+            (
+                "b",
+                Arc::new(Int32Array::from(vec![1, 2, 6, 1, 3])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let a_gt_6 = expr(Expr::Gt("a".to_string(), LiteralValue::Int(6)));
+        let b_lt_5 = expr(Expr::Lt("b".to_string(), LiteralValue::Int(5)));
+        let b_eq_6 = expr(Expr::Eq("b".to_string(), LiteralValue::Int(6)));
+        let filter = AndFilter::new(vec![
+            a_gt_6.clone(),
+            Arc::new(OrFilter::new(vec![b_lt_5.clone(), b_eq_6])),
+            b_lt_5.clone(),
+        ]);
+
+        let mut log = ExclusionLog::new();
+        let filtered = filter_with_exclusions(&batch, &filter, &mut log).unwrap();
+        assert_eq!(filtered.num_rows(), 2);
+        filter_with_exclusions(&batch, &filter, &mut log).unwrap();
+
+        // The null value of `a` counts as excluded by the first filter
+        assert_eq!(log.rows(), 10);
+        assert_eq!(log.excluded_by(&a_gt_6.label()), 4);
+        assert_eq!(log.excluded_by(&b_lt_5.label()), 2);
+        assert_eq!(log.exclusions().len(), 3);
+        assert_eq!(log.exclusions()[1].1, 0);
+        assert!(log.exclusions()[1].0.contains(" or "));
+        assert_eq!(log.kept(), 4);
+        assert!(log.to_string().ends_with("4 rows kept"));
+    }
+
+    #[test]
+    fn test_default_evaluate_mask_tracks_rows() {
+        let batch = random_batch(100, 42);
+        let filter = ExpressionFilter::new(Expr::Gt("b".to_string(), LiteralValue::Int(4)));
+        assert_eq!(
+            mask_from_filtered_rows(&filter, &batch).unwrap(),
+            naive(&batch, &[Arc::new(filter.clone())], true)
+        );
+    }
+}
@@ -124,6 +124,17 @@ impl BatchFilter for DateRangeFilter {
         cols.insert(self.date_column.clone());
         cols
     }
+
+    fn label(&self) -> String {
+        let bound =
+            |date: Option<NaiveDate>| date.map_or_else(String::new, |date| date.to_string());
+        format!(
+            "date range {}..={} ({})",
+            bound(self.start_date),
+            bound(self.end_date),
+            self.date_column
+        )
+    }
 }
 
 /// Create an expression filter for a date range
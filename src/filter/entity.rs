@@ -0,0 +1,332 @@
+//! Filters over deserialized entities
+//!
+//! `EntityFilter` is a composable predicate over domain entities such as
+//! [`Individual`], for defining a cohort after deserialization. Filters are
+//! combined with [`EntityFilterExt::and`], [`EntityFilterExt::or`] and
+//! [`EntityFilterExt::not`]:
+//!
+//! ```ignore
+//! let cohort = born_between(start, end).and(gender_is(Gender::Female)).and(has_parent());
+//! let selected = collection.filter_entities(&cohort);
+//! ```
+//!
+//! # Pushdown
+//!
+//! Filters that compare fields against constants can also be expressed as an
+//! [`Expr`] over the registry columns (see [`EntityFilter::to_expr`]), so
+//! [`EntityToBatchAdapter`](crate::filter::adapter::EntityToBatchAdapter)
+//! can apply them to record batches without deserializing. The eligible
+//! filters are:
+//!
+//! * [`born_between`], on `FOED_DAG` (a `Date32` column)
+//! * [`gender_is`] with `Male` or `Female`, on the `KOEN` codes, matched
+//!   like `Gender::from` decodes them (trimmed and case-insensitive)
+//! * [`has_parent`], on `MOR_ID` and `FAR_ID`
+//! * [`EntityFilterExt::and`] and [`EntityFilterExt::or`] of eligible filters
+//!
+//! Negations, `gender_is(Gender::Unknown)` and custom predicates such as
+//! [`IndividualFilter`](crate::filter::adapter::IndividualFilter) are not
+//! eligible and fall back to filtering deserialized entities. Negation is
+//! excluded because a missing value fails a batch comparison but passes the
+//! negated entity predicate.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+use chrono::NaiveDate;
+
+use crate::filter::expr::{Expr, LiteralValue, StringPattern};
+use crate::models::{Gender, Individual};
+
+/// Registry column holding the birth date
+const BIRTH_DATE_COLUMN: &str = "FOED_DAG";
+/// Registry column holding the gender code
+const GENDER_COLUMN: &str = "KOEN";
+/// Registry column holding the mother's PNR
+const MOTHER_COLUMN: &str = "MOR_ID";
+/// Registry column holding the father's PNR
+const FATHER_COLUMN: &str = "FAR_ID";
+
+/// Convert a date to a `Date32` literal (days since the Unix epoch)
+fn date_literal(date: NaiveDate) -> LiteralValue {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default();
+    LiteralValue::Date(date.signed_duration_since(epoch).num_days() as i32)
+}
+
+/// A predicate over deserialized entities
+pub trait EntityFilter<T>: Debug + Send + Sync {
+    /// Check whether an entity passes the filter
+    fn matches(&self, entity: &T) -> bool;
+
+    /// Names of the entity fields the filter reads
+    fn required_fields(&self) -> HashSet<String> {
+        HashSet::new()
+    }
+
+    /// Translate the filter into an equivalent expression over registry columns
+    ///
+    /// # Returns
+    /// An expression selecting exactly the rows whose entities match, or
+    /// `None` if the filter cannot be pushed down to record batches
+    fn to_expr(&self) -> Option<Expr> {
+        None
+    }
+}
+
+/// Combinators for entity filters
+pub trait EntityFilterExt<T>: EntityFilter<T> + Sized {
+    /// Match entities that pass both filters
+    fn and<F: EntityFilter<T>>(self, other: F) -> EntityAnd<Self, F> {
+        EntityAnd(self, other)
+    }
+
+    /// Match entities that pass either filter
+    fn or<F: EntityFilter<T>>(self, other: F) -> EntityOr<Self, F> {
+        EntityOr(self, other)
+    }
+
+    /// Match entities that do not pass the filter
+    fn not(self) -> EntityNot<Self> {
+        EntityNot(self)
+    }
+}
+
+impl<T, F: EntityFilter<T>> EntityFilterExt<T> for F {}
+
+impl<T> EntityFilter<T> for Box<dyn EntityFilter<T>> {
+    fn matches(&self, entity: &T) -> bool {
+        self.as_ref().matches(entity)
+    }
+
+    fn required_fields(&self) -> HashSet<String> {
+        self.as_ref().required_fields()
+    }
+
+    fn to_expr(&self) -> Option<Expr> {
+        self.as_ref().to_expr()
+    }
+}
+
+/// Conjunction of two entity filters
+#[derive(Debug, Clone)]
+pub struct EntityAnd<A, B>(pub A, pub B);
+
+impl<T, A: EntityFilter<T>, B: EntityFilter<T>> EntityFilter<T> for EntityAnd<A, B> {
+    fn matches(&self, entity: &T) -> bool {
+        self.0.matches(entity) && self.1.matches(entity)
+    }
+
+    fn required_fields(&self) -> HashSet<String> {
+        let mut fields = self.0.required_fields();
+        fields.extend(self.1.required_fields());
+        fields
+    }
+
+    fn to_expr(&self) -> Option<Expr> {
+        Some(Expr::And(vec![self.0.to_expr()?, self.1.to_expr()?]))
+    }
+}
+
+/// Disjunction of two entity filters
+#[derive(Debug, Clone)]
+pub struct EntityOr<A, B>(pub A, pub B);
+
+impl<T, A: EntityFilter<T>, B: EntityFilter<T>> EntityFilter<T> for EntityOr<A, B> {
+    fn matches(&self, entity: &T) -> bool {
+        self.0.matches(entity) || self.1.matches(entity)
+    }
+
+    fn required_fields(&self) -> HashSet<String> {
+        let mut fields = self.0.required_fields();
+        fields.extend(self.1.required_fields());
+        fields
+    }
+
+    fn to_expr(&self) -> Option<Expr> {
+        Some(Expr::Or(vec![self.0.to_expr()?, self.1.to_expr()?]))
+    }
+}
+
+/// Negation of an entity filter
+#[derive(Debug, Clone)]
+pub struct EntityNot<F>(pub F);
+
+impl<T, F: EntityFilter<T>> EntityFilter<T> for EntityNot<F> {
+    fn matches(&self, entity: &T) -> bool {
+        !self.0.matches(entity)
+    }
+
+    fn required_fields(&self) -> HashSet<String> {
+        self.0.required_fields()
+    }
+}
+
+/// Matches individuals born within a date range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BornBetween {
+    /// First birth date included
+    pub start: NaiveDate,
+    /// Last birth date included
+    pub end: NaiveDate,
+}
+
+impl EntityFilter<Individual> for BornBetween {
+    fn matches(&self, individual: &Individual) -> bool {
+        individual
+            .birth_date
+            .is_some_and(|birth_date| (self.start..=self.end).contains(&birth_date))
+    }
+
+    fn required_fields(&self) -> HashSet<String> {
+        HashSet::from(["birth_date".to_string()])
+    }
+
+    fn to_expr(&self) -> Option<Expr> {
+        Some(Expr::And(vec![
+            Expr::GtEq(BIRTH_DATE_COLUMN.to_string(), date_literal(self.start)),
+            Expr::LtEq(BIRTH_DATE_COLUMN.to_string(), date_literal(self.end)),
+        ]))
+    }
+}
+
+/// Matches individuals of a gender
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenderIs(pub Gender);
+
+impl EntityFilter<Individual> for GenderIs {
+    fn matches(&self, individual: &Individual) -> bool {
+        let gender = individual
+            .gender
+            .as_deref()
+            .map_or(Gender::Unknown, Gender::from);
+        gender == self.0
+    }
+
+    fn required_fields(&self) -> HashSet<String> {
+        HashSet::from(["gender".to_string()])
+    }
+
+    fn to_expr(&self) -> Option<Expr> {
+        // The codes `Gender::from` accepts, ignoring surrounding whitespace
+        // and case as it does; `\s` and `(?i)` follow the same Unicode rules
+        // as `str::trim` and `str::to_lowercase` for these codes
+        let pattern = match self.0 {
+            Gender::Male => r"^\s*(?i:m|male|1)\s*$",
+            Gender::Female => r"^\s*(?i:f|female|k|2)\s*$",
+            Gender::Unknown => return None,
+        };
+        Some(Expr::Matches {
+            column: GENDER_COLUMN.to_string(),
+            pattern: StringPattern::regex(pattern).ok()?,
+        })
+    }
+}
+
+/// Matches individuals with a known mother or father
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HasParent;
+
+impl EntityFilter<Individual> for HasParent {
+    fn matches(&self, individual: &Individual) -> bool {
+        individual.mother_pnr.is_some() || individual.father_pnr.is_some()
+    }
+
+    fn required_fields(&self) -> HashSet<String> {
+        HashSet::from(["mother_pnr".to_string(), "father_pnr".to_string()])
+    }
+
+    fn to_expr(&self) -> Option<Expr> {
+        Some(Expr::Or(vec![
+            Expr::IsNotNull(MOTHER_COLUMN.to_string()),
+            Expr::IsNotNull(FATHER_COLUMN.to_string()),
+        ]))
+    }
+}
+
+/// Match individuals born between `start` and `end`, both included
+#[must_use]
+pub const fn born_between(start: NaiveDate, end: NaiveDate) -> BornBetween {
+    BornBetween { start, end }
+}
+
+/// Match individuals of the given gender
+///
+/// Gender codes are decoded with `Gender::from`; individuals without a
+/// gender match `Gender::Unknown`.
+#[must_use]
+pub const fn gender_is(gender: Gender) -> GenderIs {
+    GenderIs(gender)
+}
+
+/// Match individuals with a known mother or father
+#[must_use]
+pub const fn has_parent() -> HasParent {
+    HasParent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::adapter::IndividualFilter;
+
+    #[test]
+    fn test_entity_filter_combinators() {
+        let date = |y| NaiveDate::from_ymd_opt(y, 1, 1).unwrap();
+        let mut child = Individual::new("1".to_string(), Some(date(2010)));
+        child.gender = Some("K".to_string());
+        child.mother_pnr = Some("2".to_string());
+        let mut mother = Individual::new("2".to_string(), Some(date(1980)));
+        mother.gender = Some("K".to_string());
+
+        let cohort = born_between(date(2000), date(2020))
+            .and(gender_is(Gender::Female))
+            .and(has_parent());
+        assert!(cohort.matches(&child));
+        assert!(!cohort.matches(&mother));
+        assert_eq!(cohort.required_fields().len(), 4);
+
+        let either = has_parent().or(born_between(date(1970), date(1990)));
+        assert!(either.matches(&mother));
+        assert!(has_parent().not().matches(&mother));
+
+        let custom: Box<dyn EntityFilter<Individual>> = Box::new(IndividualFilter::new(
+            |individual: &Individual| individual.pnr == "2",
+            ["pnr".to_string()],
+        ));
+        assert!(custom.and(gender_is(Gender::Female)).matches(&mother));
+    }
+
+    #[test]
+    fn test_gender_pushdown_matches_decoding() {
+        use crate::filter::expr::ExpressionFilter;
+        use arrow::array::StringArray;
+        use arrow::datatypes::{DataType, Field, Schema};
+        use arrow::record_batch::RecordBatch;
+        use std::sync::Arc;
+
+        let codes = [
+            "M", "m", "1", " 1 ", "male", "Male", "MALE", "K", "k", "F", "f", "2", "female",
+            "Female ", "\u{212A}", "", "0", "9", "mand", "11", "m f",
+        ];
+        let column: StringArray = codes.iter().map(Some).collect();
+        let schema = Schema::new(vec![Field::new(GENDER_COLUMN, DataType::Utf8, true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(column)]).unwrap();
+
+        for gender in [Gender::Male, Gender::Female] {
+            let filter = gender_is(gender);
+            let mask = ExpressionFilter::new(filter.to_expr().unwrap())
+                .evaluate(&batch)
+                .unwrap();
+            for (row, code) in codes.iter().enumerate() {
+                let mut individual = Individual::new(row.to_string(), None);
+                individual.gender = Some((*code).to_string());
+                assert_eq!(
+                    mask.value(row),
+                    filter.matches(&individual),
+                    "{gender:?} on {code:?}"
+                );
+            }
+        }
+        assert!(gender_is(Gender::Unknown).to_expr().is_none());
+    }
+}
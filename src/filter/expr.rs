@@ -56,6 +56,14 @@ pub enum Expr {
     /// Column value ends with a suffix
     EndsWith(String, String),
 
+    /// String column value matches a pattern; null values never match
+    Matches {
+        /// The column to match against
+        column: String,
+        /// The pattern to match
+        pattern: StringPattern,
+    },
+
     /// Logical AND of expressions
     And(Vec<Expr>),
 
@@ -72,6 +80,46 @@ pub enum Expr {
     AlwaysFalse,
 }
 
+/// A string pattern used by [`Expr::Matches`]
+///
+/// Regular expressions are compiled when the pattern is built, so a filter
+/// compiles its pattern once rather than per batch or per row.
+#[derive(Debug, Clone)]
+pub enum StringPattern {
+    /// Value starts with the given prefix
+    Prefix(String),
+    /// Value ends with the given suffix
+    Suffix(String),
+    /// Value contains the given substring
+    Contains(String),
+    /// Value matches the given regular expression (anywhere, unless anchored)
+    Regex(regex::Regex),
+}
+
+impl StringPattern {
+    /// Build a regular expression pattern
+    ///
+    /// # Errors
+    /// Returns an error if `pattern` is not a valid regular expression
+    pub fn regex(pattern: &str) -> Result<Self> {
+        let regex = regex::Regex::new(pattern).map_err(|e| {
+            ParquetReaderError::FilterError(format!("Invalid regex '{pattern}': {e}"))
+        })?;
+        Ok(Self::Regex(regex))
+    }
+
+    /// Whether a value matches the pattern
+    #[must_use]
+    pub fn is_match(&self, value: &str) -> bool {
+        match self {
+            Self::Prefix(prefix) => value.starts_with(prefix.as_str()),
+            Self::Suffix(suffix) => value.ends_with(suffix.as_str()),
+            Self::Contains(substring) => value.contains(substring.as_str()),
+            Self::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
 /// Represents a literal value that can be used in filter expressions
 #[derive(Debug, Clone)]
 pub enum LiteralValue {
@@ -121,7 +169,8 @@ impl Expr {
             | Self::IsNotNull(col)
             | Self::Contains(col, _)
             | Self::StartsWith(col, _)
-            | Self::EndsWith(col, _) => {
+            | Self::EndsWith(col, _)
+            | Self::Matches { column: col, .. } => {
                 columns.insert(col.clone());
             }
             Self::And(exprs) | Self::Or(exprs) => {
@@ -299,6 +348,10 @@ impl ExpressionFilter {
             Expr::EndsWith(col_name, suffix) => {
                 self.evaluate_ends_with_expression(batch, col_name, suffix)
             }
+
+            Expr::Matches { column, pattern } => {
+                self.evaluate_matches_expression(batch, column, pattern)
+            }
         }
     }
 
@@ -495,6 +548,9 @@ impl ExpressionFilter {
         // Create a boolean mask based on greater than comparison using vectorized operations
         match literal_value {
             LiteralValue::Int(n) => self.evaluate_int_gt(column, col_name, *n),
+            LiteralValue::Date(days) => {
+                Self::evaluate_date_cmp(column, col_name, *days, arrow::compute::kernels::cmp::gt)
+            }
             _ => Err(anyhow::anyhow!(
                 "Unsupported literal type for greater than comparison: {literal_value:?}"
             )),
@@ -517,12 +573,32 @@ impl ExpressionFilter {
         // Create a boolean mask based on less than comparison using vectorized operations
         match literal_value {
             LiteralValue::Int(n) => self.evaluate_int_lt(column, col_name, *n),
+            LiteralValue::Date(days) => {
+                Self::evaluate_date_cmp(column, col_name, *days, arrow::compute::kernels::cmp::lt)
+            }
             _ => Err(anyhow::anyhow!(
                 "Unsupported literal type for less than comparison: {literal_value:?}"
             )),
         }
     }
 
+    /// Evaluates a comparison of a `Date32` column against a date literal
+    fn evaluate_date_cmp(
+        column: &arrow::array::ArrayRef,
+        col_name: &str,
+        days: i32,
+        cmp: fn(
+            &dyn arrow::array::Datum,
+            &dyn arrow::array::Datum,
+        ) -> std::result::Result<BooleanArray, arrow::error::ArrowError>,
+    ) -> Result<BooleanArray> {
+        if column.data_type() != &arrow::datatypes::DataType::Date32 {
+            return Err(anyhow::anyhow!("Column {col_name} is not a date array"));
+        }
+        cmp(column, &arrow::array::Date32Array::new_scalar(days))
+            .map_err(|e| ParquetReaderError::FilterError(e.to_string()).into())
+    }
+
     /// Evaluates greater than comparison for integer types
     fn evaluate_int_gt(
         &self,
@@ -822,10 +898,91 @@ impl ExpressionFilter {
 
         Err(anyhow::anyhow!("Column {col_name} is not a string array"))
     }
+
+    /// Evaluates a `MATCHES` expression against a string column
+    fn evaluate_matches_expression(
+        &self,
+        batch: &RecordBatch,
+        col_name: &str,
+        pattern: &StringPattern,
+    ) -> Result<BooleanArray> {
+        // Get the column
+        let col_idx = batch.schema().index_of(col_name).map_err(|_| {
+            ParquetReaderError::FilterError(format!("Column {col_name} not found in batch"))
+        })?;
+        let column = batch.column(col_idx);
+
+        if let Some(str_array) = column.as_any().downcast_ref::<StringArray>() {
+            // Null values never match
+            let matches: BooleanArray = str_array
+                .iter()
+                .map(|value| Some(value.is_some_and(|v| pattern.is_match(v))))
+                .collect();
+            return Ok(matches);
+        }
+
+        Err(anyhow::anyhow!("Column {col_name} is not a string array"))
+    }
+}
+
+impl ExpressionFilter {
+    /// Evaluate the filter expression to a boolean mask
+    ///
+    /// This always uses the generic expression evaluator, without the
+    /// single-column equality fast path taken by [`BatchFilter::filter`].
+    ///
+    /// # Errors
+    /// Returns an error if expression evaluation fails
+    pub fn evaluate(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        self.evaluate_expr(batch, &self.expr)
+    }
+
+    /// Fast path for a top-level `column == literal` expression
+    ///
+    /// Compares the typed column against a scalar with Arrow's `eq` kernel and
+    /// filters the batch with Arrow's `filter_record_batch` kernel, avoiding a
+    /// full-length literal array. Returns `None` when the expression, column
+    /// type or literal type is not covered, so the caller falls back to the
+    /// generic path (which also reports missing columns).
+    fn try_eq_fast_path(&self, batch: &RecordBatch) -> Result<Option<RecordBatch>> {
+        use arrow::array::Date32Array;
+        use arrow::compute::kernels::cmp::eq;
+        use arrow::datatypes::DataType;
+
+        let Expr::Eq(col_name, literal_value) = &self.expr else {
+            return Ok(None);
+        };
+        let Ok(col_idx) = batch.schema().index_of(col_name) else {
+            return Ok(None);
+        };
+        let column = batch.column(col_idx);
+
+        let mask = match (column.data_type(), literal_value) {
+            (DataType::Utf8, LiteralValue::String(s)) => eq(column, &StringArray::new_scalar(s)),
+            (DataType::Int32, LiteralValue::Int(n)) => match i32::try_from(*n) {
+                Ok(n) => eq(column, &Int32Array::new_scalar(n)),
+                // A value outside the i32 range never matches
+                Err(_) => return Ok(Some(RecordBatch::new_empty(batch.schema()))),
+            },
+            (DataType::Int64, LiteralValue::Int(n)) => eq(column, &Int64Array::new_scalar(*n)),
+            (DataType::Date32, LiteralValue::Date(d)) => eq(column, &Date32Array::new_scalar(*d)),
+            _ => return Ok(None),
+        }
+        .map_err(|e| ParquetReaderError::FilterError(e.to_string()))?;
+
+        let filtered = arrow::compute::filter_record_batch(batch, &mask)
+            .map_err(|e| ParquetReaderError::FilterError(e.to_string()))?;
+        Ok(Some(filtered))
+    }
 }
 
 impl BatchFilter for ExpressionFilter {
     fn filter(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        // Single-column equality is common enough to warrant a kernel-only path
+        if let Some(filtered) = self.try_eq_fast_path(batch)? {
+            return Ok(filtered);
+        }
+
         // Evaluate the expression
         let mask = self.evaluate_expr(batch, &self.expr)?;
 
@@ -836,6 +993,14 @@ impl BatchFilter for ExpressionFilter {
     fn required_columns(&self) -> HashSet<String> {
         self.expr.required_columns()
     }
+
+    fn label(&self) -> String {
+        format!("{:?}", self.expr)
+    }
+
+    fn evaluate_mask(&self, batch: &RecordBatch) -> Result<BooleanArray> {
+        self.evaluate(batch)
+    }
 }
 
 /// Helper function to create equality filter for a column
@@ -864,6 +1029,25 @@ pub fn in_filter(column: &str, values: Vec<LiteralValue>) -> Expr {
     Expr::In(column.to_string(), values)
 }
 
+/// Helper function to create a prefix filter for a string column
+///
+/// Handy for ICD-10 code ranges, e.g. `starts_with_filter("C_ADIAG", "DC")`
+/// for all cancer diagnoses.
+///
+/// # Arguments
+/// * `column` - The column name
+/// * `prefix` - The prefix to match
+///
+/// # Returns
+/// An expression that matches records where the column starts with `prefix`
+#[must_use]
+pub fn starts_with_filter(column: &str, prefix: &str) -> Expr {
+    Expr::Matches {
+        column: column.to_string(),
+        pattern: StringPattern::Prefix(prefix.to_string()),
+    }
+}
+
 /// Helper function to create equality filter for PNRs
 ///
 /// # Arguments
@@ -880,3 +1064,89 @@ pub fn create_pnr_filter<S: ::std::hash::BuildHasher>(pnrs: &HashSet<String, S>)
 
     Expr::In("PNR".to_string(), values)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_eq_fast_path_matches_generic_path() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    None,
+                    Some("b"),
+                    Some("a"),
+                ])) as arrow::array::ArrayRef,
+            ),
+            (
+                "YEAR",
+                Arc::new(Int32Array::from(vec![
+                    Some(2019),
+                    Some(2019),
+                    None,
+                    Some(2020),
+                ])),
+            ),
+        ])
+        .unwrap();
+
+        for expr in [
+            eq_filter("PNR", LiteralValue::String("a".to_string())),
+            eq_filter("YEAR", LiteralValue::Int(2019)),
+            eq_filter("YEAR", LiteralValue::Int(i64::MAX)),
+        ] {
+            let filter = ExpressionFilter::new(expr);
+            let fast = filter.try_eq_fast_path(&batch).unwrap().unwrap();
+            let generic = filter_record_batch(&batch, &filter.evaluate(&batch).unwrap()).unwrap();
+            assert_eq!(fast, generic);
+        }
+
+        let unsupported = ExpressionFilter::new(eq_filter("YEAR", LiteralValue::Boolean(true)));
+        assert!(unsupported.try_eq_fast_path(&batch).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_matches_expression() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "C_ADIAG",
+            Arc::new(StringArray::from(vec![
+                Some("DC509"),
+                None,
+                Some("DI21"),
+                Some("DC19"),
+            ])) as arrow::array::ArrayRef,
+        )])
+        .unwrap();
+
+        let mask = |expr: Expr| -> Vec<bool> {
+            let mask = ExpressionFilter::new(expr).evaluate(&batch).unwrap();
+            mask.iter().map(|v| v.unwrap()).collect()
+        };
+        let matches = |pattern| Expr::Matches {
+            column: "C_ADIAG".to_string(),
+            pattern,
+        };
+
+        assert_eq!(
+            mask(starts_with_filter("C_ADIAG", "DC")),
+            vec![true, false, false, true]
+        );
+        assert_eq!(
+            mask(matches(StringPattern::Suffix("21".to_string()))),
+            vec![false, false, true, false]
+        );
+        assert_eq!(
+            mask(matches(StringPattern::Contains("C5".to_string()))),
+            vec![true, false, false, false]
+        );
+        assert_eq!(
+            mask(matches(StringPattern::regex("^DC[0-4]").unwrap())),
+            vec![false, false, false, true]
+        );
+        assert!(StringPattern::regex("(").is_err());
+    }
+}
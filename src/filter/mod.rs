@@ -6,9 +6,15 @@
 // Core modules
 pub mod core;
 pub mod date;
+pub mod entity;
 pub mod error;
 pub mod expr;
 pub mod pnr;
+pub mod pnr_columns;
+pub mod pnr_normalization;
+pub mod pnr_set;
+pub mod pseudonym;
+pub mod sample;
 
 // Generic filtering framework
 pub mod adapter;
@@ -32,20 +38,41 @@ mod r#async;
 
 // Re-export the most commonly used types and functions
 pub use self::core::{
-    AndFilter, BatchFilter, ExcludeAllFilter, IncludeAllFilter, NotFilter, OrFilter,
-    filter_record_batch, read_parquet_with_filter,
+    AndFilter, BatchFilter, ExclusionLog, ExcludeAllFilter, IncludeAllFilter, NotFilter, OrFilter,
+    evaluate_and_mask, evaluate_or_mask, filter_record_batch, filter_with_exclusions,
+    read_parquet_with_filter,
 };
 
 pub use self::expr::{
-    Expr, ExpressionFilter, LiteralValue, create_pnr_filter, eq_filter, in_filter,
+    Expr, ExpressionFilter, LiteralValue, StringPattern, create_pnr_filter, eq_filter, in_filter,
+    starts_with_filter,
 };
 
 pub use self::pnr::{
-    FilterPlan, PnrFilter, apply_filter_plan, build_filter_plan, join_and_filter_by_pnr,
+    FilterPlan, JoinCardinality, PnrFilter, apply_filter_plan, build_filter_plan,
+    join_and_filter_by_pnr,
 };
 
 pub use self::date::{DateRangeFilter, add_year_column, filter_by_year};
 
+pub use self::entity::{
+    BornBetween, EntityAnd, EntityFilter, EntityFilterExt, EntityNot, EntityOr, GenderIs,
+    HasParent, born_between, gender_is, has_parent,
+};
+
+pub use self::pnr_columns::PnrColumnSet;
+
+pub use self::pnr_normalization::{PnrNormalization, normalize_pnr};
+
+pub use self::pnr_set::PnrSet;
+
+pub use self::pseudonym::{
+    RELATED_PNR_COLUMNS, pseudonym, pseudonymize, pseudonymize_columns,
+    pseudonymize_columns_with_mapping, pseudonymize_with_mapping,
+};
+
+pub use self::sample::{reservoir_sample, sample_by_pnr, sample_fraction};
+
 // Re-export error handling utilities
 pub use self::error::{
     FilterResultExt, column_not_found, column_type_error, filter_err, filter_path_err,
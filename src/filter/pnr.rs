@@ -4,6 +4,7 @@
 //! by Danish personal identification numbers (PNR).
 
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Context;
@@ -14,16 +15,32 @@ use rayon::prelude::*;
 
 use crate::error::{ParquetReaderError, Result};
 use crate::filter::core::{BatchFilter, filter_record_batch};
+use crate::filter::error::column_type_error;
 use crate::filter::expr::{Expr, ExpressionFilter, LiteralValue};
+use crate::filter::pnr_columns::PnrColumnSet;
+use crate::filter::pnr_normalization::PnrNormalization;
+use crate::registry::PnrFilterRequirement;
+use crate::registry::factory::registry_from_name;
+use crate::utils::arrow::{DuplicatePolicy, build_lookup, concat_coerced};
+use crate::utils::io::paths::find_registry_dir;
 
 /// A filter that includes only rows with matching PNR values
+///
+/// PNRs are compared after [`PnrNormalization`], canonical by default, so
+/// `010180-1234` in the filter matches `0101801234` in the data.
 #[derive(Debug, Clone)]
 pub struct PnrFilter {
-    /// The set of PNR values to include
+    /// The set of PNR values to include, normalized
     pnr_values: HashSet<String>,
 
+    /// The normalization applied to filter values and column values
+    normalization: PnrNormalization,
+
     /// The name of the PNR column
     pnr_column: String,
+
+    /// Related-person PNR columns that also count as a match when present
+    related_columns: Vec<String>,
 }
 
 impl PnrFilter {
@@ -39,10 +56,59 @@ impl PnrFilter {
     pub fn new<S: ::std::hash::BuildHasher>(
         pnr_values: &HashSet<String, S>,
         pnr_column: Option<String>,
+    ) -> Self {
+        Self::with_normalization(pnr_values, pnr_column, PnrNormalization::default())
+    }
+
+    /// Create a new PNR filter with an explicit PNR normalization
+    ///
+    /// # Arguments
+    /// * `pnr_values` - The set of PNR values to include
+    /// * `pnr_column` - The name of the PNR column (defaults to "PNR")
+    /// * `normalization` - How filter and column PNRs are normalized before comparison
+    ///
+    /// # Returns
+    /// A new PNR filter
+    #[must_use]
+    pub fn with_normalization<S: ::std::hash::BuildHasher>(
+        pnr_values: &HashSet<String, S>,
+        pnr_column: Option<String>,
+        normalization: PnrNormalization,
     ) -> Self {
         Self {
-            pnr_values: pnr_values.iter().cloned().collect(),
+            pnr_values: normalization.apply_set(pnr_values),
+            normalization,
             pnr_column: pnr_column.unwrap_or_else(|| "PNR".to_string()),
+            related_columns: Vec::new(),
+        }
+    }
+
+    /// Get the PNR normalization of the filter
+    #[must_use]
+    pub const fn normalization(&self) -> PnrNormalization {
+        self.normalization
+    }
+
+    /// Create a PNR filter over all columns of a PNR column set
+    ///
+    /// A row is kept if its primary PNR or any related-person PNR present in
+    /// the batch is in the filter, e.g. a BEF row whose `MOR_ID` is a
+    /// selected mother.
+    ///
+    /// # Arguments
+    /// * `pnr_values` - The set of PNR values to include
+    /// * `columns` - The registry's PNR columns
+    ///
+    /// # Returns
+    /// A new PNR filter
+    #[must_use]
+    pub fn for_columns<S: ::std::hash::BuildHasher>(
+        pnr_values: &HashSet<String, S>,
+        columns: &PnrColumnSet,
+    ) -> Self {
+        Self {
+            related_columns: columns.related.clone(),
+            ..Self::new(pnr_values, Some(columns.primary.clone()))
         }
     }
 
@@ -98,7 +164,7 @@ impl PnrFilter {
                 let in_filter = if pnr_array.is_null(i) {
                     false
                 } else {
-                    pnr_filter.contains(pnr_array.value(i))
+                    pnr_filter.contains(self.normalization.apply(pnr_array.value(i)).as_ref())
                 };
                 mask_values.push(in_filter);
             }
@@ -109,9 +175,9 @@ impl PnrFilter {
             // First pass: count occurrences in the batch
             for i in 0..pnr_array.len() {
                 if !pnr_array.is_null(i) {
-                    let pnr = pnr_array.value(i);
-                    if pnr_filter.contains(pnr) {
-                        *pnr_batch_counts.entry(pnr.to_string()).or_insert(0) += 1;
+                    let pnr = self.normalization.apply(pnr_array.value(i));
+                    if pnr_filter.contains(pnr.as_ref()) {
+                        *pnr_batch_counts.entry(pnr.into_owned()).or_insert(0) += 1;
                     }
                 }
             }
@@ -121,7 +187,8 @@ impl PnrFilter {
                 let in_filter = if pnr_array.is_null(i) {
                     false
                 } else {
-                    pnr_batch_counts.contains_key(pnr_array.value(i))
+                    pnr_batch_counts
+                        .contains_key(self.normalization.apply(pnr_array.value(i)).as_ref())
                 };
                 mask_values.push(in_filter);
             }
@@ -149,7 +216,19 @@ impl BatchFilter for PnrFilter {
             })?;
 
         // Create a boolean mask using SIMD-accelerated operations where possible
-        let filter_mask = self.create_pnr_mask(pnr_array, &self.pnr_values)?;
+        let mut filter_mask = self.create_pnr_mask(pnr_array, &self.pnr_values)?;
+
+        // Rows also match on any related-person column present in the batch
+        for column in &self.related_columns {
+            let Some(related) = batch.column_by_name(column) else {
+                continue;
+            };
+            let Some(related) = related.as_any().downcast_ref::<StringArray>() else {
+                return column_type_error(column, "string");
+            };
+            let related_mask = self.create_pnr_mask(related, &self.pnr_values)?;
+            filter_mask = arrow::compute::or(&filter_mask, &related_mask)?;
+        }
 
         // Apply the filter to all columns
         filter_record_batch(batch, &filter_mask)
@@ -160,6 +239,10 @@ impl BatchFilter for PnrFilter {
         cols.insert(self.pnr_column.clone());
         cols
     }
+
+    fn label(&self) -> String {
+        format!("PNR ({})", self.pnr_column)
+    }
 }
 
 /// Create an expression filter for PNR values
@@ -187,10 +270,30 @@ pub fn create_pnr_expression_filter<S: ::std::hash::BuildHasher>(
     ExpressionFilter::new(expr)
 }
 
+/// How join keys relate to PNRs in the PNR batch of a join
+///
+/// [`join_and_filter_by_pnr`] is a semi-join: it keeps each row of the
+/// joined batch at most once, so no cardinality multiplies rows. The
+/// cardinality states the mapping the caller expects, and the join fails if
+/// the PNR batch contradicts it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JoinCardinality {
+    /// Each key belongs to one PNR, which may have many keys, as for the
+    /// LPR `RECNUM`
+    #[default]
+    ManyToOne,
+    /// Each PNR has one key, which may belong to many PNRs, as for a family ID
+    OneToMany,
+    /// Keys and PNRs map to any number of each other; nothing is checked
+    ManyToMany,
+}
+
 /// Join two record batches on a column and filter by PNR
 ///
 /// This function joins a batch with PNR column to another batch that
-/// needs to be filtered by PNR but doesn't contain PNR directly.
+/// needs to be filtered by PNR but doesn't contain PNR directly. A row of
+/// `join_batch` is kept once if its key belongs to any PNR passing the
+/// filter; see [`JoinCardinality`].
 ///
 /// # Arguments
 /// * `pnr_batch` - Batch containing PNR column
@@ -198,18 +301,21 @@ pub fn create_pnr_expression_filter<S: ::std::hash::BuildHasher>(
 /// * `join_batch` - Batch to filter
 /// * `join_column` - Name of the join column in both batches
 /// * `pnr_filter` - Optional set of PNR values to filter by
+/// * `cardinality` - The expected mapping of keys to PNRs in `pnr_batch`
 ///
 /// # Returns
 /// A filtered batch with rows from `join_batch` that match the PNR filter
 ///
 /// # Errors
-/// Returns an error if joining or filtering fails
+/// Returns an error if joining or filtering fails, or if `pnr_batch` maps
+/// keys and PNRs other than `cardinality` allows
 pub fn join_and_filter_by_pnr(
     pnr_batch: &RecordBatch,
     pnr_column: &str,
     join_batch: &RecordBatch,
     join_column: &str,
     pnr_filter: Option<&HashSet<String>>,
+    cardinality: JoinCardinality,
 ) -> Result<RecordBatch> {
     // Locate the columns
     let pnr_idx = pnr_batch
@@ -260,8 +366,38 @@ pub fn join_and_filter_by_pnr(
             ))
         })?;
 
-    // Create a map of join key to PNR
-    let mut join_to_pnr = HashMap::with_capacity(pnr_batch.num_rows());
+    // Collect the join keys of the PNRs passing the filter, compared in
+    // canonical form as by `PnrFilter`
+    let normalization = PnrNormalization::default();
+    let pnr_filter = pnr_filter.map(|filter| normalization.apply_set(filter));
+    let mut join_keys = HashSet::with_capacity(pnr_batch.num_rows());
+
+    // Every row is checked against the cardinality, including those
+    // filtered out
+    let pnr_batches = std::slice::from_ref(pnr_batch);
+    match cardinality {
+        JoinCardinality::ManyToOne => {
+            build_lookup(pnr_batches, join_column, pnr_column, DuplicatePolicy::Error).map_err(
+                |e| {
+                    ParquetReaderError::ValidationError(format!(
+                        "{e}, which JoinCardinality::ManyToOne does not allow"
+                    ))
+                },
+            )?;
+        }
+        JoinCardinality::OneToMany => {
+            // The error of the lookup names the PNR, so it is not passed on
+            build_lookup(pnr_batches, pnr_column, join_column, DuplicatePolicy::Error).map_err(
+                |_| {
+                    ParquetReaderError::ValidationError(format!(
+                        "A PNR maps to more than one key in column '{join_column}', \
+                         which JoinCardinality::OneToMany does not allow"
+                    ))
+                },
+            )?;
+        }
+        JoinCardinality::ManyToMany => {}
+    }
 
     for i in 0..pnr_batch.num_rows() {
         if !pnr_array.is_null(i) && !join_key_pnr.is_null(i) {
@@ -269,8 +405,11 @@ pub fn join_and_filter_by_pnr(
             let key = join_key_pnr.value(i);
 
             // Only include if it passes the PNR filter
-            if pnr_filter.is_none() || pnr_filter.unwrap().contains(pnr) {
-                join_to_pnr.insert(key.to_string(), pnr.to_string());
+            if pnr_filter
+                .as_ref()
+                .is_none_or(|filter| filter.contains(normalization.apply(pnr).as_ref()))
+            {
+                join_keys.insert(key);
             }
         }
     }
@@ -282,7 +421,7 @@ pub fn join_and_filter_by_pnr(
         let in_filter = if join_key.is_null(i) {
             false
         } else {
-            join_to_pnr.contains_key(join_key.value(i))
+            join_keys.contains(join_key.value(i))
         };
         mask_values.push(in_filter);
     }
@@ -352,14 +491,40 @@ impl FilterPlan {
     pub fn get_join_info(&self, registry: &str) -> Option<&(String, String)> {
         self.join_filters.get(registry)
     }
+
+    /// Get the registries joined with that are not filtered themselves
+    ///
+    /// [`apply_filter_plan`] loads these from the base directory.
+    #[must_use]
+    pub fn auxiliary_registries(&self) -> Vec<&String> {
+        let mut registries: Vec<&String> = self
+            .join_filters
+            .values()
+            .map(|(parent_registry, _)| parent_registry)
+            .filter(|parent_registry| !self.has_registry(parent_registry))
+            .collect();
+        registries.sort();
+        registries.dedup();
+        registries
+    }
 }
 
 /// Build a multi-step filter plan for efficient filtering
 ///
+/// Registries with a PNR column in their schema are filtered directly. A
+/// registry without one is filtered through a join with a directly filtered
+/// registry: the one given in `joins`, or else the one its
+/// [`PnrFilterRequirement::RequiresJoin`] names. A required registry that is
+/// not among `schemas` becomes an auxiliary registry, which
+/// [`apply_filter_plan`] loads itself. Registries that cannot be joined with
+/// a directly filtered or auxiliary registry are left out of the plan.
+///
 /// # Arguments
 /// * `schemas` - The schemas of the registries to filter
 /// * `joins` - Map of join relationships between registries
 /// * `pnr_columns` - Map of PNR column names for each registry
+/// * `requirements` - How each registry can be filtered by PNR, as reported
+///   by `RegisterLoader::pnr_filter_requirement`
 ///
 /// # Returns
 /// A filter plan for efficiently filtering the registries
@@ -368,6 +533,7 @@ pub fn build_filter_plan(
     schemas: &HashMap<String, SchemaRef>,
     joins: &HashMap<String, (String, String)>, // (registry, join_from, join_to)
     pnr_columns: &HashMap<String, String>,
+    requirements: &HashMap<String, PnrFilterRequirement>,
 ) -> FilterPlan {
     let mut plan = FilterPlan::new();
 
@@ -396,25 +562,47 @@ pub fn build_filter_plan(
         }
     }
 
+    // Finally, join the remaining registries as their loaders require
+    for (registry, requirement) in requirements {
+        if let PnrFilterRequirement::RequiresJoin { with, on } = requirement
+            && schemas.contains_key(registry)
+            && !plan.has_registry(registry)
+            && (plan.is_direct_filter(with) || !schemas.contains_key(*with))
+        {
+            plan.add_join_filter(registry.clone(), (*with).to_string(), (*on).to_string());
+        }
+    }
+
     plan
 }
 
 /// Apply a filter plan to filter multiple record batches by PNR
 ///
+/// The [auxiliary registries](FilterPlan::auxiliary_registries) of the plan
+/// are loaded from their folders under `base_dir` (see
+/// [`find_registry_dir`]), filtered to the PNRs, to find the keys of the
+/// registries joined with them. Only the registries in `batches` are
+/// returned.
+///
 /// # Arguments
 /// * `plan` - The filter plan to apply
 /// * `batches` - Map of registry name to record batches
 /// * `pnr_filter` - Set of PNR values to filter by
+/// * `base_dir` - The directory holding one folder per registry, needed if
+///   the plan has auxiliary registries
 ///
 /// # Returns
 /// A map of registry name to filtered record batches
 ///
 /// # Errors
-/// Returns an error if filtering fails
+/// Returns an error if filtering fails, or if an auxiliary registry is
+/// needed but there is no base directory, it has no folder there, or it
+/// cannot be loaded
 pub fn apply_filter_plan(
     plan: &FilterPlan,
     batches: &HashMap<String, Vec<RecordBatch>>,
     pnr_filter: &HashSet<String>,
+    base_dir: Option<&Path>,
 ) -> Result<HashMap<String, Vec<RecordBatch>>> {
     let mut filtered_batches = HashMap::with_capacity(batches.len());
 
@@ -442,37 +630,265 @@ pub fn apply_filter_plan(
         }
     }
 
+    // Load the auxiliary registries that registries to filter are joined with
+    let mut auxiliary_batches = HashMap::new();
+    for parent_registry in plan.auxiliary_registries() {
+        let needed = plan.join_filters.iter().any(|(registry, (parent, _))| {
+            parent == parent_registry && batches.contains_key(registry)
+        });
+        if needed {
+            auxiliary_batches.insert(
+                parent_registry.clone(),
+                load_auxiliary_registry(parent_registry, base_dir, pnr_filter)?,
+            );
+        }
+    }
+
     // Then, filter registries that need to be filtered via joins
     for (registry, (parent_registry, join_column)) in &plan.join_filters {
         // Use the same column name for both parent and child
-        if let Some(registry_batches) = batches.get(registry) {
-            if let Some(parent_batches) = filtered_batches.get(parent_registry) {
-                // This is a simplification - in a real implementation, you'd need
-                // to handle joining across multiple batches more carefully
-                if let Some(parent_batch) = parent_batches.first() {
-                    if let Some(pnr_column) = plan.get_pnr_column(parent_registry) {
-                        let filtered: Result<Vec<RecordBatch>> = registry_batches
-                            .par_iter()
-                            .map(|batch| {
-                                join_and_filter_by_pnr(
-                                    parent_batch,
-                                    pnr_column,
-                                    batch,
-                                    join_column,
-                                    Some(pnr_filter),
-                                )
-                            })
-                            .collect();
-
-                        let result = filtered?;
-                        if !result.is_empty() {
-                            filtered_batches.insert(registry.clone(), result);
-                        }
-                    }
-                }
-            }
+        let Some(registry_batches) = batches.get(registry) else {
+            continue;
+        };
+        let (pnr_column, parent_batches) = match plan.get_pnr_column(parent_registry) {
+            Some(pnr_column) => (pnr_column, filtered_batches.get(parent_registry)),
+            None => match auxiliary_batches.get(parent_registry) {
+                Some((pnr_column, parent_batches)) => (pnr_column, Some(parent_batches)),
+                None => continue,
+            },
+        };
+        let Some(parent_batches) = parent_batches.filter(|batches| !batches.is_empty()) else {
+            continue;
+        };
+        let parent_batch = concat_coerced(parent_batches)?;
+
+        let filtered: Result<Vec<RecordBatch>> = registry_batches
+            .par_iter()
+            .map(|batch| {
+                join_and_filter_by_pnr(
+                    &parent_batch,
+                    pnr_column,
+                    batch,
+                    join_column,
+                    Some(pnr_filter),
+                    JoinCardinality::ManyToOne,
+                )
+            })
+            .collect();
+
+        let result = filtered?;
+        if !result.is_empty() {
+            filtered_batches.insert(registry.clone(), result);
         }
     }
 
     Ok(filtered_batches)
 }
+
+/// Load an auxiliary registry of a filter plan, filtered to the PNRs
+///
+/// # Returns
+/// The PNR column of the registry and its batches
+fn load_auxiliary_registry(
+    registry: &str,
+    base_dir: Option<&Path>,
+    pnr_filter: &HashSet<String>,
+) -> Result<(String, Vec<RecordBatch>)> {
+    let base_dir = base_dir.ok_or_else(|| {
+        ParquetReaderError::InvalidOperation(format!(
+            "Filtering by PNR needs registry {registry}, but no base directory was given to load it from"
+        ))
+    })?;
+    let path = find_registry_dir(base_dir, registry).ok_or_else(|| {
+        ParquetReaderError::InvalidOperation(format!(
+            "Filtering by PNR needs registry {registry}, which has no folder under {}",
+            base_dir.display()
+        ))
+    })?;
+
+    let loader = registry_from_name(registry)?;
+    let pnr_column = loader.get_pnr_column_name().ok_or_else(|| {
+        ParquetReaderError::InvalidOperation(format!(
+            "Registry {registry} has no PNR column to filter by"
+        ))
+    })?;
+    let batches = loader
+        .load(&path, Some(pnr_filter))
+        .with_context(|| format!("Failed to load registry {registry} from {}", path.display()))?;
+    Ok((pnr_column.to_string(), batches))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::ArrayRef;
+
+    fn key_batch(pnrs: Vec<&str>, keys: Vec<&str>) -> RecordBatch {
+        RecordBatch::try_from_iter(vec![
+            ("PNR", Arc::new(StringArray::from(pnrs)) as ArrayRef),
+            ("RECNUM", Arc::new(StringArray::from(keys)) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_filter_plan_from_requirements() {
+        use arrow::datatypes::{DataType, Field, Schema};
+
+        let schema = |columns: &[&str]| {
+            Arc::new(Schema::new(
+                columns
+                    .iter()
+                    .map(|column| Field::new(*column, DataType::Utf8, true))
+                    .collect::<Vec<_>>(),
+            ))
+        };
+        let schemas = HashMap::from([
+            ("LPR_ADM".to_string(), schema(&["PNR", "RECNUM"])),
+            ("LPR_DIAG".to_string(), schema(&["RECNUM", "C_DIAG"])),
+            ("LPR3_DIAGNOSER".to_string(), schema(&["DW_EK_KONTAKT"])),
+        ]);
+        let pnr_columns = HashMap::from([("LPR_ADM".to_string(), "PNR".to_string())]);
+        let requirements = HashMap::from([
+            ("LPR_ADM".to_string(), PnrFilterRequirement::Direct),
+            (
+                "LPR_DIAG".to_string(),
+                PnrFilterRequirement::RequiresJoin {
+                    with: "LPR_ADM",
+                    on: "RECNUM",
+                },
+            ),
+            (
+                "LPR3_DIAGNOSER".to_string(),
+                PnrFilterRequirement::RequiresJoin {
+                    with: "LPR3_KONTAKTER",
+                    on: "DW_EK_KONTAKT",
+                },
+            ),
+        ]);
+
+        let plan = build_filter_plan(&schemas, &HashMap::new(), &pnr_columns, &requirements);
+        assert!(plan.is_direct_filter("LPR_ADM"));
+        assert_eq!(
+            plan.get_join_info("LPR_DIAG"),
+            Some(&("LPR_ADM".to_string(), "RECNUM".to_string()))
+        );
+        // LPR3_KONTAKTER is not among the registries to filter, so is loaded
+        assert_eq!(plan.auxiliary_registries(), vec!["LPR3_KONTAKTER"]);
+    }
+
+    #[test]
+    fn test_apply_filter_plan_loads_auxiliary_registry() {
+        use parquet::arrow::ArrowWriter;
+
+        let dir = std::env::temp_dir().join(format!("par_reader_auxiliary_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("lpr_adm")).unwrap();
+        let admissions = key_batch(vec!["a", "b", "a"], vec!["r1", "r2", "r3"]);
+        let file = std::fs::File::create(dir.join("lpr_adm").join("2020.parquet")).unwrap();
+        let mut writer = ArrowWriter::try_new(file, admissions.schema(), None).unwrap();
+        writer.write(&admissions).unwrap();
+        writer.close().unwrap();
+
+        let diagnoses = RecordBatch::try_from_iter(vec![(
+            "RECNUM",
+            Arc::new(StringArray::from(vec!["r1", "r2", "r3", "r4"])) as ArrayRef,
+        )])
+        .unwrap();
+        let schemas = HashMap::from([("LPR_DIAG".to_string(), diagnoses.schema())]);
+        let requirements = HashMap::from([(
+            "LPR_DIAG".to_string(),
+            PnrFilterRequirement::RequiresJoin {
+                with: "LPR_ADM",
+                on: "RECNUM",
+            },
+        )]);
+        let plan = build_filter_plan(&schemas, &HashMap::new(), &HashMap::new(), &requirements);
+        let batches = HashMap::from([("LPR_DIAG".to_string(), vec![diagnoses])]);
+        let filter: HashSet<String> = ["a".to_string()].into_iter().collect();
+
+        let filtered = apply_filter_plan(&plan, &batches, &filter, Some(&dir)).unwrap();
+        assert_eq!(filtered.len(), 1);
+        let recnums: Vec<&str> = filtered["LPR_DIAG"]
+            .iter()
+            .flat_map(|batch| {
+                let column = batch.column_by_name("RECNUM").unwrap();
+                let column = column.as_any().downcast_ref::<StringArray>().unwrap();
+                (0..column.len())
+                    .map(|row| column.value(row))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(recnums, vec!["r1", "r3"]);
+
+        let error = apply_filter_plan(&plan, &batches, &filter, None).unwrap_err();
+        assert!(error.to_string().contains("no base directory"), "{error}");
+        let error =
+            apply_filter_plan(&plan, &batches, &filter, Some(&dir.join("missing"))).unwrap_err();
+        assert!(error.to_string().contains("has no folder under"), "{error}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_join_cardinality() {
+        let diagnoses = RecordBatch::try_from_iter(vec![(
+            "RECNUM",
+            Arc::new(StringArray::from(vec!["r1", "r2", "r3"])) as ArrayRef,
+        )])
+        .unwrap();
+        let filter: HashSet<String> = ["a".to_string()].into_iter().collect();
+
+        // Two records of one PNR
+        let admissions = key_batch(vec!["a", "a", "b"], vec!["r1", "r2", "r3"]);
+        let join = |cardinality| {
+            join_and_filter_by_pnr(
+                &admissions,
+                "PNR",
+                &diagnoses,
+                "RECNUM",
+                Some(&filter),
+                cardinality,
+            )
+        };
+        assert_eq!(join(JoinCardinality::ManyToOne).unwrap().num_rows(), 2);
+        assert!(join(JoinCardinality::OneToMany).is_err());
+        assert_eq!(join(JoinCardinality::ManyToMany).unwrap().num_rows(), 2);
+
+        // A record shared by two PNRs is kept once
+        let admissions = key_batch(vec!["a", "b"], vec!["r1", "r1"]);
+        let error = join_and_filter_by_pnr(
+            &admissions,
+            "PNR",
+            &diagnoses,
+            "RECNUM",
+            Some(&filter),
+            JoinCardinality::ManyToOne,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("'r1'"), "{error}");
+        let joined = join_and_filter_by_pnr(
+            &admissions,
+            "PNR",
+            &diagnoses,
+            "RECNUM",
+            None,
+            JoinCardinality::ManyToMany,
+        )
+        .unwrap();
+        assert_eq!(joined.num_rows(), 1);
+
+        // Filter PNRs match in any format
+        let admissions = key_batch(vec!["0101801234", "0202801234"], vec!["r1", "r2"]);
+        let filter: HashSet<String> = ["010180-1234".to_string()].into_iter().collect();
+        let joined = join_and_filter_by_pnr(
+            &admissions,
+            "PNR",
+            &diagnoses,
+            "RECNUM",
+            Some(&filter),
+            JoinCardinality::ManyToOne,
+        )
+        .unwrap();
+        assert_eq!(joined.num_rows(), 1);
+    }
+}
@@ -0,0 +1,200 @@
+//! PNR column sets per registry
+//!
+//! Several registries hold more than one PNR-like column: BEF links each
+//! person to their mother, father and spouse, and MFR links the child to
+//! both parents. A `PnrColumnSet` names the primary PNR column together with
+//! these related-person columns, so pseudonymization, filtering and
+//! validation all treat the same columns as identifiers.
+
+use arrow::datatypes::{DataType, Schema};
+
+use crate::error::Result;
+use crate::filter::error::{column_not_found, column_type_error};
+
+/// The columns of a registry that hold PNRs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PnrColumnSet {
+    /// The column identifying the person a row describes
+    pub primary: String,
+    /// Columns referring to related persons (parents, spouse)
+    pub related: Vec<String>,
+}
+
+impl Default for PnrColumnSet {
+    fn default() -> Self {
+        Self::new("PNR")
+    }
+}
+
+impl PnrColumnSet {
+    /// Create a column set with only a primary PNR column
+    #[must_use]
+    pub fn new(primary: impl Into<String>) -> Self {
+        Self {
+            primary: primary.into(),
+            related: Vec::new(),
+        }
+    }
+
+    /// Add related-person columns to the set
+    #[must_use]
+    pub fn with_related<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.related.extend(columns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Get the PNR columns of a known registry
+    ///
+    /// Registries keyed by a record or contact number instead of a PNR
+    /// (`LPR_DIAG`, `LPR_BES`, `LPR3_DIAGNOSER`) have no PNR columns and are
+    /// filtered through a join with their parent registry.
+    ///
+    /// # Arguments
+    /// * `registry` - The registry name, e.g. "BEF" (case-insensitive)
+    ///
+    /// # Returns
+    /// The column set, or `None` if the registry holds no PNRs
+    #[must_use]
+    pub fn for_registry(registry: &str) -> Option<Self> {
+        match registry.to_uppercase().as_str() {
+            "BEF" => Some(Self::new("PNR").with_related(["MOR_ID", "FAR_ID", "AEGTE_ID"])),
+            "MFR" => Some(Self::new("CPR_BARN").with_related(["CPR_MODER", "CPR_FADER"])),
+            "LPR3_KONTAKTER" => Some(Self::new("CPR")),
+            "LPR_DIAG" | "LPR_BES" | "LPR3_DIAGNOSER" => None,
+            _ => Some(Self::default()),
+        }
+    }
+
+    /// Iterate over all columns in the set, primary first
+    pub fn columns(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.primary.as_str()).chain(self.related.iter().map(String::as_str))
+    }
+
+    /// Check whether a column holds PNRs
+    #[must_use]
+    pub fn contains(&self, column: &str) -> bool {
+        self.columns().any(|name| name == column)
+    }
+
+    /// Get the columns of the set present in a schema
+    #[must_use]
+    pub fn present_in<'a>(&'a self, schema: &Schema) -> Vec<&'a str> {
+        self.columns()
+            .filter(|name| schema.field_with_name(name).is_ok())
+            .collect()
+    }
+
+    /// Validate that a schema holds the set's PNR columns as strings
+    ///
+    /// The primary column must be present; related columns are optional, as
+    /// extracts often drop them, but must be strings when present.
+    ///
+    /// # Errors
+    /// Returns an error if the primary column is missing or a present PNR
+    /// column is not a string column
+    pub fn validate(&self, schema: &Schema) -> Result<()> {
+        if schema.field_with_name(&self.primary).is_err() {
+            return column_not_found(&self.primary);
+        }
+
+        for name in self.present_in(schema) {
+            let field = schema.field_with_name(name)?;
+            if field.data_type() != &DataType::Utf8 {
+                return column_type_error(name, "string");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::core::BatchFilter;
+    use crate::filter::pnr::PnrFilter;
+    use crate::filter::pseudonym::{pseudonym, pseudonymize_columns};
+    use arrow::array::{Array, ArrayRef, StringArray};
+    use arrow::datatypes::Field;
+    use arrow::record_batch::RecordBatch;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_registry_column_sets() {
+        let bef = PnrColumnSet::for_registry("bef").unwrap();
+        assert_eq!(
+            bef.columns().collect::<Vec<_>>(),
+            vec!["PNR", "MOR_ID", "FAR_ID", "AEGTE_ID"]
+        );
+        let mfr = PnrColumnSet::for_registry("MFR").unwrap();
+        assert_eq!(mfr.primary, "CPR_BARN");
+        assert!(mfr.contains("CPR_FADER"));
+        assert!(!mfr.contains("PNR"));
+        assert!(PnrColumnSet::for_registry("LPR3_DIAGNOSER").is_none());
+        assert_eq!(
+            PnrColumnSet::for_registry("IND"),
+            Some(PnrColumnSet::default())
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let set = PnrColumnSet::for_registry("MFR").unwrap();
+        let schema = |mother_type: DataType| {
+            Schema::new(vec![
+                Field::new("CPR_BARN", DataType::Utf8, false),
+                Field::new("CPR_MODER", mother_type, true),
+            ])
+        };
+
+        assert!(set.validate(&schema(DataType::Utf8)).is_ok());
+        assert_eq!(
+            set.present_in(&schema(DataType::Utf8)),
+            vec!["CPR_BARN", "CPR_MODER"]
+        );
+        assert!(set.validate(&schema(DataType::Int64)).is_err());
+        assert!(
+            PnrColumnSet::default()
+                .validate(&schema(DataType::Utf8))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_filter_and_pseudonymize_full_set() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "CPR_BARN",
+                Arc::new(StringArray::from(vec!["c1", "c2", "c3"])) as ArrayRef,
+            ),
+            (
+                "CPR_MODER",
+                Arc::new(StringArray::from(vec![Some("m1"), None, Some("m2")])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let set = PnrColumnSet::for_registry("MFR").unwrap();
+
+        let selected: HashSet<String> = ["c1", "m2"].iter().map(ToString::to_string).collect();
+        let filtered = PnrFilter::for_columns(&selected, &set)
+            .filter(&batch)
+            .unwrap();
+        assert_eq!(filtered.num_rows(), 2);
+
+        let pseudonymized = pseudonymize_columns(&[batch], &set, b"salt").unwrap();
+        let mothers = pseudonymized[0]
+            .column_by_name("CPR_MODER")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .clone();
+        assert_eq!(mothers.value(2), pseudonym("m2", b"salt"));
+        assert!(mothers.is_null(1));
+    }
+}
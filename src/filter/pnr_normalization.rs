@@ -0,0 +1,116 @@
+//! Normalization of PNRs for comparison
+//!
+//! The same PNR appears in different files as `010180-1234`, `0101801234`,
+//! ` 0101801234 `, or with its leading zero lost to a numeric export
+//! (`101801234`). Filters normalize both the PNRs they are built from and the
+//! values read from the PNR column, so a filter built from one format matches
+//! data in another.
+//!
+//! # Canonical form
+//!
+//! A PNR is canonicalized by removing all dashes and whitespace and, if what
+//! remains is all digits and shorter than ten, left-padding it with zeros to
+//! ten digits. Values that are not numeric, such as pseudonyms, are only
+//! stripped.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// Number of digits in a canonical PNR
+pub const PNR_LENGTH: usize = 10;
+
+/// How PNRs are normalized before being compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PnrNormalization {
+    /// Compare PNRs in the canonical form (the default)
+    #[default]
+    Canonical,
+    /// Compare PNRs exactly as stored
+    None,
+}
+
+impl PnrNormalization {
+    /// Normalize a PNR
+    ///
+    /// # Returns
+    /// The normalized PNR, borrowed if it is unchanged
+    #[must_use]
+    pub fn apply(self, pnr: &str) -> Cow<'_, str> {
+        match self {
+            Self::Canonical => normalize_pnr(pnr),
+            Self::None => Cow::Borrowed(pnr),
+        }
+    }
+
+    /// Normalize every PNR of a set
+    #[must_use]
+    pub fn apply_set<'a>(self, pnrs: impl IntoIterator<Item = &'a String>) -> HashSet<String> {
+        pnrs.into_iter()
+            .map(|pnr| self.apply(pnr).into_owned())
+            .collect()
+    }
+}
+
+/// Convert a PNR to the canonical form
+///
+/// # Returns
+/// The canonical PNR, borrowed if the input already is canonical
+#[must_use]
+pub fn normalize_pnr(pnr: &str) -> Cow<'_, str> {
+    let is_separator = |c: char| c == '-' || c.is_whitespace();
+    let is_short_number =
+        |s: &str| s.len() < PNR_LENGTH && !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+    if !pnr.contains(is_separator) && !is_short_number(pnr) {
+        return Cow::Borrowed(pnr);
+    }
+
+    let stripped: String = pnr.chars().filter(|&c| !is_separator(c)).collect();
+    if is_short_number(&stripped) {
+        Cow::Owned(format!("{stripped:0>PNR_LENGTH$}"))
+    } else {
+        Cow::Owned(stripped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_pnr() {
+        assert_eq!(normalize_pnr("0101801234"), "0101801234");
+        assert!(matches!(normalize_pnr("0101801234"), Cow::Borrowed(_)));
+        assert_eq!(normalize_pnr("010180-1234"), "0101801234");
+        assert_eq!(normalize_pnr(" 010180 1234\t"), "0101801234");
+        assert_eq!(normalize_pnr("101801234"), "0101801234");
+        assert_eq!(normalize_pnr("ABC-123"), "ABC123");
+        assert_eq!(normalize_pnr(""), "");
+        assert_eq!(PnrNormalization::None.apply("010180-1234"), "010180-1234");
+    }
+
+    #[test]
+    fn test_filter_matches_across_formats() {
+        use crate::filter::core::BatchFilter;
+        use crate::filter::pnr::PnrFilter;
+        use arrow::array::{ArrayRef, StringArray};
+        use arrow::record_batch::RecordBatch;
+        use std::sync::Arc;
+
+        let batch = RecordBatch::try_from_iter(vec![(
+            "PNR",
+            Arc::new(StringArray::from(vec![
+                "0101801234",
+                " 101801234",
+                "0202801234",
+            ])) as ArrayRef,
+        )])
+        .unwrap();
+        let pnrs = HashSet::from(["010180-1234".to_string()]);
+
+        let filtered = PnrFilter::new(&pnrs, None).filter(&batch).unwrap();
+        assert_eq!(filtered.num_rows(), 2);
+        let exact = PnrFilter::with_normalization(&pnrs, None, PnrNormalization::None);
+        assert_eq!(exact.filter(&batch).unwrap().num_rows(), 0);
+    }
+}
@@ -0,0 +1,238 @@
+//! Sets of PNRs for cohort construction
+//!
+//! Study cohorts are built by combining the PNRs of several registries, e.g.
+//! "in BEF and has an LPR contact". `PnrSet` gives these combinations names
+//! and can be saved and loaded as plain text with one PNR per line. It
+//! dereferences to `HashSet<String>`, so it can be passed to every filter
+//! API taking a set of PNRs.
+//!
+//! Sets read from files are in the canonical form of
+//! [`PnrNormalization::Canonical`], so sets from differently formatted
+//! sources combine correctly.
+
+use std::collections::HashSet;
+use std::collections::hash_set;
+use std::io::Write;
+use std::ops::Deref;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::error::util::safe_read_to_string;
+use crate::filter::pnr_normalization::PnrNormalization;
+
+/// A set of distinct PNRs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PnrSet(HashSet<String>);
+
+impl PnrSet {
+    /// Create an empty set
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a set from a text file with one PNR per line
+    ///
+    /// PNRs are converted to the canonical form and blank lines are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Self::from_file_with_normalization(path, PnrNormalization::default())
+    }
+
+    /// Read a set from a text file with one PNR per line, normalized as given
+    ///
+    /// Blank lines are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read
+    pub fn from_file_with_normalization(
+        path: &Path,
+        normalization: PnrNormalization,
+    ) -> Result<Self> {
+        let content = safe_read_to_string(path, "PNR set")?;
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| normalization.apply(line.trim()).into_owned())
+            .collect())
+    }
+
+    /// Convert every PNR of the set with a normalization
+    #[must_use]
+    pub fn normalized(&self, normalization: PnrNormalization) -> Self {
+        Self(normalization.apply_set(&self.0))
+    }
+
+    /// Write the set to a text file with one PNR per line, sorted
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        let mut pnrs: Vec<&String> = self.0.iter().collect();
+        pnrs.sort_unstable();
+
+        let file = std::fs::File::create(path)
+            .map_err(|e| anyhow::anyhow!("Failed to create file {}: {}", path.display(), e))?;
+        let mut writer = std::io::BufWriter::new(file);
+        for pnr in pnrs {
+            writeln!(writer, "{pnr}")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Get the PNRs in both sets
+    #[must_use]
+    pub fn intersect(&self, other: &Self) -> Self {
+        // Iterate the smaller set
+        let (small, large) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        small
+            .0
+            .iter()
+            .filter(|pnr| large.0.contains(*pnr))
+            .cloned()
+            .collect()
+    }
+
+    /// Get the PNRs in either set
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.0.union(&other.0).cloned().collect()
+    }
+
+    /// Get the PNRs in this set but not in `other`
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        self.0.difference(&other.0).cloned().collect()
+    }
+
+    /// Get the number of PNRs
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check whether the set is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Add a PNR, returning whether it was not already present
+    pub fn insert(&mut self, pnr: impl Into<String>) -> bool {
+        self.0.insert(pnr.into())
+    }
+
+    /// Unwrap the underlying set
+    #[must_use]
+    pub fn into_inner(self) -> HashSet<String> {
+        self.0
+    }
+}
+
+impl Deref for PnrSet {
+    type Target = HashSet<String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<HashSet<String>> for PnrSet {
+    fn as_ref(&self) -> &HashSet<String> {
+        &self.0
+    }
+}
+
+impl From<HashSet<String>> for PnrSet {
+    fn from(pnrs: HashSet<String>) -> Self {
+        Self(pnrs)
+    }
+}
+
+impl From<PnrSet> for HashSet<String> {
+    fn from(pnrs: PnrSet) -> Self {
+        pnrs.0
+    }
+}
+
+impl FromIterator<String> for PnrSet {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<String> for PnrSet {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for PnrSet {
+    type Item = String;
+    type IntoIter = hash_set::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a PnrSet {
+    type Item = &'a String;
+    type IntoIter = hash_set::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::core::BatchFilter;
+    use crate::filter::pnr::PnrFilter;
+    use arrow::array::{ArrayRef, StringArray};
+    use arrow::record_batch::RecordBatch;
+    use std::sync::Arc;
+
+    fn set(pnrs: &[&str]) -> PnrSet {
+        pnrs.iter().map(|pnr| (*pnr).to_string()).collect()
+    }
+
+    #[test]
+    fn test_pnr_set_algebra_and_file_round_trip() {
+        let bef = set(&["1", "2", "3"]);
+        let lpr = set(&["2", "3", "4"]);
+        assert_eq!(bef.intersect(&lpr), set(&["2", "3"]));
+        assert_eq!(bef.union(&lpr).len(), 4);
+        assert_eq!(bef.difference(&lpr), set(&["1"]));
+        assert!(bef.difference(&bef).is_empty());
+
+        let path =
+            std::env::temp_dir().join(format!("par_reader_pnr_set_{}.txt", std::process::id()));
+        bef.to_file(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        assert_eq!(
+            PnrSet::from_file(&path).unwrap(),
+            set(&["0000000001", "0000000002", "0000000003"])
+        );
+        let raw = PnrSet::from_file_with_normalization(&path, PnrNormalization::None).unwrap();
+        assert_eq!(raw, bef);
+        std::fs::remove_file(&path).unwrap();
+
+        // Filter APIs taking a set of PNRs accept a PnrSet
+        let batch = RecordBatch::try_from_iter(vec![(
+            "PNR",
+            Arc::new(StringArray::from(vec!["1", "4"])) as ArrayRef,
+        )])
+        .unwrap();
+        let filtered = PnrFilter::new(&bef, None).filter(&batch).unwrap();
+        assert_eq!(filtered.num_rows(), 1);
+    }
+}
@@ -0,0 +1,410 @@
+//! Reproducible random sampling of rows
+//!
+//! This module provides seeded sampling of record batches, for building small
+//! development datasets from large registries. Sampling here is row-level:
+//! rows are selected independently of the person they belong to, so a sampled
+//! person's records are generally incomplete. Use [`sample_by_pnr`] when
+//! analyses need every record of each sampled person.
+//!
+//! Samples are reproducible for a given seed and input (including batch order)
+//! with the same version of this crate.
+
+use arrow::array::{Array, BooleanArray, StringArray, UInt32Array};
+use arrow::compute::take_record_batch;
+use arrow::record_batch::RecordBatch;
+use rand::prelude::*;
+
+use crate::error::{ParquetReaderError, Result};
+use crate::filter::core::filter_record_batch;
+use crate::filter::error::{column_not_found, column_type_error};
+
+/// A reservoir slot: a row of one of the retained candidate batches
+#[derive(Debug, Clone, Copy)]
+struct ReservoirEntry {
+    /// Position of the row across all input batches
+    position: u64,
+    /// Candidate batch holding the row
+    candidate: usize,
+    /// Row within the candidate batch
+    row: u32,
+}
+
+/// Select at most `n` rows uniformly at random using seeded reservoir sampling
+///
+/// Batches are consumed one at a time without knowing the total row count in
+/// advance. Only rows that enter the reservoir are retained between batches,
+/// and replaced rows are periodically dropped, so memory use is bounded by
+/// the sample size rather than the input size.
+/// Selected rows are returned in their original order.
+///
+/// # Arguments
+/// * `batches` - The input batches, which must share a schema
+/// * `n` - Maximum number of rows to select
+/// * `seed` - Seed for the random number generator
+///
+/// # Returns
+/// The selected rows; empty if the input has no rows or `n` is zero
+///
+/// # Errors
+/// Returns an error if the selected rows cannot be extracted from a batch
+pub fn reservoir_sample(
+    batches: impl IntoIterator<Item = RecordBatch>,
+    n: usize,
+    seed: u64,
+) -> Result<Vec<RecordBatch>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut reservoir: Vec<ReservoirEntry> = Vec::with_capacity(n);
+    let mut candidates: Vec<RecordBatch> = Vec::new();
+    let mut retained_rows = 0;
+    let mut seen: u64 = 0;
+
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+
+    for batch in batches {
+        // Algorithm R: row t replaces a random slot with probability n / (t + 1)
+        let mut selected: Vec<(usize, u32)> = Vec::new();
+        for row in 0..batch.num_rows() {
+            let slot = if reservoir.len() + selected.len() < n {
+                Some(reservoir.len() + selected.len())
+            } else {
+                let j = rng.random_range(0..=seen);
+                usize::try_from(j).ok().filter(|&j| j < n)
+            };
+
+            if let Some(slot) = slot {
+                selected.push((slot, u32::try_from(row)?));
+            }
+            seen += 1;
+        }
+
+        if selected.is_empty() {
+            continue;
+        }
+
+        // Keep only the selected rows of this batch
+        let rows = UInt32Array::from_iter_values(selected.iter().map(|&(_, row)| row));
+        candidates.push(take_record_batch(&batch, &rows)?);
+        retained_rows += selected.len();
+        let candidate = candidates.len() - 1;
+        let first_position = seen - batch.num_rows() as u64;
+
+        for (index, &(slot, row)) in selected.iter().enumerate() {
+            let entry = ReservoirEntry {
+                position: first_position + u64::from(row),
+                candidate,
+                row: u32::try_from(index)?,
+            };
+            if slot < reservoir.len() {
+                reservoir[slot] = entry;
+            } else {
+                reservoir.push(entry);
+            }
+        }
+
+        // Drop replaced rows once they outnumber the live ones
+        if retained_rows > 2 * n {
+            compact(&mut reservoir, &mut candidates)?;
+            retained_rows = reservoir.len();
+        }
+    }
+
+    reservoir.sort_by_key(|entry| entry.position);
+
+    // Entries sorted by position are grouped by candidate batch
+    let mut sampled = Vec::new();
+    for group in reservoir.chunk_by(|a, b| a.candidate == b.candidate) {
+        let rows = UInt32Array::from_iter_values(group.iter().map(|entry| entry.row));
+        sampled.push(take_record_batch(&candidates[group[0].candidate], &rows)?);
+    }
+
+    Ok(sampled)
+}
+
+/// Rebuild the candidate batches so they hold only rows still in the reservoir
+fn compact(reservoir: &mut [ReservoirEntry], candidates: &mut Vec<RecordBatch>) -> Result<()> {
+    let mut order: Vec<(usize, u32, usize)> = reservoir
+        .iter()
+        .enumerate()
+        .map(|(slot, entry)| (entry.candidate, entry.row, slot))
+        .collect();
+    order.sort_unstable();
+
+    let mut compacted = Vec::new();
+    for group in order.chunk_by(|a, b| a.0 == b.0) {
+        let rows = UInt32Array::from_iter_values(group.iter().map(|&(_, row, _)| row));
+        compacted.push(take_record_batch(&candidates[group[0].0], &rows)?);
+
+        let candidate = compacted.len() - 1;
+        for (index, &(_, _, slot)) in group.iter().enumerate() {
+            reservoir[slot].candidate = candidate;
+            reservoir[slot].row = u32::try_from(index)?;
+        }
+    }
+
+    *candidates = compacted;
+    Ok(())
+}
+
+/// Select each row independently with probability `fraction`
+///
+/// The number of selected rows is random, with expectation `fraction` times
+/// the number of input rows. Batches with no selected rows are dropped.
+///
+/// # Arguments
+/// * `batches` - The input batches
+/// * `fraction` - Probability of selecting each row, between 0 and 1
+/// * `seed` - Seed for the random number generator
+///
+/// # Returns
+/// The selected rows, in their original order
+///
+/// # Errors
+/// Returns an error if `fraction` is outside `[0, 1]` or filtering fails
+pub fn sample_fraction(
+    batches: impl IntoIterator<Item = RecordBatch>,
+    fraction: f64,
+    seed: u64,
+) -> Result<Vec<RecordBatch>> {
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(ParquetReaderError::validation_error(format!(
+            "Sampling fraction must be between 0 and 1, got {fraction}"
+        ))
+        .into());
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut sampled = Vec::new();
+
+    for batch in batches {
+        let mask: BooleanArray = (0..batch.num_rows())
+            .map(|_| Some(rng.random_bool(fraction)))
+            .collect();
+        let filtered = filter_record_batch(&batch, &mask)?;
+        if filtered.num_rows() > 0 {
+            sampled.push(filtered);
+        }
+    }
+
+    Ok(sampled)
+}
+
+/// Hash a PNR with a seed, independently of platform and process
+///
+/// FNV-1a over the seed and PNR bytes, followed by the `SplitMix64`
+/// finalizer so that similar PNRs map to unrelated values.
+fn pnr_hash(pnr: &str, seed: u64) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in seed.to_le_bytes().iter().chain(pnr.as_bytes()) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// Select all rows of a random fraction of individuals
+///
+/// Each PNR is hashed with the seed and kept if its hash falls in the lowest
+/// `fraction` of the hash range. The decision depends only on the PNR and the
+/// seed, so every record of a sampled person is retained, across batches and
+/// across separately sampled registries using the same seed. Rows with a
+/// null PNR are dropped.
+///
+/// # Arguments
+/// * `batches` - The input batches
+/// * `pnr_column` - Name of the string column holding the PNR
+/// * `fraction` - Fraction of individuals to select, between 0 and 1
+/// * `seed` - Seed for the hash
+///
+/// # Returns
+/// The rows of the selected individuals, in their original order
+///
+/// # Errors
+/// Returns an error if `fraction` is outside `[0, 1]` or the PNR column is
+/// missing or not a string column
+pub fn sample_by_pnr(
+    batches: impl IntoIterator<Item = RecordBatch>,
+    pnr_column: &str,
+    fraction: f64,
+    seed: u64,
+) -> Result<Vec<RecordBatch>> {
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(ParquetReaderError::validation_error(format!(
+            "Sampling fraction must be between 0 and 1, got {fraction}"
+        ))
+        .into());
+    }
+
+    // Keep hashes below fraction * 2^64, which saturates for a fraction of 1
+    let threshold = (fraction * 2f64.powi(64)) as u64;
+    let selected = |pnr: &str| fraction >= 1.0 || pnr_hash(pnr, seed) < threshold;
+
+    let mut sampled = Vec::new();
+    for batch in batches {
+        let Some(column) = batch.column_by_name(pnr_column) else {
+            return column_not_found(pnr_column);
+        };
+        let Some(pnrs) = column.as_any().downcast_ref::<StringArray>() else {
+            return column_type_error(pnr_column, "string");
+        };
+
+        let mask: BooleanArray = (0..pnrs.len())
+            .map(|i| Some(pnrs.is_valid(i) && selected(pnrs.value(i))))
+            .collect();
+        let filtered = filter_record_batch(&batch, &mask)?;
+        if filtered.num_rows() > 0 {
+            sampled.push(filtered);
+        }
+    }
+
+    Ok(sampled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Int64Array};
+    use std::sync::Arc;
+
+    fn batches(sizes: &[usize]) -> Vec<RecordBatch> {
+        let mut next = 0;
+        sizes
+            .iter()
+            .map(|&size| {
+                let values: Vec<i64> = (next..next + size as i64).collect();
+                next += size as i64;
+                RecordBatch::try_from_iter(vec![(
+                    "ID",
+                    Arc::new(Int64Array::from(values)) as ArrayRef,
+                )])
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn ids(batches: &[RecordBatch]) -> Vec<i64> {
+        batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reservoir_sample() {
+        let input = batches(&[100, 3, 250, 0, 47]);
+
+        let sample = ids(&reservoir_sample(input.clone(), 20, 7).unwrap());
+        assert_eq!(sample.len(), 20);
+        assert!(sample.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(
+            sample,
+            ids(&reservoir_sample(input.clone(), 20, 7).unwrap())
+        );
+        assert_ne!(
+            sample,
+            ids(&reservoir_sample(input.clone(), 20, 8).unwrap())
+        );
+
+        // Fewer rows than requested returns everything
+        let all = ids(&reservoir_sample(input.clone(), 1000, 7).unwrap());
+        assert_eq!(all, (0..400).collect::<Vec<_>>());
+        assert!(reservoir_sample(input, 0, 7).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reservoir_sample_is_uniform() {
+        // Each of 10 rows should be selected in about 3 of 10 samples
+        let mut counts = [0; 10];
+        for seed in 0..2000 {
+            for id in ids(&reservoir_sample(batches(&[4, 6]), 3, seed).unwrap()) {
+                counts[usize::try_from(id).unwrap()] += 1;
+            }
+        }
+        assert!(
+            counts.iter().all(|&c| (500..700).contains(&c)),
+            "{counts:?}"
+        );
+    }
+
+    #[test]
+    fn test_sample_fraction() {
+        let input = batches(&[1000, 1000]);
+
+        let sample = ids(&sample_fraction(input.clone(), 0.1, 3).unwrap());
+        assert!((150..250).contains(&sample.len()), "{}", sample.len());
+        assert_eq!(
+            sample,
+            ids(&sample_fraction(input.clone(), 0.1, 3).unwrap())
+        );
+        assert!(sample_fraction(input.clone(), 0.0, 3).unwrap().is_empty());
+        assert_eq!(
+            ids(&sample_fraction(input.clone(), 1.0, 3).unwrap()).len(),
+            2000
+        );
+        assert!(sample_fraction(input, 1.5, 3).is_err());
+    }
+
+    #[test]
+    fn test_sample_by_pnr_keeps_whole_individuals() {
+        let make = |pnrs: Vec<Option<String>>| {
+            RecordBatch::try_from_iter(vec![("PNR", Arc::new(StringArray::from(pnrs)) as ArrayRef)])
+                .unwrap()
+        };
+        // 1000 individuals with 3 records each, spread over two batches
+        let pnrs: Vec<Option<String>> = (0..3000)
+            .map(|i| Some(format!("{:010}", i % 1000)))
+            .collect();
+        let mut second = pnrs[1500..].to_vec();
+        second.push(None);
+        let input = vec![make(pnrs[..1500].to_vec()), make(second)];
+
+        let sampled = sample_by_pnr(input.clone(), "PNR", 0.2, 11).unwrap();
+        let mut counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        for batch in &sampled {
+            let column = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            for pnr in column.iter() {
+                *counts.entry(pnr.unwrap().to_string()).or_default() += 1;
+            }
+        }
+        assert!((150..250).contains(&counts.len()), "{}", counts.len());
+        assert!(counts.values().all(|&c| c == 3));
+
+        // Same seed selects the same individuals, in any batching
+        let resampled = sample_by_pnr(input.iter().rev().cloned(), "PNR", 0.2, 11).unwrap();
+        assert_eq!(
+            resampled.iter().map(RecordBatch::num_rows).sum::<usize>(),
+            counts.len() * 3
+        );
+
+        assert!(
+            sample_by_pnr(input.clone(), "PNR", 0.0, 11)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            sample_by_pnr(input.clone(), "PNR", 1.0, 11)
+                .unwrap()
+                .iter()
+                .map(RecordBatch::num_rows)
+                .sum::<usize>(),
+            3000
+        );
+        assert!(sample_by_pnr(input, "CPR", 0.5, 11).is_err());
+    }
+}
@@ -30,7 +30,9 @@ pub mod filter_expression;
 // Core types
 pub use config::ParquetReaderConfig;
 pub use error::{Error, ParquetReaderError, ParquetResult, Result};
-pub use reader::{ParquetReader, ParquetRowIterator};
+pub use reader::{
+    FromRow, ParquetReader, ParquetRowIterator, SharedParquetReader, TypedRowIterator,
+};
 pub use schema::{SchemaCompatibilityReport, SchemaIssue};
 
 // Arrow types
@@ -110,19 +112,26 @@ pub use async_io::{
 };
 pub use filter::async_filtering::read_parquet_with_pnr_filter_async;
 
-// Re-export the procedural macros for registry definitions
-pub use macros::RegistryTrait;
+// Re-export the procedural macros for registry definitions and typed rows
+pub use macros::{FromRow, RegistryTrait};
+
+// `#[derive(FromRow)]` expands to `::par_reader` paths, so it works in crates
+// that do not depend on `parquet` directly and, through this alias, here
+extern crate self as par_reader;
+#[doc(hidden)]
+pub use parquet;
 
 // Registry factory functions
 pub use registry::factory::{load_multiple_registries, registry_from_name, registry_from_path};
 
 // PNR filtering utilities
 pub use pnr_filter::{
-    FilterPlan, apply_filter_plan, build_filter_plan, filter_batch_by_pnr, join_and_filter_by_pnr,
+    FilterPlan, JoinCardinality, apply_filter_plan, build_filter_plan, filter_batch_by_pnr,
+    join_and_filter_by_pnr,
 };
 
 // Registry manager
-pub use registry_manager::RegistryManager;
+pub use registry_manager::{DataDictionaryEntry, DatasetSummary, RegistryManager, RegistrySummary};
 
 // Algorithm modules - commented out because the module is commented out in algorithm/mod.rs
 // pub use algorithm::population::{
@@ -15,6 +15,7 @@ use std::sync::Arc;
 
 use crate::error::Result;
 use crate::error::util::{safe_open_file, validate_directory};
+use crate::filter::pnr_normalization::{PnrNormalization, normalize_pnr};
 use crate::filter_expression::{Expr, read_and_filter_parquet};
 
 /// Read a parquet file into Arrow record batches
@@ -79,12 +80,13 @@ pub fn read_parquet(
 
     // If we have a PNR filter, apply it
     if let Some(pnr_filter) = pnr_filter {
+        let pnr_filter = PnrNormalization::default().apply_set(pnr_filter);
         for batch_result in reader {
             let batch = batch_result
                 .with_context(|| format!("Failed to read record batch from {}", path.display()))?;
 
             // Filter the batch by PNR
-            let filtered_batch = filter_batch_by_pnr(&batch, pnr_filter)
+            let filtered_batch = filter_batch_by_pnr(&batch, &pnr_filter)
                 .with_context(|| "Failed to filter batch by PNR")?;
 
             // Add the filtered batch if it's not empty
@@ -105,7 +107,8 @@ pub fn read_parquet(
 }
 
 /// Filter a record batch by PNR
-/// This function only keeps rows where the PNR column value is in the provided set
+/// This function only keeps rows where the canonical PNR column value is in the provided set,
+/// which must hold canonical PNRs
 fn filter_batch_by_pnr(batch: &RecordBatch, pnr_filter: &HashSet<String>) -> Result<RecordBatch> {
     // Find the PNR column index
     let pnr_idx = batch
@@ -125,7 +128,7 @@ fn filter_batch_by_pnr(batch: &RecordBatch, pnr_filter: &HashSet<String>) -> Res
         if str_array.is_null(i) {
             values.push(false);
         } else {
-            values.push(pnr_filter.contains(str_array.value(i)));
+            values.push(pnr_filter.contains(normalize_pnr(str_array.value(i)).as_ref()));
         }
     }
     let filter_mask = BooleanArray::from(values);
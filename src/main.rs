@@ -1,130 +1,317 @@
-use log::{info, warn};
-use par_reader::Result;
+//! Command-line interface for loading and inspecting registry data
+//!
+//! ```text
+//! par-reader load --registry bef --path data/bef --pnr-file pnrs.txt --out result.parquet
+//! par-reader inspect --path data/bef
+//! par-reader preview --path data/bef --rows 20
+//! par-reader scd --lpr-path data/lpr_adm --out scd.csv
+//! ```
+//!
+//! Output files are written in the format given by their extension
+//! (`parquet`, `csv`, `arrow`, `ndjson`).
+
+use arrow::array::{ArrayRef, Int32Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::util::display::array_value_to_string;
+use log::info;
+use par_reader::algorithm::health::scd::{ScdConfig, apply_scd_to_batches};
+use par_reader::filter::PnrSet;
+use par_reader::registry::RegisterLoader;
+use par_reader::registry::direct_registry_loader::DirectRegistryLoader;
 use par_reader::registry::factory::{registry_from_name, registry_from_path};
-use std::path::Path;
+use par_reader::utils::io::parquet::{
+    find_parquet_files, load_parquet_files_parallel, read_parquet_schema,
+};
+use par_reader::utils::io::{FileSink, OutputSink};
+use par_reader::{Error, RecordBatch, Result, SchemaRef};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Instant;
 
+#[cfg(feature = "snmalloc")]
 #[global_allocator]
 static ALLOC: snmalloc_rs::SnMalloc = snmalloc_rs::SnMalloc;
 
+const USAGE: &str = "\
+Usage: par-reader <COMMAND> [OPTIONS]
+
+Commands:
+  load     Load a registry, optionally filtered by PNR
+             --path <PATH>         Registry file or directory
+             --registry <NAME>     Registry name (detected from the path if omitted)
+             --pnr-file <FILE>     File with one PNR per line to filter by
+             --out <FILE>          Write the loaded rows to this file
+  inspect  Show the schema and row counts of Parquet files
+             --path <PATH>         Parquet file or directory
+  preview  Print the first rows of a registry, reading no further
+             --path <PATH>         Registry file or directory
+             --registry <NAME>     Registry name (detected from the path if omitted)
+             --rows <N>            Number of rows to print (default: 10)
+  scd      Find individuals with a severe chronic disease diagnosis
+             --lpr-path <PATH>     Directory of LPR files with PNR and diagnosis columns
+             --pnr-column <NAME>   PNR column (default: PNR)
+             --diagnosis-column <NAME>
+                                   Diagnosis column (default: C_ADIAG)
+             --date-column <NAME>  Diagnosis date column (default: D_INDDTO)
+             --out <FILE>          Write PNR and severity to this file
+  help     Print this message";
+
+/// Options of a subcommand, given as `--name value` pairs
+struct Options {
+    values: HashMap<String, String>,
+}
+
+impl Options {
+    /// Parse options, accepting only the names in `allowed`
+    fn parse(args: &[String], allowed: &[&str]) -> Result<Self> {
+        let mut values = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .filter(|name| allowed.contains(name))
+                .ok_or_else(|| Error::ValidationError(format!("Unexpected argument '{arg}'")))?;
+            let value = args
+                .next()
+                .ok_or_else(|| Error::ValidationError(format!("Missing value for '{arg}'")))?;
+            values.insert(name.to_string(), value.clone());
+        }
+        Ok(Self { values })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    fn path(&self, name: &str) -> Option<PathBuf> {
+        self.get(name).map(PathBuf::from)
+    }
+
+    fn required_path(&self, name: &str) -> Result<PathBuf> {
+        self.path(name).ok_or_else(|| {
+            Error::ValidationError(format!("Missing required option '--{name}'")).into()
+        })
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Setup logging
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
-    // Use the base directory with registry data
-    let base_dir = Path::new("/Users/tobiaskragholm/generated_data/parquet");
-    if !base_dir.exists() {
-        warn!("Data directory not found: {}", base_dir.display());
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, rest)) = args.split_first() else {
+        println!("{USAGE}");
         return Ok(());
+    };
+
+    match command.as_str() {
+        "load" => {
+            let options = Options::parse(rest, &["path", "registry", "pnr-file", "out"])?;
+            load(&options).await
+        }
+        "inspect" => inspect(&Options::parse(rest, &["path"])?.required_path("path")?),
+        "preview" => preview(&Options::parse(rest, &["path", "registry", "rows"])?).await,
+        "scd" => {
+            let options = Options::parse(
+                rest,
+                &[
+                    "lpr-path",
+                    "pnr-column",
+                    "diagnosis-column",
+                    "date-column",
+                    "out",
+                ],
+            )?;
+            scd(&options)
+        }
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            Err(Error::ValidationError(format!("Unknown command '{command}'")).into())
+        }
     }
+}
 
-    info!("Loading registry data from: {}", base_dir.display());
-
-    // Example 1: Load BEF registry using registry_from_name
-    let bef_path = base_dir.join("bef");
-    if bef_path.exists() {
-        info!("Loading BEF registry data...");
-        let start = Instant::now();
-        let registry = registry_from_name("bef")?;
-        let batches = registry.load(&bef_path, None)?;
-        info!(
-            "Loaded {} BEF batches in {:?}",
-            batches.len(),
-            start.elapsed()
-        );
+/// Load a registry and optionally write the result
+async fn load(options: &Options) -> Result<()> {
+    let path = options.required_path("path")?;
+    let registry = match options.get("registry") {
+        Some(name) => registry_from_name(name)?,
+        None => registry_from_path(&path)?,
+    };
+    let pnr_filter = options
+        .path("pnr-file")
+        .map(|file| PnrSet::from_file(&file))
+        .transpose()?;
 
-        // Example of deserializing the batches to individuals
-        if !batches.is_empty() {
-            let sample_batch = &batches[0];
-            let deserializer = par_reader::registry::bef::create_deserializer();
-            let individuals =
-                par_reader::registry::bef::deserialize_batch(&deserializer, sample_batch)?;
-            info!(
-                "Deserialized {} individuals from first BEF batch",
-                individuals.len()
-            );
-        }
+    info!(
+        "Loading {} registry data from {}...",
+        registry.get_register_name(),
+        path.display()
+    );
+    let start = Instant::now();
+    let batches = registry.load_async(&path, pnr_filter.as_deref()).await?;
+    let rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+    info!(
+        "Loaded {} rows in {} batches in {:?}",
+        rows,
+        batches.len(),
+        start.elapsed()
+    );
+
+    if let Some(out) = options.path("out") {
+        let schema = batches
+            .first()
+            .map_or_else(|| registry.get_schema(), RecordBatch::schema);
+        write_output(&out, schema, &batches)?;
     }
+    Ok(())
+}
 
-    // Example 2: Load AKM registry using registry_from_path (auto-detect)
-    let akm_path = base_dir.join("akm");
-    if akm_path.exists() {
-        info!("Loading AKM registry data...");
-        let start = Instant::now();
-        let registry = registry_from_path(&akm_path)?;
-        let batches = registry.load(&akm_path, None)?;
-        info!(
-            "Loaded {} AKM batches in {:?}",
-            batches.len(),
-            start.elapsed()
-        );
+/// Print the schema and row counts of a Parquet file or directory
+fn inspect(path: &Path) -> Result<()> {
+    let files = if path.is_dir() {
+        find_parquet_files(path)?
+    } else {
+        vec![path.to_path_buf()]
+    };
+    if let Ok(registry) = registry_from_path(path) {
+        println!("Registry: {}", registry.get_register_name());
     }
 
-    // Example 3: Load IND registry
-    let ind_path = base_dir.join("ind");
-    if ind_path.exists() {
-        info!("Loading IND registry data...");
-        let start = Instant::now();
-        let registry = registry_from_name("ind")?;
-        let batches = registry.load(&ind_path, None)?;
-        info!(
-            "Loaded {} IND batches in {:?}",
-            batches.len(),
-            start.elapsed()
+    let mut total_rows = 0;
+    let mut schema: Option<SchemaRef> = None;
+    for file in &files {
+        let reader = SerializedFileReader::new(
+            File::open(file)
+                .map_err(|e| anyhow::anyhow!("Failed to open file {}: {}", file.display(), e))?,
+        )?;
+        let metadata = reader.metadata();
+        let rows = metadata.file_metadata().num_rows();
+        total_rows += rows;
+        println!(
+            "{}: {} rows, {} row groups",
+            file.display(),
+            rows,
+            metadata.num_row_groups()
         );
+        if schema.is_none() {
+            schema = Some(read_parquet_schema(file)?);
+        }
     }
+    println!("Total: {} rows in {} files", total_rows, files.len());
 
-    // Example 4: Load DOD registry
-    let dod_path = base_dir.join("dod");
-    if dod_path.exists() {
-        info!("Loading DOD registry data...");
-        let start = Instant::now();
-        let registry = registry_from_name("dod")?;
-        let batches = registry.load(&dod_path, None)?;
-        info!(
-            "Loaded {} DOD batches in {:?}",
-            batches.len(),
-            start.elapsed()
-        );
+    if let Some(schema) = schema {
+        println!("Schema:");
+        for field in schema.fields() {
+            let nullable = if field.is_nullable() {
+                ""
+            } else {
+                " (not null)"
+            };
+            println!("  {}: {}{}", field.name(), field.data_type(), nullable);
+        }
     }
+    Ok(())
+}
 
-    // Example 5: Load multiple registries asynchronously
-    info!("Loading multiple registries asynchronously...");
-    
-    // Create longer-lived path variables
-    let bef_path = base_dir.join("bef");
-    let akm_path = base_dir.join("akm");
-    let ind_path = base_dir.join("ind");
-    let dod_path = base_dir.join("dod");
-    
-    let registry_paths = [
-        ("bef", bef_path.as_path()),
-        ("akm", akm_path.as_path()),
-        ("ind", ind_path.as_path()),
-        ("dod", dod_path.as_path()),
-    ];
+/// Print the first rows of a registry as tab-separated values
+async fn preview(options: &Options) -> Result<()> {
+    let path = options.required_path("path")?;
+    let registry = match options.get("registry") {
+        Some(name) => registry_from_name(name)?,
+        None => registry_from_path(&path)?,
+    };
+    let rows = options
+        .get("rows")
+        .map(str::parse::<usize>)
+        .transpose()
+        .map_err(|e| Error::ValidationError(format!("Invalid value for '--rows': {e}")))?
+        .unwrap_or(10);
 
-    let start = Instant::now();
-    let result = par_reader::registry::factory::load_multiple_registries_async(
-        &registry_paths,
-        None,
-    )
-    .await;
-
-    match result {
-        Ok(batches) => {
-            info!(
-                "Loaded {} total batches from multiple registries in {:?}",
-                batches.len(),
-                start.elapsed()
-            );
-        }
-        Err(e) => {
-            warn!("Error loading multiple registries: {e}");
+    let loader = DirectRegistryLoader::new(registry.get_register_name()).with_limit(Some(rows));
+    let batches = loader.load_async(&path, None).await?;
+    let schema = batches
+        .first()
+        .map_or_else(|| loader.get_schema(), RecordBatch::schema);
+
+    let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    println!("{}", names.join("\t"));
+    for batch in &batches {
+        for row in 0..batch.num_rows() {
+            let values = batch
+                .columns()
+                .iter()
+                .map(|column| array_value_to_string(column, row))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            println!("{}", values.join("\t"));
         }
     }
+    Ok(())
+}
+
+/// Classify individuals by severe chronic disease diagnoses in LPR data
+fn scd(options: &Options) -> Result<()> {
+    let lpr_path = options.required_path("lpr-path")?;
+    let pnr_column = options.get("pnr-column").unwrap_or("PNR");
+    let diagnosis_column = options.get("diagnosis-column").unwrap_or("C_ADIAG");
+    let date_column = options.get("date-column").unwrap_or("D_INDDTO");
+
+    let start = Instant::now();
+    let batches = load_parquet_files_parallel::<std::hash::RandomState>(
+        &lpr_path, None, None, None, None, None,
+    )?;
+    let results = apply_scd_to_batches(
+        &batches,
+        pnr_column,
+        diagnosis_column,
+        date_column,
+        &ScdConfig::default(),
+    )?;
+
+    // Highest severity among each individual's SCD diagnoses
+    let severities: BTreeMap<String, i32> = results
+        .into_values()
+        .filter(|result| result.has_scd)
+        .map(|result| (result.pnr, result.max_severity))
+        .collect();
+    info!(
+        "Found {} individuals with SCD in {:?}",
+        severities.len(),
+        start.elapsed()
+    );
 
-    info!("Registry loading examples completed successfully");
+    if let Some(out) = options.path("out") {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(pnr_column, DataType::Utf8, false),
+            Field::new("SEVERITY", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from_iter_values(severities.keys())) as ArrayRef,
+                Arc::new(Int32Array::from_iter_values(severities.values().copied())),
+            ],
+        )?;
+        write_output(&out, schema, &[batch])?;
+    }
+    Ok(())
+}
+
+/// Write batches to a file in the format given by its extension
+fn write_output(path: &Path, schema: SchemaRef, batches: &[RecordBatch]) -> Result<()> {
+    let mut sink = FileSink::from_path(path, schema)?;
+    for batch in batches {
+        sink.write_batch(batch)?;
+    }
+    sink.finish()?;
+    info!("Wrote {} batches to {}", batches.len(), path.display());
     Ok(())
 }
@@ -6,6 +6,8 @@ use crate::RecordBatch;
 use crate::error::Result;
 use crate::models::core::individual::temporal::TimePeriod;
 use crate::models::core::traits::EntityModel;
+use crate::models::core::types::{ImmigrationType, UrbanRural};
+use crate::models::health::diagnosis::ScdStatus;
 use macros::PropertyField;
 
 use arrow::array::Array;
@@ -15,6 +17,7 @@ use chrono::{Datelike, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use std::default::Default;
+use std::sync::Arc;
 
 /// Role of an individual in the study context
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,6 +32,15 @@ pub enum Role {
     Other,
 }
 
+/// The input row an individual was deserialized from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// The file the row was read from
+    pub source_file: Arc<str>,
+    /// The row index within the file, counted from 0 across its batches
+    pub source_row: usize,
+}
+
 /// Core Individual entity representing a person in the study
 ///
 /// A single unified struct that handles both storage and serde operations
@@ -61,6 +73,10 @@ pub struct Individual {
     #[serde(skip)]
     pub properties: Option<HashMap<String, Box<dyn std::any::Any + Send + Sync>>>,
 
+    /// The input row this individual was read from, when the loader records it
+    #[serde(skip)]
+    pub provenance: Option<Provenance>,
+
     // Core characteristics
     /// Gender of the individual
     #[serde(alias = "KOEN")]
@@ -135,6 +151,11 @@ pub struct Individual {
     #[serde(skip_deserializing, default)]
     pub is_rural: bool,
 
+    /// Urban/rural classification of the municipality, set by
+    /// [`Individual::classify_urban_rural`]
+    #[serde(skip_deserializing, default)]
+    pub urban_rural: Option<UrbanRural>,
+
     /// Housing type
     /// 1: Single man
     /// 2: Single woman
@@ -293,6 +314,10 @@ pub struct Individual {
     #[property(name = "underlying_death_cause")]
     pub underlying_death_cause: Option<String>,
 
+    /// Severe chronic disease status, set from the results of the SCD algorithm
+    #[serde(skip)]
+    pub scd: Option<ScdStatus>,
+
     // MFR registry specific fields
     /// Birth weight in grams
     #[property(name = "birth_weight")]
@@ -338,6 +363,7 @@ impl Clone for Individual {
         cloned.father_pnr = self.father_pnr.clone();
         cloned.family_id = self.family_id.clone();
         cloned.spouse_pnr = self.spouse_pnr.clone();
+        cloned.provenance = self.provenance.clone();
 
         cloned.gender = self.gender.clone();
         cloned.birth_date = self.birth_date;
@@ -353,6 +379,7 @@ impl Clone for Individual {
         cloned.municipality_code = self.municipality_code.clone();
         cloned.regional_code = self.regional_code.clone();
         cloned.is_rural = self.is_rural;
+        cloned.urban_rural = self.urban_rural;
         cloned.household_type = self.household_type;
         cloned.family_size = self.family_size;
         cloned.household_size = self.household_size;
@@ -391,6 +418,7 @@ impl Clone for Individual {
         cloned.discharge_dates = self.discharge_dates.clone();
         cloned.death_cause = self.death_cause.clone();
         cloned.underlying_death_cause = self.underlying_death_cause.clone();
+        cloned.scd = self.scd.clone();
 
         cloned.birth_weight = self.birth_weight;
         cloned.birth_length = self.birth_length;
@@ -524,10 +552,12 @@ impl Individual {
 
             // Properties map for dynamic fields
             properties: None,
+            provenance: None,
 
             // Basic demographic information
             municipality_code: None,
             is_rural: false,
+            urban_rural: None,
             mother_pnr: None,
             father_pnr: None,
             family_id: None,
@@ -569,6 +599,7 @@ impl Individual {
             discharge_dates: None,
             death_cause: None,
             underlying_death_cause: None,
+            scd: None,
 
             // Migration information
             event_date: None,
@@ -612,6 +643,30 @@ impl Individual {
         self.properties.as_ref()
     }
 
+    /// Get a copy of a property of a given type
+    ///
+    /// The properties map is checked before the dedicated fields, so a
+    /// property whose type differs from the dedicated field of its name is
+    /// still found.
+    ///
+    /// # Returns
+    ///
+    /// The value, or `None` if the property is unset or of another type
+    #[must_use]
+    pub fn property_value<T: std::any::Any + Clone>(&self, property: &str) -> Option<T> {
+        use crate::models::core::individual::implementations::property_reflection::PropertyReflection;
+
+        self.properties()
+            .and_then(|properties| properties.get(property))
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+            .or_else(|| {
+                self.get_reflected_property(property)
+                    .and_then(|value| value.downcast::<T>().ok())
+                    .map(|value| *value)
+            })
+    }
+
     /// Store a property in the properties map
     /// This is kept for compatibility with existing code
     pub fn store_property(&mut self, property: &str, value: Box<dyn std::any::Any + Send + Sync>) {
@@ -628,6 +683,33 @@ impl Individual {
         }
     }
 
+    /// Classify the municipality as urban, intermediate or rural
+    ///
+    /// The table is keyed by the numeric municipality code, so `"0101"` and
+    /// `"101"` are the same municipality. A missing or unlisted municipality
+    /// leaves `urban_rural` as `None`; otherwise `is_rural` follows the
+    /// classification.
+    pub fn classify_urban_rural(&mut self, table: &HashMap<u16, UrbanRural>) {
+        self.urban_rural = self
+            .municipality_code
+            .as_deref()
+            .and_then(|code| code.trim().parse::<u16>().ok())
+            .and_then(|code| table.get(&code).copied());
+        if let Some(classification) = self.urban_rural {
+            self.is_rural = classification == UrbanRural::Rural;
+        }
+    }
+
+    /// Decode the immigration type (`IE_TYPE`)
+    ///
+    /// # Returns
+    /// The decoded type, with unexpected codes as `Unknown`, or `None` if the
+    /// immigration type is missing
+    #[must_use]
+    pub fn immigration_category(&self) -> Option<ImmigrationType> {
+        self.immigration_type.as_deref().map(ImmigrationType::from)
+    }
+
     /// Convert directly from a `RecordBatch` using `serde_arrow`
     pub fn from_batch(batch: &RecordBatch) -> Result<Vec<Self>> {
         match serde_arrow::from_record_batch::<Vec<Self>>(batch) {
@@ -826,6 +908,10 @@ impl Individual {
             self.compute_rural_status();
         }
 
+        if self.urban_rural.is_none() {
+            self.urban_rural = source.urban_rural;
+        }
+
         if self.household_size.is_none() {
             self.household_size = source.household_size;
         }
@@ -937,6 +1023,14 @@ impl Individual {
             self.underlying_death_cause = source.underlying_death_cause.clone();
         }
 
+        if self.scd.is_none() {
+            self.scd = source.scd.clone();
+        }
+
+        if self.provenance.is_none() {
+            self.provenance = source.provenance.clone();
+        }
+
         // Migration fields
         if self.event_date.is_none() {
             self.event_date = source.event_date;
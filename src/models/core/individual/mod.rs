@@ -4,12 +4,15 @@
 
 // Re-export the main Individual struct and related types
 pub use self::consolidated::Individual;
+pub use self::consolidated::Provenance;
 pub use self::consolidated::Role;
+pub use self::property::PropertyField;
 pub use self::temporal::TimePeriod;
 
 // Core implementation
 pub mod consolidated;
 pub mod implementations;
 
+pub mod property;
 mod relationships;
 pub mod temporal;
@@ -0,0 +1,101 @@
+//! Typed access to categorical properties of an Individual
+//!
+//! `PropertyField` names the categorical fields of [`Individual`] so they can
+//! be selected at runtime, e.g. as the dimensions of a cross-tabulation,
+//! without passing field names around as strings.
+
+use std::fmt;
+
+use crate::models::core::individual::Individual;
+
+/// A categorical property of an [`Individual`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PropertyField {
+    /// Gender (`KOEN`)
+    Gender,
+    /// Immigration type (`IE_TYPE`), decoded to an [`ImmigrationType`] label
+    ///
+    /// [`ImmigrationType`]: crate::models::core::types::ImmigrationType
+    ImmigrationType,
+    /// Geographic origin (`OPR_LAND`)
+    Origin,
+    /// Citizenship status (`STATSB`)
+    CitizenshipStatus,
+    /// Marital status (`CIVST`)
+    MaritalStatus,
+    /// Municipality code (`KOM`)
+    MunicipalityCode,
+    /// Regional code (`REG`)
+    RegionalCode,
+    /// Family type (`FAMILIE_TYPE`)
+    FamilyType,
+    /// Socioeconomic status classification (`SOCIO13`)
+    SocioeconomicStatus,
+}
+
+impl PropertyField {
+    /// Get the property name, as used by `set_property_field`
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Gender => "gender",
+            Self::ImmigrationType => "immigration_type",
+            Self::Origin => "origin",
+            Self::CitizenshipStatus => "citizenship_status",
+            Self::MaritalStatus => "marital_status",
+            Self::MunicipalityCode => "municipality_code",
+            Self::RegionalCode => "regional_code",
+            Self::FamilyType => "family_type",
+            Self::SocioeconomicStatus => "socioeconomic_status",
+        }
+    }
+
+    /// Get the registry column this property is read from
+    ///
+    /// # Returns
+    /// The column name, or `None` for immigration type, whose values are
+    /// already decoded labels
+    #[must_use]
+    pub const fn source_column(self) -> Option<&'static str> {
+        match self {
+            Self::Gender => Some("KOEN"),
+            Self::ImmigrationType => None,
+            Self::Origin => Some("OPR_LAND"),
+            Self::CitizenshipStatus => Some("STATSB"),
+            Self::MaritalStatus => Some("CIVST"),
+            Self::MunicipalityCode => Some("KOM"),
+            Self::RegionalCode => Some("REG"),
+            Self::FamilyType => Some("FAMILIE_TYPE"),
+            Self::SocioeconomicStatus => Some("SOCIO13"),
+        }
+    }
+
+    /// Get the value of this property for an individual
+    ///
+    /// # Returns
+    /// The value as a category label, or `None` if it is missing
+    #[must_use]
+    pub fn value(self, individual: &Individual) -> Option<String> {
+        match self {
+            Self::Gender => individual.gender.clone(),
+            Self::ImmigrationType => individual
+                .immigration_category()
+                .map(|category| category.to_string()),
+            Self::Origin => individual.origin.clone(),
+            Self::CitizenshipStatus => individual.citizenship_status.clone(),
+            Self::MaritalStatus => individual.marital_status.clone(),
+            Self::MunicipalityCode => individual.municipality_code.clone(),
+            Self::RegionalCode => individual.regional_code.clone(),
+            Self::FamilyType => individual.family_type.map(|value| value.to_string()),
+            Self::SocioeconomicStatus => individual
+                .socioeconomic_status
+                .map(|value| value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for PropertyField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
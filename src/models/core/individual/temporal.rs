@@ -54,6 +54,24 @@ impl TemporalValidity for Individual {
 
 // Additional temporal methods for Individual
 impl Individual {
+    /// Calculate the age in completed years at a date
+    ///
+    /// Unlike [`HealthStatus::age_at`], the age is computed whether or not the
+    /// individual was alive at the date, which suits ages at a fixed index
+    /// date in descriptive tables. The age is negative for dates before birth.
+    ///
+    /// # Returns
+    /// The age, or `None` if the birth date is unknown
+    #[must_use]
+    pub fn age_in_years_at(&self, date: NaiveDate) -> Option<i64> {
+        let birth_date = self.birth_date?;
+        let mut age = i64::from(date.year() - birth_date.year());
+        if (date.month(), date.day()) < (birth_date.month(), birth_date.day()) {
+            age -= 1;
+        }
+        Some(age)
+    }
+
     /// Determine if this individual is a child based on age at reference date
     #[must_use]
     pub fn is_child(&self, reference_date: &NaiveDate) -> bool {
@@ -8,7 +8,7 @@ pub mod registry_traits;
 pub mod traits;
 pub mod types;
 
-pub use individual::{Individual, Role};
+pub use individual::{Individual, Provenance, Role};
 pub use registry_traits::*;
 pub use traits::*;
 pub use types::*;
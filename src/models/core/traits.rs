@@ -92,7 +92,14 @@ where
     fn filter_by_date(&self, date: &NaiveDate) -> C;
 
     /// Filter by an attribute value
-    fn filter_by_attribute<V: PartialEq>(&self, attr_name: &str, value: V) -> C;
+    ///
+    /// The value is compared with the named attribute when it has the
+    /// attribute's type; an unknown attribute or a value of another type
+    /// matches nothing.
+    ///
+    /// `V` must be `'static`, as implementations find the attribute's type
+    /// through [`std::any::Any`]; pass borrowed text as a `String`.
+    fn filter_by_attribute<V: PartialEq + 'static>(&self, attr_name: &str, value: V) -> C;
 
     /// Apply multiple filters in sequence
     fn apply_filters<F>(&self, filters: Vec<F>) -> C
@@ -28,7 +28,7 @@ impl From<&str> for Gender {
     fn from(s: &str) -> Self {
         match s.trim().to_lowercase().as_str() {
             "m" | "male" | "1" => Self::Male,
-            "f" | "female" | "2" => Self::Female,
+            "f" | "female" | "k" | "2" => Self::Female,
             _ => Self::Unknown,
         }
     }
@@ -87,6 +87,56 @@ impl From<i32> for Origin {
     }
 }
 
+/// Immigration type (`IE_TYPE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImmigrationType {
+    /// Person of Danish origin
+    DanishOrigin,
+    /// Immigrant
+    Immigrant,
+    /// Descendant of immigrants
+    Descendant,
+    /// Unknown or unexpected code
+    Unknown,
+}
+
+impl ImmigrationType {
+    /// Default value for `ImmigrationType` when not specified
+    #[must_use]
+    pub const fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+impl From<&str> for ImmigrationType {
+    /// Decode an `IE_TYPE` code, ignoring surrounding whitespace and leading zeros
+    fn from(s: &str) -> Self {
+        s.trim().parse::<i32>().map_or(Self::Unknown, Self::from)
+    }
+}
+
+impl From<i32> for ImmigrationType {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::DanishOrigin,
+            2 => Self::Immigrant,
+            3 => Self::Descendant,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for ImmigrationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::DanishOrigin => "Danish origin",
+            Self::Immigrant => "Immigrant",
+            Self::Descendant => "Descendant",
+            Self::Unknown => "Unknown",
+        })
+    }
+}
+
 /// Education level using ISCED classification
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EducationLevel {
@@ -251,6 +301,17 @@ impl From<i32> for DiseaseOrigin {
     }
 }
 
+/// Degree of urbanisation of a municipality
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UrbanRural {
+    /// Urban municipality, such as the larger cities and their suburbs
+    Urban,
+    /// Intermediate municipality, with both towns and rural areas
+    Intermediate,
+    /// Rural municipality
+    Rural,
+}
+
 /// Job situation category
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum JobSituation {
@@ -553,3 +614,17 @@ impl From<i32> for SocioeconomicStatus {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_immigration_type_decoding() {
+        assert_eq!(ImmigrationType::from("1"), ImmigrationType::DanishOrigin);
+        assert_eq!(ImmigrationType::from("02"), ImmigrationType::Immigrant);
+        assert_eq!(ImmigrationType::from(" 3 "), ImmigrationType::Descendant);
+        assert_eq!(ImmigrationType::from("9"), ImmigrationType::Unknown);
+        assert_eq!(ImmigrationType::from("x"), ImmigrationType::Unknown);
+    }
+}
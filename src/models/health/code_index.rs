@@ -0,0 +1,139 @@
+//! Prefix index over diagnosis codes
+//!
+//! This module provides a trie keyed by diagnosis code characters, so that
+//! disease groups defined by ICD-10 prefix sets (e.g. `I20`-`I25`) can be
+//! queried without scanning every diagnosis.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use crate::models::health::diagnosis::Diagnosis;
+
+/// A single trie node
+#[derive(Debug, Default)]
+struct TrieNode {
+    /// Child nodes by next code byte
+    children: HashMap<u8, usize>,
+    /// Positions of diagnoses whose code ends at this node
+    diagnoses: Vec<usize>,
+}
+
+/// Prefix trie over the codes of a set of diagnoses
+///
+/// The trie is built once and can answer any number of prefix queries.
+/// Codes are indexed as stored; callers must use the same spelling
+/// (e.g. with or without the Danish `D` prefix) in their queries.
+#[derive(Debug)]
+pub struct CodePrefixIndex {
+    /// The indexed diagnoses, in insertion order
+    diagnoses: Vec<Arc<Diagnosis>>,
+    /// Trie nodes; the root is at position 0
+    nodes: Vec<TrieNode>,
+}
+
+impl CodePrefixIndex {
+    /// Build an index over the given diagnoses
+    #[must_use]
+    pub fn new(diagnoses: Vec<Arc<Diagnosis>>) -> Self {
+        let mut nodes = vec![TrieNode::default()];
+
+        for (position, diagnosis) in diagnoses.iter().enumerate() {
+            let mut node = 0;
+            for byte in diagnosis.diagnosis_code.bytes() {
+                node = match nodes[node].children.get(&byte) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.insert(byte, child);
+                        child
+                    }
+                };
+            }
+            nodes[node].diagnoses.push(position);
+        }
+
+        Self { diagnoses, nodes }
+    }
+
+    /// Number of indexed diagnoses
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.diagnoses.len()
+    }
+
+    /// Whether the index contains no diagnoses
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.diagnoses.is_empty()
+    }
+
+    /// Find the trie node for a prefix, if any code starts with it
+    fn find_node(&self, prefix: &str) -> Option<usize> {
+        prefix.bytes().try_fold(0, |node, byte| {
+            self.nodes[node].children.get(&byte).copied()
+        })
+    }
+
+    /// Get all diagnoses whose code starts with any of the given prefixes
+    ///
+    /// Each diagnosis is returned at most once, even if it matches several
+    /// overlapping prefixes, in the order it was indexed. An empty prefix
+    /// matches every diagnosis.
+    #[must_use]
+    pub fn by_prefixes(&self, prefixes: &[&str]) -> Vec<Arc<Diagnosis>> {
+        let mut positions = BTreeSet::new();
+        let mut stack: Vec<usize> = prefixes.iter().filter_map(|p| self.find_node(p)).collect();
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            positions.extend(node.diagnoses.iter().copied());
+            stack.extend(node.children.values().copied());
+        }
+
+        positions
+            .into_iter()
+            .map(|position| self.diagnoses[position].clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::core::types::DiagnosisType;
+
+    fn diagnosis(pnr: &str, code: &str) -> Arc<Diagnosis> {
+        Arc::new(Diagnosis::new(
+            pnr.to_string(),
+            code.to_string(),
+            DiagnosisType::Primary,
+            None,
+        ))
+    }
+
+    #[test]
+    fn test_by_prefixes() {
+        let index = CodePrefixIndex::new(vec![
+            diagnosis("a", "I21"),
+            diagnosis("b", "I219"),
+            diagnosis("c", "I50"),
+            diagnosis("d", "E10"),
+            diagnosis("e", "I2"),
+        ]);
+
+        let codes = |prefixes: &[&str]| -> Vec<String> {
+            index
+                .by_prefixes(prefixes)
+                .iter()
+                .map(|d| d.diagnosis_code.clone())
+                .collect()
+        };
+
+        assert_eq!(codes(&["I2"]), vec!["I21", "I219", "I2"]);
+        // Overlapping prefixes do not duplicate results
+        assert_eq!(codes(&["I21", "I2", "E"]), vec!["I21", "I219", "E10", "I2"]);
+        assert!(codes(&["X", "I3"]).is_empty());
+        assert_eq!(codes(&[""]).len(), 5);
+    }
+}
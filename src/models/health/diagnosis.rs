@@ -11,14 +11,17 @@ use crate::error::Result;
 use crate::models::collections::ModelCollection;
 use crate::models::core::traits::ArrowSchema;
 use crate::models::core::traits::EntityModel;
+use crate::models::core::traits::Filterable;
 use crate::models::core::types::DiagnosisType;
+use crate::models::health::code_index::CodePrefixIndex;
 use crate::utils::arrow::array_utils::{downcast_array, get_column};
 use arrow::array::{Array, Date32Array, Int32Array, StringArray};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 use chrono::NaiveDate;
+use std::any::Any;
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 /// Representation of a medical diagnosis
 #[derive(Debug, Clone)]
@@ -433,12 +436,10 @@ impl Default for ScdCriteria {
 #[must_use]
 pub fn get_scd_category_for_code(code: &str) -> u8 {
     // Simple mapping based on ICD-10 chapter
-    if code.starts_with('C') || code.starts_with('D') && code.len() >= 3 && &code[1..3] <= "48" {
+    let block = code.get(1..3);
+    if code.starts_with('C') || code.starts_with('D') && block.is_some_and(|b| b <= "48") {
         1 // Blood/neoplasm
-    } else if (code.starts_with('D')
-        && code.len() >= 3
-        && &code[1..3] >= "50"
-        && &code[1..3] <= "89")
+    } else if (code.starts_with('D') && block.is_some_and(|b| ("50"..="89").contains(&b)))
         || code.starts_with("M35")
         || code.starts_with("M30")
     {
@@ -483,6 +484,10 @@ pub struct ScdResult {
     pub has_congenital: bool,
     /// Total hospitalization count
     pub hospitalization_count: i32,
+    /// Number of qualifying diagnoses per category, counting distinct dates
+    /// if the algorithm required them. Recorded whether or not the
+    /// individual met the qualification threshold.
+    pub qualifying_counts: Vec<(u8, usize)>,
 }
 
 impl ScdResult {
@@ -498,6 +503,7 @@ impl ScdResult {
             max_severity: 0,
             has_congenital: false,
             hospitalization_count: 0,
+            qualifying_counts: Vec::new(),
         }
     }
 
@@ -554,6 +560,15 @@ impl ScdResult {
         self.scd_categories.len()
     }
 
+    /// Get the number of qualifying diagnoses of a category
+    #[must_use]
+    pub fn qualifying_count(&self, category: u8) -> usize {
+        self.qualifying_counts
+            .iter()
+            .find(|(qualifying, _)| *qualifying == category)
+            .map_or(0, |(_, count)| *count)
+    }
+
     /// Calculate hospitalization-based severity
     #[must_use]
     pub const fn hospitalization_severity(&self) -> i32 {
@@ -580,6 +595,33 @@ impl ScdResult {
     }
 }
 
+/// The SCD status of an individual, as stored on `Individual::scd`
+///
+/// A compact copy of an [`ScdResult`] without the diagnoses, for analysis
+/// together with demographics.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScdStatus {
+    /// Whether the individual has any SCD
+    pub has_scd: bool,
+    /// Major SCD categories present
+    pub categories: Vec<u8>,
+    /// Highest severity among SCD diagnoses
+    pub max_severity: i32,
+    /// Date of the first SCD diagnosis
+    pub earliest_date: Option<NaiveDate>,
+}
+
+impl From<&ScdResult> for ScdStatus {
+    fn from(result: &ScdResult) -> Self {
+        Self {
+            has_scd: result.has_scd,
+            categories: result.scd_categories.clone(),
+            max_severity: result.max_severity,
+            earliest_date: result.first_scd_date,
+        }
+    }
+}
+
 /// A collection of diagnoses that can be efficiently queried
 #[derive(Debug, Default)]
 pub struct DiagnosisCollection {
@@ -587,6 +629,8 @@ pub struct DiagnosisCollection {
     diagnoses_by_pnr: HashMap<String, Vec<Arc<Diagnosis>>>,
     /// SCD results by individual PNR
     scd_results: HashMap<String, ScdResult>,
+    /// Code prefix index, built on first prefix query and reset on insertion
+    code_index: OnceLock<CodePrefixIndex>,
 }
 
 impl DiagnosisCollection {
@@ -596,9 +640,72 @@ impl DiagnosisCollection {
         Self {
             diagnoses_by_pnr: HashMap::new(),
             scd_results: HashMap::new(),
+            code_index: OnceLock::new(),
         }
     }
 
+    /// Get the PNRs of all individuals with at least one diagnosis
+    #[must_use]
+    pub fn individuals(&self) -> Vec<String> {
+        self.diagnoses_by_pnr.keys().cloned().collect()
+    }
+
+    /// Get the PNRs of all evaluated individuals, with or without SCD
+    ///
+    /// This is every individual with diagnoses or an SCD result, each once,
+    /// and so the denominator for SCD prevalences.
+    #[must_use]
+    pub fn all_individuals(&self) -> HashSet<String> {
+        self.diagnoses_by_pnr
+            .keys()
+            .chain(self.scd_results.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// Get the code prefix index, building it on first use
+    ///
+    /// The index is shared by all subsequent prefix queries until the
+    /// collection is modified. Diagnoses are indexed by PNR, and in the order
+    /// they were added for each individual.
+    #[must_use]
+    pub fn code_index(&self) -> &CodePrefixIndex {
+        self.code_index.get_or_init(|| {
+            let mut individuals: Vec<_> = self.diagnoses_by_pnr.iter().collect();
+            individuals.sort_unstable_by_key(|(pnr, _)| *pnr);
+            let diagnoses = individuals
+                .into_iter()
+                .flat_map(|(_, diagnoses)| diagnoses.iter().cloned())
+                .collect();
+            CodePrefixIndex::new(diagnoses)
+        })
+    }
+
+    /// Get all diagnoses whose code starts with any of the given prefixes
+    ///
+    /// Backed by a prefix trie that is built once per collection state, so
+    /// repeated disease-group queries (e.g. `&["I20", "I21", "I22", "I23",
+    /// "I24", "I25"]`) do not rescan the collection. Diagnoses are returned
+    /// in the order of [`DiagnosisCollection::code_index`], so the result of
+    /// a query does not depend on hash map iteration order.
+    #[must_use]
+    pub fn by_code_prefixes(&self, prefixes: &[&str]) -> Vec<Arc<Diagnosis>> {
+        self.code_index().by_prefixes(prefixes)
+    }
+
+    /// Create a collection holding the given diagnoses, without SCD results
+    fn from_diagnoses(diagnoses: impl IntoIterator<Item = Arc<Diagnosis>>) -> Self {
+        let mut collection = Self::new();
+        for diagnosis in diagnoses {
+            collection
+                .diagnoses_by_pnr
+                .entry(diagnosis.individual_pnr.clone())
+                .or_default()
+                .push(diagnosis);
+        }
+        collection
+    }
+
     /// Get all diagnoses for an individual
     #[must_use]
     pub fn get_diagnoses(&self, pnr: &str) -> Vec<Arc<Diagnosis>> {
@@ -674,6 +781,9 @@ impl ModelCollection<Diagnosis> for DiagnosisCollection {
         let pnr = diagnosis.individual_pnr.clone();
         let diagnosis_arc = Arc::new(diagnosis);
 
+        // The prefix index no longer reflects the collection
+        self.code_index.take();
+
         self.diagnoses_by_pnr
             .entry(pnr)
             .or_default()
@@ -724,3 +834,135 @@ impl ModelCollection<Diagnosis> for DiagnosisCollection {
         self.diagnoses_by_pnr.values().map(std::vec::Vec::len).sum()
     }
 }
+
+// Narrowed collections share the diagnoses of this one but hold no SCD
+// results, as those were derived from the diagnoses before narrowing
+impl Filterable<Diagnosis, Self> for DiagnosisCollection {
+    /// Keep the diagnoses made on or before `date`
+    ///
+    /// Diagnoses without a date are dropped.
+    fn filter_by_date(&self, date: &NaiveDate) -> Self {
+        Self::from_diagnoses(
+            self.filter(|diagnosis| diagnosis.diagnosis_date.is_some_and(|made| made <= *date)),
+        )
+    }
+
+    /// Keep the diagnoses whose attribute equals `value`
+    ///
+    /// The attributes are `individual_pnr` and `diagnosis_code` (compared
+    /// with a `String` or `&str`), `diagnosis_type` (a `DiagnosisType`),
+    /// `diagnosis_date` (a `NaiveDate`), `is_scd` (a `bool`) and `severity`
+    /// (an `i32`).
+    fn filter_by_attribute<V: PartialEq + 'static>(&self, attr_name: &str, value: V) -> Self {
+        let value: &dyn Any = &value;
+        let text = value
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| value.downcast_ref::<&str>().copied());
+
+        Self::from_diagnoses(self.filter(|diagnosis| match attr_name {
+            "individual_pnr" => text == Some(diagnosis.individual_pnr.as_str()),
+            "diagnosis_code" => text == Some(diagnosis.diagnosis_code.as_str()),
+            "diagnosis_type" => value.downcast_ref() == Some(&diagnosis.diagnosis_type),
+            "diagnosis_date" => {
+                diagnosis.diagnosis_date.is_some()
+                    && value.downcast_ref() == diagnosis.diagnosis_date.as_ref()
+            }
+            "is_scd" => value.downcast_ref() == Some(&diagnosis.is_scd),
+            "severity" => value.downcast_ref() == Some(&diagnosis.severity),
+            _ => false,
+        }))
+    }
+
+    /// Keep the diagnoses matching every filter
+    ///
+    /// See [`crate::models::health::predicate`] for the predicate constructors.
+    fn apply_filters<F>(&self, filters: Vec<F>) -> Self
+    where
+        F: Fn(&Diagnosis) -> bool,
+    {
+        Self::from_diagnoses(
+            self.filter(|diagnosis| filters.iter().all(|filter| filter(diagnosis))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::health::predicate::{code_prefix, date_range, diagnosis_type};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn collection() -> DiagnosisCollection {
+        let mut collection = DiagnosisCollection::new();
+        for (pnr, code, kind, made) in [
+            ("a", "I21", DiagnosisType::Primary, Some(date(2016, 3, 1))),
+            ("a", "I50", DiagnosisType::Secondary, Some(date(2017, 1, 1))),
+            ("b", "I10", DiagnosisType::Primary, Some(date(2019, 1, 1))),
+            ("b", "E10", DiagnosisType::Primary, Some(date(2015, 6, 1))),
+            ("c", "I63", DiagnosisType::Primary, None),
+        ] {
+            collection.add(Diagnosis::new(pnr.into(), code.into(), kind, made));
+        }
+        collection
+    }
+
+    fn codes(collection: &DiagnosisCollection) -> Vec<String> {
+        let mut codes: Vec<String> = collection
+            .all()
+            .iter()
+            .map(|diagnosis| diagnosis.diagnosis_code.clone())
+            .collect();
+        codes.sort();
+        codes
+    }
+
+    #[test]
+    fn test_apply_filters() {
+        let narrowed = collection().apply_filters(vec![
+            code_prefix("I"),
+            diagnosis_type(DiagnosisType::Primary),
+            date_range(date(2015, 1, 1), date(2018, 12, 31)),
+        ]);
+        assert_eq!(codes(&narrowed), ["I21"]);
+        assert_eq!(narrowed.individuals(), ["a"]);
+    }
+
+    #[test]
+    fn test_filter_by_date_and_attribute() {
+        let collection = collection();
+        assert_eq!(
+            codes(&collection.filter_by_date(&date(2016, 12, 31))),
+            ["E10", "I21"]
+        );
+        assert_eq!(
+            codes(&collection.filter_by_attribute("individual_pnr", "b")),
+            ["E10", "I10"]
+        );
+        assert_eq!(
+            codes(&collection.filter_by_attribute("diagnosis_type", DiagnosisType::Secondary)),
+            ["I50"]
+        );
+        assert_eq!(
+            codes(&collection.filter_by_attribute("diagnosis_date", date(2019, 1, 1))),
+            ["I10"]
+        );
+        assert_eq!(collection.filter_by_attribute("severity", 1).count(), 5);
+        assert_eq!(collection.filter_by_attribute("severity", 1_i64).count(), 0);
+        assert_eq!(collection.filter_by_attribute("unknown", 1).count(), 0);
+    }
+
+    #[test]
+    fn test_by_code_prefixes_order() {
+        // By PNR, then in the order each individual's diagnoses were added
+        let codes: Vec<String> = collection()
+            .by_code_prefixes(&["I", "E"])
+            .iter()
+            .map(|diagnosis| diagnosis.diagnosis_code.clone())
+            .collect();
+        assert_eq!(codes, ["I21", "I50", "I10", "E10", "I63"]);
+    }
+}
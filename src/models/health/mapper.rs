@@ -6,7 +6,9 @@
 use crate::models::health::diagnosis::{Diagnosis, DiagnosisCollection};
 use crate::models::core::types::DiagnosisType;
 use crate::models::collections::collection_traits::ModelCollection;
-use std::collections::HashMap;
+use crate::error::{ParquetReaderError, Result};
+use crate::utils::arrow::DuplicatePolicy;
+use std::collections::{HashMap, HashSet};
 
 /// Trait for mapping registry models to health models
 pub trait HealthMapper<T> {
@@ -54,6 +56,8 @@ impl<T: DiagnosisMapper> DiagnosisRegistryMapper for T {
 pub struct RecnumToPnrMap {
     /// Map from RECNUM to PNR values
     pub recnum_to_pnr: HashMap<String, String>,
+    /// RECNUMs seen with more than one PNR
+    pub ambiguous_recnums: HashSet<String>,
 }
 
 impl RecnumToPnrMap {
@@ -61,28 +65,88 @@ impl RecnumToPnrMap {
     #[must_use] pub fn new() -> Self {
         Self {
             recnum_to_pnr: HashMap::new(),
+            ambiguous_recnums: HashSet::new(),
         }
     }
     
     /// Add a mapping from RECNUM to PNR
-    pub fn add_mapping(&mut self, recnum: String, pnr: String) {
-        self.recnum_to_pnr.insert(recnum, pnr);
+    ///
+    /// A RECNUM already mapped to another PNR keeps its first PNR and is
+    /// recorded as ambiguous.
+    ///
+    /// # Returns
+    /// `false` if the RECNUM was already mapped to another PNR
+    pub fn add_mapping(&mut self, recnum: String, pnr: String) -> bool {
+        match self.recnum_to_pnr.get(&recnum) {
+            Some(existing) if *existing != pnr => {
+                self.ambiguous_recnums.insert(recnum);
+                false
+            }
+            Some(_) => true,
+            None => {
+                self.recnum_to_pnr.insert(recnum, pnr);
+                true
+            }
+        }
     }
     
     /// Build a mapping from a batch of `LPR_ADM` records
-    pub fn build_from_adm_records<T>(records: &[T]) -> Self 
+    ///
+    /// # Arguments
+    /// * `records` - The `LPR_ADM` records
+    /// * `policy` - Which PNR to keep for a RECNUM mapping to more than one,
+    ///   as for [`crate::utils::arrow::build_lookup`]
+    ///
+    /// # Returns
+    /// The mapping, with the ambiguous RECNUMs in `ambiguous_recnums`
+    ///
+    /// # Errors
+    /// Returns an error if a RECNUM maps to more than one PNR and the policy
+    /// is [`DuplicatePolicy::Error`]
+    pub fn build_from_adm_records<T>(records: &[T], policy: DuplicatePolicy) -> Result<Self>
     where 
         T: RecnumProvider + PnrProvider,
     {
         let mut map = Self::new();
         
         for record in records {
-            if let (Some(recnum), Some(pnr)) = (record.record_number(), record.pnr()) {
-                map.add_mapping(recnum, pnr);
+            if let (Some(recnum), Some(pnr)) = (record.record_number(), record.pnr())
+                && !map.add_mapping(recnum.clone(), pnr.clone())
+                && policy == DuplicatePolicy::Last
+            {
+                map.recnum_to_pnr.insert(recnum, pnr);
+            }
+        }
+
+        if map.ambiguous_count() > 0 {
+            let mut examples: Vec<&String> = map.ambiguous_recnums.iter().collect();
+            examples.sort_unstable();
+            examples.truncate(5);
+            let message = format!(
+                "{} of {} RECNUMs map to more than one PNR, including {:?}",
+                map.ambiguous_count(),
+                map.recnum_to_pnr.len(),
+                examples
+            );
+            match policy {
+                DuplicatePolicy::Error => {
+                    return Err(ParquetReaderError::ValidationError(message).into());
+                }
+                DuplicatePolicy::First => {
+                    log::warn!("{message}; keeping the first PNR of each");
+                }
+                DuplicatePolicy::Last => {
+                    log::warn!("{message}; keeping the last PNR of each");
+                }
             }
         }
         
-        map
+        Ok(map)
+    }
+
+    /// Get the number of RECNUMs seen with more than one PNR
+    #[must_use] pub fn ambiguous_count(&self) -> usize {
+        self.ambiguous_recnums.len()
     }
     
     /// Look up a PNR by RECNUM
@@ -110,4 +174,47 @@ pub trait PnrProvider {
         "B" => DiagnosisType::Secondary,
         _ => DiagnosisType::Other,
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct AdmRecord(Option<&'static str>, Option<&'static str>);
+
+    impl RecnumProvider for AdmRecord {
+        fn record_number(&self) -> Option<String> {
+            self.0.map(str::to_string)
+        }
+    }
+
+    impl PnrProvider for AdmRecord {
+        fn pnr(&self) -> Option<String> {
+            self.1.map(str::to_string)
+        }
+    }
+
+    #[test]
+    fn test_ambiguous_recnums() {
+        let records = [
+            AdmRecord(Some("r1"), Some("a")),
+            AdmRecord(Some("r1"), Some("a")),
+            AdmRecord(Some("r2"), Some("b")),
+            AdmRecord(Some("r2"), Some("c")),
+            AdmRecord(None, Some("d")),
+        ];
+
+        let error =
+            RecnumToPnrMap::build_from_adm_records(&records, DuplicatePolicy::Error).unwrap_err();
+        assert!(error.to_string().contains("1 of 2 RECNUMs"), "{error}");
+
+        let map = RecnumToPnrMap::build_from_adm_records(&records, DuplicatePolicy::First).unwrap();
+        assert_eq!(map.ambiguous_count(), 1);
+        assert!(map.ambiguous_recnums.contains("r2"));
+        assert_eq!(map.lookup_pnr("r1").map(String::as_str), Some("a"));
+        assert_eq!(map.lookup_pnr("r2").map(String::as_str), Some("b"));
+
+        let map = RecnumToPnrMap::build_from_adm_records(&records, DuplicatePolicy::Last).unwrap();
+        assert_eq!(map.lookup_pnr("r2").map(String::as_str), Some("c"));
+    }
+}
@@ -3,8 +3,12 @@
 //! This module contains models related to health status, diagnoses,
 //! and medical conditions.
 
+pub mod code_index;
 pub mod diagnosis;
 pub mod mapper;
+pub mod predicate;
 
-pub use diagnosis::Diagnosis;
-pub use mapper::{DiagnosisMapper, RecnumProvider, PnrProvider, RecnumToPnrMap};
\ No newline at end of file
+pub use code_index::CodePrefixIndex;
+pub use diagnosis::{Diagnosis, ScdStatus};
+pub use mapper::{DiagnosisMapper, PnrProvider, RecnumProvider, RecnumToPnrMap};
+pub use predicate::DiagnosisPredicate;
\ No newline at end of file
@@ -0,0 +1,74 @@
+//! Predicates narrowing a diagnosis collection
+//!
+//! Each constructor returns a [`DiagnosisPredicate`] for
+//! [`Filterable::apply_filters`], which keeps the diagnoses matching all of
+//! the given predicates:
+//!
+//! - [`code_prefix`] and [`code_prefixes`] match the ICD-10 code
+//! - [`date_range`] matches the diagnosis date, inclusive at both ends
+//! - [`diagnosis_type`] matches primary, secondary or other diagnoses
+//!
+//! Primary cardiovascular diagnoses made in 2015 to 2018, for example:
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use par_reader::models::{DiagnosisType, Filterable};
+//! use par_reader::models::health::diagnosis::DiagnosisCollection;
+//! use par_reader::models::health::predicate::{code_prefix, date_range, diagnosis_type};
+//!
+//! let collection = DiagnosisCollection::new();
+//! let cardiovascular = collection.apply_filters(vec![
+//!     code_prefix("I"),
+//!     diagnosis_type(DiagnosisType::Primary),
+//!     date_range(
+//!         NaiveDate::from_ymd_opt(2015, 1, 1).unwrap(),
+//!         NaiveDate::from_ymd_opt(2018, 12, 31).unwrap(),
+//!     ),
+//! ]);
+//! ```
+//!
+//! [`Filterable::apply_filters`]: crate::models::Filterable::apply_filters
+
+use chrono::NaiveDate;
+
+use crate::models::core::types::DiagnosisType;
+use crate::models::health::diagnosis::Diagnosis;
+
+/// A predicate on a single diagnosis
+pub type DiagnosisPredicate = Box<dyn Fn(&Diagnosis) -> bool + Send + Sync>;
+
+/// Match diagnoses whose code starts with a prefix, such as `"I"` or `"E10"`
+#[must_use]
+pub fn code_prefix(prefix: &str) -> DiagnosisPredicate {
+    let prefix = prefix.to_string();
+    Box::new(move |diagnosis| diagnosis.diagnosis_code.starts_with(&prefix))
+}
+
+/// Match diagnoses whose code starts with any of the prefixes
+#[must_use]
+pub fn code_prefixes(prefixes: &[&str]) -> DiagnosisPredicate {
+    let prefixes: Vec<String> = prefixes.iter().map(ToString::to_string).collect();
+    Box::new(move |diagnosis| {
+        prefixes
+            .iter()
+            .any(|prefix| diagnosis.diagnosis_code.starts_with(prefix))
+    })
+}
+
+/// Match diagnoses made from `from` to `to`, inclusive
+///
+/// Diagnoses without a date never match.
+#[must_use]
+pub fn date_range(from: NaiveDate, to: NaiveDate) -> DiagnosisPredicate {
+    Box::new(move |diagnosis| {
+        diagnosis
+            .diagnosis_date
+            .is_some_and(|date| (from..=to).contains(&date))
+    })
+}
+
+/// Match diagnoses of a type
+#[must_use]
+pub fn diagnosis_type(diagnosis_type: DiagnosisType) -> DiagnosisPredicate {
+    Box::new(move |diagnosis| diagnosis.diagnosis_type == diagnosis_type)
+}
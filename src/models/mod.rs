@@ -26,7 +26,8 @@ pub use crate::common::traits::{
 // Re-export common types for backward compatibility
 pub use core::types::{
     CitizenshipStatus, DiagnosisType, DiseaseOrigin, DiseaseSeverity, EducationLevel, FamilyType,
-    Gender, HousingType, JobSituation, MaritalStatus, Origin, ScdCategory, SocioeconomicStatus,
+    Gender, HousingType, ImmigrationType, JobSituation, MaritalStatus, Origin, ScdCategory,
+    SocioeconomicStatus, UrbanRural,
 };
 
 // Re-export commonly used entity models for backward compatibility
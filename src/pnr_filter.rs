@@ -10,7 +10,15 @@ use std::collections::HashSet;
 
 // Re-export from the new centralized module
 pub use crate::filter::pnr::{
-    FilterPlan, PnrFilter, apply_filter_plan, build_filter_plan, join_and_filter_by_pnr,
+    FilterPlan, JoinCardinality, PnrFilter, apply_filter_plan, build_filter_plan,
+    join_and_filter_by_pnr,
+};
+pub use crate::filter::pnr_columns::PnrColumnSet;
+pub use crate::filter::pnr_normalization::{PnrNormalization, normalize_pnr};
+pub use crate::filter::pnr_set::PnrSet;
+pub use crate::filter::pseudonym::{
+    RELATED_PNR_COLUMNS, pseudonym, pseudonymize, pseudonymize_columns,
+    pseudonymize_columns_with_mapping, pseudonymize_with_mapping,
 };
 
 /// Filter a record batch by PNR values - DEPRECATED
@@ -1,7 +1,11 @@
 //! Module for reading Parquet files with schema validation.
 
+pub mod shared;
+pub mod typed;
+
 use std::collections::HashMap;
 use std::fs::File;
+use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -10,6 +14,9 @@ use parquet::file::reader::{FileReader, SerializedFileReader};
 
 use crate::schema::{find_schema_incompatibilities, schemas_compatible, SchemaCompatibilityReport};
 
+pub use shared::SharedParquetReader;
+pub use typed::{get_field, FromRow, FromRowValue, TypedRowIterator};
+
 /// A struct for reading Parquet files with schema validation
 pub struct ParquetReader {
     metadata_cache: HashMap<String, Arc<parquet::file::metadata::ParquetMetaData>>,
@@ -31,6 +38,9 @@ impl ParquetReader {
     }
     
     /// Creates a new `ParquetReader` with a specific cache size
+    ///
+    /// A size of 0 disables caching: footers are read whenever needed and
+    /// never retained, which suits one-shot loads of many unique files.
     #[must_use] pub fn with_cache_size(max_cache_size: usize) -> Self {
         Self {
             metadata_cache: HashMap::with_capacity(max_cache_size),
@@ -53,24 +63,28 @@ impl ParquetReader {
         })?;
 
         let reader = SerializedFileReader::new(file)?;
+        if self.max_cache_size == 0 {
+            return Ok(reader);
+        }
 
         // Cache metadata for later schema comparison
         let file_path = path.to_string_lossy().to_string();
         
         // Check if we need to evict some cache entries
-        if self.metadata_cache.len() >= self.max_cache_size {
+        if !self.metadata_cache.contains_key(&file_path)
+            && self.metadata_cache.len() >= self.max_cache_size
+        {
             // Evict least recently used entries (simple approach: just remove 20% of entries)
-            let num_to_remove = self.max_cache_size / 5;
-            if num_to_remove > 0 {
-                let keys_to_remove: Vec<String> = self.metadata_cache
-                    .keys()
-                    .take(num_to_remove)
-                    .cloned()
-                    .collect();
-                    
-                for key in keys_to_remove {
-                    self.metadata_cache.remove(&key);
-                }
+            let num_to_remove = (self.max_cache_size / 5).max(1);
+            let keys_to_remove: Vec<String> = self
+                .metadata_cache
+                .keys()
+                .take(num_to_remove)
+                .cloned()
+                .collect();
+
+            for key in keys_to_remove {
+                self.metadata_cache.remove(&key);
             }
         }
         
@@ -93,22 +107,14 @@ impl ParquetReader {
 
         // Read the first file's schema to compare with others
         let first_path = paths[0];
-        let Some(first_metadata) = self.metadata_cache.get(first_path) else {
-            return Err(parquet::errors::ParquetError::General(format!(
-                "Metadata for {first_path} not found in cache"
-            )));
-        };
+        let first_metadata = self.cached_metadata(first_path)?;
 
         let first_schema = first_metadata.file_metadata().schema();
         let first_num_columns = first_metadata.file_metadata().schema().get_fields().len();
 
         // Compare with all other files
         for path in &paths[1..] {
-            let Some(metadata) = self.metadata_cache.get(*path) else {
-                return Err(parquet::errors::ParquetError::General(format!(
-                    "Metadata for {path} not found in cache"
-                )));
-            };
+            let metadata = self.cached_metadata(path)?;
 
             let current_schema = metadata.file_metadata().schema();
             let current_num_columns = metadata.file_metadata().schema().get_fields().len();
@@ -153,21 +159,13 @@ impl ParquetReader {
 
         // Read the first file's schema
         let first_path = paths[0];
-        let Some(first_metadata) = self.metadata_cache.get(first_path) else {
-            return Err(parquet::errors::ParquetError::General(format!(
-                "Metadata for {first_path} not found in cache"
-            )));
-        };
+        let first_metadata = self.cached_metadata(first_path)?;
 
         let first_schema = first_metadata.file_metadata().schema();
 
         // Compare with all other files
         for path in &paths[1..] {
-            let Some(metadata) = self.metadata_cache.get(*path) else {
-                return Err(parquet::errors::ParquetError::General(format!(
-                    "Metadata for {path} not found in cache"
-                )));
-            };
+            let metadata = self.cached_metadata(path)?;
 
             let current_schema = metadata.file_metadata().schema();
 
@@ -184,6 +182,29 @@ impl ParquetReader {
         Ok(report)
     }
 
+    /// Returns the cached metadata of a file
+    ///
+    /// Without caching, the footer is read from the file instead.
+    fn cached_metadata(
+        &self,
+        path: &str,
+    ) -> ParquetResult<Arc<parquet::file::metadata::ParquetMetaData>> {
+        if self.max_cache_size == 0 {
+            let file = File::open(path).map_err(|e| {
+                parquet::errors::ParquetError::General(format!("Failed to open file {path}: {e}"))
+            })?;
+            return Ok(Arc::new(
+                parquet::file::metadata::ParquetMetaDataReader::new().parse_and_finish(&file)?,
+            ));
+        }
+
+        self.metadata_cache.get(path).cloned().ok_or_else(|| {
+            parquet::errors::ParquetError::General(format!(
+                "Metadata for {path} not found in cache"
+            ))
+        })
+    }
+
     /// Reads multiple Parquet files and returns their rows as an iterator
     ///
     /// # Errors
@@ -197,7 +218,7 @@ impl ParquetReader {
 
         // Pre-cache metadata for all files
         for path in paths {
-            if !self.metadata_cache.contains_key(*path) {
+            if self.max_cache_size > 0 && !self.metadata_cache.contains_key(*path) {
                 let _ = self.read_file(path)?;
             }
         }
@@ -209,6 +230,41 @@ impl ParquetReader {
             current_iter: None,
         })
     }
+
+    /// Reads multiple Parquet files and deserializes their rows as `T`
+    ///
+    /// In addition to the schema validation of [`read_files`](Self::read_files),
+    /// every file must contain the columns listed by `T::columns()`.
+    ///
+    /// # Errors
+    /// Returns an error if schemas are incompatible, if any file cannot be read
+    /// or if a file lacks a column required by `T`
+    pub fn iter_as<'a, T: FromRow>(
+        &'a mut self,
+        paths: &'a [&'a str],
+    ) -> ParquetResult<TypedRowIterator<'a, T>> {
+        for path in paths {
+            let reader = self.read_file(path)?;
+            let schema = reader.metadata().file_metadata().schema();
+            let missing: Vec<&str> = T::columns()
+                .iter()
+                .copied()
+                .filter(|column| !schema.get_fields().iter().any(|f| f.name() == *column))
+                .collect();
+
+            if !missing.is_empty() {
+                return Err(parquet::errors::ParquetError::General(format!(
+                    "File {path} is missing required columns: {}",
+                    missing.join(", ")
+                )));
+            }
+        }
+
+        Ok(TypedRowIterator {
+            rows: self.read_files(paths)?,
+            _marker: PhantomData,
+        })
+    }
 }
 
 /// Iterator over rows from multiple Parquet files
@@ -251,4 +307,71 @@ impl Iterator for ParquetRowIterator<'_> {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, StringArray};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    /// Write `count` one-row files to a fresh directory
+    fn write_files(name: &str, count: usize) -> (std::path::PathBuf, Vec<String>) {
+        let dir = std::env::temp_dir().join(format!("par_reader_{name}_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = (0..count)
+            .map(|index| {
+                let path = dir.join(format!("{index}.parquet"));
+                let batch = RecordBatch::try_from_iter(vec![(
+                    "PNR",
+                    Arc::new(StringArray::from(vec!["0101801234"])) as ArrayRef,
+                )])
+                .unwrap();
+                let mut writer =
+                    ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), None)
+                        .unwrap();
+                writer.write(&batch).unwrap();
+                writer.close().unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        (dir, paths)
+    }
+
+    #[test]
+    fn test_zero_cache_size_disables_caching() {
+        let (dir, paths) = write_files("no_cache", 2);
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+        let mut reader = ParquetReader::with_cache_size(0);
+        assert_eq!(reader.read_files(&paths).unwrap().count(), 2);
+        assert!(reader.metadata_cache.is_empty());
+        reader.validate_schemas(&paths).unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_never_exceeds_its_size() {
+        let (dir, paths) = write_files("cache_size", 6);
+
+        for max_cache_size in [0, 1, 4] {
+            let mut reader = ParquetReader::with_cache_size(max_cache_size);
+            for path in &paths {
+                reader.read_file(path).unwrap();
+                assert!(reader.metadata_cache.len() <= max_cache_size);
+            }
+            // The last file stays cached while the cache is enabled
+            let last = paths.last().unwrap();
+            assert_eq!(reader.metadata_cache.contains_key(last), max_cache_size > 0);
+
+            // Re-reading a cached file evicts nothing
+            let cached = reader.metadata_cache.len();
+            reader.read_file(last).unwrap();
+            assert_eq!(reader.metadata_cache.len(), cached);
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
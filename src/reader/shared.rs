@@ -0,0 +1,269 @@
+//! A metadata-caching Parquet reader shared across threads
+//!
+//! [`ParquetReader`](super::ParquetReader) caches file footers in a plain map
+//! behind `&mut self`, so concurrent loads cannot share it and each ends up
+//! reopening the footers of the same files. [`SharedParquetReader`] keeps the
+//! cache behind a lock and takes `&self`, so it can be shared through an
+//! `Arc` by threads or tasks. A footer is read once per file even when many
+//! loads miss the cache at the same time; loads of other files are not held
+//! up meanwhile.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReader,
+    ParquetRecordBatchReaderBuilder,
+};
+use parquet::errors::{ParquetError, Result as ParquetResult};
+use parquet::file::metadata::{ParquetMetaData, ParquetMetaDataReader};
+
+use crate::schema::schemas_compatible;
+
+/// The cached footer of one file, filled by the first load that needs it
+type MetadataSlot = Arc<Mutex<Option<Arc<ParquetMetaData>>>>;
+
+/// A Parquet reader whose metadata cache can be shared across threads
+pub struct SharedParquetReader {
+    metadata_cache: RwLock<HashMap<String, MetadataSlot>>,
+    // Maximum number of entries to keep in the metadata cache
+    max_cache_size: usize,
+    // Number of footers read from disk, for measuring the cache
+    footer_reads: AtomicUsize,
+}
+
+impl Default for SharedParquetReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SharedParquetReader {
+    /// Creates a new `SharedParquetReader` with default settings
+    #[must_use]
+    pub fn new() -> Self {
+        // Default to caching metadata for up to 100 files, as ParquetReader
+        Self::with_cache_size(100)
+    }
+
+    /// Creates a new `SharedParquetReader` with a specific cache size
+    ///
+    /// As for [`ParquetReader`](super::ParquetReader), a size of 0 disables
+    /// caching.
+    #[must_use]
+    pub fn with_cache_size(max_cache_size: usize) -> Self {
+        Self {
+            metadata_cache: RwLock::new(HashMap::with_capacity(max_cache_size)),
+            max_cache_size,
+            footer_reads: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the metadata of a file, reading its footer on a cache miss
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or its footer is invalid
+    pub fn metadata(&self, path: &str) -> ParquetResult<Arc<ParquetMetaData>> {
+        if self.max_cache_size == 0 {
+            return self.read_footer(path);
+        }
+
+        let slot = self.slot(path)?;
+        let mut metadata = slot.lock().map_err(|_| lock_error("metadata entry"))?;
+        if let Some(metadata) = metadata.as_ref() {
+            return Ok(Arc::clone(metadata));
+        }
+
+        let read = self.read_footer(path)?;
+        *metadata = Some(Arc::clone(&read));
+        Ok(read)
+    }
+
+    /// Reads a file as Arrow record batches using its cached metadata
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or its footer is invalid
+    pub fn read_batches(
+        &self,
+        path: &str,
+        batch_size: usize,
+    ) -> ParquetResult<ParquetRecordBatchReader> {
+        let metadata =
+            ArrowReaderMetadata::try_new(self.metadata(path)?, ArrowReaderOptions::new())?;
+        ParquetRecordBatchReaderBuilder::new_with_metadata(open(path)?, metadata)
+            .with_batch_size(batch_size)
+            .build()
+    }
+
+    /// Validates that all files in the list have compatible schemas
+    ///
+    /// Unlike [`ParquetReader::validate_schemas`](super::ParquetReader::validate_schemas),
+    /// files not yet cached are read rather than rejected.
+    ///
+    /// # Errors
+    /// Returns an error if any of the file schemas are incompatible or if a
+    /// footer cannot be read
+    pub fn validate_schemas(&self, paths: &[&str]) -> ParquetResult<()> {
+        let Some((first_path, others)) = paths.split_first() else {
+            return Ok(());
+        };
+        let first_metadata = self.metadata(first_path)?;
+        let first_schema = first_metadata.file_metadata().schema();
+
+        for path in others {
+            let metadata = self.metadata(path)?;
+            if !schemas_compatible(first_schema, metadata.file_metadata().schema()) {
+                return Err(ParquetError::General(format!(
+                    "Schema for {path} is incompatible with {first_path}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of files whose metadata is cached
+    #[must_use]
+    pub fn cached_files(&self) -> usize {
+        self.metadata_cache.read().map_or(0, |cache| {
+            cache.values().filter(|slot| is_filled(slot)).count()
+        })
+    }
+
+    /// Returns the number of footers read from disk so far
+    #[must_use]
+    pub fn footer_reads(&self) -> usize {
+        self.footer_reads.load(Ordering::Relaxed)
+    }
+
+    /// Removes all cached metadata
+    ///
+    /// # Errors
+    /// Returns an error if the cache lock is poisoned
+    pub fn clear_cache(&self) -> ParquetResult<()> {
+        self.metadata_cache
+            .write()
+            .map_err(|_| lock_error("metadata cache"))?
+            .clear();
+        Ok(())
+    }
+
+    /// Read the footer of a file from disk
+    fn read_footer(&self, path: &str) -> ParquetResult<Arc<ParquetMetaData>> {
+        let file = open(path)?;
+        let metadata = ParquetMetaDataReader::new().parse_and_finish(&file)?;
+        self.footer_reads.fetch_add(1, Ordering::Relaxed);
+        Ok(Arc::new(metadata))
+    }
+
+    /// Get the cache entry of a file, adding an empty one if it has none
+    fn slot(&self, path: &str) -> ParquetResult<MetadataSlot> {
+        if let Some(slot) = self
+            .metadata_cache
+            .read()
+            .map_err(|_| lock_error("metadata cache"))?
+            .get(path)
+        {
+            return Ok(Arc::clone(slot));
+        }
+
+        let mut cache = self
+            .metadata_cache
+            .write()
+            .map_err(|_| lock_error("metadata cache"))?;
+        if !cache.contains_key(path) && cache.len() >= self.max_cache_size {
+            // Evict 20% of the entries, as ParquetReader does
+            let num_to_remove = (self.max_cache_size / 5).max(1);
+            let keys_to_remove: Vec<String> = cache.keys().take(num_to_remove).cloned().collect();
+            for key in keys_to_remove {
+                cache.remove(&key);
+            }
+        }
+        Ok(Arc::clone(cache.entry(path.to_string()).or_default()))
+    }
+}
+
+/// Check whether a cache entry holds metadata
+fn is_filled(slot: &MetadataSlot) -> bool {
+    slot.lock().is_ok_and(|metadata| metadata.is_some())
+}
+
+/// Open a file, naming it in the error
+fn open(path: &str) -> ParquetResult<File> {
+    File::open(Path::new(path))
+        .map_err(|e| ParquetError::General(format!("Failed to open file {path}: {e}")))
+}
+
+/// The error of a poisoned lock
+fn lock_error(what: &str) -> ParquetError {
+    ParquetError::General(format!("Failed to acquire lock on {what}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, StringArray};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    #[test]
+    fn test_shared_reader_reads_each_footer_once() {
+        let dir = std::env::temp_dir().join(format!("par_reader_shared_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths: Vec<String> = ["a", "b"]
+            .iter()
+            .map(|name| {
+                let path = dir.join(format!("{name}.parquet"));
+                let batch = RecordBatch::try_from_iter(vec![(
+                    "PNR",
+                    Arc::new(StringArray::from(vec!["0101801234", "0202802345"])) as ArrayRef,
+                )])
+                .unwrap();
+                let mut writer =
+                    ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), None)
+                        .unwrap();
+                writer.write(&batch).unwrap();
+                writer.close().unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+
+        let reader = Arc::new(SharedParquetReader::new());
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let reader = Arc::clone(&reader);
+                let paths = paths.clone();
+                std::thread::spawn(move || {
+                    paths
+                        .iter()
+                        .map(|path| {
+                            reader
+                                .read_batches(path, 1024)
+                                .unwrap()
+                                .map(|batch| batch.unwrap().num_rows())
+                                .sum::<usize>()
+                        })
+                        .sum::<usize>()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 4);
+        }
+
+        assert_eq!(reader.footer_reads(), 2);
+        assert_eq!(reader.cached_files(), 2);
+        let refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+        reader.validate_schemas(&refs).unwrap();
+        assert_eq!(reader.footer_reads(), 2);
+        assert!(reader.metadata("missing.parquet").is_err());
+
+        reader.clear_cache().unwrap();
+        assert_eq!(reader.cached_files(), 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+//! Typed deserialization of Parquet rows
+//!
+//! This module provides the `FromRow` trait, which converts untyped
+//! `parquet::record::Row`s into user structs, and the iterator returned by
+//! `ParquetReader::iter_as`. `FromRow` is usually derived with
+//! `#[derive(FromRow)]`, which maps struct fields to columns by name.
+
+use std::marker::PhantomData;
+
+use chrono::{Duration, NaiveDate};
+use parquet::errors::{ParquetError, Result as ParquetResult};
+use parquet::record::{Field, Row};
+
+use super::ParquetRowIterator;
+
+/// A type that can be deserialized from a Parquet row
+pub trait FromRow: Sized {
+    /// Columns read by `from_row`
+    ///
+    /// Files without all of these columns are rejected before iteration.
+    fn columns() -> &'static [&'static str];
+
+    /// Deserialize a row
+    ///
+    /// # Errors
+    /// Returns an error if a column is missing or has an incompatible type
+    fn from_row(row: &Row) -> ParquetResult<Self>;
+}
+
+/// A type that can be extracted from a single Parquet field
+///
+/// `value` is `None` when the row has no column of the requested name.
+pub trait FromRowValue: Sized {
+    /// Convert a field value
+    ///
+    /// # Errors
+    /// Returns a description of the mismatch if the value cannot be converted
+    fn from_value(value: Option<&Field>) -> Result<Self, String>;
+}
+
+/// Extract a typed value from a named column of a row
+///
+/// # Errors
+/// Returns an error if the column is missing (for non-`Option` types) or has
+/// an incompatible type
+pub fn get_field<T: FromRowValue>(row: &Row, column: &str) -> ParquetResult<T> {
+    let value = row
+        .get_column_iter()
+        .find(|(name, _)| name.as_str() == column)
+        .map(|(_, field)| field);
+
+    T::from_value(value)
+        .map_err(|e| ParquetError::General(format!("Failed to read column {column}: {e}")))
+}
+
+/// Unwrap a field value that must be present and non-null
+fn required(value: Option<&Field>) -> Result<&Field, String> {
+    match value {
+        None => Err("column not found".to_string()),
+        Some(Field::Null) => Err("unexpected null".to_string()),
+        Some(field) => Ok(field),
+    }
+}
+
+impl<T: FromRowValue> FromRowValue for Option<T> {
+    fn from_value(value: Option<&Field>) -> Result<Self, String> {
+        match value {
+            None | Some(Field::Null) => Ok(None),
+            Some(field) => T::from_value(Some(field)).map(Some),
+        }
+    }
+}
+
+impl FromRowValue for String {
+    fn from_value(value: Option<&Field>) -> Result<Self, String> {
+        match required(value)? {
+            Field::Str(s) => Ok(s.clone()),
+            other => Err(format!("expected string, found {other}")),
+        }
+    }
+}
+
+impl FromRowValue for bool {
+    fn from_value(value: Option<&Field>) -> Result<Self, String> {
+        match required(value)? {
+            Field::Bool(b) => Ok(*b),
+            other => Err(format!("expected boolean, found {other}")),
+        }
+    }
+}
+
+impl FromRowValue for i32 {
+    fn from_value(value: Option<&Field>) -> Result<Self, String> {
+        match required(value)? {
+            Field::Byte(v) => Ok(Self::from(*v)),
+            Field::Short(v) => Ok(Self::from(*v)),
+            Field::Int(v) => Ok(*v),
+            other => Err(format!("expected 32-bit integer, found {other}")),
+        }
+    }
+}
+
+impl FromRowValue for i64 {
+    fn from_value(value: Option<&Field>) -> Result<Self, String> {
+        match required(value)? {
+            Field::Byte(v) => Ok(Self::from(*v)),
+            Field::Short(v) => Ok(Self::from(*v)),
+            Field::Int(v) => Ok(Self::from(*v)),
+            Field::Long(v) => Ok(*v),
+            other => Err(format!("expected integer, found {other}")),
+        }
+    }
+}
+
+impl FromRowValue for f64 {
+    fn from_value(value: Option<&Field>) -> Result<Self, String> {
+        match required(value)? {
+            Field::Float(v) => Ok(Self::from(*v)),
+            Field::Double(v) => Ok(*v),
+            Field::Int(v) => Ok(Self::from(*v)),
+            other => Err(format!("expected floating point number, found {other}")),
+        }
+    }
+}
+
+impl FromRowValue for NaiveDate {
+    fn from_value(value: Option<&Field>) -> Result<Self, String> {
+        match required(value)? {
+            Field::Date(days) => NaiveDate::from_ymd_opt(1970, 1, 1)
+                .and_then(|epoch| epoch.checked_add_signed(Duration::days(i64::from(*days))))
+                .ok_or_else(|| format!("date out of range: {days}")),
+            other => Err(format!("expected date, found {other}")),
+        }
+    }
+}
+
+/// Iterator over rows from multiple Parquet files, deserialized as `T`
+pub struct TypedRowIterator<'a, T> {
+    pub(super) rows: ParquetRowIterator<'a>,
+    pub(super) _marker: PhantomData<T>,
+}
+
+impl<T: FromRow> Iterator for TypedRowIterator<'_, T> {
+    type Item = ParquetResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows
+            .next()
+            .map(|row| row.and_then(|row| T::from_row(&row)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::ParquetReader;
+    use arrow::array::{ArrayRef, Date32Array, Int32Array, StringArray};
+    use arrow::record_batch::RecordBatch;
+    use macros::FromRow;
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq, FromRow)]
+    struct Person {
+        #[field(name = "PNR")]
+        pnr: String,
+        #[field(name = "FOED_DAG")]
+        birth_date: Option<NaiveDate>,
+        #[field(name = "YEAR")]
+        year: i64,
+    }
+
+    fn write_file(path: &std::path::Path, batch: &RecordBatch) {
+        let file = std::fs::File::create(path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+        writer.write(batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_iter_as() {
+        let dir = std::env::temp_dir().join(format!("par_reader_iter_as_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let batch = |pnrs: Vec<&str>, days: Vec<Option<i32>>, years: Vec<i32>| {
+            RecordBatch::try_from_iter(vec![
+                ("PNR", Arc::new(StringArray::from(pnrs)) as ArrayRef),
+                ("FOED_DAG", Arc::new(Date32Array::from(days)) as ArrayRef),
+                ("YEAR", Arc::new(Int32Array::from(years)) as ArrayRef),
+            ])
+            .unwrap()
+        };
+        let first = dir.join("a.parquet");
+        let second = dir.join("b.parquet");
+        write_file(&first, &batch(vec!["1"], vec![Some(0)], vec![2020]));
+        write_file(&second, &batch(vec!["2"], vec![None], vec![2021]));
+
+        let first = first.to_string_lossy().to_string();
+        let second = second.to_string_lossy().to_string();
+        let paths = [first.as_str(), second.as_str()];
+        let mut reader = ParquetReader::new();
+        let people: Vec<Person> = reader
+            .iter_as::<Person>(&paths)
+            .unwrap()
+            .collect::<ParquetResult<_>>()
+            .unwrap();
+
+        assert_eq!(
+            people,
+            vec![
+                Person {
+                    pnr: "1".to_string(),
+                    birth_date: NaiveDate::from_ymd_opt(1970, 1, 1),
+                    year: 2020,
+                },
+                Person {
+                    pnr: "2".to_string(),
+                    birth_date: None,
+                    year: 2021,
+                },
+            ]
+        );
+
+        // Files missing a required column are rejected up front
+        #[derive(Debug, FromRow)]
+        struct Missing {
+            #[allow(dead_code)]
+            recnum: String,
+        }
+        assert!(reader.iter_as::<Missing>(&paths).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
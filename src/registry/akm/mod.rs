@@ -12,12 +12,12 @@ pub mod field_mapping;
 #[registry(name = "AKM", description = "Labour register")]
 pub struct AkmRegistry {
     /// Person ID (CPR number)
-    #[field(name = "PNR")]
+    #[field(name = "PNR", pnr = true)]
     pub pnr: String,
 
     /// Socioeconomic status code
     #[field(name = "SOCIO13")]
-    pub socioeconomic_status: Option<String>,
+    pub socioeconomic_status: Option<i32>,
 }
 
 /// Helper function to create a new AKM deserializer
@@ -33,22 +33,3 @@ pub fn deserialize_batch(
     // Use the inner deserializer to deserialize the batch
     deserializer.inner.deserialize_batch(batch)
 }
-
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for AkmRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "AKM"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for AKM
-        let fields = vec![
-            arrow::datatypes::Field::new("PNR", arrow::datatypes::DataType::Utf8, false),
-            arrow::datatypes::Field::new("SOCIO13", arrow::datatypes::DataType::Utf8, true),
-        ];
-
-        std::sync::Arc::new(arrow::datatypes::Schema::new(fields))
-    }
-}
@@ -158,5 +158,13 @@ use crate::schema::field_def::{
                 individual.position_in_family = Some(position);
             }),
         ),
+        // Municipality code mapping
+        FieldMapping::new(
+            FieldDefinition::new("KOM", "municipality_code", FieldType::String, true),
+            Extractors::string("KOM"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.municipality_code = Some(value);
+            }),
+        ),
     ]
 }
@@ -13,7 +13,7 @@ pub mod field_mapping;
 #[registry(name = "BEF", description = "Population registry")]
 pub struct BefRegistry {
     /// Person ID (CPR number)
-    #[field(name = "PNR")]
+    #[field(name = "PNR", pnr = true)]
     pub pnr: String,
 
     /// Gender code
@@ -70,6 +70,10 @@ pub struct BefRegistry {
     /// Position in family
     #[field(name = "PLADS")]
     pub position_in_family: Option<i32>,
+
+    /// Municipality code
+    #[field(name = "KOM")]
+    pub municipality_code: Option<String>,
 }
 
 /// Helper function to create a new BEF deserializer
@@ -85,34 +89,3 @@ pub fn deserialize_batch(
     // Use the inner deserializer to deserialize the batch
     deserializer.inner.deserialize_batch(batch)
 }
-
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for BefRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "BEF"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for BEF
-        let fields = vec![
-            arrow::datatypes::Field::new("PNR", arrow::datatypes::DataType::Utf8, false),
-            arrow::datatypes::Field::new("KOEN", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("FOED_DAG", arrow::datatypes::DataType::Date32, true),
-            arrow::datatypes::Field::new("MOR_ID", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("FAR_ID", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("FAMILIE_ID", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("AEGTE_ID", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("ALDER", arrow::datatypes::DataType::Int32, true),
-            arrow::datatypes::Field::new("ANTPERSF", arrow::datatypes::DataType::Int32, true),
-            arrow::datatypes::Field::new("ANTPERSH", arrow::datatypes::DataType::Int32, true),
-            arrow::datatypes::Field::new("BOP_VFRA", arrow::datatypes::DataType::Date32, true),
-            arrow::datatypes::Field::new("FAMILIE_TYPE", arrow::datatypes::DataType::Int32, true),
-            arrow::datatypes::Field::new("IE_TYPE", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("PLADS", arrow::datatypes::DataType::Int32, true),
-        ];
-
-        std::sync::Arc::new(arrow::datatypes::Schema::new(fields))
-    }
-}
@@ -0,0 +1,290 @@
+//! Assembly of configured registry loaders
+//!
+//! Some registries need more than a name to load usefully: `LPR_DIAG` and
+//! `LPR_BES` reach PNRs only through the `RECNUM` of `LPR_ADM`, and large
+//! registries are often read for a few columns. [`RegistryLoaderBuilder`]
+//! takes the registry type and these settings and returns a ready loader.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+
+use crate::async_io::Loader;
+use crate::config::ParquetReaderConfig;
+use crate::error::{ParquetReaderError, Result};
+use crate::registry::RegisterLoader;
+use crate::registry::direct_registry_loader::DirectRegistryLoader;
+use crate::utils::register::{DateEncoding, FloatFormat, RegistryType};
+
+/// Settings of a loader built by [`RegistryLoaderBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoaderConfig {
+    /// Adapt column types the field extractors cannot read, such as `Date64` dates
+    pub adapt_types: bool,
+    /// Record the source file and row on each deserialized individual
+    pub provenance: bool,
+    /// Fail on a directory without Parquet files instead of loading no batches
+    pub fail_on_empty_directory: bool,
+    /// Check the files of a directory for compatible schemas before loading
+    pub validate_schema: bool,
+    /// How decimal fields stored as text are parsed
+    pub float_format: FloatFormat,
+    /// How date fields stored as integers are read
+    pub date_encoding: DateEncoding,
+    /// Read empty or whitespace-only text in string fields as missing
+    pub empty_string_as_null: bool,
+    /// The maximum number of rows to load, or every row (if `None`)
+    pub limit: Option<usize>,
+}
+
+impl Default for LoaderConfig {
+    fn default() -> Self {
+        Self {
+            adapt_types: true,
+            provenance: false,
+            fail_on_empty_directory: false,
+            validate_schema: false,
+            float_format: FloatFormat::default(),
+            date_encoding: DateEncoding::default(),
+            empty_string_as_null: true,
+            limit: None,
+        }
+    }
+}
+
+/// Builder of a fully configured registry loader
+#[derive(Debug, Clone)]
+pub struct RegistryLoaderBuilder {
+    registry: RegistryType,
+    pnr_lookup: Option<Arc<HashMap<String, String>>>,
+    projection: Option<Vec<String>>,
+    empty_string_overrides: HashMap<String, bool>,
+    config: LoaderConfig,
+    reader_config: Option<ParquetReaderConfig>,
+}
+
+impl RegistryLoaderBuilder {
+    /// Start building a loader of a registry
+    #[must_use]
+    pub fn new(registry: RegistryType) -> Self {
+        Self {
+            registry,
+            pnr_lookup: None,
+            projection: None,
+            empty_string_overrides: HashMap::new(),
+            config: LoaderConfig::default(),
+            reader_config: None,
+        }
+    }
+
+    /// Give rows the PNR their key maps to
+    ///
+    /// Only registries without a PNR column take a lookup: `LPR_DIAG` and
+    /// `LPR_BES` keyed by `RECNUM`, and `LPR3_DIAGNOSER` keyed by
+    /// `DW_EK_KONTAKT`. Such a lookup can be built with `build_lookup`.
+    #[must_use]
+    pub fn with_pnr_lookup(mut self, lookup: Arc<HashMap<String, String>>) -> Self {
+        self.pnr_lookup = Some(lookup);
+        self
+    }
+
+    /// Read only the given declared columns
+    ///
+    /// The PNR column and the key column of a PNR lookup are always read.
+    #[must_use]
+    pub fn with_projection<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.projection = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Read empty text of one column as missing or not, whatever
+    /// [`LoaderConfig::empty_string_as_null`] says
+    #[must_use]
+    pub fn with_column_empty_string_as_null(
+        mut self,
+        column: impl Into<String>,
+        empty_string_as_null: bool,
+    ) -> Self {
+        self.empty_string_overrides
+            .insert(column.into(), empty_string_as_null);
+        self
+    }
+
+    /// Use the given loader settings instead of the defaults
+    #[must_use]
+    pub const fn with_config(mut self, config: LoaderConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Read files with the given settings, such as `ignore_extra_columns`
+    /// and `date_range_checks`
+    #[must_use]
+    pub fn with_reader_config(mut self, config: ParquetReaderConfig) -> Self {
+        self.reader_config = Some(config);
+        self
+    }
+
+    /// Build the loader
+    ///
+    /// The loader is returned as a [`DirectRegistryLoader`] rather than a
+    /// `Box<dyn RegisterLoader>`: the PNR lookup is applied when
+    /// deserializing, which only the concrete loader provides. It implements
+    /// [`RegisterLoader`] and can be boxed as one.
+    ///
+    /// # Returns
+    ///
+    /// The configured loader
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a PNR lookup is given for a registry with a PNR
+    /// column, or if a projected column is not declared by the registry
+    pub fn build(self) -> Result<DirectRegistryLoader> {
+        let mut loader = DirectRegistryLoader::new(self.registry.as_str())
+            .with_adapt_types(self.config.adapt_types)
+            .with_provenance(self.config.provenance)
+            .with_fail_on_empty_directory(self.config.fail_on_empty_directory)
+            .with_validate_schema(self.config.validate_schema)
+            .with_float_format(self.config.float_format)
+            .with_date_encoding(self.config.date_encoding)
+            .with_empty_string_as_null(self.config.empty_string_as_null)
+            .with_limit(self.config.limit);
+        if let Some(reader_config) = self.reader_config {
+            loader = loader.with_reader_config(reader_config);
+        }
+        for (column, empty_string_as_null) in self.empty_string_overrides {
+            loader = loader.with_column_empty_string_as_null(column, empty_string_as_null);
+        }
+
+        let key_column = pnr_lookup_key(self.registry);
+        if let Some(lookup) = self.pnr_lookup {
+            let key_column = key_column.ok_or_else(|| {
+                ParquetReaderError::InvalidOperation(format!(
+                    "Registry {} has a PNR column and takes no PNR lookup",
+                    self.registry
+                ))
+            })?;
+            loader = loader.with_pnr_lookup(key_column, lookup);
+        }
+
+        if let Some(columns) = self.projection {
+            let declared = loader.get_schema();
+            if let Some(column) = columns
+                .iter()
+                .find(|column| declared.field_with_name(column).is_err())
+            {
+                return Err(ParquetReaderError::column_not_found(column.as_str()).into());
+            }
+
+            let mut columns: HashSet<String> = columns.into_iter().collect();
+            columns.extend(
+                loader
+                    .get_pnr_column_name()
+                    .into_iter()
+                    .chain(key_column)
+                    .map(str::to_string),
+            );
+            loader = loader.with_projection(columns);
+        }
+
+        Ok(loader)
+    }
+
+    /// Build a PNR-filterable loader of the registry's Parquet files
+    ///
+    /// The loader reads the columns of the built [`DirectRegistryLoader`],
+    /// after projection, and filters on the registry's PNR column.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::build`]
+    pub fn build_async(self) -> Result<Loader> {
+        let loader = self.build()?;
+        Ok(pnr_filterable_loader(
+            loader.get_schema(),
+            loader.get_pnr_column_name().unwrap_or("PNR"),
+        ))
+    }
+}
+
+/// Create a PNR-filterable loader reading the columns of a schema
+#[must_use]
+pub fn pnr_filterable_loader(schema: SchemaRef, pnr_column: impl Into<String>) -> Loader {
+    Loader::with_schema_ref(schema).with_pnr_column(pnr_column)
+}
+
+/// Get the column whose values a PNR lookup of a registry is keyed by
+const fn pnr_lookup_key(registry: RegistryType) -> Option<&'static str> {
+    match registry {
+        RegistryType::LprDiag | RegistryType::LprBes => Some("RECNUM"),
+        RegistryType::Lpr3Diagnoser => Some("DW_EK_KONTAKT"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::traits::AsyncLoader;
+    use arrow::array::{ArrayRef, StringArray};
+    use arrow::record_batch::RecordBatch;
+
+    #[test]
+    fn test_build_lpr_diag_with_pnr_lookup() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "RECNUM",
+                Arc::new(StringArray::from(vec![Some("1"), Some("2"), None])) as ArrayRef,
+            ),
+            (
+                "C_DIAG",
+                Arc::new(StringArray::from(vec!["DE10", "DJ45", "DQ21"])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let lookup = Arc::new(HashMap::from([("1".to_string(), "0101801234".to_string())]));
+
+        let loader = RegistryLoaderBuilder::new(RegistryType::LprDiag)
+            .with_pnr_lookup(lookup.clone())
+            .with_projection(["C_DIAG"])
+            .build()
+            .unwrap();
+        let individuals = loader.deserialize_batch(&batch).unwrap();
+        assert_eq!(individuals.len(), 1);
+        assert_eq!(individuals[0].pnr, "0101801234");
+        assert_eq!(individuals[0].diagnoses, Some(vec!["DE10".to_string()]));
+
+        let schema = loader.get_schema();
+        let columns: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(columns, ["RECNUM", "C_DIAG"]);
+
+        // The async loader reads the same projected columns
+        let async_loader = RegistryLoaderBuilder::new(RegistryType::LprDiag)
+            .with_projection(["C_DIAG"])
+            .build_async()
+            .unwrap();
+        assert_eq!(async_loader.get_schema(), Some(schema));
+
+        // BEF has a PNR column, and no column named OTHER
+        assert!(
+            RegistryLoaderBuilder::new(RegistryType::BEF)
+                .with_pnr_lookup(lookup)
+                .build()
+                .is_err()
+        );
+        let error = RegistryLoaderBuilder::new(RegistryType::BEF)
+            .with_projection(["PNR", "OTHER"])
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ParquetReaderError>(),
+            Some(ParquetReaderError::ColumnNotFound { column }) if column == "OTHER"
+        ));
+    }
+}
@@ -13,7 +13,7 @@ pub mod field_mapping;
 #[registry(name = "DOD", description = "Death registry")]
 pub struct DodRegistry {
     /// Person ID (CPR number)
-    #[field(name = "PNR")]
+    #[field(name = "PNR", pnr = true)]
     pub pnr: String,
 
     /// Date of death
@@ -42,24 +42,3 @@ pub fn deserialize_batch(
     // Use the inner deserializer to deserialize the batch
     deserializer.inner.deserialize_batch(batch)
 }
-
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for DodRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "DOD"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for DOD
-        let fields = vec![
-            arrow::datatypes::Field::new("PNR", arrow::datatypes::DataType::Utf8, false),
-            arrow::datatypes::Field::new("DODDATO", arrow::datatypes::DataType::Date32, true),
-            arrow::datatypes::Field::new("C_AARSAG", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("C_TILSTAND", arrow::datatypes::DataType::Utf8, true),
-        ];
-
-        std::sync::Arc::new(arrow::datatypes::Schema::new(fields))
-    }
-}
\ No newline at end of file
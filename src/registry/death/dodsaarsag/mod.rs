@@ -14,7 +14,7 @@ pub mod schema;
 #[registry(name = "DODSAARSAG", description = "Cause of Death registry")]
 pub struct DodsaarsagRegistry {
     /// Person ID (CPR number)
-    #[field(name = "PNR")]
+    #[field(name = "PNR", pnr = true)]
     pub pnr: String,
 
     /// Death cause (ICD-10 code)
@@ -44,26 +44,5 @@ pub fn deserialize_batch(
     deserializer.inner.deserialize_batch(batch)
 }
 
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for DodsaarsagRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "DODSAARSAG"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create Arrow schema for Dodsaarsag
-        let fields = vec![
-            arrow::datatypes::Field::new("PNR", arrow::datatypes::DataType::Utf8, false),
-            arrow::datatypes::Field::new("C_AARSAG", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("C_TILSTAND", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("D_DATO", arrow::datatypes::DataType::Date32, true),
-        ];
-
-        std::sync::Arc::new(arrow::datatypes::Schema::new(fields))
-    }
-}
-
 // Re-export the standardized schema function for compatibility
-pub use schema::dodsaarsag_standardized_schema;
\ No newline at end of file
+pub use schema::dodsaarsag_standardized_schema;
@@ -3,7 +3,11 @@
 //! This module provides a deserializer that directly maps registry data to Individual models
 //! without requiring intermediate registry-specific structs.
 
+use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
+use log::debug;
+use std::any::Any;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -11,13 +15,19 @@ use crate::error::Result;
 use crate::models::core::Individual;
 use crate::registry::trait_deserializer::{RegistryDeserializer, RegistryFieldExtractor};
 
-use crate::schema::{RegistrySchema, create_registry_schema};
+use crate::schema::adapt::{
+    DateFormatConfig, TypeCompatibility, adapt_record_batch, check_schema_with_adaptation,
+    check_type_compatibility, is_numeric,
+};
+use crate::schema::{FieldType, RegistrySchema, create_registry_schema};
+use crate::utils::register::extractors::{DateEncoding, FloatFormat, StringFormat};
 
 // Import field mapping modules from registry-specific modules
 use crate::registry::akm::field_mapping as akm_mapping;
 use crate::registry::bef::field_mapping as bef_mapping;
 use crate::registry::death::dod::field_mapping as dod_mapping;
 use crate::registry::death::dodsaarsag::field_mapping as dodsaarsag_mapping;
+use crate::registry::idan::field_mapping as idan_mapping;
 use crate::registry::ind::field_mapping as ind_mapping;
 use crate::registry::lpr::v2::adm::field_mapping as lpr_adm_mapping;
 use crate::registry::lpr::v2::bes::field_mapping as lpr_bes_mapping;
@@ -32,6 +42,16 @@ use crate::registry::vnds::field_mapping as vnds_mapping;
 #[derive(Debug)]
 pub struct DirectIndividualDeserializer {
     inner: Arc<dyn RegistryDeserializer>,
+    /// Arrow schema of the declared fields, with their declared nullability
+    arrow_schema: Arc<Schema>,
+    /// Whether to adapt column types the field extractors cannot read
+    adapt_types: bool,
+    /// How decimal fields stored as text are parsed
+    float_format: FloatFormat,
+    /// How date fields stored as integers are read
+    date_encoding: DateEncoding,
+    /// How string fields read empty text
+    string_format: StringFormat,
 }
 
 impl DirectIndividualDeserializer {
@@ -45,14 +65,31 @@ impl DirectIndividualDeserializer {
     ///
     /// A new instance of the deserializer
     #[must_use] pub fn new(registry_name: &str) -> Self {
-        // Create a registry-specific schema based on the registry name
-        let schema = match registry_name {
+        Self {
+            inner: Self::create_inner(
+                registry_name,
+                FloatFormat::default(),
+                DateEncoding::default(),
+                &StringFormat::default(),
+            ),
+            arrow_schema: Self::registry_schema(registry_name).arrow_schema(),
+            adapt_types: true,
+            float_format: FloatFormat::default(),
+            date_encoding: DateEncoding::default(),
+            string_format: StringFormat::default(),
+        }
+    }
+
+    /// Create the registry-specific schema of a registry name
+    fn registry_schema(registry_name: &str) -> RegistrySchema {
+        match registry_name {
             "VNDS" => Self::create_vnds_schema(),
             "BEF" => Self::create_bef_schema(),
             "AKM" => Self::create_akm_schema(),
             "MFR" => Self::create_mfr_schema(),
             "DOD" => Self::create_dod_schema(),
             "DODSAARSAG" => Self::create_dodsaarsag_schema(),
+            "IDAN" => Self::create_idan_schema(),
             "IND" => Self::create_ind_schema(),
             "UDDF" => Self::create_uddf_schema(),
             "LPR_ADM" => Self::create_lpr_adm_schema(),
@@ -61,7 +98,17 @@ impl DirectIndividualDeserializer {
             "LPR3_KONTAKTER" => Self::create_lpr3_kontakter_schema(),
             "LPR3_DIAGNOSER" => Self::create_lpr3_diagnoser_schema(),
             _ => Self::create_default_schema(registry_name),
-        };
+        }
+    }
+
+    /// Create the registry deserializer with field extractors of a registry
+    fn create_inner(
+        registry_name: &str,
+        float_format: FloatFormat,
+        date_encoding: DateEncoding,
+        string_format: &StringFormat,
+    ) -> Arc<dyn RegistryDeserializer> {
+        let schema = Self::registry_schema(registry_name);
 
         // Get PNR column name for this registry type
         let pnr_column = match registry_name {
@@ -71,6 +118,7 @@ impl DirectIndividualDeserializer {
             "MFR" => Some("CPR_BARN"),
             "DOD" => Some("PNR"),
             "DODSAARSAG" => Some("PNR"),
+            "IDAN" => Some("PNR"),
             "IND" => Some("PNR"),
             "UDDF" => Some("PNR"),
             "LPR_ADM" => Some("PNR"),
@@ -80,16 +128,159 @@ impl DirectIndividualDeserializer {
         };
 
         // Create a deserializer implementation with the schema
-        let inner = Arc::new(
-            crate::registry::trait_deserializer_impl::RegistryDeserializerImpl::new(
+        Arc::new(
+            crate::registry::trait_deserializer_impl::RegistryDeserializerImpl::new_with_formats(
                 registry_name,
                 format!("{registry_name} registry"),
                 schema,
                 pnr_column,
+                float_format,
+                date_encoding,
+                string_format,
             ),
+        )
+    }
+
+    /// Parse decimal fields stored as text with the given format
+    ///
+    /// By default such text is read with a decimal point, and text that is
+    /// not a number leaves the field unset.
+    #[must_use]
+    pub fn with_float_format(mut self, float_format: FloatFormat) -> Self {
+        self.float_format = float_format;
+        self.rebuild_inner()
+    }
+
+    /// Read date fields stored as integers with the given encoding
+    ///
+    /// By default integer columns are not read as dates; registries known
+    /// to store packed `YYYYMMDD` integers opt in with
+    /// [`DateEncoding::PackedInteger`].
+    #[must_use]
+    pub fn with_date_encoding(mut self, date_encoding: DateEncoding) -> Self {
+        self.date_encoding = date_encoding;
+        self.rebuild_inner()
+    }
+
+    /// Read empty or whitespace-only text in string fields as missing
+    ///
+    /// Enabled by default, so a `""` standing for a missing value leaves
+    /// the field `None` rather than `Some("")`. Columns overridden with
+    /// [`Self::with_column_empty_string_as_null`] keep their own setting.
+    #[must_use]
+    pub fn with_empty_string_as_null(mut self, empty_string_as_null: bool) -> Self {
+        self.string_format.empty_string_as_null = empty_string_as_null;
+        self.rebuild_inner()
+    }
+
+    /// Read empty text of one column as missing or not, whatever the default
+    ///
+    /// For the rare column where `""` is a meaningful value, pass `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The source column name, e.g. "KOM"
+    /// * `empty_string_as_null` - Whether empty text of the column is missing
+    #[must_use]
+    pub fn with_column_empty_string_as_null(
+        mut self,
+        column: impl Into<String>,
+        empty_string_as_null: bool,
+    ) -> Self {
+        self.string_format
+            .column_overrides
+            .insert(column.into(), empty_string_as_null);
+        self.rebuild_inner()
+    }
+
+    /// Recreate the field extractors with the current formats
+    fn rebuild_inner(mut self) -> Self {
+        self.inner = Self::create_inner(
+            self.inner.registry_type(),
+            self.float_format,
+            self.date_encoding,
+            &self.string_format,
         );
+        self
+    }
+
+    /// Enable or disable type adaptation before field extraction
+    ///
+    /// This mirrors `ParquetReaderConfig::adapt_types` and is enabled by
+    /// default; see [`Self::harmonize_batch`].
+    #[must_use]
+    pub const fn with_adapt_types(mut self, adapt_types: bool) -> Self {
+        self.adapt_types = adapt_types;
+        self
+    }
+
+    /// Adapt the declared columns of a batch to the types the extractors read
+    ///
+    /// The field extractors read dates from `Date32` (or strings they parse
+    /// themselves), decimals from `Float64`, strings from `Utf8` and integers
+    /// from any integer type. A declared column of another type that
+    /// `check_schema_with_adaptation` finds compatible, such as a `Date64`
+    /// date, is converted with `adapt_record_batch`. Incompatible columns and
+    /// columns not in the field map are left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - The record batch to harmonize
+    ///
+    /// # Returns
+    ///
+    /// The batch itself if type adaptation is disabled or nothing needs
+    /// converting, otherwise the converted batch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a column cannot be converted
+    pub fn harmonize_batch<'a>(&self, batch: &'a RecordBatch) -> Result<Cow<'a, RecordBatch>> {
+        if !self.adapt_types {
+            return Ok(Cow::Borrowed(batch));
+        }
 
-        Self { inner }
+        let schema = batch.schema();
+        let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+        let mut changed = false;
+        for (source, _, field_type) in self.inner.field_map() {
+            let Ok(index) = schema.index_of(source) else {
+                continue;
+            };
+            let source_type = fields[index].data_type();
+            let target_type = match field_type {
+                FieldType::Date if source_type != &DataType::Utf8 => DataType::Date32,
+                FieldType::Decimal => DataType::Float64,
+                FieldType::String | FieldType::PNR | FieldType::Category => DataType::Utf8,
+                FieldType::Integer if !is_numeric(source_type) => DataType::Int32,
+                _ => continue,
+            };
+            if check_type_compatibility(source_type, &target_type) == TypeCompatibility::Compatible
+            {
+                fields[index] = fields[index].clone().with_data_type(target_type);
+                changed = true;
+            }
+        }
+        if !changed {
+            return Ok(Cow::Borrowed(batch));
+        }
+
+        let target = Schema::new_with_metadata(fields, schema.metadata().clone());
+        let report = check_schema_with_adaptation(&schema, &target);
+        for adaptation in &report.adaptations {
+            debug!(
+                "Adapting column '{}' of {} from {:?} to {:?}",
+                adaptation.field_name,
+                self.inner.registry_type(),
+                adaptation.source_type,
+                adaptation.target_type
+            );
+        }
+        Ok(Cow::Owned(adapt_record_batch(
+            batch,
+            &target,
+            &DateFormatConfig::default(),
+        )?))
     }
 
     /// Create VNDS migration registry schema
@@ -134,6 +325,13 @@ impl DirectIndividualDeserializer {
         create_registry_schema("DODSAARSAG", "DODSAARSAG Cause of Death registry", field_mappings)
     }
 
+    /// Create IDAN employment registry schema
+    fn create_idan_schema() -> RegistrySchema {
+        // Get field mappings from the IDAN registry module
+        let field_mappings = idan_mapping::create_field_mappings();
+        create_registry_schema("IDAN", "IDAN Employment registry", field_mappings)
+    }
+
     /// Create IND income registry schema
     fn create_ind_schema() -> RegistrySchema {
         // Get field mappings from the IND registry module
@@ -206,7 +404,8 @@ impl DirectIndividualDeserializer {
     ///
     /// A Result containing a Vec of deserialized Individuals
     pub fn deserialize_batch(&self, batch: &RecordBatch) -> Result<Vec<Individual>> {
-        self.inner.deserialize_batch(batch)
+        let batch = self.harmonize_batch(batch)?;
+        self.inner.deserialize_batch(&batch)
     }
 
     /// Deserialize a single row from a record batch
@@ -220,7 +419,43 @@ impl DirectIndividualDeserializer {
     ///
     /// A Result containing an Option with the deserialized Individual
     pub fn deserialize_row(&self, batch: &RecordBatch, row: usize) -> Result<Option<Individual>> {
-        self.inner.deserialize_row(batch, row)
+        let row_batch = batch.slice(row, 1);
+        let row_batch = self.harmonize_batch(&row_batch)?;
+        self.inner.deserialize_row(&row_batch, 0)
+    }
+
+    /// Deserialize a row whose PNR comes from outside the batch
+    ///
+    /// Registries keyed by `RECNUM` or a contact id have no PNR column. The
+    /// row is extracted like [`Self::deserialize_row`] and then given `pnr`;
+    /// a row without a PNR is skipped. The batch is read as is, so it should
+    /// already be harmonized with [`Self::harmonize_batch`].
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - The record batch
+    /// * `row` - The row index to deserialize
+    /// * `pnr` - The PNR of the row, if known
+    ///
+    /// # Returns
+    ///
+    /// A Result containing an Option with the deserialized Individual
+    pub fn deserialize_row_with_pnr(
+        &self,
+        batch: &RecordBatch,
+        row: usize,
+        pnr: Option<&str>,
+    ) -> Result<Option<Individual>> {
+        let Some(pnr) = pnr else {
+            return Ok(None);
+        };
+
+        let mut individual = Individual::new(String::new(), None);
+        for extractor in self.inner.field_extractors() {
+            extractor.extract_and_set(batch, row, &mut individual as &mut dyn Any)?;
+        }
+        individual.pnr = pnr.to_string();
+        Ok(Some(individual))
     }
 
     /// Get field extractors used by this deserializer
@@ -232,4 +467,114 @@ impl DirectIndividualDeserializer {
     #[must_use] pub fn field_mapping(&self) -> HashMap<String, String> {
         self.inner.field_mapping()
     }
-}
\ No newline at end of file
+
+    /// Get the Arrow schema of the declared fields
+    ///
+    /// Unlike [`Self::field_map`], the schema keeps the declared nullability
+    /// of each field.
+    #[must_use] pub fn arrow_schema(&self) -> Arc<Schema> {
+        self.arrow_schema.clone()
+    }
+
+    /// Get the declared `(source, target, type)` field map
+    #[must_use] pub fn field_map(&self) -> &[(String, String, FieldType)] {
+        self.inner.field_map()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Date64Array, StringArray};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_deserialize_date64_bef_batch() {
+        let birth_date = NaiveDate::from_ymd_opt(2010, 3, 14).unwrap();
+        let millis = birth_date
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp_millis();
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["1", "2"])) as ArrayRef,
+            ),
+            (
+                "FOED_DAG",
+                Arc::new(Date64Array::from(vec![Some(millis), None])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let deserializer = DirectIndividualDeserializer::new("BEF");
+        let harmonized = deserializer.harmonize_batch(&batch).unwrap();
+        assert_eq!(
+            harmonized
+                .schema()
+                .field_with_name("FOED_DAG")
+                .unwrap()
+                .data_type(),
+            &DataType::Date32
+        );
+        let individuals = deserializer.deserialize_batch(&batch).unwrap();
+        assert_eq!(individuals.len(), 2);
+        assert_eq!(individuals[0].birth_date, Some(birth_date));
+        assert_eq!(individuals[1].birth_date, None);
+        let row = deserializer.deserialize_row(&batch, 0).unwrap().unwrap();
+        assert_eq!(row.birth_date, Some(birth_date));
+
+        // Without adaptation the Date64 column is not read
+        let individuals = DirectIndividualDeserializer::new("BEF")
+            .with_adapt_types(false)
+            .deserialize_batch(&batch)
+            .unwrap();
+        assert_eq!(individuals[0].birth_date, None);
+    }
+
+    #[test]
+    fn test_empty_string_as_null() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["1", "2", "3"])) as ArrayRef,
+            ),
+            (
+                "KOM",
+                Arc::new(StringArray::from(vec![Some("0101"), Some(""), Some("  ")])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let municipalities = |deserializer: DirectIndividualDeserializer| {
+            deserializer
+                .deserialize_batch(&batch)
+                .unwrap()
+                .into_iter()
+                .map(|individual| individual.municipality_code)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            municipalities(DirectIndividualDeserializer::new("BEF")),
+            vec![Some("0101".to_string()), None, None]
+        );
+        let kept = vec![
+            Some("0101".to_string()),
+            Some(String::new()),
+            Some("  ".to_string()),
+        ];
+        assert_eq!(
+            municipalities(
+                DirectIndividualDeserializer::new("BEF").with_empty_string_as_null(false)
+            ),
+            kept
+        );
+        assert_eq!(
+            municipalities(
+                DirectIndividualDeserializer::new("BEF")
+                    .with_column_empty_string_as_null("KOM", false)
+            ),
+            kept
+        );
+    }
+}
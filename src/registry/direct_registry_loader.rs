@@ -5,11 +5,16 @@
 //! intermediate registry-specific structs.
 
 use crate::RecordBatch;
+use crate::config::ParquetReaderConfig;
 use crate::error::Result;
-use crate::models::core::Individual;
+use crate::models::core::{Individual, Provenance};
 use crate::registry::RegisterLoader;
 use crate::registry::direct_deserializer::DirectIndividualDeserializer;
-use arrow::datatypes::SchemaRef;
+use crate::utils::arrow::array_utils::{downcast_array, get_column};
+use crate::utils::register::extractors::{DateEncoding, FloatFormat};
+use arrow::array::{Array, StringArray};
+use arrow::datatypes::{DataType, SchemaRef};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Registry loader that uses direct deserialization without intermediate structs
@@ -21,6 +26,20 @@ pub struct DirectRegistryLoader {
     deserializer: DirectIndividualDeserializer,
     /// PNR column name (if any)
     pnr_column: Option<&'static str>,
+    /// Whether individuals record the file and row they were read from
+    provenance: bool,
+    /// Whether a directory without Parquet files is an error
+    fail_on_empty_directory: bool,
+    /// Whether the files of a directory are checked for compatible schemas
+    validate_schema: bool,
+    /// The key column and key-to-PNR map giving rows their PNR (if any)
+    pnr_lookup: Option<(&'static str, Arc<HashMap<String, String>>)>,
+    /// The declared columns to read, or all of them (if `None`)
+    projection: Option<HashSet<String>>,
+    /// The maximum number of rows to load (if any)
+    limit: Option<usize>,
+    /// The settings files are read with (if not the default)
+    reader_config: Option<ParquetReaderConfig>,
 }
 
 impl DirectRegistryLoader {
@@ -46,6 +65,7 @@ impl DirectRegistryLoader {
             "MFR" => Some("CPR_BARN"),
             "DOD" => Some("PNR"),
             "DODSAARSAG" => Some("PNR"),
+            "IDAN" => Some("PNR"),
             "IND" => Some("PNR"),
             "UDDF" => Some("PNR"),
             "LPR_ADM" => Some("PNR"),
@@ -60,9 +80,151 @@ impl DirectRegistryLoader {
             registry_name,
             deserializer,
             pnr_column,
+            provenance: false,
+            fail_on_empty_directory: false,
+            validate_schema: false,
+            pnr_lookup: None,
+            projection: None,
+            limit: None,
+            reader_config: None,
         }
     }
 
+    /// Give each row the PNR its key maps to
+    ///
+    /// For registries without a PNR column, such as `LPR_DIAG` keyed by
+    /// `RECNUM`. Rows whose key is null or not in the lookup are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_column` - The column holding the keys
+    /// * `lookup` - The map from keys to PNRs, e.g. from `build_lookup`
+    #[must_use]
+    pub fn with_pnr_lookup(
+        mut self,
+        key_column: &'static str,
+        lookup: Arc<HashMap<String, String>>,
+    ) -> Self {
+        self.pnr_lookup = Some((key_column, lookup));
+        self
+    }
+
+    /// Read only the given declared columns
+    ///
+    /// Names that are not declared columns of the registry are ignored.
+    #[must_use]
+    pub fn with_projection(mut self, columns: HashSet<String>) -> Self {
+        self.projection = Some(columns);
+        self
+    }
+
+    /// Load at most `limit` rows, or every row (if `None`)
+    ///
+    /// Reading stops once the limit is reached, so a preview of a large
+    /// registry reads only its first files. See [`RegisterLoader::limit`].
+    #[must_use]
+    pub const fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Read files with the given settings
+    ///
+    /// See [`RegisterLoader::reader_config`]. By default files are read as
+    /// they are, without type adaptation or date range checks.
+    #[must_use]
+    pub fn with_reader_config(mut self, config: ParquetReaderConfig) -> Self {
+        self.reader_config = Some(config);
+        self
+    }
+
+    /// Fail with `Error::EmptyDirectory` when a directory has no Parquet files
+    ///
+    /// By default such a directory loads as no batches.
+    #[must_use]
+    pub const fn with_fail_on_empty_directory(mut self, enabled: bool) -> Self {
+        self.fail_on_empty_directory = enabled;
+        self
+    }
+
+    /// Check the files of a directory for compatible schemas before loading
+    ///
+    /// A mismatch fails the load with `Error::IncompatibleSchemas`. This is
+    /// off by default, as vintages of a registry often differ in columns.
+    #[must_use]
+    pub const fn with_validate_schema(mut self, enabled: bool) -> Self {
+        self.validate_schema = enabled;
+        self
+    }
+
+    /// Record the source file and row on each deserialized individual
+    ///
+    /// This is off by default, as it adds a field to every individual.
+    #[must_use]
+    pub const fn with_provenance(mut self, enabled: bool) -> Self {
+        self.provenance = enabled;
+        self
+    }
+
+    /// Enable or disable type adaptation before field extraction
+    ///
+    /// See [`DirectIndividualDeserializer::harmonize_batch`].
+    #[must_use]
+    pub fn with_adapt_types(mut self, adapt_types: bool) -> Self {
+        self.deserializer = self.deserializer.with_adapt_types(adapt_types);
+        self
+    }
+
+    /// Parse decimal fields stored as text with the given format
+    ///
+    /// See [`DirectIndividualDeserializer::with_float_format`].
+    #[must_use]
+    pub fn with_float_format(mut self, float_format: FloatFormat) -> Self {
+        self.deserializer = self.deserializer.with_float_format(float_format);
+        self
+    }
+
+    /// Read date fields stored as integers with the given encoding
+    ///
+    /// See [`DirectIndividualDeserializer::with_date_encoding`].
+    #[must_use]
+    pub fn with_date_encoding(mut self, date_encoding: DateEncoding) -> Self {
+        self.deserializer = self.deserializer.with_date_encoding(date_encoding);
+        self
+    }
+
+    /// Read empty or whitespace-only text in string fields as missing
+    ///
+    /// See [`DirectIndividualDeserializer::with_empty_string_as_null`].
+    #[must_use]
+    pub fn with_empty_string_as_null(mut self, empty_string_as_null: bool) -> Self {
+        self.deserializer = self
+            .deserializer
+            .with_empty_string_as_null(empty_string_as_null);
+        self
+    }
+
+    /// Read empty text of one column as missing or not, whatever the default
+    ///
+    /// See [`DirectIndividualDeserializer::with_column_empty_string_as_null`].
+    #[must_use]
+    pub fn with_column_empty_string_as_null(
+        mut self,
+        column: impl Into<String>,
+        empty_string_as_null: bool,
+    ) -> Self {
+        self.deserializer = self
+            .deserializer
+            .with_column_empty_string_as_null(column, empty_string_as_null);
+        self
+    }
+
+    /// Check whether individuals record the file and row they were read from
+    #[must_use]
+    pub const fn records_provenance(&self) -> bool {
+        self.provenance
+    }
+
     /// Deserialize a batch of records
     ///
     /// # Arguments
@@ -73,7 +235,90 @@ impl DirectRegistryLoader {
     ///
     /// A Result containing a Vec of deserialized Individual models
     pub fn deserialize_batch(&self, batch: &RecordBatch) -> Result<Vec<Individual>> {
-        self.deserializer.deserialize_batch(batch)
+        if self.pnr_lookup.is_none() {
+            return self.deserializer.deserialize_batch(batch);
+        }
+        Ok(self
+            .deserialize_rows(batch)?
+            .into_iter()
+            .map(|(_, individual)| individual)
+            .collect())
+    }
+
+    /// Deserialize a batch read from a file
+    ///
+    /// With provenance enabled, each individual records `source_file` and its
+    /// row in the file; otherwise this is [`Self::deserialize_batch`].
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - The record batch to deserialize
+    /// * `source_file` - The file the batch was read from
+    /// * `first_row` - The row of the file the batch starts at
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a Vec of deserialized Individual models
+    pub fn deserialize_batch_from(
+        &self,
+        batch: &RecordBatch,
+        source_file: &Arc<str>,
+        first_row: usize,
+    ) -> Result<Vec<Individual>> {
+        if !self.provenance {
+            return self.deserialize_batch(batch);
+        }
+
+        Ok(self
+            .deserialize_rows(batch)?
+            .into_iter()
+            .map(|(row, mut individual)| {
+                individual.provenance = Some(Provenance {
+                    source_file: Arc::clone(source_file),
+                    source_row: first_row + row,
+                });
+                individual
+            })
+            .collect())
+    }
+
+    /// Deserialize the rows of a batch, keeping the row of each individual
+    fn deserialize_rows(&self, batch: &RecordBatch) -> Result<Vec<(usize, Individual)>> {
+        // Harmonize once, so the rows need no further adaptation
+        let batch = self.deserializer.harmonize_batch(batch)?;
+        let key_array = match &self.pnr_lookup {
+            Some((key_column, _)) => get_column(&batch, key_column, &DataType::Utf8, true)?,
+            None => None,
+        };
+        let keys = key_array
+            .as_ref()
+            .zip(self.pnr_lookup.as_ref())
+            .map(|(array, (key_column, lookup))| {
+                downcast_array::<StringArray>(array, key_column, "Utf8").map(|keys| (keys, lookup))
+            })
+            .transpose()?;
+
+        let mut individuals = Vec::with_capacity(batch.num_rows());
+        for row in 0..batch.num_rows() {
+            let individual = match keys {
+                Some((keys, lookup)) => {
+                    let pnr = keys
+                        .is_valid(row)
+                        .then(|| lookup.get(keys.value(row)))
+                        .flatten();
+                    self.deserializer.deserialize_row_with_pnr(
+                        &batch,
+                        row,
+                        pnr.map(String::as_str),
+                    )?
+                }
+                None => self.deserializer.deserialize_row(&batch, row)?,
+            };
+            if let Some(individual) = individual {
+                individuals.push((row, individual));
+            }
+        }
+        Ok(individuals)
     }
 }
 
@@ -83,20 +328,17 @@ impl RegisterLoader for DirectRegistryLoader {
     }
 
     fn get_schema(&self) -> SchemaRef {
-        // Convert the field extractors to an Arrow schema
-        let _field_mapping = self.deserializer.field_mapping();
-        let field_extractors = self.deserializer.field_extractors();
-
-        // Build the schema from the field definitions
-        let fields = field_extractors
+        // Build the schema from the declared fields so column types and
+        // nullability match
+        let schema = self.deserializer.arrow_schema();
+        let Some(columns) = self.projection.as_ref() else {
+            return schema;
+        };
+        let fields = schema
+            .fields()
             .iter()
-            .map(|extractor| {
-                let field_def = extractor.get_field_definition();
-                let name = field_def.name.clone();
-                let data_type = field_def.field_type.to_arrow_type(true);
-                let is_nullable = field_def.nullable;
-                Arc::new(arrow::datatypes::Field::new(name, data_type, is_nullable))
-            })
+            .filter(|field| columns.contains(field.name()))
+            .cloned()
             .collect::<Vec<_>>();
 
         Arc::new(arrow::datatypes::Schema::new(fields))
@@ -114,4 +356,362 @@ impl RegisterLoader for DirectRegistryLoader {
             None
         }
     }
+
+    fn get_field_mapping(&self) -> HashMap<String, String> {
+        self.deserializer.field_mapping()
+    }
+
+    fn fail_on_empty_directory(&self) -> bool {
+        self.fail_on_empty_directory
+    }
+
+    fn validate_schema(&self) -> bool {
+        self.validate_schema
+    }
+
+    fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    fn reader_config(&self) -> ParquetReaderConfig {
+        self.reader_config
+            .clone()
+            .unwrap_or_else(|| ParquetReaderConfig {
+                adapt_types: false,
+                ..ParquetReaderConfig::default()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_missing_and_empty_directories() {
+        let dir = std::env::temp_dir().join(format!("par_reader_empty_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let loader = DirectRegistryLoader::new("BEF");
+        assert!(loader.load(&dir, None).unwrap().is_empty());
+
+        let error = loader
+            .with_fail_on_empty_directory(true)
+            .load(&dir, None)
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::EmptyDirectory { path }) if *path == dir
+        ));
+
+        let error = DirectRegistryLoader::new("BEF")
+            .load(&dir.join("missing"), None)
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::IoError(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pnr_filter_requirement() {
+        use crate::registry::PnrFilterRequirement;
+
+        assert_eq!(
+            DirectRegistryLoader::new("BEF").pnr_filter_requirement(),
+            PnrFilterRequirement::Direct
+        );
+        assert_eq!(
+            DirectRegistryLoader::new("LPR_DIAG").pnr_filter_requirement(),
+            PnrFilterRequirement::RequiresJoin {
+                with: "LPR_ADM",
+                on: "RECNUM"
+            }
+        );
+        assert_eq!(
+            DirectRegistryLoader::new("LPR3_DIAGNOSER").pnr_filter_requirement(),
+            PnrFilterRequirement::RequiresJoin {
+                with: "LPR3_KONTAKTER",
+                on: "DW_EK_KONTAKT"
+            }
+        );
+    }
+
+    #[test]
+    fn test_idan_reads_job_columns() {
+        use crate::utils::register::RegistryType;
+        use arrow::array::{ArrayRef, Float64Array};
+        use parquet::arrow::ArrowWriter;
+
+        let dir = std::env::temp_dir().join(format!("par_reader_idan_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["0101801234"])) as ArrayRef,
+            ),
+            (
+                "ARBGNR",
+                Arc::new(StringArray::from(vec!["12345"])) as ArrayRef,
+            ),
+            (
+                "JOBLON",
+                Arc::new(Float64Array::from(vec![31_500.0])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(
+            std::fs::File::create(dir.join("idan2020.parquet")).unwrap(),
+            batch.schema(),
+            None,
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let loader = RegistryType::IDAN.loader();
+        let batches = loader.load(&dir, None).unwrap();
+        assert!(batches[0].column_by_name("ARBGNR").is_some());
+        assert!(batches[0].column_by_name("JOBLON").is_some());
+
+        let individuals = DirectRegistryLoader::new("IDAN")
+            .deserialize_batch(&batches[0])
+            .unwrap();
+        assert_eq!(individuals[0].pnr, "0101801234");
+        assert_eq!(
+            individuals[0].property_value::<String>("employer_number"),
+            Some("12345".to_string())
+        );
+        assert_eq!(
+            individuals[0].property_value::<f64>("job_salary"),
+            Some(31_500.0)
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reader_config_extra_columns() {
+        use crate::config::ParquetReaderConfig;
+        use arrow::array::{ArrayRef, Int32Array};
+        use parquet::arrow::ArrowWriter;
+
+        let dir = std::env::temp_dir().join(format!("par_reader_extra_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("idan2020.parquet");
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["0101801234"])) as ArrayRef,
+            ),
+            (
+                "ARBGNR",
+                Arc::new(StringArray::from(vec!["12345"])) as ArrayRef,
+            ),
+            (
+                "NEW_COLUMN",
+                Arc::new(Int32Array::from(vec![1])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let mut writer =
+            ArrowWriter::try_new(std::fs::File::create(&file).unwrap(), batch.schema(), None)
+                .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        // By default the extra column is dropped
+        let batches = DirectRegistryLoader::new("IDAN").load(&dir, None).unwrap();
+        assert!(batches[0].column_by_name("ARBGNR").is_some());
+        assert!(batches[0].column_by_name("NEW_COLUMN").is_none());
+
+        let strict = DirectRegistryLoader::new("IDAN").with_reader_config(ParquetReaderConfig {
+            ignore_extra_columns: false,
+            ..ParquetReaderConfig::default()
+        });
+        assert!(strict.load(&dir, None).is_err());
+        assert!(strict.load(&file, None).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reader_config_date_range_checks() {
+        use crate::config::ParquetReaderConfig;
+        use crate::utils::arrow::DateRangeCheck;
+        use arrow::array::{ArrayRef, Date32Array};
+        use chrono::NaiveDate;
+        use parquet::arrow::ArrowWriter;
+
+        let dir = std::env::temp_dir().join(format!("par_reader_dates_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let day = |year| {
+            let date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+            (date - NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days() as i32
+        };
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["0101801234", "0101001234"])) as ArrayRef,
+            ),
+            (
+                "FOED_DAG",
+                Arc::new(Date32Array::from(vec![day(1980), day(1800)])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(
+            std::fs::File::create(dir.join("bef2020.parquet")).unwrap(),
+            batch.schema(),
+            None,
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let check = DateRangeCheck::new(
+            "FOED_DAG",
+            NaiveDate::from_ymd_opt(1900, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2030, 12, 31).unwrap(),
+        );
+        let loader = DirectRegistryLoader::new("BEF").with_reader_config(ParquetReaderConfig {
+            date_range_checks: vec![check.nulling_out_of_range()],
+            ..ParquetReaderConfig::default()
+        });
+        let batches = loader.load(&dir, None).unwrap();
+        let dates = batches[0].column_by_name("FOED_DAG").unwrap();
+        assert_eq!(dates.null_count(), 1);
+        assert!(dates.is_null(1));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_limit_spans_files() {
+        use crate::utils::register::RegistryType;
+        use crate::utils::test::synthetic::{SYNTHETIC_BATCH_SIZE, write_synthetic_registry};
+
+        let dir = write_synthetic_registry(RegistryType::BEF, 10_000, 1).unwrap();
+        let rows = |limit| {
+            DirectRegistryLoader::new("BEF")
+                .with_limit(limit)
+                .load(&dir, None)
+                .unwrap()
+                .iter()
+                .map(RecordBatch::num_rows)
+                .sum::<usize>()
+        };
+
+        assert_eq!(rows(Some(5)), 5);
+        assert_eq!(
+            rows(Some(SYNTHETIC_BATCH_SIZE + 10)),
+            SYNTHETIC_BATCH_SIZE + 10
+        );
+        assert_eq!(rows(Some(20_000)), 10_000);
+        assert_eq!(rows(None), 10_000);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_multi() {
+        use crate::utils::register::RegistryType;
+        use crate::utils::test::synthetic::{synthetic_id, write_synthetic_registry};
+        use arrow::array::{ArrayRef, StringArray};
+        use parquet::arrow::ArrowWriter;
+
+        let first = write_synthetic_registry(RegistryType::BEF, 100, 1).unwrap();
+        let second = write_synthetic_registry(RegistryType::BEF, 50, 2).unwrap();
+        let file = second.join("part_0000.parquet");
+        let loader = DirectRegistryLoader::new("BEF");
+        let rows =
+            |batches: Vec<RecordBatch>| batches.iter().map(RecordBatch::num_rows).sum::<usize>();
+
+        assert_eq!(
+            rows(loader.load_multi(&[&first, &second], None).unwrap()),
+            150
+        );
+        assert_eq!(
+            rows(loader.load_multi(&[&first, &file], None).unwrap()),
+            150
+        );
+
+        // Both deliveries hold the first 50 synthetic PNRs
+        let filter: HashSet<String> = [1, 2, 3, 70].into_iter().map(synthetic_id).collect();
+        assert_eq!(
+            rows(
+                loader
+                    .load_multi(&[&first, &second], Some(&filter))
+                    .unwrap()
+            ),
+            7
+        );
+        let limited = DirectRegistryLoader::new("BEF").with_limit(Some(120));
+        assert_eq!(
+            rows(limited.load_multi(&[&first, &second], None).unwrap()),
+            120
+        );
+
+        let other = std::env::temp_dir().join(format!("par_reader_multi_{}", std::process::id()));
+        std::fs::create_dir_all(&other).unwrap();
+        let batch = RecordBatch::try_from_iter(vec![(
+            "PNR",
+            Arc::new(StringArray::from(vec!["0101801234"])) as ArrayRef,
+        )])
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(
+            std::fs::File::create(other.join("a.parquet")).unwrap(),
+            batch.schema(),
+            None,
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let error = loader.load_multi(&[&first, &other], None).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::IncompatibleSchemas { .. })
+        ));
+        let error = loader
+            .load_multi(&[&first, &other.join("missing")], None)
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::IoError(_))
+        ));
+
+        for dir in [first, second, other] {
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_load_bytes() {
+        use crate::utils::register::RegistryType;
+        use crate::utils::test::synthetic::{generate_synthetic_registry, synthetic_id};
+        use parquet::arrow::ArrowWriter;
+
+        let batches = generate_synthetic_registry(RegistryType::BEF, 100, 1).unwrap();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batches[0].schema(), None).unwrap();
+        writer.write(&batches[0]).unwrap();
+        writer.close().unwrap();
+        let bytes = bytes::Bytes::from(buffer);
+
+        let filter: HashSet<String> = [1, 2, 3].into_iter().map(synthetic_id).collect();
+        let loader = DirectRegistryLoader::new("BEF");
+        let rows =
+            |batches: Vec<RecordBatch>| batches.iter().map(RecordBatch::num_rows).sum::<usize>();
+        assert_eq!(rows(loader.load_bytes(bytes.clone(), None).unwrap()), 100);
+        assert_eq!(
+            rows(loader.load_bytes(bytes.clone(), Some(&filter)).unwrap()),
+            3
+        );
+
+        let loader = loader.with_limit(Some(10));
+        assert_eq!(rows(loader.load_bytes(bytes, None).unwrap()), 10);
+    }
 }
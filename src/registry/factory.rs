@@ -6,83 +6,33 @@ use super::RegisterLoader;
 use crate::RecordBatch;
 use crate::error::{ParquetReaderError, Result};
 use crate::registry::direct_registry_loader::DirectRegistryLoader;
+use crate::utils::io::parquet::{find_parquet_files, read_parquet_schema};
+use crate::utils::register::{RegistryType, rank_registry_types};
+use arrow::datatypes::{Schema, SchemaRef};
 use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 
 /// Create a registry loader from a registry name using direct deserialization
+///
+/// The name is parsed case-insensitively as a [`RegistryType`].
+///
+/// # Errors
+/// Returns an error if the name is not a known registry
 pub fn registry_from_name(name: &str) -> Result<Arc<dyn RegisterLoader>> {
-    match name.to_lowercase().as_str() {
-        "akm" => {
-            // Use direct deserialization for AKM
-            let registry = DirectRegistryLoader::new("AKM");
-            Ok(Arc::new(registry))
-        }
-        "bef" => {
-            // Use direct deserialization for BEF
-            let registry = DirectRegistryLoader::new("BEF");
-            Ok(Arc::new(registry))
-        }
-        "dod" => {
-            // Use direct deserialization for DOD
-            let registry = DirectRegistryLoader::new("DOD");
-            Ok(Arc::new(registry))
-        }
-        "dodsaarsag" => {
-            // Use direct deserialization for DODSAARSAG
-            let registry = DirectRegistryLoader::new("DODSAARSAG");
-            Ok(Arc::new(registry))
-        }
-        "ind" => {
-            // Use direct deserialization for IND
-            let registry = DirectRegistryLoader::new("IND");
-            Ok(Arc::new(registry))
-        }
-        "mfr" => {
-            // Use direct deserialization for MFR
-            let registry = DirectRegistryLoader::new("MFR");
-            Ok(Arc::new(registry))
-        }
-        "uddf" => {
-            // Use direct deserialization for UDDF
-            let registry = DirectRegistryLoader::new("UDDF");
-            Ok(Arc::new(registry))
-        }
-        "vnds" => {
-            // Use direct deserialization for VNDS
-            let registry = DirectRegistryLoader::new("VNDS");
-            Ok(Arc::new(registry))
-        }
-        "lpr_adm" => {
-            // Use direct deserialization for LPR_ADM
-            let registry = DirectRegistryLoader::new("LPR_ADM");
-            Ok(Arc::new(registry))
-        }
-        "lpr_diag" => {
-            // Use direct deserialization for LPR_DIAG
-            let registry = DirectRegistryLoader::new("LPR_DIAG");
-            Ok(Arc::new(registry))
-        }
-        "lpr_bes" => {
-            // Use direct deserialization for LPR_BES
-            let registry = DirectRegistryLoader::new("LPR_BES");
-            Ok(Arc::new(registry))
-        }
-        "lpr3_kontakter" => {
-            // Use direct deserialization for LPR3_KONTAKTER
-            let registry = DirectRegistryLoader::new("LPR3_KONTAKTER");
-            Ok(Arc::new(registry))
-        }
-        "lpr3_diagnoser" => {
-            // Use direct deserialization for LPR3_DIAGNOSER
-            let registry = DirectRegistryLoader::new("LPR3_DIAGNOSER");
-            Ok(Arc::new(registry))
-        }
-        _ => Err(ParquetReaderError::MetadataError(format!("Unknown registry: {name}")).into()),
-    }
+    Ok(Arc::from(name.parse::<RegistryType>()?.loader()))
 }
 
 /// Create a registry loader based on a path using direct deserialization
+///
+/// The registry is inferred from the directory or file name. If the name does
+/// not identify a registry, it is detected from the columns of the Parquet
+/// files instead (see [`detect_registry_candidates`]).
+///
+/// # Errors
+/// Returns an error if neither the name nor the columns identify a registry,
+/// or if the columns match several registries or too few columns of one; the
+/// error then lists the candidates, most likely first
 pub fn registry_from_path(path: &Path) -> Result<Arc<dyn RegisterLoader>> {
     // Try to infer registry from directory name
     if let Some(dir_name) = path.file_name().and_then(|f| f.to_str()) {
@@ -131,12 +81,76 @@ pub fn registry_from_path(path: &Path) -> Result<Arc<dyn RegisterLoader>> {
         }
     }
 
-    // If we can't infer from the path, return an error
-    Err(ParquetReaderError::MetadataError(format!(
-        "Could not determine registry type from path: {}",
-        path.display()
-    ))
-    .into())
+    // Fall back to detecting the registry from the file columns
+    let schema = detection_schema(path)?;
+    let (candidates, is_clear) = rank_registry_names(&schema);
+    match candidates.first() {
+        None => Err(ParquetReaderError::MetadataError(format!(
+            "Could not determine registry type from path or columns: {}",
+            path.display()
+        ))
+        .into()),
+        Some(name) if is_clear => Ok(Arc::new(DirectRegistryLoader::new(name))),
+        Some(_) => Err(ParquetReaderError::MetadataError(format!(
+            "Uncertain registry type for {}; candidates, most likely first: {}",
+            path.display(),
+            candidates.join(", ")
+        ))
+        .into()),
+    }
+}
+
+/// Detect the candidate registries of a Parquet file or directory from its columns
+///
+/// The schema of the file, or of the first file found in the directory, is
+/// ranked with [`rank_registry_types`].
+///
+/// # Arguments
+/// * `path` - A Parquet file or a directory of Parquet files
+///
+/// # Returns
+/// The candidate registry names, most likely first
+///
+/// # Errors
+/// Returns an error if no Parquet file is found or its schema cannot be read
+pub fn detect_registry_candidates(path: &Path) -> Result<Vec<&'static str>> {
+    let schema = detection_schema(path)?;
+    Ok(rank_registry_names(&schema).0)
+}
+
+/// Read the schema used to detect the registry of a file or directory
+fn detection_schema(path: &Path) -> Result<SchemaRef> {
+    let file = if path.is_dir() {
+        find_parquet_files(path)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                ParquetReaderError::MetadataError(format!(
+                    "No Parquet files found to detect the registry type of {}",
+                    path.display()
+                ))
+            })?
+    } else {
+        path.to_path_buf()
+    };
+    read_parquet_schema(&file)
+}
+
+/// Rank the registry loaders matching a schema
+///
+/// # Returns
+/// The candidate registry names, most likely first, and whether the first is
+/// a clear match: the only detected registry, with at least
+/// [`MIN_CONFIDENCE`](crate::utils::register::MIN_CONFIDENCE) of its expected
+/// columns present
+fn rank_registry_names(schema: &Schema) -> (Vec<&'static str>, bool) {
+    let ranked = rank_registry_types(schema);
+    let is_clear = matches!(ranked.as_slice(), [detection] if detection.is_confident());
+    let candidates = ranked
+        .iter()
+        .map(|detection| detection.registry.as_str())
+        .collect();
+    (candidates, is_clear)
 }
 
 /// Load data from multiple registries and combine them using direct deserialization
@@ -357,4 +371,130 @@ pub fn load_registry_time_periods(
     
     info!("Loaded data from {} time periods for {}", result.len(), registry_name);
     Ok(result)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, StringArray};
+    use parquet::arrow::ArrowWriter;
+    use std::fs::File;
+
+    fn write_columns(path: &Path, columns: &[&str]) {
+        let batch = RecordBatch::try_from_iter(columns.iter().map(|column| {
+            (*column, Arc::new(StringArray::from(vec!["x"])) as ArrayRef)
+        }))
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(File::create(path).unwrap(), batch.schema(), None)
+            .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_registry_from_path_detects_columns() {
+        let dir = std::env::temp_dir().join(format!("par_reader_detect_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // A mislabeled file is detected from its columns
+        let education = dir.join("export_2020.parquet");
+        write_columns(
+            &education,
+            &["PNR", "HFAUDD", "HF_VFRA", "HF_VTIL", "INSTNR"],
+        );
+        assert_eq!(registry_from_path(&education).unwrap().get_register_name(), "UDDF");
+
+        let diagnoses = dir.join("export_diagnoses.parquet");
+        write_columns(&diagnoses, &["RECNUM", "C_DIAG", "C_DIAGTYPE"]);
+        assert_eq!(registry_from_path(&diagnoses).unwrap().get_register_name(), "LPR_DIAG");
+
+        // Too few of the BEF columns need confirmation
+        let family = dir.join("export_family.parquet");
+        write_columns(&family, &["PNR", "KOEN", "OTHER"]);
+        assert!(registry_from_path(&family).is_err());
+        assert_eq!(detect_registry_candidates(&family).unwrap(), vec!["BEF"]);
+
+        // Columns of two registries are ambiguous
+        let mixed = dir.join("export_mixed.parquet");
+        write_columns(&mixed, &["PNR", "SOCIO13", "HFAUDD"]);
+        let Err(error) = registry_from_path(&mixed) else {
+            panic!("expected an ambiguous registry type");
+        };
+        let error = error.to_string();
+        assert!(error.contains("AKM, UDDF"), "{error}");
+        assert_eq!(detect_registry_candidates(&mixed).unwrap(), vec!["AKM", "UDDF"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_marked_pnr_columns_match_direct_loaders() {
+        use crate::registry::bef::BefRegistryDeserializer;
+        use crate::registry::lpr::v3::diagnoser::Lpr3DiagnoserRegistryDeserializer;
+        use crate::registry::lpr::v3::kontakter::Lpr3KontakterRegistryDeserializer;
+        use crate::registry::mfr::MfrRegistryDeserializer;
+
+        for (marked, registry) in [
+            (BefRegistryDeserializer::new().get_pnr_column_name(), "BEF"),
+            (MfrRegistryDeserializer::new().get_pnr_column_name(), "MFR"),
+            (
+                Lpr3KontakterRegistryDeserializer::new().get_pnr_column_name(),
+                "LPR3_KONTAKTER",
+            ),
+            (
+                Lpr3DiagnoserRegistryDeserializer::new().get_pnr_column_name(),
+                "LPR3_DIAGNOSER",
+            ),
+        ] {
+            let direct = DirectRegistryLoader::new(registry).get_pnr_column_name();
+            assert_eq!(marked, direct, "{registry}");
+        }
+    }
+
+    #[test]
+    fn test_generated_schemas_match_direct_loaders() {
+        use crate::registry::bef::BefRegistryDeserializer;
+        use crate::registry::lpr::v2::adm::LprAdmRegistryDeserializer;
+        use crate::registry::lpr::v3::kontakter::Lpr3KontakterRegistryDeserializer;
+
+        for (generated, registry) in [
+            (BefRegistryDeserializer::new().get_schema(), "BEF"),
+            (LprAdmRegistryDeserializer::new().get_schema(), "LPR_ADM"),
+            (Lpr3KontakterRegistryDeserializer::new().get_schema(), "LPR3_KONTAKTER"),
+        ] {
+            let direct = DirectRegistryLoader::new(registry).get_schema();
+            for field in direct.fields() {
+                let generated = generated.field_with_name(field.name()).unwrap();
+                assert_eq!(generated.data_type(), field.data_type(), "{registry}");
+            }
+        }
+
+        let schema = LprAdmRegistryDeserializer::new().get_schema();
+        assert_eq!(schema.field(0).name(), "PNR");
+        assert!(!schema.field(0).is_nullable());
+        let referral_date = schema.field_with_name("D_HENDTO").unwrap();
+        assert_eq!(referral_date.data_type(), &arrow::datatypes::DataType::Date32);
+        assert!(referral_date.is_nullable());
+    }
+
+    #[test]
+    fn test_id_value_uses_id_field() {
+        use crate::registry::bef::BefRegistry;
+        use crate::registry::lpr::v2::diag::LprDiagRegistry;
+
+        let diag = LprDiagRegistry {
+            record_number: "R1".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(diag.id_value(), Some("R1"));
+
+        let person = BefRegistry {
+            pnr: "0101011234".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(person.id_value(), Some("0101011234"));
+
+        // A missing identity has no value
+        assert_eq!(BefRegistry::default().id_value(), None);
+    }
+}
@@ -0,0 +1,59 @@
+//! Field mappings for IDAN registry deserialization
+//!
+//! This module defines the field mappings for the IDAN (employment) registry.
+//! The job columns are stored in the properties map of the individual.
+
+use crate::schema::field_def::FieldMapping;
+use crate::schema::field_def::{
+    FieldDefinition, FieldType,
+    mapping::{Extractors, ModelSetters},
+};
+
+/// Create a mapping of a text column stored as a property
+fn string_property(column: &str, property: &'static str) -> FieldMapping {
+    FieldMapping::new(
+        FieldDefinition::new(column, property, FieldType::String, true),
+        Extractors::string(column),
+        ModelSetters::string_setter(move |individual, value| {
+            individual.store_property(property, Box::new(value));
+        }),
+    )
+}
+
+/// Create field mappings for IDAN registry
+#[must_use]
+pub fn create_field_mappings() -> Vec<FieldMapping> {
+    vec![
+        // PNR mapping (required)
+        FieldMapping::new(
+            FieldDefinition::new("PNR", "pnr", FieldType::PNR, false),
+            Extractors::string("PNR"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.pnr = value;
+            }),
+        ),
+        // Employer and workplace identifiers
+        string_property("ARBGNR", "employer_number"),
+        string_property("ARBNR", "workplace_number"),
+        string_property("CVRNR", "cvr_number"),
+        // Job category
+        FieldMapping::new(
+            FieldDefinition::new("JOBKAT", "job_category", FieldType::Integer, true),
+            Extractors::integer("JOBKAT"),
+            ModelSetters::i32_setter(|individual, value| {
+                individual.store_property("job_category", Box::new(value));
+            }),
+        ),
+        // Job salary
+        FieldMapping::new(
+            FieldDefinition::new("JOBLON", "job_salary", FieldType::Decimal, true),
+            Extractors::decimal("JOBLON"),
+            ModelSetters::f64_setter(|individual, value| {
+                individual.store_property("job_salary", Box::new(value));
+            }),
+        ),
+        // Position codes
+        string_property("LSTILL", "main_position"),
+        string_property("STILL", "position"),
+    ]
+}
@@ -0,0 +1,8 @@
+//! IDAN registry
+//!
+//! The IDAN (Integrerede Database for Arbejdsmarkedsforskning) registry
+//! contains the jobs of each person: employer, workplace, job category and
+//! salary. Its columns have no dedicated `Individual` fields, so they are
+//! stored as properties by the direct deserializer.
+
+pub mod field_mapping;
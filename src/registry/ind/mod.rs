@@ -6,13 +6,16 @@ use crate::RegistryTrait;
 
 // Re-export the field_mapping module
 pub mod field_mapping;
+pub mod years;
+
+pub use years::{YearDerivation, load_ind_years};
 
 /// Income registry with tax information
 #[derive(RegistryTrait, Debug)]
 #[registry(name = "IND", description = "Income registry")]
 pub struct IndRegistry {
     /// Person ID (CPR number)
-    #[field(name = "PNR")]
+    #[field(name = "PNR", pnr = true)]
     pub pnr: String,
 
     /// Annual income
@@ -45,29 +48,3 @@ pub fn deserialize_batch(
     // Use the inner deserializer to deserialize the batch
     deserializer.inner.deserialize_batch(batch)
 }
-
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for IndRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "IND"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for IND
-        let fields = vec![
-            arrow::datatypes::Field::new("PNR", arrow::datatypes::DataType::Utf8, false),
-            arrow::datatypes::Field::new(
-                "PERINDKIALT_13",
-                arrow::datatypes::DataType::Float64,
-                true,
-            ),
-            arrow::datatypes::Field::new("LOENMV_13", arrow::datatypes::DataType::Float64, true),
-            arrow::datatypes::Field::new("VERSION", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("YEAR", arrow::datatypes::DataType::Int32, true),
-        ];
-
-        std::sync::Arc::new(arrow::datatypes::Schema::new(fields))
-    }
-}
\ No newline at end of file
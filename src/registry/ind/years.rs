@@ -0,0 +1,375 @@
+//! Year-filtered loading of IND
+//!
+//! IND is delivered as one file per year, each also carrying a `YEAR`
+//! column. Loading a few years should not read every file: files are pruned
+//! by the min/max statistics of their `YEAR` column or, failing that, by the
+//! year in their name (e.g. `2019.parquet`). The rows of the remaining
+//! files are then filtered on `YEAR`, which also covers files that could not
+//! be pruned.
+//!
+//! Some vintages lack the `YEAR` column. By default (see [`YearDerivation`])
+//! it is derived from the year in the file name, so temporal queries see the
+//! same column in every vintage.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::{BooleanArray, Int32Array};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::statistics::Statistics;
+use rayon::prelude::*;
+
+use crate::error::{ParquetReaderError, Result};
+use crate::models::core::individual::temporal::extract_time_period_from_filename;
+use crate::registry::RegisterLoader;
+use crate::utils::arrow::array_utils::{downcast_array, get_column};
+use crate::utils::io::parquet::{find_parquet_files, read_parquet};
+
+/// The column holding the income year
+pub const YEAR_COLUMN: &str = "YEAR";
+
+/// How the `YEAR` column of loaded files relates to their file names
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum YearDerivation {
+    /// Add `YEAR` from the file name to files without the column, and warn
+    /// about rows whose `YEAR` differs from the year in the file name
+    #[default]
+    FromFileName,
+    /// Leave the `YEAR` column as read
+    Skip,
+}
+
+/// Get the year in a file name
+///
+/// A name that is a time period (`2019.parquet`, `201903.parquet`, ...)
+/// gives its year; otherwise the first four-digit number of the stem
+/// between 1900 and 2100 is taken, e.g. 2019 of `ind2019.parquet`.
+#[must_use]
+pub fn year_from_file_name(path: &Path) -> Option<i32> {
+    if let Some(period) = extract_time_period_from_filename(path) {
+        return Some(period.year());
+    }
+
+    let stem = path.file_stem()?.to_str()?;
+    stem.split(|c: char| !c.is_ascii_digit())
+        .filter(|digits| digits.len() == 4)
+        .filter_map(|digits| digits.parse::<i32>().ok())
+        .find(|year| (1900..=2100).contains(year))
+}
+
+/// Check whether a file may contain rows of the given years
+///
+/// The statistics of the file's `YEAR` column decide if every row group has
+/// them: the file is ruled out only if they exclude all the years. Otherwise
+/// the year in the file name (see [`year_from_file_name`]) decides, and a
+/// file with neither may contain any year. The statistics come first so
+/// that a file whose rows disagree with its name is still read, and the
+/// conflict reported (see [`YearDerivation::FromFileName`]).
+///
+/// # Errors
+///
+/// Returns an error if the file metadata cannot be read
+pub fn file_may_contain_years(path: &Path, years: &BTreeSet<i32>) -> Result<bool> {
+    if let Some(ranges) = year_statistics(path)? {
+        return Ok(ranges.iter().any(|(min, max)| {
+            years
+                .iter()
+                .any(|year| (*min..=*max).contains(&i64::from(*year)))
+        }));
+    }
+
+    Ok(year_from_file_name(path).is_none_or(|year| years.contains(&year)))
+}
+
+/// Get the min/max statistics of each row group's `YEAR` column
+///
+/// # Returns
+///
+/// The ranges, or `None` if the file has no `YEAR` column or a row group
+/// lacks its statistics
+fn year_statistics(path: &Path) -> Result<Option<Vec<(i64, i64)>>> {
+    let file = File::open(path).map_err(|e| {
+        ParquetReaderError::IoError(format!("Failed to open {}: {e}", path.display()))
+    })?;
+    let reader = SerializedFileReader::new(file)?;
+    let metadata = reader.metadata();
+    let Some(column) = metadata
+        .file_metadata()
+        .schema_descr()
+        .columns()
+        .iter()
+        .position(|column| column.path().string() == YEAR_COLUMN)
+    else {
+        return Ok(None);
+    };
+
+    Ok(metadata
+        .row_groups()
+        .iter()
+        .map(|row_group| match row_group.column(column).statistics() {
+            Some(Statistics::Int32(stats)) => stats
+                .min_opt()
+                .zip(stats.max_opt())
+                .map(|(min, max)| (i64::from(*min), i64::from(*max))),
+            Some(Statistics::Int64(stats)) => {
+                stats.min_opt().copied().zip(stats.max_opt().copied())
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+/// Load the rows of an IND registry for the given years
+///
+/// Files that cannot contain the years (see [`file_may_contain_years`]) are
+/// skipped unread. The others are read in parallel with the loader's schema
+/// and PNR filter, the `YEAR` column is derived as `derivation` says, and
+/// rows are kept if their `YEAR` is one of the years. Batches still without
+/// a `YEAR` column, from files named by a requested year, are kept whole.
+///
+/// # Arguments
+///
+/// * `loader` - The IND loader, which provides the schema
+/// * `base_path` - An IND file, or a directory of IND files
+/// * `years` - The years to load
+/// * `pnr_filter` - Optional set of PNRs to keep
+/// * `derivation` - Whether to derive `YEAR` from the file names
+///
+/// # Returns
+///
+/// The record batches of the requested years
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read
+pub fn load_ind_years(
+    loader: &dyn RegisterLoader,
+    base_path: &Path,
+    years: &BTreeSet<i32>,
+    pnr_filter: Option<&HashSet<String>>,
+    derivation: YearDerivation,
+) -> Result<Vec<RecordBatch>> {
+    let files: Vec<PathBuf> = if base_path.is_dir() {
+        find_parquet_files(base_path)?
+    } else {
+        vec![base_path.to_path_buf()]
+    };
+    let files: Vec<PathBuf> = files
+        .into_iter()
+        .filter_map(|file| match file_may_contain_years(&file, years) {
+            Ok(true) => Some(Ok(file)),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<Result<_>>()?;
+    log::debug!(
+        "Reading {} {} files for years {years:?}",
+        files.len(),
+        loader.get_register_name()
+    );
+
+    let schema = loader.get_schema();
+    let batches: Vec<Vec<RecordBatch>> = files
+        .par_iter()
+        .map(|file| {
+            let batches = read_parquet(file, Some(schema.as_ref()), pnr_filter, None, None, None)?;
+            match derivation {
+                YearDerivation::FromFileName => derive_year(batches, file, &schema),
+                YearDerivation::Skip => Ok(batches),
+            }
+        })
+        .collect::<Result<_>>()?;
+
+    batches
+        .into_iter()
+        .flatten()
+        .filter_map(|batch| filter_years(&batch, years).transpose())
+        .collect()
+}
+
+/// Add the year of a file's name as its `YEAR` column, if it has none
+///
+/// The derived column is nullable, as read columns are, and placed where
+/// `schema` puts `YEAR` among the batch's columns, so batches match whether
+/// their file had the column or not. If the file has the column, rows whose
+/// `YEAR` differs from the year in the name are counted and reported in a
+/// warning instead.
+fn derive_year(
+    batches: Vec<RecordBatch>,
+    file: &Path,
+    schema: &Schema,
+) -> Result<Vec<RecordBatch>> {
+    let Some(year) = year_from_file_name(file) else {
+        return Ok(batches);
+    };
+
+    let mut conflicts = 0;
+    let batches = batches
+        .into_iter()
+        .map(
+            |batch| match get_column(&batch, YEAR_COLUMN, &DataType::Int32, false)? {
+                Some(array) => {
+                    let values = downcast_array::<Int32Array>(&array, YEAR_COLUMN, "Int32")?;
+                    conflicts += values
+                        .iter()
+                        .flatten()
+                        .filter(|value| *value != year)
+                        .count();
+                    Ok(batch)
+                }
+                None => {
+                    let mut fields: Vec<Field> = batch
+                        .schema()
+                        .fields()
+                        .iter()
+                        .map(|field| field.as_ref().clone())
+                        .collect();
+                    let position = schema.index_of(YEAR_COLUMN).map_or(fields.len(), |index| {
+                        fields
+                            .iter()
+                            .take_while(|field| {
+                                schema
+                                    .index_of(field.name())
+                                    .is_ok_and(|other| other < index)
+                            })
+                            .count()
+                    });
+                    fields.insert(position, Field::new(YEAR_COLUMN, DataType::Int32, true));
+                    let mut columns = batch.columns().to_vec();
+                    columns.insert(
+                        position,
+                        Arc::new(Int32Array::from(vec![year; batch.num_rows()])),
+                    );
+                    Ok(RecordBatch::try_new(
+                        Arc::new(Schema::new(fields)),
+                        columns,
+                    )?)
+                }
+            },
+        )
+        .collect::<Result<Vec<_>>>()?;
+
+    if conflicts > 0 {
+        log::warn!(
+            "{conflicts} rows of {} have a YEAR other than {year} of the file name",
+            file.display()
+        );
+    }
+    Ok(batches)
+}
+
+/// Keep the rows of a batch whose `YEAR` is one of the years
+///
+/// # Returns
+///
+/// The filtered batch, the batch itself if it has no `YEAR` column, or
+/// `None` if no rows remain
+fn filter_years(batch: &RecordBatch, years: &BTreeSet<i32>) -> Result<Option<RecordBatch>> {
+    let Some(array) = get_column(batch, YEAR_COLUMN, &DataType::Int32, false)? else {
+        return Ok(Some(batch.clone()));
+    };
+    let values = downcast_array::<Int32Array>(&array, YEAR_COLUMN, "Int32")?;
+    let mask: BooleanArray = values
+        .iter()
+        .map(|year| Some(year.is_some_and(|year| years.contains(&year))))
+        .collect();
+
+    let filtered = filter_record_batch(batch, &mask)?;
+    Ok((filtered.num_rows() > 0).then_some(filtered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::direct_registry_loader::DirectRegistryLoader;
+    use arrow::array::{ArrayRef, Float64Array, StringArray};
+    use parquet::arrow::ArrowWriter;
+
+    /// Write an IND file of one row per year, or of two rows without `YEAR`
+    fn write_ind(path: &Path, years: Option<Vec<i32>>) {
+        let rows = years.as_ref().map_or(2, Vec::len);
+        let mut columns = vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["0101801234"; rows])) as ArrayRef,
+                false,
+            ),
+            (
+                "PERINDKIALT_13",
+                Arc::new(Float64Array::from(vec![1.0; rows])) as ArrayRef,
+                true,
+            ),
+        ];
+        if let Some(years) = years {
+            columns.push((
+                YEAR_COLUMN,
+                Arc::new(Int32Array::from(years)) as ArrayRef,
+                true,
+            ));
+        }
+        let batch = RecordBatch::try_from_iter_with_nullable(columns).unwrap();
+        let mut writer =
+            ArrowWriter::try_new(File::create(path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_load_ind_years() {
+        let dir = std::env::temp_dir().join(format!("par_reader_ind_years_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_ind(&dir.join("2018.parquet"), Some(vec![2018, 2018]));
+        write_ind(&dir.join("2019.parquet"), Some(vec![2019]));
+        // Named without a year: pruned by statistics, or filtered by rows
+        write_ind(&dir.join("ind_old.parquet"), Some(vec![2010, 2011]));
+        write_ind(&dir.join("ind_mixed.parquet"), Some(vec![2019, 2020]));
+
+        let years = BTreeSet::from([2019]);
+        assert!(!file_may_contain_years(&dir.join("2018.parquet"), &years).unwrap());
+        assert!(!file_may_contain_years(&dir.join("ind_old.parquet"), &years).unwrap());
+        assert!(file_may_contain_years(&dir.join("ind_mixed.parquet"), &years).unwrap());
+        // The statistics of YEAR outrank a conflicting file name
+        write_ind(&dir.join("misnamed_2017.parquet"), Some(vec![2019]));
+        assert!(file_may_contain_years(&dir.join("misnamed_2017.parquet"), &years).unwrap());
+        std::fs::remove_file(dir.join("misnamed_2017.parquet")).unwrap();
+
+        let loader = DirectRegistryLoader::new("IND");
+        let batches = load_ind_years(&loader, &dir, &years, None, YearDerivation::Skip).unwrap();
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 2);
+        for batch in &batches {
+            let array = get_column(batch, YEAR_COLUMN, &DataType::Int32, true)
+                .unwrap()
+                .unwrap();
+            let values = downcast_array::<Int32Array>(&array, YEAR_COLUMN, "Int32").unwrap();
+            assert!(values.iter().all(|year| year == Some(2019)));
+        }
+
+        // A vintage without YEAR gets it from the file name, unless skipped
+        write_ind(&dir.join("ind2021.parquet"), None);
+        let years = BTreeSet::from([2021]);
+        let derived =
+            load_ind_years(&loader, &dir, &years, None, YearDerivation::FromFileName).unwrap();
+        assert_eq!(derived.len(), 1);
+        assert_eq!(derived[0].num_rows(), 2);
+        // The derived YEAR matches the one read from files that have it
+        let read = load_ind_years(
+            &loader,
+            &dir.join("2019.parquet"),
+            &BTreeSet::from([2019]),
+            None,
+            YearDerivation::FromFileName,
+        )
+        .unwrap();
+        assert_eq!(derived[0].schema(), read[0].schema());
+        let skipped = load_ind_years(&loader, &dir, &years, None, YearDerivation::Skip).unwrap();
+        assert!(skipped[0].column_by_name(YEAR_COLUMN).is_none());
+        assert_eq!(year_from_file_name(Path::new("ind_old.parquet")), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -20,6 +20,22 @@ pub fn create_field_mappings() -> Vec<FieldMapping> {
                 individual.pnr = value;
             }),
         ),
+        // CPR check
+        FieldMapping::new(
+            FieldDefinition::new("CPRTJEK", "cpr_check", FieldType::String, true),
+            Extractors::string("CPRTJEK"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("cpr_check", Box::new(value));
+            }),
+        ),
+        // CPR type
+        FieldMapping::new(
+            FieldDefinition::new("CPRTYPE", "cpr_type", FieldType::String, true),
+            Extractors::string("CPRTYPE"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("cpr_type", Box::new(value));
+            }),
+        ),
         // Action diagnosis
         FieldMapping::new(
             FieldDefinition::new("C_ADIAG", "action_diagnosis", FieldType::String, true),
@@ -47,6 +63,54 @@ pub fn create_field_mappings() -> Vec<FieldMapping> {
                 individual.store_property("department_code", Box::new(code.to_string()));
             }),
         ),
+        // Department key
+        FieldMapping::new(
+            FieldDefinition::new("K_AFD", "department_key", FieldType::String, true),
+            Extractors::string("K_AFD"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("department_key", Box::new(value));
+            }),
+        ),
+        // Hospital code
+        FieldMapping::new(
+            FieldDefinition::new("C_SGH", "hospital_code", FieldType::String, true),
+            Extractors::string("C_SGH"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("hospital_code", Box::new(value));
+            }),
+        ),
+        // Specialty code
+        FieldMapping::new(
+            FieldDefinition::new("C_SPEC", "specialty_code", FieldType::String, true),
+            Extractors::string("C_SPEC"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("specialty_code", Box::new(value));
+            }),
+        ),
+        // Patient type
+        FieldMapping::new(
+            FieldDefinition::new("C_PATTYPE", "patient_type", FieldType::String, true),
+            Extractors::string("C_PATTYPE"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("patient_type", Box::new(value));
+            }),
+        ),
+        // Admission method
+        FieldMapping::new(
+            FieldDefinition::new("C_INDM", "admission_method", FieldType::String, true),
+            Extractors::string("C_INDM"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("admission_method", Box::new(value));
+            }),
+        ),
+        // Contact reason
+        FieldMapping::new(
+            FieldDefinition::new("C_KONTAARS", "contact_reason", FieldType::String, true),
+            Extractors::string("C_KONTAARS"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("contact_reason", Box::new(value));
+            }),
+        ),
         // Municipality code
         FieldMapping::new(
             FieldDefinition::new("C_KOM", "municipality_code", FieldType::String, true),
@@ -74,6 +138,22 @@ pub fn create_field_mappings() -> Vec<FieldMapping> {
                 }
             }),
         ),
+        // Admission hour
+        FieldMapping::new(
+            FieldDefinition::new("V_INDTIME", "admission_hour", FieldType::Integer, true),
+            Extractors::integer("V_INDTIME"),
+            ModelSetters::i32_setter(|individual, value| {
+                individual.store_property("admission_hour", Box::new(value));
+            }),
+        ),
+        // Admission minute
+        FieldMapping::new(
+            FieldDefinition::new("V_INDMINUT", "admission_minute", FieldType::Integer, true),
+            Extractors::integer("V_INDMINUT"),
+            ModelSetters::i32_setter(|individual, value| {
+                individual.store_property("admission_minute", Box::new(value));
+            }),
+        ),
         // Discharge date
         FieldMapping::new(
             FieldDefinition::new("D_UDDTO", "discharge_date", FieldType::Date, true),
@@ -90,6 +170,54 @@ pub fn create_field_mappings() -> Vec<FieldMapping> {
                 }
             }),
         ),
+        // Discharge hour
+        FieldMapping::new(
+            FieldDefinition::new("V_UDTIME", "discharge_hour", FieldType::Integer, true),
+            Extractors::integer("V_UDTIME"),
+            ModelSetters::i32_setter(|individual, value| {
+                individual.store_property("discharge_hour", Box::new(value));
+            }),
+        ),
+        // Discharge method
+        FieldMapping::new(
+            FieldDefinition::new("C_UDM", "discharge_method", FieldType::String, true),
+            Extractors::string("C_UDM"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("discharge_method", Box::new(value));
+            }),
+        ),
+        // Referral date
+        FieldMapping::new(
+            FieldDefinition::new("D_HENDTO", "referral_date", FieldType::Date, true),
+            Extractors::date("D_HENDTO"),
+            ModelSetters::date_setter(|individual, value| {
+                individual.store_property("referral_date", Box::new(value));
+            }),
+        ),
+        // Referral method
+        FieldMapping::new(
+            FieldDefinition::new("C_HENM", "referral_method", FieldType::String, true),
+            Extractors::string("C_HENM"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("referral_method", Box::new(value));
+            }),
+        ),
+        // Referring hospital
+        FieldMapping::new(
+            FieldDefinition::new("C_HSGH", "referring_hospital", FieldType::String, true),
+            Extractors::string("C_HSGH"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("referring_hospital", Box::new(value));
+            }),
+        ),
+        // Referring department
+        FieldMapping::new(
+            FieldDefinition::new("C_HAFD", "referring_department", FieldType::String, true),
+            Extractors::string("C_HAFD"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("referring_department", Box::new(value));
+            }),
+        ),
         // Age
         FieldMapping::new(
             FieldDefinition::new("V_ALDER", "age", FieldType::Integer, true),
@@ -98,6 +226,14 @@ pub fn create_field_mappings() -> Vec<FieldMapping> {
                 individual.age = Some(value);
             }),
         ),
+        // Age in days
+        FieldMapping::new(
+            FieldDefinition::new("V_ALDDG", "age_in_days", FieldType::Integer, true),
+            Extractors::integer("V_ALDDG"),
+            ModelSetters::i32_setter(|individual, value| {
+                individual.store_property("age_in_days", Box::new(value));
+            }),
+        ),
         // Length of stay
         FieldMapping::new(
             FieldDefinition::new("V_SENGDAGE", "length_of_stay", FieldType::Integer, true),
@@ -123,5 +259,13 @@ pub fn create_field_mappings() -> Vec<FieldMapping> {
                 individual.store_property("lpr_record_number", Box::new(record_num.to_string()));
             }),
         ),
+        // Version
+        FieldMapping::new(
+            FieldDefinition::new("VERSION", "version", FieldType::String, true),
+            Extractors::string("VERSION"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("version", Box::new(value));
+            }),
+        ),
     ]
 }
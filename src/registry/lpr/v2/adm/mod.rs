@@ -3,7 +3,6 @@
 //! The `LPR_ADM` registry contains administrative records from the Danish National Patient Registry.
 
 use crate::RegistryTrait;
-use arrow::datatypes::{DataType, Field, Schema};
 use chrono::NaiveDate;
 
 // Re-export the field_mapping module
@@ -11,12 +10,22 @@ pub mod field_mapping;
 
 // Define LPR ADM Registry using the derive macro
 #[derive(RegistryTrait, Debug)]
-#[registry(name = "LPR_ADM", description = "LPR Administrative registry")]
+#[registry(
+    name = "LPR_ADM",
+    description = "LPR Administrative registry",
+    loader_name = "lpr_adm"
+)]
 pub struct LprAdmRegistry {
     // Core identification fields
-    #[field(name = "PNR")]
+    #[field(name = "PNR", pnr = true)]
     pub pnr: String,
 
+    #[field(name = "CPRTJEK")]
+    pub cpr_check: Option<String>,
+
+    #[field(name = "CPRTYPE")]
+    pub cpr_type: Option<String>,
+
     // Admission-related fields
     #[field(name = "C_ADIAG")]
     pub action_diagnosis: Option<String>,
@@ -24,23 +33,72 @@ pub struct LprAdmRegistry {
     #[field(name = "C_AFD")]
     pub department_code: Option<String>,
 
+    #[field(name = "K_AFD")]
+    pub department_key: Option<String>,
+
+    #[field(name = "C_SGH")]
+    pub hospital_code: Option<String>,
+
+    #[field(name = "C_SPEC")]
+    pub specialty_code: Option<String>,
+
+    #[field(name = "C_PATTYPE")]
+    pub patient_type: Option<String>,
+
+    #[field(name = "C_INDM")]
+    pub admission_method: Option<String>,
+
+    #[field(name = "C_KONTAARS")]
+    pub contact_reason: Option<String>,
+
     #[field(name = "C_KOM")]
     pub municipality_code: Option<String>,
 
     #[field(name = "D_INDDTO")]
     pub admission_date: Option<NaiveDate>,
 
+    #[field(name = "V_INDTIME")]
+    pub admission_hour: Option<i32>,
+
+    #[field(name = "V_INDMINUT")]
+    pub admission_minute: Option<i32>,
+
     #[field(name = "D_UDDTO")]
     pub discharge_date: Option<NaiveDate>,
 
+    #[field(name = "V_UDTIME")]
+    pub discharge_hour: Option<i32>,
+
+    #[field(name = "C_UDM")]
+    pub discharge_method: Option<String>,
+
+    // Referral fields
+    #[field(name = "D_HENDTO")]
+    pub referral_date: Option<NaiveDate>,
+
+    #[field(name = "C_HENM")]
+    pub referral_method: Option<String>,
+
+    #[field(name = "C_HSGH")]
+    pub referring_hospital: Option<String>,
+
+    #[field(name = "C_HAFD")]
+    pub referring_department: Option<String>,
+
     #[field(name = "V_ALDER")]
     pub age: Option<i32>,
 
+    #[field(name = "V_ALDDG")]
+    pub age_in_days: Option<i32>,
+
     #[field(name = "V_SENGDAGE")]
     pub length_of_stay: Option<i32>,
 
     #[field(name = "RECNUM")]
     pub record_number: Option<String>,
+
+    #[field(name = "VERSION")]
+    pub version: Option<String>,
 }
 
 /// Helper function to create a new LPR admission deserializer
@@ -57,46 +115,28 @@ pub fn deserialize_batch(
     deserializer.inner.deserialize_batch(batch)
 }
 
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for LprAdmRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "lpr_adm"
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::RegisterLoader;
+
+    #[test]
+    fn test_schema_columns_have_field_mappings() {
+        let deserializer = create_deserializer();
+        let mapped: Vec<&str> = deserializer
+            .field_map()
+            .iter()
+            .map(|(source, _, _)| source.as_str())
+            .collect();
+
+        let schema = deserializer.get_schema();
+        assert_eq!(schema.fields().len(), 27);
+        for field in schema.fields() {
+            assert!(
+                mapped.contains(&field.name().as_str()),
+                "column {} has no field mapping",
+                field.name()
+            );
+        }
     }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for LPR_ADM
-        let fields = vec![
-            Field::new("PNR", DataType::Utf8, false),
-            Field::new("C_ADIAG", DataType::Utf8, true),
-            Field::new("C_AFD", DataType::Utf8, true),
-            Field::new("C_HAFD", DataType::Utf8, true),
-            Field::new("C_HENM", DataType::Utf8, true),
-            Field::new("C_HSGH", DataType::Utf8, true),
-            Field::new("C_INDM", DataType::Utf8, true),
-            Field::new("C_KOM", DataType::Utf8, true),
-            Field::new("C_KONTAARS", DataType::Utf8, true),
-            Field::new("C_PATTYPE", DataType::Utf8, true),
-            Field::new("C_SGH", DataType::Utf8, true),
-            Field::new("C_SPEC", DataType::Utf8, true),
-            Field::new("C_UDM", DataType::Utf8, true),
-            Field::new("CPRTJEK", DataType::Utf8, true),
-            Field::new("CPRTYPE", DataType::Utf8, true),
-            Field::new("D_HENDTO", DataType::Date32, true),
-            Field::new("D_INDDTO", DataType::Date32, true),
-            Field::new("D_UDDTO", DataType::Date32, true),
-            Field::new("K_AFD", DataType::Utf8, true),
-            Field::new("RECNUM", DataType::Utf8, true),
-            Field::new("V_ALDDG", DataType::Int32, true),
-            Field::new("V_ALDER", DataType::Int32, true),
-            Field::new("V_INDMINUT", DataType::Int32, true),
-            Field::new("V_INDTIME", DataType::Int32, true),
-            Field::new("V_SENGDAGE", DataType::Int32, true),
-            Field::new("V_UDTIME", DataType::Int32, true),
-            Field::new("VERSION", DataType::Utf8, true),
-        ];
-
-        std::sync::Arc::new(Schema::new(fields))
-    }
-}
\ No newline at end of file
+}
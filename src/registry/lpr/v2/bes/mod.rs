@@ -3,7 +3,6 @@
 //! The `LPR_BES` registry contains outpatient visit records from the Danish National Patient Registry.
 
 use crate::RegistryTrait;
-use arrow::datatypes::{DataType, Field, Schema};
 use chrono::NaiveDate;
 
 // Re-export the field_mapping module
@@ -14,7 +13,8 @@ pub mod field_mapping;
 #[registry(
     name = "LPR_BES",
     description = "LPR Outpatient Visits (besøg)",
-    id_field = "RECNUM"
+    id_field = "RECNUM",
+    loader_name = "lpr_bes"
 )]
 pub struct LprBesRegistry {
     // Core identification fields
@@ -45,24 +45,3 @@ pub fn deserialize_batch(
     // Use the inner deserializer to deserialize the batch
     deserializer.inner.deserialize_batch(batch)
 }
-
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for LprBesRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "lpr_bes"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for LPR_BES
-        let fields = vec![
-            Field::new("RECNUM", DataType::Utf8, false),
-            Field::new("D_AMBDTO", DataType::Date32, true),
-            Field::new("LEVERANCEDATO", DataType::Date32, true),
-            Field::new("VERSION", DataType::Utf8, true),
-        ];
-
-        std::sync::Arc::new(Schema::new(fields))
-    }
-}
\ No newline at end of file
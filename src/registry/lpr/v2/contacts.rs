@@ -0,0 +1,493 @@
+//! Contact-level view of LPR2 data
+//!
+//! LPR2 splits a single hospital contact across `LPR_ADM` (the admission),
+//! `LPR_DIAG` (its diagnoses) and `LPR_BES` (its outpatient visits), linked by
+//! `RECNUM`. This module joins the three into one [`LprContact`] per admission.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use arrow::array::{Array, ArrayRef, Int32Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use chrono::NaiveDate;
+
+use crate::error::{ParquetReaderError, Result};
+use crate::utils::arrow::array_utils::get_column;
+use crate::utils::arrow::conversion::arrow_array_to_date;
+use crate::utils::io::find_lpr_files;
+use crate::utils::io::parquet::{load_parquet_files_parallel, read_parquet};
+
+/// A single LPR2 hospital contact joined across `LPR_ADM`, `LPR_DIAG` and `LPR_BES`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LprContact {
+    /// Person identifier
+    pub pnr: String,
+    /// Record number linking the three tables (`RECNUM`)
+    pub record_number: String,
+    /// Admission date (`D_INDDTO`)
+    pub admission_date: Option<NaiveDate>,
+    /// Discharge date (`D_UDDTO`)
+    pub discharge_date: Option<NaiveDate>,
+    /// Action diagnosis (`C_ADIAG`)
+    pub action_diagnosis: Option<String>,
+    /// Registered bed days (`V_SENGDAGE`)
+    pub bed_days: Option<i32>,
+    /// Secondary diagnoses from `LPR_DIAG`, in file order
+    pub secondary_diagnoses: Vec<String>,
+    /// Outpatient visit dates from `LPR_BES` (`D_AMBDTO`), in file order
+    pub outpatient_dates: Vec<NaiveDate>,
+}
+
+impl LprContact {
+    /// Length of stay in days
+    ///
+    /// Computed as discharge minus admission date, so a same-day contact or
+    /// transfer has a length of zero. When either date is missing the
+    /// registered bed days (`V_SENGDAGE`) are used instead.
+    #[must_use]
+    pub fn length_of_stay_days(&self) -> Option<i64> {
+        match (self.admission_date, self.discharge_date) {
+            (Some(admission), Some(discharge)) => Some((discharge - admission).num_days()),
+            _ => self.bed_days.map(i64::from),
+        }
+    }
+
+    /// Date the contact ended, derived from the length of stay if needed
+    fn end_date(&self) -> Option<NaiveDate> {
+        self.discharge_date.or_else(|| {
+            let admission = self.admission_date?;
+            admission.checked_add_signed(chrono::Duration::days(self.length_of_stay_days()?))
+        })
+    }
+}
+
+/// Flag contacts that are readmissions within `within_days` of a prior discharge
+///
+/// Contacts are compared per PNR in admission order against the latest
+/// discharge seen so far for that person. A contact counts as a readmission
+/// when it starts between 1 and `within_days` days after that discharge.
+/// Contacts starting on or before the prior discharge date - same-day
+/// transfers and overlapping contacts - are treated as a continuation of the
+/// earlier stay rather than a readmission, but still extend the stay if they
+/// end later. Contacts without an admission date are never flagged.
+///
+/// # Arguments
+/// * `contacts` - The contacts to inspect, in any order
+/// * `within_days` - Maximum gap in days between discharge and readmission
+///
+/// # Returns
+/// One flag per input contact, in input order
+#[must_use]
+pub fn compute_readmissions(contacts: &[LprContact], within_days: i64) -> Vec<bool> {
+    let mut by_pnr: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, contact) in contacts.iter().enumerate() {
+        if contact.admission_date.is_some() {
+            by_pnr.entry(contact.pnr.as_str()).or_default().push(idx);
+        }
+    }
+
+    let mut flags = vec![false; contacts.len()];
+    for indices in by_pnr.values_mut() {
+        indices.sort_by_key(|&idx| (contacts[idx].admission_date, contacts[idx].end_date()));
+
+        let mut last_discharge: Option<NaiveDate> = None;
+        for &idx in indices.iter() {
+            let contact = &contacts[idx];
+            let Some(admission) = contact.admission_date else {
+                continue;
+            };
+
+            if let Some(discharge) = last_discharge {
+                let gap = (admission - discharge).num_days();
+                flags[idx] = gap > 0 && gap <= within_days;
+            }
+
+            let end = contact.end_date().unwrap_or(admission);
+            if last_discharge.is_none_or(|discharge| end > discharge) {
+                last_discharge = Some(end);
+            }
+        }
+    }
+
+    flags
+}
+
+/// Load LPR2 contacts by joining `LPR_ADM`, `LPR_DIAG` and `LPR_BES` on `RECNUM`
+///
+/// The three tables are located under `base_path` using the standard LPR
+/// directory discovery. The PNR filter is applied to `LPR_ADM`; only
+/// diagnoses and visits whose `RECNUM` belongs to a retained admission are
+/// attached. Missing `LPR_DIAG` or `LPR_BES` tables yield contacts without
+/// secondary diagnoses or visit dates.
+///
+/// # Arguments
+/// * `base_path` - Directory containing the LPR2 tables
+/// * `pnr_filter` - Optional set of PNRs to restrict the admissions to
+///
+/// # Returns
+/// One contact per admission row with a PNR and `RECNUM`, in `LPR_ADM` order
+///
+/// # Errors
+/// Returns an error if no `LPR_ADM` table is found or a table cannot be read
+pub fn load_contacts(
+    base_path: &Path,
+    pnr_filter: Option<&HashSet<String>>,
+) -> Result<Vec<LprContact>> {
+    let paths = find_lpr_files(base_path)?;
+    let adm_path = paths.lpr_adm.ok_or_else(|| {
+        ParquetReaderError::ValidationError(format!(
+            "No LPR_ADM files found in directory: {}",
+            base_path.display()
+        ))
+    })?;
+
+    let adm_schema = Schema::new(vec![
+        Field::new("PNR", DataType::Utf8, false),
+        Field::new("RECNUM", DataType::Utf8, true),
+        Field::new("C_ADIAG", DataType::Utf8, true),
+        Field::new("D_INDDTO", DataType::Date32, true),
+        Field::new("D_UDDTO", DataType::Date32, true),
+        Field::new("V_SENGDAGE", DataType::Int32, true),
+    ]);
+
+    let mut contacts = Vec::new();
+    for batch in load_table(&adm_path, &adm_schema, pnr_filter)? {
+        contacts.extend(contacts_from_adm_batch(&batch)?);
+    }
+
+    let recnums: HashSet<&str> = contacts.iter().map(|c| c.record_number.as_str()).collect();
+
+    let diagnoses = match &paths.lpr_diag {
+        Some(path) => diagnoses_by_recnum(path, &recnums)?,
+        None => {
+            log::warn!("No LPR_DIAG files found; contacts will have no secondary diagnoses");
+            HashMap::new()
+        }
+    };
+    let visits = match &paths.lpr_bes {
+        Some(path) => visits_by_recnum(path, &recnums)?,
+        None => {
+            log::warn!("No LPR_BES files found; contacts will have no outpatient visits");
+            HashMap::new()
+        }
+    };
+
+    for contact in &mut contacts {
+        if let Some(codes) = diagnoses.get(&contact.record_number) {
+            contact.secondary_diagnoses = codes
+                .iter()
+                .filter(|code| contact.action_diagnosis.as_ref() != Some(*code))
+                .cloned()
+                .collect();
+        }
+        if let Some(dates) = visits.get(&contact.record_number) {
+            contact.outpatient_dates.clone_from(dates);
+        }
+    }
+
+    Ok(contacts)
+}
+
+/// Load a single LPR table from a directory or a single file
+fn load_table(
+    path: &Path,
+    schema: &Schema,
+    pnr_filter: Option<&HashSet<String>>,
+) -> Result<Vec<RecordBatch>> {
+    if path.is_dir() {
+        load_parquet_files_parallel(path, Some(schema), pnr_filter, None, None, None)
+    } else {
+        read_parquet(path, Some(schema), pnr_filter, None, None, None)
+    }
+}
+
+/// Get a column as a `StringArray`, or `None` if it is absent and not required
+fn string_column(batch: &RecordBatch, name: &str, required: bool) -> Result<Option<ArrayRef>> {
+    let column = get_column(batch, name, &DataType::Utf8, required)?;
+    if let Some(col) = &column
+        && col.as_any().downcast_ref::<StringArray>().is_none()
+    {
+        return Err(ParquetReaderError::InvalidDataType {
+            column: name.to_string(),
+            expected: "StringArray".to_string(),
+        }
+        .into());
+    }
+    Ok(column)
+}
+
+/// Value of a string column at a row, treating nulls and blanks as absent
+fn string_value(column: Option<&ArrayRef>, row: usize) -> Option<String> {
+    let array = column?.as_any().downcast_ref::<StringArray>()?;
+    if array.is_null(row) {
+        return None;
+    }
+    let value = array.value(row).trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Build contacts from one `LPR_ADM` batch
+fn contacts_from_adm_batch(batch: &RecordBatch) -> Result<Vec<LprContact>> {
+    let pnr_col = string_column(batch, "PNR", true)?;
+    let recnum_col = string_column(batch, "RECNUM", true)?;
+    let adiag_col = string_column(batch, "C_ADIAG", false)?;
+    let admission_col = get_column(batch, "D_INDDTO", &DataType::Date32, false)?;
+    let discharge_col = get_column(batch, "D_UDDTO", &DataType::Date32, false)?;
+    let bed_days_col = get_column(batch, "V_SENGDAGE", &DataType::Int32, false)?;
+    let bed_days = bed_days_col
+        .as_ref()
+        .and_then(|col| col.as_any().downcast_ref::<Int32Array>());
+
+    let mut contacts = Vec::with_capacity(batch.num_rows());
+    for row in 0..batch.num_rows() {
+        let (Some(pnr), Some(record_number)) = (
+            string_value(pnr_col.as_ref(), row),
+            string_value(recnum_col.as_ref(), row),
+        ) else {
+            continue;
+        };
+
+        contacts.push(LprContact {
+            pnr,
+            record_number,
+            admission_date: admission_col
+                .as_ref()
+                .and_then(|col| arrow_array_to_date(col, row)),
+            discharge_date: discharge_col
+                .as_ref()
+                .and_then(|col| arrow_array_to_date(col, row)),
+            action_diagnosis: string_value(adiag_col.as_ref(), row),
+            bed_days: bed_days.and_then(|arr| (!arr.is_null(row)).then(|| arr.value(row))),
+            secondary_diagnoses: Vec::new(),
+            outpatient_dates: Vec::new(),
+        });
+    }
+
+    Ok(contacts)
+}
+
+/// Collect `LPR_DIAG` diagnosis codes for the given record numbers
+fn diagnoses_by_recnum(
+    path: &Path,
+    recnums: &HashSet<&str>,
+) -> Result<HashMap<String, Vec<String>>> {
+    let schema = Schema::new(vec![
+        Field::new("RECNUM", DataType::Utf8, false),
+        Field::new("C_DIAG", DataType::Utf8, true),
+    ]);
+
+    let mut diagnoses: HashMap<String, Vec<String>> = HashMap::new();
+    for batch in load_table(path, &schema, None)? {
+        let recnum_col = string_column(&batch, "RECNUM", true)?;
+        let diag_col = string_column(&batch, "C_DIAG", true)?;
+
+        for row in 0..batch.num_rows() {
+            let Some(recnum) = string_value(recnum_col.as_ref(), row) else {
+                continue;
+            };
+            if !recnums.contains(recnum.as_str()) {
+                continue;
+            }
+            if let Some(code) = string_value(diag_col.as_ref(), row) {
+                diagnoses.entry(recnum).or_default().push(code);
+            }
+        }
+    }
+
+    Ok(diagnoses)
+}
+
+/// Collect `LPR_BES` outpatient visit dates for the given record numbers
+fn visits_by_recnum(
+    path: &Path,
+    recnums: &HashSet<&str>,
+) -> Result<HashMap<String, Vec<NaiveDate>>> {
+    let schema = Schema::new(vec![
+        Field::new("RECNUM", DataType::Utf8, false),
+        Field::new("D_AMBDTO", DataType::Date32, true),
+    ]);
+
+    let mut visits: HashMap<String, Vec<NaiveDate>> = HashMap::new();
+    for batch in load_table(path, &schema, None)? {
+        let recnum_col = string_column(&batch, "RECNUM", true)?;
+        let date_col = get_column(&batch, "D_AMBDTO", &DataType::Date32, true)?;
+
+        for row in 0..batch.num_rows() {
+            let Some(recnum) = string_value(recnum_col.as_ref(), row) else {
+                continue;
+            };
+            if !recnums.contains(recnum.as_str()) {
+                continue;
+            }
+            if let Some(date) = date_col
+                .as_ref()
+                .and_then(|col| arrow_array_to_date(col, row))
+            {
+                visits.entry(recnum).or_default().push(date);
+            }
+        }
+    }
+
+    Ok(visits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Date32Array;
+    use parquet::arrow::ArrowWriter;
+    use std::fs::File;
+    use std::sync::Arc;
+
+    fn write_batch(path: &Path, columns: Vec<(&str, ArrayRef)>) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        let mut writer =
+            ArrowWriter::try_new(File::create(path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    fn days(date: &str) -> i32 {
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        i32::try_from((date - epoch).num_days()).unwrap()
+    }
+
+    #[test]
+    fn test_load_contacts_joins_on_recnum() {
+        let dir = std::env::temp_dir().join(format!("par_reader_lpr2_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        write_batch(
+            &dir.join("lpr_adm/lpr_adm2010.parquet"),
+            vec![
+                (
+                    "PNR",
+                    Arc::new(StringArray::from(vec!["A", "B", "A"])) as ArrayRef,
+                ),
+                ("RECNUM", Arc::new(StringArray::from(vec!["1", "2", "3"]))),
+                (
+                    "C_ADIAG",
+                    Arc::new(StringArray::from(vec!["DI21", "DJ18", "DE10"])),
+                ),
+                (
+                    "D_INDDTO",
+                    Arc::new(Date32Array::from(vec![
+                        days("2010-01-01"),
+                        days("2010-02-01"),
+                        days("2010-03-01"),
+                    ])),
+                ),
+            ],
+        );
+        write_batch(
+            &dir.join("lpr_diag/lpr_diag2010.parquet"),
+            vec![
+                (
+                    "RECNUM",
+                    Arc::new(StringArray::from(vec!["1", "1", "2", "3"])) as ArrayRef,
+                ),
+                (
+                    "C_DIAG",
+                    Arc::new(StringArray::from(vec!["DI21", "DI10", "DJ44", "DE11"])),
+                ),
+            ],
+        );
+        write_batch(
+            &dir.join("lpr_bes/lpr_bes2010.parquet"),
+            vec![
+                (
+                    "RECNUM",
+                    Arc::new(StringArray::from(vec!["3", "3"])) as ArrayRef,
+                ),
+                (
+                    "D_AMBDTO",
+                    Arc::new(Date32Array::from(vec![
+                        days("2010-03-05"),
+                        days("2010-03-09"),
+                    ])),
+                ),
+            ],
+        );
+
+        let filter: HashSet<String> = std::iter::once("A".to_string()).collect();
+        let contacts = load_contacts(&dir, Some(&filter)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(contacts.len(), 2);
+        assert!(contacts.iter().all(|c| c.pnr == "A"));
+
+        let first = &contacts[0];
+        assert_eq!(first.record_number, "1");
+        assert_eq!(first.action_diagnosis.as_deref(), Some("DI21"));
+        assert_eq!(first.secondary_diagnoses, vec!["DI10".to_string()]);
+        assert!(first.outpatient_dates.is_empty());
+
+        let second = &contacts[1];
+        assert_eq!(second.record_number, "3");
+        assert_eq!(second.secondary_diagnoses, vec!["DE11".to_string()]);
+        assert_eq!(
+            second.outpatient_dates,
+            vec![
+                NaiveDate::from_ymd_opt(2010, 3, 5).unwrap(),
+                NaiveDate::from_ymd_opt(2010, 3, 9).unwrap()
+            ]
+        );
+    }
+
+    fn contact(pnr: &str, admission: &str, discharge: Option<&str>) -> LprContact {
+        let parse = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+        LprContact {
+            pnr: pnr.to_string(),
+            record_number: format!("{pnr}-{admission}"),
+            admission_date: Some(parse(admission)),
+            discharge_date: discharge.map(parse),
+            action_diagnosis: None,
+            bed_days: None,
+            secondary_diagnoses: Vec::new(),
+            outpatient_dates: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_length_of_stay_days() {
+        assert_eq!(
+            contact("A", "2010-01-01", Some("2010-01-05")).length_of_stay_days(),
+            Some(4)
+        );
+        assert_eq!(
+            contact("A", "2010-01-01", Some("2010-01-01")).length_of_stay_days(),
+            Some(0)
+        );
+
+        let mut missing = contact("A", "2010-01-01", None);
+        assert_eq!(missing.length_of_stay_days(), None);
+        missing.bed_days = Some(3);
+        assert_eq!(missing.length_of_stay_days(), Some(3));
+    }
+
+    #[test]
+    fn test_compute_readmissions() {
+        let contacts = vec![
+            contact("A", "2010-01-20", Some("2010-01-25")),
+            contact("A", "2010-01-01", Some("2010-01-10")),
+            // Same-day transfer from the first stay
+            contact("A", "2010-01-10", Some("2010-01-12")),
+            // Overlapping contact inside the first stay
+            contact("A", "2010-01-05", Some("2010-01-06")),
+            contact("A", "2010-03-01", Some("2010-03-01")),
+            contact("B", "2010-01-15", None),
+        ];
+
+        assert_eq!(
+            compute_readmissions(&contacts, 30),
+            vec![true, false, false, false, false, false]
+        );
+        assert_eq!(
+            compute_readmissions(&contacts, 7),
+            vec![false, false, false, false, false, false]
+        );
+    }
+}
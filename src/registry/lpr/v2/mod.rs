@@ -1,3 +1,6 @@
 pub mod adm;
 pub mod bes;
+pub mod contacts;
 pub mod diag;
+
+pub use contacts::{LprContact, compute_readmissions, load_contacts};
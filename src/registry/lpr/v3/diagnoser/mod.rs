@@ -3,8 +3,6 @@
 //! The `LPR3_DIAGNOSER` registry contains diagnosis records from the Danish National Patient Registry version 3.
 
 use crate::RegistryTrait;
-use arrow::datatypes::{DataType, Field, Schema};
-use std::sync::Arc;
 
 // Re-export the field_mapping module
 pub mod field_mapping;
@@ -14,7 +12,9 @@ pub mod field_mapping;
 #[registry(
     name = "LPR3_DIAGNOSER",
     description = "LPR v3 Diagnosis Records",
-    id_field = "DW_EK_KONTAKT"
+    id_field = "DW_EK_KONTAKT",
+    loader_name = "lpr3_diagnoser",
+    join_column = "DW_EK_KONTAKT"
 )]
 pub struct Lpr3DiagnoserRegistry {
     // Core identification fields
@@ -55,37 +55,3 @@ pub fn deserialize_batch(
     // Use the inner deserializer to deserialize the batch
     deserializer.inner.deserialize_batch(batch)
 }
-
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for Lpr3DiagnoserRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "lpr3_diagnoser"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for LPR3_DIAGNOSER
-        let fields = vec![
-            Field::new("DW_EK_KONTAKT", DataType::Utf8, false),
-            Field::new("diagnosekode", DataType::Utf8, true),
-            Field::new("diagnosetype", DataType::Utf8, true),
-            Field::new("senere_afkraeftet", DataType::Utf8, true),
-            Field::new("diagnosekode_parent", DataType::Utf8, true),
-            Field::new("diagnosetype_parent", DataType::Utf8, true),
-            Field::new("lprindberetningssystem", DataType::Utf8, true),
-        ];
-
-        Arc::new(Schema::new(fields))
-    }
-
-    /// This registry has no PNR column, needs to be joined with KONTAKTER
-    fn get_pnr_column_name(&self) -> Option<&'static str> {
-        None
-    }
-
-    /// Returns the join column name for joining with kontakter
-    fn get_join_column_name(&self) -> Option<&'static str> {
-        Some("DW_EK_KONTAKT")
-    }
-}
\ No newline at end of file
@@ -141,6 +141,54 @@ pub fn create_field_mappings() -> Vec<FieldMapping> {
                 }
             }),
         ),
+        // Times of day of the contact, kept as properties
+        FieldMapping::new(
+            FieldDefinition::new("tidspunkt_start", "start_time", FieldType::Time, true),
+            Extractors::time("tidspunkt_start"),
+            ModelSetters::time_setter(|individual, value| {
+                individual.store_property("lpr3_start_time", Box::new(value));
+            }),
+        ),
+        FieldMapping::new(
+            FieldDefinition::new("tidspunkt_slut", "end_time", FieldType::Time, true),
+            Extractors::time("tidspunkt_slut"),
+            ModelSetters::time_setter(|individual, value| {
+                individual.store_property("lpr3_end_time", Box::new(value));
+            }),
+        ),
+        FieldMapping::new(
+            FieldDefinition::new(
+                "tidspunkt_behandling_start",
+                "treatment_start_time",
+                FieldType::Time,
+                true,
+            ),
+            Extractors::time("tidspunkt_behandling_start"),
+            ModelSetters::time_setter(|individual, value| {
+                individual.store_property("lpr3_treatment_start_time", Box::new(value));
+            }),
+        ),
+        // Treatment start date
+        FieldMapping::new(
+            FieldDefinition::new(
+                "dato_behandling_start",
+                "treatment_start_date",
+                FieldType::Date,
+                true,
+            ),
+            Extractors::date("dato_behandling_start"),
+            ModelSetters::date_setter(|individual, value| {
+                individual.store_property("lpr3_treatment_start_date", Box::new(value));
+            }),
+        ),
+        // Reporting date
+        FieldMapping::new(
+            FieldDefinition::new("dato_indberetning", "reporting_date", FieldType::Date, true),
+            Extractors::date("dato_indberetning"),
+            ModelSetters::date_setter(|individual, value| {
+                individual.store_property("lpr3_reporting_date", Box::new(value));
+            }),
+        ),
         // Primary diagnosis
         FieldMapping::new(
             FieldDefinition::new(
@@ -243,5 +291,18 @@ pub fn create_field_mappings() -> Vec<FieldMapping> {
                 }
             }),
         ),
+        // Reporting system
+        FieldMapping::new(
+            FieldDefinition::new(
+                "lprindberetningssystem",
+                "reporting_system",
+                FieldType::String,
+                true,
+            ),
+            Extractors::string("lprindberetningssystem"),
+            ModelSetters::string_setter(|individual, value| {
+                individual.store_property("lpr3_reporting_system", Box::new(value));
+            }),
+        ),
     ]
 }
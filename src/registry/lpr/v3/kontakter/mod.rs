@@ -3,19 +3,21 @@
 //! The `LPR3_KONTAKTER` registry contains contact records from the Danish National Patient Registry version 3.
 
 use crate::RegistryTrait;
-use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
 use chrono::{NaiveDate, NaiveTime};
-use std::sync::Arc;
 
 // Re-export the field_mapping module
 pub mod field_mapping;
 
 // Define LPR3 KONTAKTER Registry using the derive macro
 #[derive(RegistryTrait, Debug)]
-#[registry(name = "LPR3_KONTAKTER", description = "LPR v3 Contact Records")]
+#[registry(
+    name = "LPR3_KONTAKTER",
+    description = "LPR v3 Contact Records",
+    loader_name = "lpr3_kontakter"
+)]
 pub struct Lpr3KontakterRegistry {
     // Core identification fields
-    #[field(name = "CPR")]
+    #[field(name = "CPR", pnr = true)]
     pub pnr: String,
 
     #[field(name = "DW_EK_KONTAKT")]
@@ -93,49 +95,3 @@ pub fn deserialize_batch(
     // Use the inner deserializer to deserialize the batch
     deserializer.inner.deserialize_batch(batch)
 }
-
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for Lpr3KontakterRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "lpr3_kontakter"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for LPR3_KONTAKTER
-        let fields = vec![
-            Field::new("CPR", DataType::Utf8, false),
-            Field::new("DW_EK_KONTAKT", DataType::Utf8, true),
-            Field::new("DW_EK_FORLOEB", DataType::Utf8, true),
-            Field::new("SORENHED_IND", DataType::Utf8, true),
-            Field::new("SORENHED_HEN", DataType::Utf8, true),
-            Field::new("SORENHED_ANS", DataType::Utf8, true),
-            Field::new("dato_start", DataType::Date32, true),
-            Field::new("tidspunkt_start", DataType::Time32(TimeUnit::Second), true),
-            Field::new("dato_slut", DataType::Date32, true),
-            Field::new("tidspunkt_slut", DataType::Time32(TimeUnit::Second), true),
-            Field::new("dato_behandling_start", DataType::Date32, true),
-            Field::new(
-                "tidspunkt_behandling_start",
-                DataType::Time32(TimeUnit::Second),
-                true,
-            ),
-            Field::new("dato_indberetning", DataType::Date32, true),
-            Field::new("aktionsdiagnose", DataType::Utf8, true),
-            Field::new("kontaktaarsag", DataType::Utf8, true),
-            Field::new("prioritet", DataType::Utf8, true),
-            Field::new("kontakttype", DataType::Utf8, true),
-            Field::new("henvisningsaarsag", DataType::Utf8, true),
-            Field::new("henvisningsmaade", DataType::Utf8, true),
-            Field::new("lprindberetningssystem", DataType::Utf8, true),
-        ];
-
-        Arc::new(Schema::new(fields))
-    }
-
-    /// Returns the column name containing the PNR, if any
-    fn get_pnr_column_name(&self) -> Option<&'static str> {
-        Some("CPR")
-    }
-}
\ No newline at end of file
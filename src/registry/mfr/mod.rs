@@ -13,7 +13,7 @@ pub mod field_mapping;
 #[registry(name = "MFR", description = "Medical Birth Registry")]
 pub struct MfrRegistry {
     /// Child's personal identification number
-    #[field(name = "CPR_BARN")]
+    #[field(name = "CPR_BARN", pnr = true)]
     pub pnr: String,
 
     /// Birth date
@@ -42,29 +42,3 @@ pub fn deserialize_batch(
     // Use the inner deserializer to deserialize the batch
     deserializer.inner.deserialize_batch(batch)
 }
-
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for MfrRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "MFR"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for MFR
-        let fields = vec![
-            arrow::datatypes::Field::new("CPR_BARN", arrow::datatypes::DataType::Utf8, false),
-            arrow::datatypes::Field::new("FOEDSELSDATO", arrow::datatypes::DataType::Date32, true),
-            arrow::datatypes::Field::new("CPR_MODER", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("CPR_FADER", arrow::datatypes::DataType::Utf8, true),
-        ];
-
-        std::sync::Arc::new(arrow::datatypes::Schema::new(fields))
-    }
-
-    /// Returns the column name containing the PNR
-    fn get_pnr_column_name(&self) -> Option<&'static str> {
-        Some("CPR_BARN")
-    }
-}
\ No newline at end of file
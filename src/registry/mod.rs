@@ -20,14 +20,33 @@
 
 use crate::RecordBatch;
 use crate::Result;
+use crate::config::ParquetReaderConfig;
 
 use arrow::datatypes::SchemaRef;
-use std::collections::HashSet;
+use bytes::Bytes;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use std::future::Future;
 use std::pin::Pin;
 
+/// How the records of a registry can be filtered by PNR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PnrFilterRequirement {
+    /// The registry has a PNR column and is filtered by it
+    Direct,
+    /// The registry has no PNR column; its key column `on` is joined with
+    /// registry `with`, filtered by PNR, to find the records to keep
+    RequiresJoin {
+        /// The registry to join with, e.g. `LPR_ADM`
+        with: &'static str,
+        /// The key column both registries share, e.g. `RECNUM`
+        on: &'static str,
+    },
+    /// The registry cannot be filtered by PNR
+    Unsupported,
+}
+
 /// Base trait for registry loaders
 pub trait RegisterLoader: Send + Sync {
     /// Get the name of the register
@@ -57,10 +76,104 @@ pub trait RegisterLoader: Send + Sync {
         }
     }
 
+    /// Load records from an in-memory Parquet file
+    ///
+    /// The buffer is read like a single file given to [`load`](Self::load),
+    /// keeping at most [`limit`](Self::limit) rows. The PNR filter is applied
+    /// only if the registry has a PNR column.
+    fn load_bytes(
+        &self,
+        bytes: Bytes,
+        pnr_filter: Option<&HashSet<String>>,
+    ) -> Result<Vec<RecordBatch>> {
+        let schema = self.get_schema();
+        if pnr_filter.is_some() && self.get_pnr_column_name().is_none() {
+            log::warn!(
+                "PNR filtering requested but registry {} doesn't support PNR filtering",
+                self.get_register_name()
+            );
+        }
+        let pnr_filter = pnr_filter.filter(|_| self.get_pnr_column_name().is_some());
+        let batches = crate::utils::io::parquet::read_parquet_bytes_with_config(
+            bytes,
+            Some(&schema),
+            pnr_filter,
+            &self.reader_config(),
+        )?;
+
+        Ok(match self.limit() {
+            Some(limit) => truncate_rows(batches, limit),
+            None => batches,
+        })
+    }
+
+    /// Load records from several directories or files as if they were one
+    ///
+    /// For a registry split across mount points or delivery batches. The
+    /// Parquet files of all paths are checked for mutually compatible
+    /// schemas before any data is read, then each path is loaded with
+    /// [`load`](Self::load) and the batches are returned in the order of the
+    /// paths, keeping at most [`limit`](Self::limit) rows in total.
+    ///
+    /// # Errors
+    ///
+    /// A path that does not exist is an `Error::IoError`, and files with
+    /// incompatible schemas, within or across paths, are an
+    /// `Error::IncompatibleSchemas`. Otherwise as for
+    /// [`load_async`](Self::load_async).
+    fn load_multi(
+        &self,
+        base_paths: &[&Path],
+        pnr_filter: Option<&HashSet<String>>,
+    ) -> Result<Vec<RecordBatch>> {
+        let mut files = Vec::new();
+        for base_path in base_paths {
+            if base_path.is_dir() {
+                files.extend(crate::utils::find_parquet_files(base_path)?);
+            } else if base_path.exists() {
+                files.push(base_path.to_path_buf());
+            } else {
+                return Err(crate::Error::IoError(format!(
+                    "Path does not exist: {}",
+                    base_path.display()
+                ))
+                .into());
+            }
+        }
+        if !files.is_empty() {
+            check_schema_compatibility(&files)?;
+        }
+
+        let mut batches = Vec::new();
+        for base_path in base_paths {
+            batches.extend(self.load(base_path, pnr_filter)?);
+        }
+        log::info!(
+            "Loaded {} batches of registry {} from {} paths",
+            batches.len(),
+            self.get_register_name(),
+            base_paths.len()
+        );
+
+        Ok(match self.limit() {
+            Some(limit) => truncate_rows(batches, limit),
+            None => batches,
+        })
+    }
+
     /// Load records from the register asynchronously
     ///
     /// This is the main implementation that handles both directory and file loading
     /// in an efficient and consistent way
+    ///
+    /// # Errors
+    ///
+    /// A path that does not exist is an `Error::IoError`. A directory
+    /// without Parquet files is an `Error::EmptyDirectory` if
+    /// [`fail_on_empty_directory`](Self::fail_on_empty_directory) is set,
+    /// and otherwise loads as no batches. Files with incompatible schemas
+    /// are an `Error::IncompatibleSchemas` if
+    /// [`validate_schema`](Self::validate_schema) is set.
     fn load_async<'a>(
         &'a self,
         base_path: &'a Path,
@@ -72,11 +185,22 @@ pub trait RegisterLoader: Send + Sync {
         // Get the PNR column name if available
         let pnr_column = self.get_pnr_column_name();
 
+        // Get the settings files are read with
+        let config = self.reader_config();
+
         // Move into async block
         Box::pin(async move {
             // Check if path exists and is a directory or file
             let metadata = tokio::fs::metadata(base_path).await.map_err(|e| {
-                anyhow::anyhow!("Failed to access path {}: {}", base_path.display(), e)
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    crate::Error::IoError(format!("Path does not exist: {}", base_path.display()))
+                } else {
+                    crate::Error::IoError(format!(
+                        "Failed to access path {}: {}",
+                        base_path.display(),
+                        e
+                    ))
+                }
             })?;
 
             if metadata.is_dir() {
@@ -94,6 +218,12 @@ pub trait RegisterLoader: Send + Sync {
                 .map_err(|e| anyhow::anyhow!("Task join error: {}", e))??;
 
                 if parquet_files.is_empty() {
+                    if self.fail_on_empty_directory() {
+                        return Err(crate::Error::EmptyDirectory {
+                            path: base_path.to_path_buf(),
+                        }
+                        .into());
+                    }
                     log::warn!(
                         "No parquet files found in directory: {}",
                         base_path.display()
@@ -107,6 +237,18 @@ pub trait RegisterLoader: Send + Sync {
                     base_path.display()
                 );
 
+                if self.validate_schema() {
+                    let files = parquet_files.clone();
+                    tokio::task::spawn_blocking(move || check_schema_compatibility(&files))
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Task join error: {}", e))??;
+                }
+
+                if let Some(limit) = self.limit() {
+                    return read_head(parquet_files, schema, pnr_filter, pnr_column, limit, config)
+                        .await;
+                }
+
                 // Handle PNR filtering if needed
                 if let Some(pnr_filter) = pnr_filter {
                     // If PNR column is available, apply filtering
@@ -119,12 +261,11 @@ pub trait RegisterLoader: Send + Sync {
                         let base_path_owned = base_path.to_path_buf(); // Create owned copy
                         tokio::task::spawn_blocking(move || {
                             // Use the optimized parallel loading that handles PNR filtering
-                            crate::utils::load_parquet_files_parallel(
+                            crate::utils::io::load_parquet_files_with_config(
                                 &base_path_owned,
                                 Some(schema_ref.as_ref()),
                                 Some(&pnr_filter),
-                                None,
-                                None,
+                                &config,
                             )
                         })
                         .await
@@ -140,12 +281,11 @@ pub trait RegisterLoader: Send + Sync {
                         let schema_ref = schema.clone();
                         let base_path_owned = base_path.to_path_buf(); // Create owned copy
                         tokio::task::spawn_blocking(move || {
-                            crate::utils::load_parquet_files_parallel(
+                            crate::utils::io::load_parquet_files_with_config(
                                 &base_path_owned,
                                 Some(schema_ref.as_ref()),
                                 None::<&HashSet<String>>, // No filtering
-                                None,
-                                None,
+                                &config,
                             )
                         })
                         .await
@@ -156,12 +296,11 @@ pub trait RegisterLoader: Send + Sync {
                     let schema_ref = schema.clone();
                     let base_path_owned = base_path.to_path_buf(); // Create owned copy
                     tokio::task::spawn_blocking(move || {
-                        crate::utils::load_parquet_files_parallel(
+                        crate::utils::io::load_parquet_files_with_config(
                             &base_path_owned,
                             Some(schema_ref.as_ref()),
                             None::<&HashSet<String>>, // No filtering
-                            None,
-                            None,
+                            &config,
                         )
                     })
                     .await
@@ -171,6 +310,11 @@ pub trait RegisterLoader: Send + Sync {
                 // SINGLE FILE HANDLING
                 log::info!("Loading from single file: {}", base_path.display());
 
+                if let Some(limit) = self.limit() {
+                    let files = vec![base_path.to_path_buf()];
+                    return read_head(files, schema, pnr_filter, pnr_column, limit, config).await;
+                }
+
                 // Handle PNR filtering if needed
                 if let Some(pnr_filter) = pnr_filter {
                     // If PNR column is available, apply filtering
@@ -181,12 +325,11 @@ pub trait RegisterLoader: Send + Sync {
                         // Use tokio's spawn_blocking for CPU-intensive work
                         let base_path_owned = base_path.to_path_buf(); // Create owned copy
                         tokio::task::spawn_blocking(move || {
-                            crate::utils::read_parquet(
+                            crate::utils::io::read_parquet_with_config(
                                 &base_path_owned,
                                 Some(schema_ref.as_ref()),
                                 Some(&pnr_filter),
-                                None,
-                                None,
+                                &config,
                             )
                         })
                         .await
@@ -202,12 +345,11 @@ pub trait RegisterLoader: Send + Sync {
                         let schema_ref = schema.clone();
                         let base_path_owned = base_path.to_path_buf(); // Create owned copy
                         tokio::task::spawn_blocking(move || {
-                            crate::utils::read_parquet(
+                            crate::utils::io::read_parquet_with_config(
                                 &base_path_owned,
                                 Some(schema_ref.as_ref()),
                                 None::<&HashSet<String>>, // No filtering
-                                None,
-                                None,
+                                &config,
                             )
                         })
                         .await
@@ -218,12 +360,11 @@ pub trait RegisterLoader: Send + Sync {
                     let schema_ref = schema.clone();
                     let base_path_owned = base_path.to_path_buf(); // Create owned copy
                     tokio::task::spawn_blocking(move || {
-                        crate::utils::read_parquet(
+                        crate::utils::io::read_parquet_with_config(
                             &base_path_owned,
                             Some(schema_ref.as_ref()),
                             None::<&HashSet<String>>, // No filtering
-                            None,
-                            None,
+                            &config,
                         )
                     })
                     .await
@@ -234,11 +375,34 @@ pub trait RegisterLoader: Send + Sync {
     }
 
     /// Returns whether this registry supports direct PNR filtering
-    /// Some registries require joins to filter by PNR
+    /// Some registries require joins to filter by PNR, see [`Self::pnr_filter_requirement`]
     fn supports_pnr_filter(&self) -> bool {
         self.get_pnr_column_name().is_some()
     }
 
+    /// Returns how this registry can be filtered by PNR
+    /// Default implementation joins `LPR_DIAG` and `LPR_BES` with `LPR_ADM` on
+    /// `RECNUM`, `LPR3_DIAGNOSER` with `LPR3_KONTAKTER` on `DW_EK_KONTAKT`,
+    /// and filters other registries directly if they have a PNR column
+    fn pnr_filter_requirement(&self) -> PnrFilterRequirement {
+        use crate::utils::register::RegistryType;
+
+        match self.get_register_name().parse::<RegistryType>() {
+            Ok(RegistryType::LprDiag | RegistryType::LprBes) => {
+                PnrFilterRequirement::RequiresJoin {
+                    with: RegistryType::LprAdm.as_str(),
+                    on: "RECNUM",
+                }
+            }
+            Ok(RegistryType::Lpr3Diagnoser) => PnrFilterRequirement::RequiresJoin {
+                with: RegistryType::Lpr3Kontakter.as_str(),
+                on: "DW_EK_KONTAKT",
+            },
+            _ if self.get_pnr_column_name().is_some() => PnrFilterRequirement::Direct,
+            _ => PnrFilterRequirement::Unsupported,
+        }
+    }
+
     /// Returns the column name containing the PNR, if any
     /// Default implementation assumes "PNR" is the column name
     fn get_pnr_column_name(&self) -> Option<&'static str> {
@@ -250,6 +414,48 @@ pub trait RegisterLoader: Send + Sync {
         None
     }
 
+    /// Returns the name each source column is deserialized to, by source column
+    /// Default implementation declares no mapping
+    fn get_field_mapping(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    /// Returns whether loading a directory without Parquet files is an error
+    /// Default implementation loads such a directory as no batches
+    fn fail_on_empty_directory(&self) -> bool {
+        false
+    }
+
+    /// Returns whether the files of a directory are checked for mutually
+    /// compatible schemas, with [`check_schema_compatibility`], before any
+    /// data is read
+    /// Default implementation loads without the check
+    fn validate_schema(&self) -> bool {
+        false
+    }
+
+    /// Returns the maximum number of rows to load, if any
+    ///
+    /// Files are read in order, and reading stops once the limit is reached;
+    /// with a PNR filter the limit counts the rows that pass the filter
+    /// Default implementation loads every row
+    fn limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the settings files are read with
+    ///
+    /// Files are projected to the schema as `ignore_extra_columns` says, and
+    /// the `date_range_checks` are applied to the loaded batches.
+    /// Default implementation reads files as they are, without type
+    /// adaptation or date range checks
+    fn reader_config(&self) -> ParquetReaderConfig {
+        ParquetReaderConfig {
+            adapt_types: false,
+            ..ParquetReaderConfig::default()
+        }
+    }
+
     /// Enable or disable the unified schema system
     /// Default implementation does nothing, registries that support the unified system
     /// should override this method.
@@ -274,6 +480,45 @@ pub trait RegisterLoader: Send + Sync {
     }
 }
 
+/// Keep the first `limit` rows of batches
+fn truncate_rows(batches: Vec<RecordBatch>, mut remaining: usize) -> Vec<RecordBatch> {
+    batches
+        .into_iter()
+        .map_while(|batch| {
+            (remaining > 0).then(|| {
+                let batch = batch.slice(0, remaining.min(batch.num_rows()));
+                remaining -= batch.num_rows();
+                batch
+            })
+        })
+        .collect()
+}
+
+/// Read the first `limit` rows of files on a blocking task
+///
+/// The PNR filter is applied only if the registry has a PNR column.
+async fn read_head(
+    files: Vec<std::path::PathBuf>,
+    schema: SchemaRef,
+    pnr_filter: Option<&HashSet<String>>,
+    pnr_column: Option<&'static str>,
+    limit: usize,
+    config: ParquetReaderConfig,
+) -> Result<Vec<RecordBatch>> {
+    let pnr_filter = pnr_filter.filter(|_| pnr_column.is_some()).cloned();
+    tokio::task::spawn_blocking(move || {
+        crate::utils::io::parquet::read_parquet_files_head_with_config(
+            &files,
+            Some(schema.as_ref()),
+            pnr_filter.as_ref(),
+            limit,
+            &config,
+        )
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Task join error: {}", e))?
+}
+
 // Registry implementations
 pub mod akm;
 pub mod bef;
@@ -282,6 +527,7 @@ pub mod death {
     pub mod dod;
     pub mod dodsaarsag;
 }
+pub mod idan;
 pub mod ind;
 pub mod lpr;
 pub mod mfr;
@@ -292,10 +538,22 @@ pub mod vnds;
 pub mod factory;
 pub mod unified_registry;
 
+mod schema_diff;
+pub use schema_diff::{ColumnDiff, ColumnStatus, SchemaDiff, schema_diff};
+
+mod pnr_set;
+pub use pnr_set::load_pnr_set;
+
+mod builder;
+pub use builder::{LoaderConfig, RegistryLoaderBuilder, pnr_filterable_loader};
+
+mod preflight;
+pub use preflight::check_schema_compatibility;
+
 mod transform;
 pub use transform::{
-    add_postal_code_region, add_year_column, filter_by_date_range, filter_out_missing_values,
-    map_categorical_values, scale_numeric_values, transform_records,
+    add_postal_code_region, add_year_column, classify_urban_rural, filter_by_date_range,
+    filter_out_missing_values, map_categorical_values, scale_numeric_values, transform_records,
 };
 
 // Centralized registry deserialization
@@ -0,0 +1,119 @@
+//! Enumeration of the PNRs in a registry
+//!
+//! Building a cohort often starts from "every individual in this registry".
+//! Only the PNR column is read for that, projected at the Parquet level, so
+//! no other columns are decoded and no records are deserialized.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use rayon::prelude::*;
+
+use crate::error::{ParquetReaderError, Result};
+use crate::filter::pnr_normalization::normalize_pnr;
+use crate::registry::RegisterLoader;
+use crate::utils::arrow::distinct::for_each_batch;
+use crate::utils::io::parquet::find_parquet_files;
+
+/// Load the distinct PNRs of a registry
+///
+/// Files are read in parallel, each with only the registry's PNR column
+/// projected. PNRs are converted to the canonical form of
+/// [`PnrNormalization::Canonical`](crate::filter::PnrNormalization::Canonical)
+/// and nulls are skipped.
+///
+/// # Arguments
+/// * `loader` - The registry loader, which names the PNR column
+/// * `base_path` - A Parquet file, or a directory searched with [`find_parquet_files`]
+///
+/// # Returns
+/// The set of distinct PNRs
+///
+/// # Errors
+/// Returns an error if the registry has no PNR column (e.g. registries keyed
+/// by `RECNUM` or a contact id, which must be joined to obtain PNRs), or if a
+/// file cannot be read or lacks the PNR column
+pub fn load_pnr_set(loader: &dyn RegisterLoader, base_path: &Path) -> Result<HashSet<String>> {
+    let registry = loader.get_register_name();
+    let pnr_column = loader.get_pnr_column_name().ok_or_else(|| {
+        let key = loader.get_join_column_name().unwrap_or("RECNUM");
+        ParquetReaderError::InvalidOperation(format!(
+            "Registry {registry} has no PNR column; its records are keyed by {key} and must be joined with another registry to obtain PNRs"
+        ))
+    })?;
+
+    let files = if base_path.is_dir() {
+        find_parquet_files(base_path)?
+    } else {
+        vec![base_path.to_path_buf()]
+    };
+
+    files
+        .par_iter()
+        .map(|file| {
+            let mut pnrs = HashSet::new();
+            for_each_batch(file, pnr_column, |values| {
+                pnrs.extend(
+                    values
+                        .iter()
+                        .flatten()
+                        .map(|pnr| normalize_pnr(pnr).into_owned()),
+                );
+            })
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to read PNR column {pnr_column} of {registry} file {}: {e}",
+                    file.display()
+                )
+            })?;
+            Ok(pnrs)
+        })
+        .try_reduce(HashSet::new, |mut all, pnrs| {
+            all.extend(pnrs);
+            Ok(all)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::direct_registry_loader::DirectRegistryLoader;
+    use arrow::array::{ArrayRef, Int32Array, StringArray};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use std::fs::File;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_load_pnr_set() {
+        let dir = std::env::temp_dir().join(format!("par_reader_pnr_set_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, pnrs) in [
+            ("2019", vec![Some("0101801234"), Some("0202801234")]),
+            ("2020", vec![Some("020280-1234"), None]),
+        ] {
+            let batch = RecordBatch::try_from_iter(vec![
+                ("PNR", Arc::new(StringArray::from(pnrs)) as ArrayRef),
+                ("AAR", Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef),
+            ])
+            .unwrap();
+            let file = File::create(dir.join(format!("bef_{name}.parquet"))).unwrap();
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+        }
+
+        let pnrs = load_pnr_set(&DirectRegistryLoader::new("BEF"), &dir).unwrap();
+        assert_eq!(
+            pnrs,
+            HashSet::from(["0101801234".to_string(), "0202801234".to_string()])
+        );
+
+        let error = load_pnr_set(&DirectRegistryLoader::new("LPR3_DIAGNOSER"), &dir).unwrap_err();
+        assert!(error.to_string().contains("no PNR column"));
+        // MFR declares CPR_BARN, which these files lack
+        assert!(load_pnr_set(&DirectRegistryLoader::new("MFR"), &dir).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
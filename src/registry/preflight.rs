@@ -0,0 +1,108 @@
+//! Schema checks of registry files before loading
+//!
+//! Loading a directory concatenates the batches of all its files, so a file
+//! whose schema differs from the others otherwise surfaces only once data is
+//! read, if at all. [`check_schema_compatibility`] compares the footers of
+//! the files up front, using the checks of
+//! [`ParquetReader::get_schema_compatibility_report`].
+
+use std::path::PathBuf;
+
+use crate::error::{ParquetReaderError, Result};
+use crate::reader::ParquetReader;
+
+/// Check that Parquet files have mutually compatible schemas
+///
+/// Only the footers are read. Every file is compared to the first one.
+///
+/// # Arguments
+///
+/// * `files` - The files to load together
+///
+/// # Errors
+///
+/// Returns an `Error::IncompatibleSchemas` holding the report of every
+/// issue if the schemas differ, or an error if a footer cannot be read
+pub fn check_schema_compatibility(files: &[PathBuf]) -> Result<()> {
+    let paths: Vec<String> = files
+        .iter()
+        .map(|file| file.to_string_lossy().to_string())
+        .collect();
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+    // Cache every footer, so the report finds them all
+    let mut reader = ParquetReader::with_cache_size(paths.len());
+    for path in &paths {
+        reader.read_file(path)?;
+    }
+
+    let report = reader.get_schema_compatibility_report(&paths)?;
+    if report.compatible {
+        Ok(())
+    } else {
+        Err(ParquetReaderError::IncompatibleSchemas { report }.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::RegisterLoader;
+    use crate::registry::direct_registry_loader::DirectRegistryLoader;
+    use arrow::array::{ArrayRef, Int32Array, StringArray};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use std::fs::File;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    fn write_file(path: &Path, columns: Vec<(&str, ArrayRef)>) {
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        let mut writer =
+            ArrowWriter::try_new(File::create(path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_check_schema_compatibility() {
+        let dir = std::env::temp_dir().join(format!("par_reader_preflight_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pnr = || Arc::new(StringArray::from(vec!["0101801234"])) as ArrayRef;
+        write_file(&dir.join("a.parquet"), vec![("PNR", pnr())]);
+        write_file(&dir.join("b.parquet"), vec![("PNR", pnr())]);
+        check_schema_compatibility(&[dir.join("a.parquet"), dir.join("b.parquet")]).unwrap();
+
+        write_file(
+            &dir.join("c.parquet"),
+            vec![
+                ("PNR", pnr()),
+                (
+                    "INDUD_KODE",
+                    Arc::new(Int32Array::from(vec![1])) as ArrayRef,
+                ),
+            ],
+        );
+        let error = check_schema_compatibility(&[dir.join("a.parquet"), dir.join("c.parquet")])
+            .unwrap_err();
+        let Some(ParquetReaderError::IncompatibleSchemas { report }) =
+            error.downcast_ref::<ParquetReaderError>()
+        else {
+            panic!("expected incompatible schemas, got {error}");
+        };
+        assert_eq!(report.issues.len(), 1);
+        assert!(report.issues[0].file_path.ends_with("c.parquet"));
+
+        // Loaders check the files of a directory only when asked to
+        let loader = DirectRegistryLoader::new("VNDS").with_validate_schema(true);
+        let error = loader.load(&dir, None).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ParquetReaderError>(),
+            Some(ParquetReaderError::IncompatibleSchemas { .. })
+        ));
+        let loader = DirectRegistryLoader::new("VNDS");
+        assert!(!loader.validate_schema());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+//! Schema diff between a registry's declared schema and the files on disk
+//!
+//! This module compares the schema declared by a [`RegisterLoader`] against the
+//! actual Parquet schema found at a path, so mislabeled directories and drifting
+//! exports can be caught before any data is loaded.
+
+use std::fmt;
+use std::path::Path;
+
+use arrow::datatypes::DataType;
+
+use crate::error::Result;
+use crate::registry::RegisterLoader;
+use crate::utils::io::parquet::{find_parquet_files, read_parquet_schema};
+
+/// Classification of a single column in a [`SchemaDiff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnStatus {
+    /// Declared by the registry and present with the declared type
+    Expected,
+    /// Present in the files but not declared by the registry
+    Extra,
+    /// Declared by the registry but absent from the files
+    Missing,
+    /// Present in the files with a different type than declared
+    TypeMismatch {
+        /// The type declared by the registry
+        expected: DataType,
+        /// The type found in the files
+        actual: DataType,
+    },
+}
+
+/// Diff entry for a single column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnDiff {
+    /// Column name
+    pub name: String,
+    /// How the column compares against the declared schema
+    pub status: ColumnStatus,
+}
+
+/// Report comparing a registry's declared schema against actual file schemas
+#[derive(Debug, Clone)]
+pub struct SchemaDiff {
+    /// Name of the registry the files were compared against
+    pub registry: String,
+    /// Number of Parquet files inspected
+    pub files_inspected: usize,
+    /// Per-column diff entries, declared columns first, then extras
+    pub columns: Vec<ColumnDiff>,
+}
+
+impl SchemaDiff {
+    /// Whether the files contain every declared column with the declared type
+    ///
+    /// Extra columns do not make a diff incompatible.
+    #[must_use]
+    pub fn is_compatible(&self) -> bool {
+        self.columns
+            .iter()
+            .all(|c| matches!(c.status, ColumnStatus::Expected | ColumnStatus::Extra))
+    }
+
+    /// Names of columns with a given status predicate
+    fn names_where(&self, predicate: impl Fn(&ColumnStatus) -> bool) -> Vec<&str> {
+        self.columns
+            .iter()
+            .filter(|c| predicate(&c.status))
+            .map(|c| c.name.as_str())
+            .collect()
+    }
+
+    /// Columns present in the files but not declared
+    #[must_use]
+    pub fn extra_columns(&self) -> Vec<&str> {
+        self.names_where(|s| *s == ColumnStatus::Extra)
+    }
+
+    /// Declared columns absent from the files
+    #[must_use]
+    pub fn missing_columns(&self) -> Vec<&str> {
+        self.names_where(|s| *s == ColumnStatus::Missing)
+    }
+
+    /// Columns whose type differs from the declared type
+    #[must_use]
+    pub fn mismatched_columns(&self) -> Vec<&str> {
+        self.names_where(|s| matches!(s, ColumnStatus::TypeMismatch { .. }))
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Schema diff for {} ({} files inspected):",
+            self.registry, self.files_inspected
+        )?;
+        for column in &self.columns {
+            match &column.status {
+                ColumnStatus::Expected => writeln!(f, "  = {}", column.name)?,
+                ColumnStatus::Extra => writeln!(f, "  + {} (extra)", column.name)?,
+                ColumnStatus::Missing => writeln!(f, "  - {} (missing)", column.name)?,
+                ColumnStatus::TypeMismatch { expected, actual } => writeln!(
+                    f,
+                    "  ~ {} (expected {expected}, found {actual})",
+                    column.name
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare a registry's declared schema against the Parquet files at a path
+///
+/// `base_path` may be a single Parquet file or a directory, in which case all
+/// Parquet files found (recursively) are inspected. A column counts as present
+/// if it appears in any file; its type is taken from the first file containing it.
+///
+/// # Arguments
+/// * `loader` - The registry loader providing the declared schema
+/// * `base_path` - Path to a Parquet file or a directory of Parquet files
+///
+/// # Returns
+/// A [`SchemaDiff`] classifying each column
+///
+/// # Errors
+/// Returns an error if the path cannot be read or a file schema cannot be read
+pub fn schema_diff(loader: &dyn RegisterLoader, base_path: &Path) -> Result<SchemaDiff> {
+    let files = if base_path.is_dir() {
+        find_parquet_files(base_path)?
+    } else {
+        vec![base_path.to_path_buf()]
+    };
+
+    // Union of the columns found in the files, keeping first-seen order and type
+    let mut actual: Vec<(String, DataType)> = Vec::new();
+    for file in &files {
+        let file_schema = read_parquet_schema(file)?;
+        for field in file_schema.fields() {
+            if !actual.iter().any(|(name, _)| name == field.name()) {
+                actual.push((field.name().clone(), field.data_type().clone()));
+            }
+        }
+    }
+
+    let declared = loader.get_schema();
+    let mut columns = Vec::with_capacity(declared.fields().len() + actual.len());
+
+    for field in declared.fields() {
+        let status = match actual.iter().find(|(name, _)| name == field.name()) {
+            None => ColumnStatus::Missing,
+            Some((_, data_type)) if data_type == field.data_type() => ColumnStatus::Expected,
+            Some((_, data_type)) => ColumnStatus::TypeMismatch {
+                expected: field.data_type().clone(),
+                actual: data_type.clone(),
+            },
+        };
+        columns.push(ColumnDiff {
+            name: field.name().clone(),
+            status,
+        });
+    }
+
+    for (name, _) in &actual {
+        if declared.field_with_name(name).is_err() {
+            columns.push(ColumnDiff {
+                name: name.clone(),
+                status: ColumnStatus::Extra,
+            });
+        }
+    }
+
+    Ok(SchemaDiff {
+        registry: loader.get_register_name().to_string(),
+        files_inspected: files.len(),
+        columns,
+    })
+}
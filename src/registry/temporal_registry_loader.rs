@@ -6,10 +6,10 @@
 use crate::RecordBatch;
 use crate::error::Result;
 use crate::models::core::individual::temporal::TimePeriod;
-use crate::registry::RegisterLoader;
+use crate::registry::{RegisterLoader, check_schema_compatibility};
 use crate::utils::io::paths::temporal::get_registry_time_period_files;
 use arrow::datatypes::SchemaRef;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use rayon::prelude::*;
@@ -186,6 +186,18 @@ impl RegisterLoader for TemporalRegistryLoader {
         self.inner_loader.get_join_column_name()
     }
 
+    fn get_field_mapping(&self) -> HashMap<String, String> {
+        self.inner_loader.get_field_mapping()
+    }
+
+    fn fail_on_empty_directory(&self) -> bool {
+        self.inner_loader.fail_on_empty_directory()
+    }
+
+    fn validate_schema(&self) -> bool {
+        self.inner_loader.validate_schema()
+    }
+
     fn load_async<'a>(
         &'a self,
         base_path: &'a Path,
@@ -194,11 +206,14 @@ impl RegisterLoader for TemporalRegistryLoader {
         // Create a future that loads all time periods
         let base_path = base_path.to_owned(); // Clone the base_path
         let inner_loader = self.inner_loader.clone(); // Clone the Arc
+        let fail_on_empty_directory = self.fail_on_empty_directory();
+        let validate_schema = self.validate_schema();
         
         Box::pin(async move {
             // Find all files with time periods
+            let search_path = base_path.clone();
             let period_files = tokio::task::spawn_blocking(move || {
-                get_registry_time_period_files(&base_path)
+                get_registry_time_period_files(&search_path)
             })
             .await
             .map_err(|e| anyhow::anyhow!("Task join error: {}", e))??;
@@ -207,9 +222,19 @@ impl RegisterLoader for TemporalRegistryLoader {
             let file_paths: Vec<PathBuf> = period_files.values().cloned().collect();
             
             if file_paths.is_empty() {
+                if fail_on_empty_directory {
+                    return Err(crate::error::Error::EmptyDirectory { path: base_path }.into());
+                }
                 return Ok(Vec::new());
             }
 
+            if validate_schema {
+                let files = file_paths.clone();
+                tokio::task::spawn_blocking(move || check_schema_compatibility(&files))
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Task join error: {}", e))??;
+            }
+
             // Use the inner loader to load all files
             let mut all_batches = Vec::new();
             
@@ -11,6 +11,7 @@ use log::info;
 
 use crate::error::Result;
 use crate::models::core::Individual;
+use crate::schema::field_def::FieldType;
 
 /// Deserialize a single row from a record batch based on registry name
 ///
@@ -100,6 +101,13 @@ pub trait RegistryDeserializer: Send + Sync + std::fmt::Debug {
     /// This provides a mapping from registry field names to `SerdeIndividual`
     /// field names for backward compatibility.
     fn field_mapping(&self) -> HashMap<String, String>;
+
+    /// Get the declared field map of this registry
+    ///
+    /// Each entry is a `(source column, target field, field type)` triple in
+    /// declaration order. This exposes the column contract of the registry so
+    /// external validators can check incoming files against it.
+    fn field_map(&self) -> &[(String, String, FieldType)];
     
     /// Get the ID field type used by this registry
     ///
@@ -163,16 +171,13 @@ pub trait RegistryDeserializer: Send + Sync + std::fmt::Debug {
         // Check appropriate ID field based on registry type
         let has_valid_id = match self.id_field_type() {
             "pnr" => !individual.pnr.is_empty(),
-            "record_number" => individual.properties()
-                .and_then(|props| props.get("record_number"))
-                .and_then(|v| v.downcast_ref::<Option<String>>())
-                .and_then(|v| v.as_ref().map(|s| !s.is_empty()))
-                .unwrap_or(false),
-            "dw_ek_kontakt" => individual.properties()
-                .and_then(|props| props.get("dw_ek_kontakt"))
-                .and_then(|v| v.downcast_ref::<Option<String>>())
-                .and_then(|v| v.as_ref().map(|s| !s.is_empty()))
-                .unwrap_or(false),
+            // The id may be stored as a String or an Option<String>, after
+            // the type of the registry field
+            id @ ("record_number" | "dw_ek_kontakt") => individual
+                .property_value::<Option<String>>(id)
+                .flatten()
+                .or_else(|| individual.property_value::<String>(id))
+                .is_some_and(|id| !id.is_empty()),
             _ => !individual.pnr.is_empty(), // Default to checking PNR for backward compatibility
         };
 
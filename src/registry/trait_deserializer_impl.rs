@@ -9,7 +9,8 @@ use crate::registry::trait_deserializer::{RegistryDeserializer, RegistryFieldExt
 use crate::schema::RegistrySchema;
 use crate::schema::field_def::FieldType;
 use crate::utils::register::extractors::{
-    DateExtractor, FloatExtractor, IntegerExtractor, Setter, StringExtractor,
+    DateEncoding, DateExtractor, FloatExtractor, FloatFormat, IntegerExtractor, Setter,
+    StringExtractor, StringFormat, TimeExtractor,
 };
 
 /// Generic implementation of a registry deserializer
@@ -20,6 +21,8 @@ pub struct RegistryDeserializerImpl {
     registry_desc: String,
     field_extractors: Vec<Box<dyn RegistryFieldExtractor>>,
     field_map: HashMap<String, String>,
+    /// Declared `(source, target, type)` triples in schema order
+    field_descriptors: Vec<(String, String, FieldType)>,
     /// The type of field used as the primary identifier
     /// Possible values: "pnr", "`record_number`", "`dw_ek_kontakt`"
     id_field: String,
@@ -32,6 +35,30 @@ impl RegistryDeserializerImpl {
         registry_desc: impl Into<String>,
         schema: RegistrySchema,
         id_field: Option<impl Into<String>>,
+    ) -> Self {
+        Self::new_with_formats(
+            registry_type,
+            registry_desc,
+            schema,
+            id_field,
+            FloatFormat::default(),
+            DateEncoding::default(),
+            &StringFormat::default(),
+        )
+    }
+
+    /// Create a new registry deserializer implementation whose decimal
+    /// fields parse text with the given format, whose date fields read
+    /// integers with the given encoding and whose string fields read empty
+    /// text as the given format says
+    pub fn new_with_formats(
+        registry_type: impl Into<String>,
+        registry_desc: impl Into<String>,
+        schema: RegistrySchema,
+        id_field: Option<impl Into<String>>,
+        float_format: FloatFormat,
+        date_encoding: DateEncoding,
+        string_format: &StringFormat,
     ) -> Self {
         let registry_type = registry_type.into();
         let registry_desc = registry_desc.into();
@@ -40,6 +67,7 @@ impl RegistryDeserializerImpl {
         // Create field extractors from schema mappings
         let mut field_extractors: Vec<Box<dyn RegistryFieldExtractor>> = Vec::new();
         let mut field_map = HashMap::new();
+        let mut field_descriptors = Vec::with_capacity(schema.field_mappings.len());
 
         // Convert schema mappings to field extractors
         for mapping in &schema.field_mappings {
@@ -48,6 +76,11 @@ impl RegistryDeserializerImpl {
 
             // Add to field map
             field_map.insert(source_field.clone(), target_field.clone());
+            field_descriptors.push((
+                source_field.clone(),
+                target_field.clone(),
+                mapping.field_def.field_type,
+            ));
 
             // Create appropriate field extractor based on field type
             match &mapping.field_def.field_type {
@@ -57,6 +90,9 @@ impl RegistryDeserializerImpl {
                         &source_field,
                         &target_field,
                         Setter::new(mapping.setter.clone()),
+                    )
+                    .with_empty_string_as_null(
+                        string_format.empty_string_as_null_for(&source_field),
                     );
                     field_extractors.push(Box::new(extractor));
                 }
@@ -75,7 +111,8 @@ impl RegistryDeserializerImpl {
                         &source_field,
                         &target_field,
                         Setter::new(mapping.setter.clone()),
-                    );
+                    )
+                    .with_format(float_format);
                     field_extractors.push(Box::new(extractor));
                 }
                 FieldType::Date => {
@@ -84,6 +121,16 @@ impl RegistryDeserializerImpl {
                         &source_field,
                         &target_field,
                         Setter::new(mapping.setter.clone()),
+                    )
+                    .with_encoding(date_encoding);
+                    field_extractors.push(Box::new(extractor));
+                }
+                FieldType::Time => {
+                    // Create time extractor
+                    let extractor = TimeExtractor::new(
+                        &source_field,
+                        &target_field,
+                        Setter::new(mapping.setter.clone()),
                     );
                     field_extractors.push(Box::new(extractor));
                 }
@@ -98,6 +145,7 @@ impl RegistryDeserializerImpl {
             registry_desc,
             field_extractors,
             field_map,
+            field_descriptors,
             id_field,
         }
     }
@@ -116,6 +164,10 @@ impl RegistryDeserializer for RegistryDeserializerImpl {
         self.field_map.clone()
     }
 
+    fn field_map(&self) -> &[(String, String, FieldType)] {
+        &self.field_descriptors
+    }
+
     fn id_field_type(&self) -> &'static str {
         match self.id_field.as_str() {
             "pnr" => "pnr",
@@ -15,6 +15,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::error::{Error, Result};
+use crate::models::core::Individual;
+use crate::models::core::types::UrbanRural;
 
 /// Transform multiple record batches using a provided transformation function
 pub fn transform_records(
@@ -294,6 +296,15 @@ pub fn add_postal_code_region(
         .with_context(|| "Failed to create batch with region column")
 }
 
+/// Classify the municipality of each individual as urban, intermediate or rural
+///
+/// See [`Individual::classify_urban_rural`] for how the table is looked up.
+pub fn classify_urban_rural(individuals: &mut [Individual], table: &HashMap<u16, UrbanRural>) {
+    for individual in individuals {
+        individual.classify_urban_rural(table);
+    }
+}
+
 /// Determine Danish region from postal code
 fn determine_region_from_postal_code(postal_code: &str) -> &'static str {
     postal_code
@@ -310,3 +321,32 @@ fn determine_region_from_postal_code(postal_code: &str) -> &'static str {
             _ => "Unknown",
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_urban_rural() {
+        let table = HashMap::from([(101, UrbanRural::Urban), (787, UrbanRural::Rural)]);
+        let mut individuals: Vec<Individual> = [Some("0101"), Some("787"), Some("999"), None]
+            .into_iter()
+            .map(|code| {
+                let mut individual = Individual::new("0101011234".to_string(), None);
+                individual.municipality_code = code.map(ToString::to_string);
+                individual
+            })
+            .collect();
+
+        classify_urban_rural(&mut individuals, &table);
+
+        let classes: Vec<_> = individuals.iter().map(|i| i.urban_rural).collect();
+        assert_eq!(
+            classes,
+            vec![Some(UrbanRural::Urban), Some(UrbanRural::Rural), None, None]
+        );
+        assert!(!individuals[0].is_rural);
+        assert!(individuals[1].is_rural);
+        assert_eq!(individuals[3].municipality_code, None);
+    }
+}
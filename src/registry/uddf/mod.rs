@@ -13,7 +13,7 @@ pub mod field_mapping;
 #[registry(name = "UDDF", description = "Education registry")]
 pub struct UddfRegistry {
     /// Person ID (CPR number)
-    #[field(name = "PNR")]
+    #[field(name = "PNR", pnr = true)]
     pub pnr: String,
 
     /// CPR check
@@ -62,29 +62,3 @@ pub fn deserialize_batch(
     // Use the inner deserializer to deserialize the batch
     deserializer.inner.deserialize_batch(batch)
 }
-
-// Implement RegisterLoader for the macro-generated deserializer
-impl crate::registry::RegisterLoader for UddfRegistryDeserializer {
-    /// Get the name of the register
-    fn get_register_name(&self) -> &'static str {
-        "UDDF"
-    }
-
-    /// Get the schema for this register
-    fn get_schema(&self) -> crate::SchemaRef {
-        // Create a simple Arrow schema for UDDF
-        let fields = vec![
-            arrow::datatypes::Field::new("PNR", arrow::datatypes::DataType::Utf8, false),
-            arrow::datatypes::Field::new("CPRTJEK", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("CPRTYPE", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("HFAUDD", arrow::datatypes::DataType::Int32, true),
-            arrow::datatypes::Field::new("HF_KILDE", arrow::datatypes::DataType::Utf8, true),
-            arrow::datatypes::Field::new("HF_VFRA", arrow::datatypes::DataType::Date32, true),
-            arrow::datatypes::Field::new("HF_VTIL", arrow::datatypes::DataType::Date32, true),
-            arrow::datatypes::Field::new("INSTNR", arrow::datatypes::DataType::Int32, true),
-            arrow::datatypes::Field::new("VERSION", arrow::datatypes::DataType::Utf8, true),
-        ];
-
-        std::sync::Arc::new(arrow::datatypes::Schema::new(fields))
-    }
-}
\ No newline at end of file
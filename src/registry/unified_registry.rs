@@ -33,11 +33,10 @@ pub fn create_loader(
         original_schema
     };
 
-    // Create the loader with specific implementation - we use concrete type instead of trait
-    // Pass pnr_column directly since with_pnr_column takes ownership through impl Into<String>
-    let loader = Arc::new(
-        crate::async_io::Loader::with_schema_ref(schema.clone()).with_pnr_column(pnr_column),
-    );
+    let loader = Arc::new(crate::registry::builder::pnr_filterable_loader(
+        schema.clone(),
+        pnr_column,
+    ));
 
     (schema, loader)
 }
@@ -7,14 +7,25 @@ use crate::RecordBatch;
 use crate::SchemaRef;
 use crate::error::{Error, Result};
 use crate::pnr_filter::{apply_filter_plan, build_filter_plan};
-use crate::registry::{RegisterLoader};
+use crate::registry::{PnrFilterRequirement, RegisterLoader, load_pnr_set};
 use crate::registry::factory::{registry_from_name, registry_from_path};
-
+use crate::schema::ValueLabelSet;
+use crate::utils::io::LoadMetrics;
+use crate::utils::io::paths::find_registry_dir;
+use crate::utils::logging::LoadSpan;
+
+use arrow::array::{Array, Date32Array, StringArray};
+use arrow::datatypes::DataType;
+use arrow::temporal_conversions::date32_to_datetime;
+use chrono::NaiveDate;
 use futures::future::join_all;
 use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 /// Manager for working with multiple Danish registry data sources
 ///
@@ -33,6 +44,10 @@ pub struct RegistryManager {
     max_cache_entries: usize,
     /// Join relationships between registries
     joins: HashMap<String, (String, String, String)>, // (child, parent, parent_column, child_column)
+    /// Metrics accumulated over uncached loads
+    metrics: Mutex<LoadMetrics>,
+    /// Value labels of categorical columns, for the data dictionary
+    value_labels: RwLock<ValueLabelSet>,
 }
 
 impl RegistryManager {
@@ -46,6 +61,8 @@ impl RegistryManager {
             filtered_cache: Mutex::new(HashMap::new()),
             max_cache_entries: 20, // Default cache size
             joins: HashMap::new(),
+            metrics: Mutex::new(LoadMetrics::default()),
+            value_labels: RwLock::new(ValueLabelSet::new()),
         }
     }
 
@@ -59,6 +76,8 @@ impl RegistryManager {
             filtered_cache: Mutex::new(HashMap::new()),
             max_cache_entries: 20,
             joins,
+            metrics: Mutex::new(LoadMetrics::default()),
+            value_labels: RwLock::new(ValueLabelSet::new()),
         }
     }
 
@@ -145,7 +164,14 @@ impl RegistryManager {
             .clone();
 
         // Load the data
+        let span = LoadSpan::registry(name);
+        let guard = span.enter();
+        let start = Instant::now();
         let data = loader.load(&path, None)?;
+        self.record_load(name, &data, start.elapsed());
+        let rows: usize = data.iter().map(RecordBatch::num_rows).sum();
+        span.record("rows", rows as u64);
+        drop(guard);
 
         // Cache the data
         let mut cache = self.data_cache.write().map_err(|_| {
@@ -203,7 +229,12 @@ impl RegistryManager {
         // Now we can safely use await since we no longer hold any read locks
 
         // Load the data asynchronously
-        let data = loader.load_async(&path, None).await?;
+        let span = LoadSpan::registry(name);
+        let start = Instant::now();
+        let data = span.in_scope_async(loader.load_async(&path, None)).await?;
+        self.record_load(name, &data, start.elapsed());
+        let rows: usize = data.iter().map(RecordBatch::num_rows).sum();
+        span.record("rows", rows as u64);
 
         // Cache the data
         let mut cache = self.data_cache.write().map_err(|_| {
@@ -314,11 +345,13 @@ impl RegistryManager {
         let schemas = self.get_schemas(names)?;
         let joins = self.get_joins_for_registries(names);
         let pnr_columns = self.get_pnr_columns(names)?;
+        let requirements = self.get_pnr_filter_requirements(names)?;
 
-        let plan = build_filter_plan(&schemas, &joins, &pnr_columns);
+        let plan = build_filter_plan(&schemas, &joins, &pnr_columns, &requirements);
 
         // Apply the filter plan
-        let filtered_data = apply_filter_plan(&plan, &data, pnr_filter)?;
+        let base_dir = self.get_base_dir(names);
+        let filtered_data = apply_filter_plan(&plan, &data, pnr_filter, base_dir.as_deref())?;
 
         // Cache the filtered data
         {
@@ -379,16 +412,17 @@ impl RegistryManager {
         }
 
         // Prepare the filter plan data before calling async operations
-        let (schemas, joins, pnr_columns) = {
+        let (schemas, joins, pnr_columns, requirements) = {
             let schemas = self.get_schemas(names)?;
             let joins = self.get_joins_for_registries(names);
             let pnr_columns = self.get_pnr_columns(names)?;
+            let requirements = self.get_pnr_filter_requirements(names)?;
 
-            (schemas, joins, pnr_columns)
+            (schemas, joins, pnr_columns, requirements)
         };
 
         // Build the filter plan before any async operations
-        let plan = build_filter_plan(&schemas, &joins, &pnr_columns);
+        let plan = build_filter_plan(&schemas, &joins, &pnr_columns, &requirements);
 
         // Not cached, load and filter the data asynchronously
         // Use string slices as the names to pass to load_multiple_async
@@ -396,7 +430,9 @@ impl RegistryManager {
         let data = self.load_multiple_async(&name_refs).await?;
 
         // Apply the filter plan
-        let filtered_data = apply_filter_plan(&plan, &data, &pnr_filter_cloned)?;
+        let base_dir = self.get_base_dir(&name_refs);
+        let filtered_data =
+            apply_filter_plan(&plan, &data, &pnr_filter_cloned, base_dir.as_deref())?;
 
         // Cache the filtered data
         {
@@ -446,6 +482,145 @@ impl RegistryManager {
         Ok(loader.get_schema())
     }
 
+    /// Summarize the loaded registries
+    ///
+    /// Only registries whose data has been loaded (and is still cached) are
+    /// included; registering a source alone does not read it.
+    ///
+    /// # Returns
+    /// Row, column, date range and distinct PNR counts per loaded registry
+    #[must_use]
+    pub fn summary(&self) -> DatasetSummary {
+        let Ok(cache) = self.data_cache.read() else {
+            return DatasetSummary::default();
+        };
+        let loaders = self.loaders.read().ok();
+
+        let mut registries: Vec<RegistrySummary> = cache
+            .iter()
+            .map(|(name, batches)| {
+                let pnr_column = loaders
+                    .as_ref()
+                    .and_then(|loaders| loaders.get(name))
+                    .and_then(|loader| loader.get_pnr_column_name());
+                RegistrySummary::from_batches(name, batches, pnr_column)
+            })
+            .collect();
+        registries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        DatasetSummary { registries }
+    }
+
+    /// Get the metrics accumulated over all loads so far
+    ///
+    /// Each registry's wall-clock load time is recorded in
+    /// `registry_timings`, and the rows it returned in `rows_returned`.
+    /// Loads served from the cache are not counted.
+    #[must_use]
+    pub fn load_metrics(&self) -> LoadMetrics {
+        self.metrics
+            .lock()
+            .map(|metrics| metrics.clone())
+            .unwrap_or_default()
+    }
+
+    /// Set the value labels of categorical columns
+    ///
+    /// The labels document the columns of [`Self::data_dictionary`]; they do
+    /// not change the loaded data.
+    pub fn set_value_labels(&self, labels: ValueLabelSet) -> Result<()> {
+        *self.value_labels.write().map_err(|_| {
+            Error::InvalidOperation("Failed to acquire write lock on value labels".to_string())
+        })? = labels;
+        Ok(())
+    }
+
+    /// Describe the columns of the registered registries
+    ///
+    /// A registry whose data has been loaded (and is still cached) is
+    /// described by the columns of that data, so the dictionary records the
+    /// types actually loaded; others by the columns their loader declares.
+    ///
+    /// # Returns
+    /// One entry per registry and column, sorted by registry name, with the
+    /// columns of a registry in schema order
+    pub fn data_dictionary(&self) -> Result<Vec<DataDictionaryEntry>> {
+        let loaders = self.loaders.read().map_err(|_| {
+            Error::InvalidOperation("Failed to acquire read lock on loaders".to_string())
+        })?;
+        let cache = self.data_cache.read().map_err(|_| {
+            Error::InvalidOperation("Failed to acquire read lock on data cache".to_string())
+        })?;
+        let labels = self.value_labels.read().map_err(|_| {
+            Error::InvalidOperation("Failed to acquire read lock on value labels".to_string())
+        })?;
+
+        let mut names: Vec<&String> = loaders.keys().collect();
+        names.sort();
+
+        let mut entries = Vec::new();
+        for name in names {
+            let loader = &loaders[name];
+            let schema = cache
+                .get(name)
+                .and_then(|batches| batches.first())
+                .map_or_else(|| loader.get_schema(), RecordBatch::schema);
+            let field_mapping = loader.get_field_mapping();
+
+            entries.extend(schema.fields().iter().map(|field| {
+                DataDictionaryEntry {
+                    registry: name.clone(),
+                    source_name: field.name().clone(),
+                    canonical_name: field_mapping.get(field.name()).cloned(),
+                    data_type: field.data_type().to_string(),
+                    nullable: field.is_nullable(),
+                    value_labels: labels
+                        .column_labels(field.name())
+                        .into_iter()
+                        .map(|(code, label)| (code.to_string(), label.to_string()))
+                        .collect(),
+                }
+            }));
+        }
+
+        Ok(entries)
+    }
+
+    /// Write the data dictionary to a `.csv` or `.json` file
+    ///
+    /// The CSV has one row per [`DataDictionaryEntry`], with the value labels
+    /// as `code=label` pairs separated by `"; "`. The JSON is an array of the
+    /// entries.
+    ///
+    /// # Errors
+    /// Returns an error if the path has another extension or cannot be written
+    pub fn export_data_dictionary(&self, path: &Path) -> Result<()> {
+        let entries = self.data_dictionary()?;
+        let content = match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => {
+                data_dictionary_csv(&entries)?
+            }
+            Some(extension) if extension.eq_ignore_ascii_case("json") => {
+                serde_json::to_string_pretty(&entries)?
+            }
+            _ => {
+                return Err(Error::ValidationError(format!(
+                    "Data dictionary must be a .csv or .json file: {}",
+                    path.display()
+                ))
+                .into());
+            }
+        };
+
+        std::fs::write(path, content).map_err(|e| {
+            Error::IoError(format!(
+                "Failed to write data dictionary {}: {e}",
+                path.display()
+            ))
+        })?;
+        Ok(())
+    }
+
     /// Set the cache size limit
     pub const fn set_cache_size(&mut self, size: usize) {
         self.max_cache_entries = size;
@@ -485,6 +660,17 @@ impl RegistryManager {
         format!("{}_{}", prefix, pnr_filter.len())
     }
 
+    /// Record the timing and row count of an uncached load
+    fn record_load(&self, name: &str, data: &[RecordBatch], elapsed: Duration) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            metrics.record_registry(name, elapsed);
+            metrics.rows_returned += data
+                .iter()
+                .map(|batch| batch.num_rows() as u64)
+                .sum::<u64>();
+        }
+    }
+
     /// Evict entries from the cache
     fn evict_cache(&self, cache: &mut HashMap<String, Vec<RecordBatch>>) {
         // Simple eviction strategy: remove oldest entries (first 25%)
@@ -561,6 +747,39 @@ impl RegistryManager {
         Ok(pnr_columns)
     }
 
+    /// Get how the specified registries can be filtered by PNR
+    fn get_pnr_filter_requirements(
+        &self,
+        names: &[&str],
+    ) -> Result<HashMap<String, PnrFilterRequirement>> {
+        let loaders = self.loaders.read().map_err(|_| {
+            Error::InvalidOperation("Failed to acquire read lock on loaders".to_string())
+        })?;
+
+        names
+            .iter()
+            .map(|&name| {
+                let loader = loaders.get(name).ok_or_else(|| {
+                    Error::ValidationError(format!("No loader registered for {name}"))
+                })?;
+                Ok((name.to_string(), loader.pnr_filter_requirement()))
+            })
+            .collect()
+    }
+
+    /// Get the directory auxiliary registries of a filter plan are loaded from
+    ///
+    /// Registries are expected to be folders of one directory, so this is the
+    /// directory holding the first of the specified registries.
+    fn get_base_dir(&self, names: &[&str]) -> Option<PathBuf> {
+        let paths = self.paths.read().ok()?;
+        names
+            .iter()
+            .find_map(|&name| paths.get(name))
+            .and_then(|path| path.parent())
+            .map(Path::to_path_buf)
+    }
+
     /// Get join relationships for the specified registries
     fn get_joins_for_registries(&self, names: &[&str]) -> HashMap<String, (String, String)> {
         let mut joins = HashMap::new();
@@ -582,3 +801,364 @@ impl Default for RegistryManager {
         Self::new()
     }
 }
+
+/// Load the PNRs present in every one of several registries
+///
+/// Each registry's PNR set is read with [`load_pnr_set`] from its folder
+/// under `base_dir` (see [`find_registry_dir`]) and intersected with the
+/// PNRs of the registries before it. The number of PNRs left after each
+/// registry is logged, so the reduction of the cohort can be followed.
+///
+/// # Arguments
+/// * `registries` - The registry names, e.g. `&["BEF", "IND"]`
+/// * `base_dir` - The directory holding one folder per registry
+///
+/// # Returns
+/// The PNRs present in all the registries
+///
+/// # Errors
+/// Returns an error if no registries are given, if a registry is unknown or
+/// has no folder under `base_dir`, or if its PNR set cannot be loaded
+pub fn load_intersection(registries: &[&str], base_dir: &Path) -> Result<HashSet<String>> {
+    let mut intersection: Option<HashSet<String>> = None;
+    for registry in registries {
+        let loader = registry_from_name(registry)?;
+        let path = find_registry_dir(base_dir, registry).ok_or_else(|| {
+            Error::InvalidOperation(format!(
+                "No folder of registry {registry} under {}",
+                base_dir.display()
+            ))
+        })?;
+        let pnrs = load_pnr_set(loader.as_ref(), &path)?;
+
+        intersection = Some(match intersection {
+            None => {
+                log::info!("{registry}: {} PNRs", pnrs.len());
+                pnrs
+            }
+            Some(previous) => {
+                let remaining: HashSet<String> = previous
+                    .iter()
+                    .filter(|pnr| pnrs.contains(*pnr))
+                    .cloned()
+                    .collect();
+                log::info!(
+                    "{registry}: {} PNRs, {} of {} remaining ({} removed)",
+                    pnrs.len(),
+                    remaining.len(),
+                    previous.len(),
+                    previous.len() - remaining.len()
+                );
+                remaining
+            }
+        });
+    }
+
+    Ok(intersection.ok_or_else(|| {
+        Error::InvalidOperation("No registries to intersect the PNRs of".to_string())
+    })?)
+}
+
+/// One column of a registry, as described by [`RegistryManager::data_dictionary`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DataDictionaryEntry {
+    /// Registry name
+    pub registry: String,
+    /// Column name in the source files
+    pub source_name: String,
+    /// Name the column is deserialized to, if the loader maps it
+    pub canonical_name: Option<String>,
+    /// Arrow data type
+    pub data_type: String,
+    /// Whether the column may contain nulls
+    pub nullable: bool,
+    /// Labels of the column's codes, by code
+    pub value_labels: BTreeMap<String, String>,
+}
+
+/// Render data dictionary entries as CSV
+fn data_dictionary_csv(entries: &[DataDictionaryEntry]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "registry",
+        "source_name",
+        "canonical_name",
+        "data_type",
+        "nullable",
+        "value_labels",
+    ])?;
+    for entry in entries {
+        let value_labels = entry
+            .value_labels
+            .iter()
+            .map(|(code, label)| format!("{code}={label}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        writer.write_record([
+            entry.registry.as_str(),
+            &entry.source_name,
+            entry.canonical_name.as_deref().unwrap_or_default(),
+            &entry.data_type,
+            if entry.nullable { "true" } else { "false" },
+            &value_labels,
+        ])?;
+    }
+    let csv = writer
+        .into_inner()
+        .map_err(|e| Error::IoError(format!("Failed to render data dictionary: {e}")))?;
+    Ok(String::from_utf8(csv)?)
+}
+
+/// Overview of a single loaded registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistrySummary {
+    /// Registry name
+    pub name: String,
+    /// Number of rows across all batches
+    pub rows: usize,
+    /// Number of columns
+    pub columns: usize,
+    /// The date column the range was computed from (the first date column)
+    pub date_column: Option<String>,
+    /// Earliest and latest non-null date in the date column
+    pub date_range: Option<(NaiveDate, NaiveDate)>,
+    /// Number of distinct non-null PNRs, if the registry has a PNR column
+    pub distinct_pnrs: Option<usize>,
+}
+
+impl RegistrySummary {
+    /// Summarize the batches of a registry
+    ///
+    /// # Arguments
+    /// * `name` - The registry name
+    /// * `batches` - The registry's record batches
+    /// * `pnr_column` - The PNR column, if the registry has one
+    ///
+    /// # Returns
+    /// The registry summary
+    #[must_use]
+    pub fn from_batches(name: &str, batches: &[RecordBatch], pnr_column: Option<&str>) -> Self {
+        let schema = batches.first().map(RecordBatch::schema);
+        let date_column = schema.as_ref().and_then(|schema| {
+            schema
+                .fields()
+                .iter()
+                .find(|field| field.data_type() == &DataType::Date32)
+                .map(|field| field.name().clone())
+        });
+
+        let mut date_range: Option<(i32, i32)> = None;
+        let mut pnrs: Option<HashSet<&str>> = pnr_column
+            .filter(|column| {
+                schema
+                    .as_ref()
+                    .is_some_and(|schema| schema.field_with_name(column).is_ok())
+            })
+            .map(|_| HashSet::new());
+
+        for batch in batches {
+            if let Some(dates) = date_column
+                .as_deref()
+                .and_then(|column| batch.column_by_name(column))
+                .and_then(|array| array.as_any().downcast_ref::<Date32Array>())
+                && let (Some(min), Some(max)) =
+                    (arrow::compute::min(dates), arrow::compute::max(dates))
+            {
+                date_range =
+                    Some(date_range.map_or((min, max), |(lo, hi)| (lo.min(min), hi.max(max))));
+            }
+
+            if let Some(pnrs) = pnrs.as_mut()
+                && let Some(values) = pnr_column
+                    .and_then(|column| batch.column_by_name(column))
+                    .and_then(|array| array.as_any().downcast_ref::<StringArray>())
+            {
+                pnrs.extend(values.iter().flatten());
+            }
+        }
+
+        let to_date = |days: i32| date32_to_datetime(days).map(|datetime| datetime.date());
+
+        Self {
+            name: name.to_string(),
+            rows: batches.iter().map(RecordBatch::num_rows).sum(),
+            columns: schema.map_or(0, |schema| schema.fields().len()),
+            date_column,
+            date_range: date_range.and_then(|(min, max)| Some((to_date(min)?, to_date(max)?))),
+            distinct_pnrs: pnrs.map(|pnrs| pnrs.len()),
+        }
+    }
+}
+
+/// Overview of the registries loaded by a [`RegistryManager`]
+///
+/// The `Display` impl renders one line per registry, for a quick sanity
+/// check after loading a study population.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatasetSummary {
+    /// Per-registry summaries, sorted by registry name
+    pub registries: Vec<RegistrySummary>,
+}
+
+impl DatasetSummary {
+    /// Total number of rows across all registries
+    #[must_use]
+    pub fn total_rows(&self) -> usize {
+        self.registries.iter().map(|registry| registry.rows).sum()
+    }
+}
+
+impl fmt::Display for DatasetSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.registries.is_empty() {
+            return writeln!(f, "No registries loaded");
+        }
+
+        writeln!(
+            f,
+            "{:<16} {:>12} {:>8}  {:<36} {:>12}",
+            "Registry", "Rows", "Columns", "Date range", "PNRs"
+        )?;
+        for registry in &self.registries {
+            let date_range = match (&registry.date_column, registry.date_range) {
+                (Some(column), Some((min, max))) => format!("{column}: {min} - {max}"),
+                (Some(column), None) => format!("{column}: -"),
+                (None, _) => "-".to_string(),
+            };
+            let pnrs = registry
+                .distinct_pnrs
+                .map_or_else(|| "-".to_string(), |count| count.to_string());
+            writeln!(
+                f,
+                "{:<16} {:>12} {:>8}  {:<36} {:>12}",
+                registry.name, registry.rows, registry.columns, date_range, pnrs
+            )?;
+        }
+        writeln!(
+            f,
+            "{} registries, {} rows",
+            self.registries.len(),
+            self.total_rows()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::ArrayRef;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_registry_summary() {
+        let batch = |pnrs: Vec<Option<&str>>, dates: Vec<Option<i32>>| {
+            RecordBatch::try_from_iter(vec![
+                ("PNR", Arc::new(StringArray::from(pnrs)) as ArrayRef),
+                ("FOED_DAG", Arc::new(Date32Array::from(dates)) as ArrayRef),
+            ])
+            .unwrap()
+        };
+        let batches = vec![
+            batch(vec![Some("1"), Some("2")], vec![Some(365), None]),
+            batch(vec![Some("2"), None], vec![Some(0), Some(730)]),
+        ];
+
+        let summary = RegistrySummary::from_batches("BEF", &batches, Some("PNR"));
+        assert_eq!(summary.rows, 4);
+        assert_eq!(summary.columns, 2);
+        assert_eq!(summary.date_column.as_deref(), Some("FOED_DAG"));
+        assert_eq!(
+            summary.date_range,
+            Some((
+                NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(1972, 1, 1).unwrap()
+            ))
+        );
+        assert_eq!(summary.distinct_pnrs, Some(2));
+
+        let dataset = DatasetSummary {
+            registries: vec![summary],
+        };
+        let text = dataset.to_string();
+        assert!(text.contains("FOED_DAG: 1970-01-01 - 1972-01-01"));
+        assert!(text.contains("1 registries, 4 rows"));
+        assert_eq!(RegistryManager::new().summary(), DatasetSummary::default());
+    }
+
+    #[test]
+    fn test_load_intersection() {
+        use parquet::arrow::ArrowWriter;
+
+        let dir =
+            std::env::temp_dir().join(format!("par_reader_intersection_{}", std::process::id()));
+        for (registry, pnrs) in [
+            ("bef", vec!["0101801234", "0202801234", "0303801234"]),
+            ("IND", vec!["0202801234", "0303801234", "0404801234"]),
+        ] {
+            std::fs::create_dir_all(dir.join(registry)).unwrap();
+            let batch = RecordBatch::try_from_iter(vec![(
+                "PNR",
+                Arc::new(StringArray::from(pnrs)) as ArrayRef,
+            )])
+            .unwrap();
+            let file = std::fs::File::create(dir.join(registry).join("2020.parquet")).unwrap();
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+        }
+
+        assert_eq!(
+            load_intersection(&["BEF", "IND"], &dir).unwrap(),
+            HashSet::from(["0202801234".to_string(), "0303801234".to_string()])
+        );
+        assert_eq!(load_intersection(&["BEF"], &dir).unwrap().len(), 3);
+        assert!(load_intersection(&[], &dir).is_err());
+        let error = load_intersection(&["BEF", "AKM"], &dir).unwrap_err();
+        assert!(error.to_string().contains("No folder of registry AKM"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_data_dictionary() {
+        let manager = RegistryManager::new();
+        manager.register("AKM", Path::new("akm")).unwrap();
+        let mut labels = ValueLabelSet::new();
+        labels.insert("SOCIO13", "110", "Employee, basic level");
+        manager.set_value_labels(labels).unwrap();
+
+        let entries = manager.data_dictionary().unwrap();
+        let socio13 = entries
+            .iter()
+            .find(|entry| entry.source_name == "SOCIO13")
+            .unwrap();
+        assert_eq!(socio13.registry, "AKM");
+        assert!(socio13.canonical_name.is_some());
+        assert_eq!(socio13.value_labels["110"], "Employee, basic level");
+
+        let dir =
+            std::env::temp_dir().join(format!("par_reader_dictionary_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        manager
+            .export_data_dictionary(&dir.join("dictionary.csv"))
+            .unwrap();
+        let csv = std::fs::read_to_string(dir.join("dictionary.csv")).unwrap();
+        assert!(csv.starts_with("registry,source_name,"));
+        assert!(csv.contains("\"110=Employee, basic level\""));
+
+        manager
+            .export_data_dictionary(&dir.join("dictionary.json"))
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("dictionary.json")).unwrap())
+                .unwrap();
+        assert_eq!(json.as_array().unwrap().len(), entries.len());
+
+        assert!(
+            manager
+                .export_data_dictionary(&dir.join("dictionary.txt"))
+                .is_err()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
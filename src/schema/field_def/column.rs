@@ -0,0 +1,75 @@
+//! Conversion of registry struct fields back to Arrow arrays
+//!
+//! The field extractors read each column of a registry into the field of its
+//! registry struct. [`IntoArrowArray`] goes the other way: it builds the
+//! column of a field from the values of many records, so records can be
+//! serialized with the schema they were read with.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, Date32Array, Float64Array, Int32Array, StringArray, Time32SecondArray,
+};
+use arrow::datatypes::Date32Type;
+use chrono::{NaiveDate, NaiveTime, Timelike};
+
+/// A field type that can be collected into the Arrow array of its column
+pub trait IntoArrowArray: Sized {
+    /// Build the array of a column from the values of its field
+    ///
+    /// The array has the type of [`FieldType::to_arrow_type`](super::FieldType::to_arrow_type)
+    /// for the field, and `None` values become nulls.
+    fn into_array(values: Vec<Self>) -> ArrayRef;
+}
+
+/// Implement [`IntoArrowArray`] for a type and its `Option`
+macro_rules! into_arrow_array {
+    ($type:ty, $array:ty, $convert:expr) => {
+        impl IntoArrowArray for Option<$type> {
+            fn into_array(values: Vec<Self>) -> ArrayRef {
+                let convert = $convert;
+                Arc::new(
+                    values
+                        .into_iter()
+                        .map(|value| value.and_then(convert))
+                        .collect::<$array>(),
+                )
+            }
+        }
+
+        impl IntoArrowArray for $type {
+            fn into_array(values: Vec<Self>) -> ArrayRef {
+                <Option<$type>>::into_array(values.into_iter().map(Some).collect())
+            }
+        }
+    };
+}
+
+into_arrow_array!(String, StringArray, Some);
+into_arrow_array!(i32, Int32Array, Some);
+into_arrow_array!(f64, Float64Array, Some);
+into_arrow_array!(NaiveDate, Date32Array, |date| Some(
+    Date32Type::from_naive_date(date)
+));
+into_arrow_array!(NaiveTime, Time32SecondArray, |time: NaiveTime| {
+    i32::try_from(time.num_seconds_from_midnight()).ok()
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Array;
+
+    #[test]
+    fn test_into_array_keeps_nulls() {
+        let dates =
+            <Option<NaiveDate>>::into_array(vec![NaiveDate::from_ymd_opt(2019, 3, 15), None]);
+        let dates = dates.as_any().downcast_ref::<Date32Array>().unwrap();
+        assert_eq!(dates.value_as_date(0), NaiveDate::from_ymd_opt(2019, 3, 15));
+        assert!(dates.is_null(1));
+
+        let pnrs = String::into_array(vec!["0101801234".to_string()]);
+        assert_eq!(pnrs.null_count(), 0);
+        assert_eq!(pnrs.len(), 1);
+    }
+}
@@ -4,12 +4,14 @@
 //! their data types, and mappings to the Individual model.
 
 mod adapt;
+mod column;
 pub mod field;
 mod macros;
 pub mod mapping;
 mod registry_schema;
 
 pub use adapt::SchemaAdapter;
+pub use column::IntoArrowArray;
 pub use field::{FieldDefinition, FieldType};
 pub use mapping::{Extractors, FieldMapping, ModelSetter, ModelSetters};
 pub use registry_schema::{RegistrySchema, create_registry_schema};
@@ -8,6 +8,8 @@ use parquet::schema::types::Type;
 pub mod adapt;
 // New field definition system
 pub mod field_def;
+// Code-to-label tables of categorical columns
+pub mod value_labels;
 
 // Re-export the main adaptation types and functions for easier access
 pub use adapt::{
@@ -19,6 +21,7 @@ pub use adapt::{
 pub use field_def::{
     FieldDefinition, FieldType, FieldMapping, ModelSetter, RegistrySchema, create_registry_schema,
 };
+pub use value_labels::ValueLabelSet;
 
 /// A struct that represents the compatibility between parquet file schemas
 #[derive(Debug)]
@@ -0,0 +1,332 @@
+//! Value labels of categorical registry columns
+//!
+//! Registry documentation ships code-to-label tables for categorical
+//! columns, such as `SOCIO13` code `110` for "Employee, basic level", in the
+//! manner of Stata and SAS value labels. A [`ValueLabelSet`] holds such
+//! tables, loaded from CSV or JSON, and decodes codes for display: as a label
+//! column next to the codes of a batch, or as the categories of a
+//! [`CrossTab`](crate::collections::CrossTab). Codes without a label keep
+//! their raw value.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use serde::Deserialize;
+
+use crate::error::{ParquetReaderError, Result};
+use crate::utils::arrow::array_utils::{downcast_array, get_column};
+
+/// Suffix of the label column added by [`ValueLabelSet::with_label_column`]
+pub const LABEL_COLUMN_SUFFIX: &str = "_LABEL";
+
+/// Code-to-label tables of categorical columns
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValueLabelSet {
+    /// Labels by column, then code
+    labels: HashMap<String, HashMap<String, String>>,
+}
+
+/// One `(column, code, label)` entry of a JSON label file
+#[derive(Deserialize)]
+struct ValueLabelEntry {
+    column: String,
+    code: serde_json::Value,
+    label: String,
+}
+
+impl ValueLabelSet {
+    /// Create an empty label set
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the label of a code, replacing any previous label
+    pub fn insert(
+        &mut self,
+        column: impl Into<String>,
+        code: impl Into<String>,
+        label: impl Into<String>,
+    ) {
+        self.labels
+            .entry(column.into())
+            .or_default()
+            .insert(code.into(), label.into());
+    }
+
+    /// Load labels from a `.csv` or `.json` file
+    ///
+    /// See [`Self::from_csv_str`] and [`Self::from_json_str`] for the formats.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, has another extension,
+    /// or is malformed
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ParquetReaderError::IoError(format!(
+                "Failed to read value labels {}: {e}",
+                path.display()
+            ))
+        })?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => {
+                Self::from_csv_str(&content)
+            }
+            Some(extension) if extension.eq_ignore_ascii_case("json") => {
+                Self::from_json_str(&content)
+            }
+            _ => Err(ParquetReaderError::ValidationError(format!(
+                "Value labels must be a .csv or .json file: {}",
+                path.display()
+            ))
+            .into()),
+        }
+    }
+
+    /// Parse labels from CSV
+    ///
+    /// The header names the `column`, `code` and `label` fields, in any
+    /// order and case; other fields are ignored. Fields may be quoted, so
+    /// labels can contain commas, and surrounding whitespace is trimmed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CSV is malformed, a header field is missing or
+    /// a row is too short
+    pub fn from_csv_str(content: &str) -> Result<Self> {
+        let invalid = |e: csv::Error| {
+            ParquetReaderError::ValidationError(format!("Invalid value label CSV: {e}"))
+        };
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+
+        let header = reader.headers().map_err(invalid)?.clone();
+        let position = |name: &str| {
+            header
+                .iter()
+                .position(|field| field.eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    ParquetReaderError::ValidationError(format!(
+                        "Value label CSV has no '{name}' field"
+                    ))
+                })
+        };
+        let (column, code, label) = (position("column")?, position("code")?, position("label")?);
+
+        let mut labels = Self::new();
+        for (index, record) in reader.records().enumerate() {
+            let record = record.map_err(invalid)?;
+            let field = |position: usize| {
+                record.get(position).ok_or_else(|| {
+                    ParquetReaderError::ValidationError(format!(
+                        "Value label CSV row {} has {} fields, expected at least {}",
+                        index + 1,
+                        record.len(),
+                        column.max(code).max(label) + 1
+                    ))
+                })
+            };
+            labels.insert(field(column)?, field(code)?, field(label)?);
+        }
+        Ok(labels)
+    }
+
+    /// Parse labels from JSON
+    ///
+    /// The JSON is an array of `{"column": .., "code": .., "label": ..}`
+    /// objects. Codes may be strings or numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON does not have this form
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        let entries: Vec<ValueLabelEntry> = serde_json::from_str(content).map_err(|e| {
+            ParquetReaderError::ValidationError(format!("Invalid value label JSON: {e}"))
+        })?;
+
+        let mut labels = Self::new();
+        for entry in entries {
+            let code = match entry.code {
+                serde_json::Value::String(code) => code,
+                serde_json::Value::Number(code) => code.to_string(),
+                other => {
+                    return Err(ParquetReaderError::ValidationError(format!(
+                        "Value label code of column {} must be a string or number, got {other}",
+                        entry.column
+                    ))
+                    .into());
+                }
+            };
+            labels.insert(entry.column, code, entry.label);
+        }
+        Ok(labels)
+    }
+
+    /// Check whether a column has labels
+    #[must_use]
+    pub fn has_column(&self, column: &str) -> bool {
+        self.labels.contains_key(column)
+    }
+
+    /// Get the labels of a column, sorted by code
+    #[must_use]
+    pub fn column_labels(&self, column: &str) -> BTreeMap<&str, &str> {
+        self.labels
+            .get(column)
+            .into_iter()
+            .flatten()
+            .map(|(code, label)| (code.as_str(), label.as_str()))
+            .collect()
+    }
+
+    /// Get the label of a code
+    #[must_use]
+    pub fn label(&self, column: &str, code: &str) -> Option<&str> {
+        self.labels
+            .get(column)
+            .and_then(|codes| codes.get(code))
+            .map(String::as_str)
+    }
+
+    /// Get the label of a code, or the code itself if it has no label
+    #[must_use]
+    pub fn decode<'a>(&'a self, column: &str, code: &'a str) -> &'a str {
+        self.label(column, code).unwrap_or(code)
+    }
+
+    /// Decode a code into a type parsed from its label
+    ///
+    /// Like [`Self::decode`], a code without a label is parsed as is.
+    ///
+    /// # Returns
+    ///
+    /// The parsed value, or `None` if the label does not parse
+    #[must_use]
+    pub fn decode_as<T: FromStr>(&self, column: &str, code: &str) -> Option<T> {
+        self.decode(column, code).parse().ok()
+    }
+
+    /// Decode the codes of a batch column
+    ///
+    /// Codes of any type are read by their string form, so numeric codes
+    /// match labels of e.g. `"110"`. Nulls stay null.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column is missing or cannot be read as strings
+    pub fn label_array(&self, batch: &RecordBatch, column: &str) -> Result<ArrayRef> {
+        let array =
+            get_column(batch, column, &DataType::Utf8, true)?.expect("required column is present");
+        let codes = downcast_array::<StringArray>(&array, column, "Utf8")?;
+        let labels: StringArray = (0..codes.len())
+            .map(|row| {
+                codes
+                    .is_valid(row)
+                    .then(|| self.decode(column, codes.value(row)))
+            })
+            .collect();
+        Ok(Arc::new(labels))
+    }
+
+    /// Add a label column after the last column of a batch
+    ///
+    /// The column is named after the code column with
+    /// [`LABEL_COLUMN_SUFFIX`], e.g. `SOCIO13_LABEL`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::label_array`]
+    pub fn with_label_column(&self, batch: &RecordBatch, column: &str) -> Result<RecordBatch> {
+        let labels = self.label_array(batch, column)?;
+
+        let mut fields: Vec<Field> = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone())
+            .collect();
+        fields.push(Field::new(
+            format!("{column}{LABEL_COLUMN_SUFFIX}"),
+            DataType::Utf8,
+            true,
+        ));
+        let mut columns = batch.columns().to_vec();
+        columns.push(labels);
+
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int32Array;
+
+    #[test]
+    fn test_value_labels_from_csv_and_json() {
+        let csv = "column,code,label\nSOCIO13,110,\"Employee, basic level\"\nSOCIO13,310,Student\n";
+        let labels = ValueLabelSet::from_csv_str(csv).unwrap();
+        assert_eq!(
+            labels.label("SOCIO13", "110"),
+            Some("Employee, basic level")
+        );
+        assert_eq!(labels.decode("SOCIO13", "999"), "999");
+        assert_eq!(labels.decode("KOEN", "1"), "1");
+        assert_eq!(
+            labels
+                .column_labels("SOCIO13")
+                .into_keys()
+                .collect::<Vec<_>>(),
+            ["110", "310"]
+        );
+
+        let json = r#"[{"column": "SOCIO13", "code": 110, "label": "Employee, basic level"},
+                       {"column": "SOCIO13", "code": "310", "label": "Student"}]"#;
+        assert_eq!(ValueLabelSet::from_json_str(json).unwrap(), labels);
+
+        let mut numeric = ValueLabelSet::new();
+        numeric.insert("KOEN", "1", "2");
+        assert_eq!(numeric.decode_as::<u8>("KOEN", "1"), Some(2));
+
+        assert!(ValueLabelSet::from_csv_str("column,label\nSOCIO13,Student").is_err());
+        assert!(ValueLabelSet::from_csv_str("column,code,label\nSOCIO13,110").is_err());
+        let quoted =
+            ValueLabelSet::from_csv_str("column,code,label\nSOCIO13, 110 ,\"The \"\"Other\"\"\"")
+                .unwrap();
+        assert_eq!(quoted.label("SOCIO13", "110"), Some("The \"Other\""));
+    }
+
+    #[test]
+    fn test_with_label_column() {
+        let labels =
+            ValueLabelSet::from_csv_str("code,label,column\n110,\"Employee, basic level\",SOCIO13")
+                .unwrap();
+        let batch = RecordBatch::try_from_iter(vec![(
+            "SOCIO13",
+            Arc::new(Int32Array::from(vec![Some(110), Some(999), None])) as ArrayRef,
+        )])
+        .unwrap();
+
+        let labelled = labels.with_label_column(&batch, "SOCIO13").unwrap();
+        let column = labelled
+            .column_by_name("SOCIO13_LABEL")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(column.value(0), "Employee, basic level");
+        assert_eq!(column.value(1), "999");
+        assert!(column.is_null(2));
+    }
+}
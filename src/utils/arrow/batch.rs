@@ -0,0 +1,461 @@
+//! Utilities for combining and reshaping record batches
+//!
+//! Files of the same registry drift slightly between years: a column may be
+//! nullable in one file and not in another, or stored as `Int32` in old files
+//! and `Int64` in new ones. The helpers here reconcile such batches so they
+//! can be handled as one, and regroup rows into batches of a uniform size.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, new_null_array};
+use arrow::compute::{concat_batches, interleave_record_batch};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use crate::error::{Error, Result};
+use crate::schema::adapt::{
+    DateFormatConfig, TypeCompatibility, adapt_record_batch, check_type_compatibility, is_numeric,
+};
+
+/// Find the type both `a` and `b` can be widened to
+///
+/// Only numeric widening is performed (e.g. `Int32` and `Int64` to `Int64`,
+/// `Int64` and `Float32` to `Float64`); a `Null` column takes the other type.
+fn common_type(a: &DataType, b: &DataType) -> Option<DataType> {
+    if a == b || b == &DataType::Null {
+        return Some(a.clone());
+    }
+    if a == &DataType::Null {
+        return Some(b.clone());
+    }
+    if !is_numeric(a) || !is_numeric(b) {
+        return None;
+    }
+
+    if check_type_compatibility(a, b) == TypeCompatibility::Compatible {
+        Some(b.clone())
+    } else if check_type_compatibility(b, a) == TypeCompatibility::Compatible {
+        Some(a.clone())
+    } else if check_type_compatibility(a, &DataType::Float64) == TypeCompatibility::Compatible
+        && check_type_compatibility(b, &DataType::Float64) == TypeCompatibility::Compatible
+    {
+        // e.g. Int64 and UInt64, which have no lossless integer supertype
+        Some(DataType::Float64)
+    } else {
+        None
+    }
+}
+
+/// Compute a schema every batch can be adapted to
+///
+/// Fields appear in the order they are first seen. A field is nullable if it
+/// is nullable in any batch or missing from any batch.
+///
+/// # Errors
+/// Returns an error if a field has types that cannot be widened to a common type
+pub fn unify_schemas(schemas: &[SchemaRef]) -> Result<Schema> {
+    let mut fields: Vec<Field> = Vec::new();
+
+    for schema in schemas {
+        for field in schema.fields() {
+            if let Some(existing) = fields.iter_mut().find(|f| f.name() == field.name()) {
+                let data_type =
+                    common_type(existing.data_type(), field.data_type()).ok_or_else(|| {
+                        Error::SchemaError(format!(
+                            "Incompatible types for field '{}': {:?} and {:?}",
+                            field.name(),
+                            existing.data_type(),
+                            field.data_type()
+                        ))
+                    })?;
+                let nullable = existing.is_nullable() || field.is_nullable();
+                *existing = existing
+                    .clone()
+                    .with_data_type(data_type)
+                    .with_nullable(nullable);
+            } else {
+                fields.push(field.as_ref().clone());
+            }
+        }
+    }
+
+    for field in &mut fields {
+        if schemas
+            .iter()
+            .any(|schema| schema.field_with_name(field.name()).is_err())
+        {
+            field.set_nullable(true);
+        }
+    }
+
+    Ok(Schema::new(fields))
+}
+
+/// Concatenate batches with compatible but differing schemas
+///
+/// The batches are adapted to a common schema (see [`unify_schemas`]) using
+/// the schema adaptation module, then concatenated. Columns missing from a
+/// batch are filled with nulls.
+///
+/// # Arguments
+/// * `batches` - The batches to concatenate
+///
+/// # Returns
+/// A single batch with all rows, in order; an empty batch with no columns if
+/// `batches` is empty
+///
+/// # Errors
+/// Returns an error if a column has incompatible types across batches
+pub fn concat_coerced(batches: &[RecordBatch]) -> Result<RecordBatch> {
+    let schemas: Vec<SchemaRef> = batches.iter().map(RecordBatch::schema).collect();
+    let schema = Arc::new(unify_schemas(&schemas)?);
+
+    let date_config = DateFormatConfig::default();
+    let adapted = batches
+        .iter()
+        .map(|batch| {
+            if batch.schema() == schema {
+                Ok(batch.clone())
+            } else {
+                adapt_record_batch(batch, &schema, &date_config)
+            }
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(concat_batches(&schema, &adapted)?)
+}
+
+/// Concatenate batches under the union of their schemas
+///
+/// For registries that gained columns over time. Unlike [`concat_coerced`],
+/// no types are converted: a column must have the same type in every batch
+/// that has it, except that a `Null` column takes the type of the others.
+/// Fields appear in the order they are first seen, and columns missing from a
+/// batch are filled with nulls of the type of the batches defining them.
+///
+/// # Arguments
+/// * `batches` - The batches to concatenate
+///
+/// # Returns
+/// A single batch with all rows, in order; an empty batch with no columns if
+/// `batches` is empty
+///
+/// # Errors
+/// Returns an error naming the column and both types if a column has
+/// different types in two batches
+pub fn concat_schema_union(batches: &[RecordBatch]) -> Result<RecordBatch> {
+    let mut fields: Vec<Field> = Vec::new();
+    for batch in batches {
+        for field in batch.schema().fields() {
+            let Some(existing) = fields.iter_mut().find(|f| f.name() == field.name()) else {
+                fields.push(field.as_ref().clone());
+                continue;
+            };
+            if existing.data_type() == &DataType::Null {
+                existing.set_data_type(field.data_type().clone());
+            } else if field.data_type() != &DataType::Null
+                && existing.data_type() != field.data_type()
+            {
+                return Err(Error::SchemaError(format!(
+                    "Column '{}' has incompatible types {} and {}",
+                    field.name(),
+                    existing.data_type(),
+                    field.data_type()
+                ))
+                .into());
+            }
+            if field.is_nullable() {
+                existing.set_nullable(true);
+            }
+        }
+    }
+    for field in &mut fields {
+        if batches
+            .iter()
+            .any(|batch| batch.schema().field_with_name(field.name()).is_err())
+        {
+            field.set_nullable(true);
+        }
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    let aligned = batches
+        .iter()
+        .map(|batch| {
+            let columns: Vec<ArrayRef> = schema
+                .fields()
+                .iter()
+                .map(|field| match batch.column_by_name(field.name()) {
+                    Some(column) if column.data_type() == field.data_type() => column.clone(),
+                    // Missing, or a `Null` column
+                    _ => new_null_array(field.data_type(), batch.num_rows()),
+                })
+                .collect();
+            RecordBatch::try_new(schema.clone(), columns)
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(concat_batches(&schema, &aligned)?)
+}
+
+/// Re-slice batches into batches of a fixed number of rows
+///
+/// Rows are regrouped across batch boundaries so every output batch has
+/// exactly `rows_per_batch` rows except possibly the last. Only the rows of
+/// one output batch are copied at a time; the schema and column order are
+/// those of the input. Empty input yields no batches.
+///
+/// # Arguments
+/// * `batches` - The batches to rechunk, all with the same schema
+/// * `rows_per_batch` - Number of rows per output batch
+///
+/// # Returns
+/// The rechunked batches, with the rows in their original order
+///
+/// # Errors
+/// Returns an error if `rows_per_batch` is zero or the batches have
+/// different schemas
+pub fn rechunk(batches: &[RecordBatch], rows_per_batch: usize) -> Result<Vec<RecordBatch>> {
+    if rows_per_batch == 0 {
+        return Err(
+            Error::ValidationError("Rows per batch must be greater than zero".to_string()).into(),
+        );
+    }
+    let Some(schema) = batches.first().map(RecordBatch::schema) else {
+        return Ok(Vec::new());
+    };
+
+    let mut chunks = Vec::new();
+    let mut pending: Vec<RecordBatch> = Vec::new();
+    let mut pending_rows = 0;
+
+    for batch in batches {
+        let mut offset = 0;
+        while offset < batch.num_rows() {
+            let length = (rows_per_batch - pending_rows).min(batch.num_rows() - offset);
+            pending.push(batch.slice(offset, length));
+            pending_rows += length;
+            offset += length;
+
+            if pending_rows == rows_per_batch {
+                chunks.push(concat_batches(&schema, &pending)?);
+                pending.clear();
+                pending_rows = 0;
+            }
+        }
+    }
+    if pending_rows > 0 {
+        chunks.push(concat_batches(&schema, &pending)?);
+    }
+
+    Ok(chunks)
+}
+
+/// Select rows of several batches by their global row index
+///
+/// Row `i` counts rows across the batches in order, so the first row of the
+/// second batch follows the last row of the first. The rows are gathered
+/// with Arrow's `interleave` kernel, a `take` across several batches, in the
+/// order of `global_indices`; repeated indices repeat rows.
+///
+/// # Arguments
+/// * `batches` - The batches to select from, all with the same schema
+/// * `global_indices` - The global indices of the rows to select
+///
+/// # Returns
+/// One batch with the selected rows, with the schema of the input
+///
+/// # Errors
+/// Returns an error if an index is out of range, naming the valid range, or
+/// if the batches have different schemas
+pub fn take_rows(batches: &[RecordBatch], global_indices: &[usize]) -> Result<RecordBatch> {
+    let Some(schema) = batches.first().map(RecordBatch::schema) else {
+        if let Some(index) = global_indices.first() {
+            return Err(Error::ValidationError(format!(
+                "Row index {index} is out of range; there are no rows"
+            ))
+            .into());
+        }
+        return Ok(RecordBatch::new_empty(Arc::new(Schema::empty())));
+    };
+
+    // The global index of the first row of each batch
+    let mut starts = Vec::with_capacity(batches.len());
+    let mut total_rows = 0;
+    for batch in batches {
+        starts.push(total_rows);
+        total_rows += batch.num_rows();
+    }
+
+    let positions = global_indices
+        .iter()
+        .map(|&index| {
+            if index >= total_rows {
+                return Err(Error::ValidationError(format!(
+                    "Row index {index} is out of range 0..{total_rows}"
+                ))
+                .into());
+            }
+            // The last batch starting at or before the index; empty batches
+            // share their start with the next batch and are skipped
+            let batch = starts.partition_point(|&start| start <= index) - 1;
+            Ok((batch, index - starts[batch]))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if positions.is_empty() {
+        return Ok(RecordBatch::new_empty(schema));
+    }
+    let batch_refs: Vec<&RecordBatch> = batches.iter().collect();
+    Ok(interleave_record_batch(&batch_refs, &positions)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, ArrayRef, Float64Array, Int32Array, Int64Array, StringArray};
+
+    #[test]
+    fn test_concat_coerced() {
+        let old = RecordBatch::try_from_iter_with_nullable(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["1"])) as ArrayRef,
+                false,
+            ),
+            (
+                "AAR",
+                Arc::new(Int32Array::from(vec![2019])) as ArrayRef,
+                false,
+            ),
+        ])
+        .unwrap();
+        let new = RecordBatch::try_from_iter_with_nullable(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec![Some("2"), None])) as ArrayRef,
+                true,
+            ),
+            (
+                "AAR",
+                Arc::new(Int64Array::from(vec![2020, 2021])) as ArrayRef,
+                false,
+            ),
+            (
+                "BELOB",
+                Arc::new(Float64Array::from(vec![1.5, 2.5])) as ArrayRef,
+                false,
+            ),
+        ])
+        .unwrap();
+
+        let combined = concat_coerced(&[old.clone(), new]).unwrap();
+        let schema = combined.schema();
+        assert_eq!(combined.num_rows(), 3);
+        assert!(schema.field_with_name("PNR").unwrap().is_nullable());
+        assert_eq!(
+            schema.field_with_name("AAR").unwrap().data_type(),
+            &DataType::Int64
+        );
+        // Missing from the old batch, so nullable and null-filled
+        assert!(schema.field_with_name("BELOB").unwrap().is_nullable());
+        assert!(combined.column(2).is_null(0));
+
+        let text = RecordBatch::try_from_iter(vec![(
+            "AAR",
+            Arc::new(StringArray::from(vec!["2022"])) as ArrayRef,
+        )])
+        .unwrap();
+        assert!(concat_coerced(&[old, text]).is_err());
+        assert_eq!(concat_coerced(&[]).unwrap().num_columns(), 0);
+    }
+
+    #[test]
+    fn test_concat_schema_union() {
+        let early = RecordBatch::try_from_iter(vec![(
+            "PNR",
+            Arc::new(StringArray::from(vec!["1", "2"])) as ArrayRef,
+        )])
+        .unwrap();
+        let late = RecordBatch::try_from_iter(vec![
+            ("PNR", Arc::new(StringArray::from(vec!["3"])) as ArrayRef),
+            ("BELOB", Arc::new(Float64Array::from(vec![1.5])) as ArrayRef),
+        ])
+        .unwrap();
+
+        let combined = concat_schema_union(&[early.clone(), late]).unwrap();
+        assert_eq!(combined.num_rows(), 3);
+        let amounts = combined.column_by_name("BELOB").unwrap();
+        assert_eq!(amounts.data_type(), &DataType::Float64);
+        assert_eq!(amounts.null_count(), 2);
+        assert!(amounts.is_valid(2));
+
+        let numeric = RecordBatch::try_from_iter(vec![(
+            "PNR",
+            Arc::new(Int64Array::from(vec![4])) as ArrayRef,
+        )])
+        .unwrap();
+        let message = concat_schema_union(&[early, numeric])
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("'PNR'"), "{message}");
+        assert!(message.contains("Utf8 and Int64"), "{message}");
+        assert_eq!(concat_schema_union(&[]).unwrap().num_columns(), 0);
+    }
+
+    #[test]
+    fn test_rechunk() {
+        let batch = |values: Vec<i32>| {
+            RecordBatch::try_from_iter(vec![(
+                "AAR",
+                Arc::new(Int32Array::from(values)) as ArrayRef,
+            )])
+            .unwrap()
+        };
+        let batches = vec![
+            batch(vec![1, 2, 3]),
+            batch(vec![]),
+            batch(vec![4, 5, 6, 7, 8]),
+        ];
+
+        let chunks = rechunk(&batches, 3).unwrap();
+        let sizes: Vec<usize> = chunks.iter().map(RecordBatch::num_rows).collect();
+        assert_eq!(sizes, vec![3, 3, 2]);
+        assert_eq!(chunks[0].schema(), batches[0].schema());
+        let values = chunks[1]
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(values.values(), &[4, 5, 6]);
+
+        assert!(rechunk(&batches, 0).is_err());
+        assert!(rechunk(&[], 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_take_rows() {
+        let batch = |values: Vec<i32>| {
+            RecordBatch::try_from_iter(vec![(
+                "AAR",
+                Arc::new(Int32Array::from(values)) as ArrayRef,
+            )])
+            .unwrap()
+        };
+        let batches = vec![batch(vec![10, 11]), batch(vec![]), batch(vec![12, 13, 14])];
+
+        let taken = take_rows(&batches, &[4, 0, 2, 2]).unwrap();
+        assert_eq!(taken.schema(), batches[0].schema());
+        let values = taken
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(values.values(), &[14, 10, 12, 12]);
+
+        assert_eq!(take_rows(&batches, &[]).unwrap().num_rows(), 0);
+        let message = take_rows(&batches, &[1, 5]).unwrap_err().to_string();
+        assert!(message.contains("0..5"), "{message}");
+        assert!(take_rows(&[], &[0]).is_err());
+    }
+}
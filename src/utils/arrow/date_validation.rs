@@ -0,0 +1,301 @@
+//! Plausibility checks for date columns
+//!
+//! Registry data occasionally contains impossible dates from data-entry
+//! errors, such as births in 1800 or deaths in 2099. The helpers here find
+//! dates outside a plausible range and can null them out, so implausible
+//! values are quarantined before analysis instead of skewing it.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, BooleanArray, Date32Array, Date64Array};
+use arrow::compute::nullif;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+use chrono::NaiveDate;
+use log::warn;
+
+use crate::error::{Error, Result};
+
+/// The dates of a column that fall outside a plausible range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateValidationReport {
+    /// The column checked
+    pub column: String,
+    /// The earliest plausible date
+    pub min: NaiveDate,
+    /// The latest plausible date
+    pub max: NaiveDate,
+    /// The number of non-null dates checked
+    pub checked: usize,
+    /// The row indices of dates before `min` or after `max`
+    pub out_of_range_rows: Vec<usize>,
+}
+
+impl DateValidationReport {
+    /// Get the number of out-of-range dates
+    #[must_use]
+    pub fn out_of_range_count(&self) -> usize {
+        self.out_of_range_rows.len()
+    }
+
+    /// Check whether every date is within the range
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.out_of_range_rows.is_empty()
+    }
+}
+
+/// Find the dates of a column outside `min..=max`
+///
+/// Null dates are not checked. Both `Date32` and `Date64` columns are
+/// supported.
+///
+/// # Arguments
+///
+/// * `batch` - The batch to check
+/// * `column` - The date column
+/// * `min` - The earliest plausible date
+/// * `max` - The latest plausible date
+///
+/// # Returns
+///
+/// A report of the rows with out-of-range dates
+///
+/// # Errors
+///
+/// Returns an error if the column does not exist or is not a date column
+pub fn validate_date_range(
+    batch: &RecordBatch,
+    column: &str,
+    min: NaiveDate,
+    max: NaiveDate,
+) -> Result<DateValidationReport> {
+    let array = batch
+        .column_by_name(column)
+        .ok_or_else(|| Error::ColumnNotFound {
+            column: column.to_string(),
+        })?;
+
+    let dates: Vec<Option<NaiveDate>> = match array.data_type() {
+        DataType::Date32 => {
+            let array = array.as_any().downcast_ref::<Date32Array>().unwrap();
+            (0..array.len())
+                .map(|row| array.value_as_date(row).filter(|_| array.is_valid(row)))
+                .collect()
+        }
+        DataType::Date64 => {
+            let array = array.as_any().downcast_ref::<Date64Array>().unwrap();
+            (0..array.len())
+                .map(|row| array.value_as_date(row).filter(|_| array.is_valid(row)))
+                .collect()
+        }
+        _ => {
+            return Err(Error::InvalidDataType {
+                column: column.to_string(),
+                expected: "Date32 or Date64".to_string(),
+            }
+            .into());
+        }
+    };
+
+    let checked = dates.iter().flatten().count();
+    let out_of_range_rows = dates
+        .iter()
+        .enumerate()
+        .filter(|(_, date)| date.is_some_and(|date| date < min || date > max))
+        .map(|(row, _)| row)
+        .collect();
+
+    Ok(DateValidationReport {
+        column: column.to_string(),
+        min,
+        max,
+        checked,
+        out_of_range_rows,
+    })
+}
+
+/// Replace the out-of-range dates of a report with nulls
+///
+/// # Arguments
+///
+/// * `batch` - The batch the report was made from
+/// * `report` - The report of the dates to null out
+///
+/// # Returns
+///
+/// The batch with the reported dates set to null; the column becomes nullable
+///
+/// # Errors
+///
+/// Returns an error if the reported column does not exist in the batch
+pub fn null_out_of_range_dates(
+    batch: &RecordBatch,
+    report: &DateValidationReport,
+) -> Result<RecordBatch> {
+    if report.is_valid() {
+        return Ok(batch.clone());
+    }
+
+    let schema = batch.schema();
+    let index = schema
+        .index_of(&report.column)
+        .map_err(|_| Error::ColumnNotFound {
+            column: report.column.clone(),
+        })?;
+
+    let mut mask = vec![false; batch.num_rows()];
+    for &row in &report.out_of_range_rows {
+        mask[row] = true;
+    }
+    let nulled = nullif(batch.column(index), &BooleanArray::from(mask))?;
+
+    let mut fields: Vec<_> = schema.fields().iter().cloned().collect();
+    fields[index] = Arc::new(fields[index].as_ref().clone().with_nullable(true));
+    let mut columns = batch.columns().to_vec();
+    columns[index] = nulled;
+
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )?)
+}
+
+/// A plausible date range to enforce on a column after loading
+///
+/// See `ParquetReaderConfig::date_range_checks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateRangeCheck {
+    /// The date column
+    pub column: String,
+    /// The earliest plausible date
+    pub min: NaiveDate,
+    /// The latest plausible date
+    pub max: NaiveDate,
+    /// Whether to null out-of-range dates, rather than only report them
+    pub null_out_of_range: bool,
+}
+
+impl DateRangeCheck {
+    /// Create a check that reports out-of-range dates without changing them
+    #[must_use]
+    pub fn new(column: impl Into<String>, min: NaiveDate, max: NaiveDate) -> Self {
+        Self {
+            column: column.into(),
+            min,
+            max,
+            null_out_of_range: false,
+        }
+    }
+
+    /// Null out-of-range dates instead of only reporting them
+    #[must_use]
+    pub const fn nulling_out_of_range(mut self) -> Self {
+        self.null_out_of_range = true;
+        self
+    }
+
+    /// Check a batch, nulling out-of-range dates if configured
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column does not exist or is not a date column
+    pub fn apply(&self, batch: &RecordBatch) -> Result<(RecordBatch, DateValidationReport)> {
+        let report = validate_date_range(batch, &self.column, self.min, self.max)?;
+        let batch = if self.null_out_of_range {
+            null_out_of_range_dates(batch, &report)?
+        } else {
+            batch.clone()
+        };
+        Ok((batch, report))
+    }
+}
+
+/// Apply date range checks to loaded batches, logging implausible dates
+///
+/// A check whose column is missing from a batch is skipped, so one set of
+/// checks can be used for files of different registries.
+///
+/// # Errors
+///
+/// Returns an error if a checked column is not a date column
+pub fn apply_date_range_checks(
+    batches: Vec<RecordBatch>,
+    checks: &[DateRangeCheck],
+) -> Result<Vec<RecordBatch>> {
+    if checks.is_empty() {
+        return Ok(batches);
+    }
+
+    batches
+        .into_iter()
+        .map(|mut batch| {
+            for check in checks {
+                if batch.column_by_name(&check.column).is_none() {
+                    continue;
+                }
+                let (checked, report) = check.apply(&batch)?;
+                if !report.is_valid() {
+                    warn!(
+                        "{} of {} dates in column '{}' are outside {} to {}{}",
+                        report.out_of_range_count(),
+                        report.checked,
+                        report.column,
+                        report.min,
+                        report.max,
+                        if check.null_out_of_range {
+                            " and were set to null"
+                        } else {
+                            ""
+                        }
+                    );
+                }
+                batch = checked;
+            }
+            Ok(batch)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::Field;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn days(value: NaiveDate) -> i32 {
+        (value - date(1970, 1, 1)).num_days() as i32
+    }
+
+    #[test]
+    fn test_validate_and_null_out_of_range_dates() {
+        let schema = Schema::new(vec![Field::new("FOED_DAG", DataType::Date32, false)]);
+        let values = [date(1800, 1, 1), date(1990, 5, 17), date(2099, 12, 31)];
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Date32Array::from(
+                values.iter().map(|value| days(*value)).collect::<Vec<_>>(),
+            ))],
+        )
+        .unwrap();
+
+        let check = DateRangeCheck::new("FOED_DAG", date(1900, 1, 1), date(2025, 12, 31));
+        let report = validate_date_range(&batch, "FOED_DAG", check.min, check.max).unwrap();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.out_of_range_rows, vec![0, 2]);
+        assert!(!report.is_valid());
+
+        let (nulled, _) = check.nulling_out_of_range().apply(&batch).unwrap();
+        let column = nulled.column(0);
+        assert!(nulled.schema().field(0).is_nullable());
+        assert_eq!(column.null_count(), 2);
+        assert!(column.is_valid(1));
+
+        assert!(
+            validate_date_range(&batch, "MISSING", date(1900, 1, 1), date(2025, 1, 1)).is_err()
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! Differences between two loads of a dataset
+//!
+//! When a registry refresh arrives, the question is what changed: who
+//! entered or left the population, and whose values differ. [`diff_datasets`]
+//! matches the rows of two sets of batches on key columns, such as `PNR` of
+//! `BEF`, and reports added keys, removed keys and the changed columns of
+//! each key in both.
+//!
+//! Only the old side is indexed. [`diff_dataset_stream`] takes the new side
+//! as a stream of batches, e.g. straight from a Parquet reader, so it never
+//! has to be held in memory as a whole.
+
+use std::collections::{HashMap, HashSet};
+
+use arrow::array::Array;
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+
+use crate::error::{ParquetReaderError, Result};
+
+/// The values of a row's key columns, in the order of the key columns
+pub type DiffKey = Vec<String>;
+
+/// A column whose value differs between the old and new row of a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnChange {
+    /// The column name
+    pub column: String,
+    /// The old value, or `None` if null
+    pub old: Option<String>,
+    /// The new value, or `None` if null
+    pub new: Option<String>,
+}
+
+/// The changed columns of a key found in both datasets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedRow {
+    /// The key of the row
+    pub key: DiffKey,
+    /// The columns that differ
+    pub changes: Vec<ColumnChange>,
+}
+
+/// The differences between two datasets
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatasetDiff {
+    /// Keys only in the new dataset, in the order they appear
+    pub added: Vec<DiffKey>,
+    /// Keys only in the old dataset, in the order they appear
+    pub removed: Vec<DiffKey>,
+    /// Keys in both datasets whose values differ
+    pub changed: Vec<ChangedRow>,
+    /// Columns only in the new dataset
+    pub added_columns: Vec<String>,
+    /// Columns only in the old dataset
+    pub removed_columns: Vec<String>,
+    /// Rows skipped for a null key or a key seen before on their side
+    pub skipped_rows: usize,
+}
+
+impl DatasetDiff {
+    /// Check whether the datasets have the same keys, columns and values
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+    }
+}
+
+/// Compare two datasets row by row on key columns
+///
+/// Values are compared by their display form, so a column whose type
+/// changed between loads, e.g. from `Int32` to `Int64`, differs only where
+/// its values do. Columns outside the keys are compared if both rows have
+/// them. Rows with a null key are skipped, and of rows sharing a key only
+/// the first on each side is compared.
+///
+/// # Arguments
+///
+/// * `old` - The batches of the earlier load
+/// * `new` - The batches of the later load
+/// * `key_columns` - The columns identifying a row, such as `["PNR"]`
+///
+/// # Returns
+///
+/// The added and removed keys and the changed rows
+///
+/// # Errors
+///
+/// Returns an error if a batch lacks a key column or a value cannot be
+/// displayed
+pub fn diff_datasets(
+    old: &[RecordBatch],
+    new: &[RecordBatch],
+    key_columns: &[&str],
+) -> Result<DatasetDiff> {
+    diff_dataset_stream(old, new.iter().cloned().map(Ok), key_columns)
+}
+
+/// Compare a dataset to a stream of batches of its later load
+///
+/// As [`diff_datasets`], but each new batch is compared and dropped before
+/// the next is pulled. Only the old batches, an index of their keys and the
+/// set of new keys are held.
+///
+/// # Errors
+///
+/// Returns the first error of the stream, or an error as [`diff_datasets`]
+pub fn diff_dataset_stream<I>(
+    old: &[RecordBatch],
+    new: I,
+    key_columns: &[&str],
+) -> Result<DatasetDiff>
+where
+    I: IntoIterator<Item = Result<RecordBatch>>,
+{
+    let mut diff = DatasetDiff::default();
+
+    // Index the old keys by batch and row, keeping their order of appearance
+    let mut old_keys: Vec<DiffKey> = Vec::new();
+    let mut old_index: HashMap<DiffKey, (usize, usize)> = HashMap::new();
+    for (batch_index, batch) in old.iter().enumerate() {
+        for (row, key) in row_keys(batch, key_columns)?.into_iter().enumerate() {
+            match key {
+                Some(key) if !old_index.contains_key(&key) => {
+                    old_index.insert(key.clone(), (batch_index, row));
+                    old_keys.push(key);
+                }
+                _ => diff.skipped_rows += 1,
+            }
+        }
+    }
+
+    let mut seen: HashSet<DiffKey> = HashSet::new();
+    let mut new_columns: Vec<String> = Vec::new();
+    for batch in new {
+        let batch = batch?;
+        add_columns(&mut new_columns, &batch);
+
+        for (row, key) in row_keys(&batch, key_columns)?.into_iter().enumerate() {
+            let Some(key) = key.filter(|key| !seen.contains(key)) else {
+                diff.skipped_rows += 1;
+                continue;
+            };
+
+            match old_index.get(&key) {
+                Some(&(batch_index, old_row)) => {
+                    let changes =
+                        changed_columns(&old[batch_index], old_row, &batch, row, key_columns)?;
+                    if !changes.is_empty() {
+                        diff.changed.push(ChangedRow {
+                            key: key.clone(),
+                            changes,
+                        });
+                    }
+                }
+                None => diff.added.push(key.clone()),
+            }
+            seen.insert(key);
+        }
+    }
+
+    diff.removed = old_keys
+        .into_iter()
+        .filter(|key| !seen.contains(key))
+        .collect();
+
+    let mut old_columns: Vec<String> = Vec::new();
+    for batch in old {
+        add_columns(&mut old_columns, batch);
+    }
+    diff.added_columns = new_columns
+        .iter()
+        .filter(|column| !old_columns.contains(column))
+        .cloned()
+        .collect();
+    diff.removed_columns = old_columns
+        .into_iter()
+        .filter(|column| !new_columns.contains(column))
+        .collect();
+
+    Ok(diff)
+}
+
+/// Add the columns of a batch not yet in the list, in schema order
+fn add_columns(columns: &mut Vec<String>, batch: &RecordBatch) {
+    for field in batch.schema().fields() {
+        if !columns.contains(field.name()) {
+            columns.push(field.name().clone());
+        }
+    }
+}
+
+/// Get the key of every row of a batch, or `None` for rows with a null key
+fn row_keys(batch: &RecordBatch, key_columns: &[&str]) -> Result<Vec<Option<DiffKey>>> {
+    let arrays = key_columns
+        .iter()
+        .map(|column| {
+            batch
+                .column_by_name(column)
+                .ok_or_else(|| ParquetReaderError::column_not_found(*column))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    (0..batch.num_rows())
+        .map(|row| {
+            arrays
+                .iter()
+                .map(|array| display_value(array.as_ref(), row))
+                .collect::<Result<Option<DiffKey>>>()
+        })
+        .collect()
+}
+
+/// Compare the non-key columns that two rows both have
+fn changed_columns(
+    old: &RecordBatch,
+    old_row: usize,
+    new: &RecordBatch,
+    new_row: usize,
+    key_columns: &[&str],
+) -> Result<Vec<ColumnChange>> {
+    let mut changes = Vec::new();
+    for (field, new_array) in new.schema().fields().iter().zip(new.columns()) {
+        if key_columns.contains(&field.name().as_str()) {
+            continue;
+        }
+        let Some(old_array) = old.column_by_name(field.name()) else {
+            continue;
+        };
+
+        let old_value = display_value(old_array.as_ref(), old_row)?;
+        let new_value = display_value(new_array.as_ref(), new_row)?;
+        if old_value != new_value {
+            changes.push(ColumnChange {
+                column: field.name().clone(),
+                old: old_value,
+                new: new_value,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+/// Get the display form of a value, or `None` if it is null
+fn display_value(array: &dyn Array, row: usize) -> Result<Option<String>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    Ok(Some(array_value_to_string(array, row)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Int32Array, Int64Array, StringArray};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_diff_bef_refresh() {
+        let old = RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["a", "b", "c"])) as ArrayRef,
+            ),
+            (
+                "KOM",
+                Arc::new(Int32Array::from(vec![Some(101), Some(147), None])) as ArrayRef,
+            ),
+            (
+                "CIVST",
+                Arc::new(StringArray::from(vec!["U", "G", "U"])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        // The refresh widens KOM, drops CIVST and adds FM_MARK
+        let new = RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec![
+                    Some("c"),
+                    Some("b"),
+                    Some("d"),
+                    None,
+                ])) as ArrayRef,
+            ),
+            (
+                "KOM",
+                Arc::new(Int64Array::from(vec![
+                    Some(751),
+                    Some(147),
+                    Some(101),
+                    None,
+                ])) as ArrayRef,
+            ),
+            (
+                "FM_MARK",
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let diff = diff_datasets(std::slice::from_ref(&old), &[new], &["PNR"]).unwrap();
+        assert_eq!(diff.added, vec![vec!["d".to_string()]]);
+        assert_eq!(diff.removed, vec![vec!["a".to_string()]]);
+        assert_eq!(
+            diff.changed,
+            vec![ChangedRow {
+                key: vec!["c".to_string()],
+                changes: vec![ColumnChange {
+                    column: "KOM".to_string(),
+                    old: None,
+                    new: Some("751".to_string()),
+                }],
+            }]
+        );
+        assert_eq!(diff.added_columns, ["FM_MARK"]);
+        assert_eq!(diff.removed_columns, ["CIVST"]);
+        assert_eq!(diff.skipped_rows, 1);
+
+        assert!(
+            diff_datasets(
+                std::slice::from_ref(&old),
+                std::slice::from_ref(&old),
+                &["PNR"]
+            )
+            .unwrap()
+            .is_empty()
+        );
+        assert!(diff_datasets(&[old], &[], &["RECNUM"]).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! Distinct value counting over Parquet files
+//!
+//! This module counts distinct PNRs across multi-file registries. The default
+//! path streams the PNR column into a HyperLogLog sketch, which uses a fixed
+//! 16 KiB of memory regardless of the number of individuals; an exact path
+//! backed by a `HashSet` is available when precision matters.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+
+use arrow::array::{Array, StringArray};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ProjectionMask;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use crate::error::{Error, Result};
+use crate::utils::io::parquet::{find_parquet_files, resolve_batch_size};
+
+/// Precision of the sketch used by [`approx_distinct_pnr`] (2^14 registers)
+pub const HLL_PRECISION: u8 = 14;
+
+/// A HyperLogLog cardinality sketch
+///
+/// With `2^p` registers the relative standard error of the estimate is about
+/// `1.04 / sqrt(2^p)`; small cardinalities use linear counting and are close
+/// to exact.
+#[derive(Debug, Clone)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Create an empty sketch with `2^precision` registers
+    ///
+    /// # Panics
+    /// Panics if `precision` is outside `4..=18`
+    #[must_use]
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (4..=18).contains(&precision),
+            "HyperLogLog precision must be between 4 and 18"
+        );
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// Add a value to the sketch
+    pub fn insert<T: Hash + ?Sized>(&mut self, value: &T) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        self.insert_hash(hasher.finish());
+    }
+
+    /// Add a pre-computed 64-bit hash to the sketch
+    pub fn insert_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - self.precision)) as usize;
+        let remaining = hash << self.precision;
+        let max_rank = 64 - u32::from(self.precision) + 1;
+        let rank = (remaining.leading_zeros() + 1).min(max_rank) as u8;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Merge another sketch of the same precision into this one
+    ///
+    /// # Errors
+    /// Returns an error if the precisions differ
+    pub fn merge(&mut self, other: &Self) -> Result<()> {
+        if self.precision != other.precision {
+            return Err(Error::ValidationError(format!(
+                "Cannot merge HyperLogLog sketches of precision {} and {}",
+                self.precision, other.precision
+            ))
+            .into());
+        }
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+        Ok(())
+    }
+
+    /// Estimate the number of distinct values added
+    #[must_use]
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-i32::from(rank)))
+            .sum();
+        let raw = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+
+        estimate.round() as u64
+    }
+}
+
+/// Count the distinct PNRs in a Parquet file or directory of Parquet files
+///
+/// Only the PNR column is read, batch by batch. With `exact` disabled the
+/// count is a HyperLogLog estimate with a relative standard error of about
+/// 0.8% (`1.04 / sqrt(2^14)`), so ~95% of estimates fall within 1.6% of the
+/// true count, using constant memory. With `exact` enabled every PNR is kept
+/// in a `HashSet`, so memory grows with the number of individuals. Nulls are
+/// not counted.
+///
+/// # Arguments
+/// * `base_path` - A Parquet file, or a directory searched with [`find_parquet_files`]
+/// * `pnr_column` - Name of the PNR column
+/// * `exact` - Count exactly instead of estimating
+///
+/// # Returns
+/// The (estimated) number of distinct non-null PNRs
+///
+/// # Errors
+/// Returns an error if a file cannot be read, or lacks the PNR column or
+/// holds it as a non-string column
+pub fn approx_distinct_pnr(base_path: &Path, pnr_column: &str, exact: bool) -> Result<u64> {
+    let files = if base_path.is_dir() {
+        find_parquet_files(base_path)?
+    } else {
+        vec![base_path.to_path_buf()]
+    };
+
+    if exact {
+        let mut pnrs: HashSet<String> = HashSet::new();
+        for file in &files {
+            for_each_batch(file, pnr_column, |values| {
+                pnrs.extend(values.iter().flatten().map(str::to_string));
+            })?;
+        }
+        return Ok(pnrs.len() as u64);
+    }
+
+    let mut sketch = HyperLogLog::new(HLL_PRECISION);
+    for file in &files {
+        for_each_batch(file, pnr_column, |values| {
+            for pnr in values.iter().flatten() {
+                sketch.insert(pnr);
+            }
+        })?;
+    }
+    Ok(sketch.estimate())
+}
+
+/// Stream a single string column of a Parquet file batch by batch
+pub(crate) fn for_each_batch(
+    path: &Path,
+    column: &str,
+    mut f: impl FnMut(&StringArray),
+) -> Result<()> {
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open file {}: {}", path.display(), e))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+
+    let index = builder
+        .schema()
+        .index_of(column)
+        .map_err(|_| Error::ColumnNotFound {
+            column: column.to_string(),
+        })?;
+    let projection = ProjectionMask::roots(builder.parquet_schema(), [index]);
+    let reader = builder
+        .with_projection(projection)
+        .with_batch_size(resolve_batch_size(None)?)
+        .build()?;
+
+    for batch in reader {
+        let batch: RecordBatch = batch?;
+        let values = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| Error::InvalidDataType {
+                column: column.to_string(),
+                expected: "Utf8".to_string(),
+            })?;
+        f(values);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::ArrayRef;
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_hyperloglog_error_bound() {
+        let mut sketch = HyperLogLog::new(HLL_PRECISION);
+        for i in 0..100_000 {
+            sketch.insert(&format!("{i:010}"));
+            // Duplicates do not change the estimate
+            sketch.insert(&format!("{:010}", i / 2));
+        }
+        let error = (sketch.estimate() as f64 - 100_000.0).abs() / 100_000.0;
+        assert!(error < 0.03, "relative error {error}");
+
+        let mut small = HyperLogLog::new(HLL_PRECISION);
+        for pnr in ["a", "b", "c", "a"] {
+            small.insert(pnr);
+        }
+        assert_eq!(small.estimate(), 3);
+        assert!(small.merge(&HyperLogLog::new(10)).is_err());
+    }
+
+    #[test]
+    fn test_approx_distinct_pnr_over_files() {
+        let dir = std::env::temp_dir().join(format!("par_reader_distinct_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (name, pnrs) in [
+            ("a", vec![Some("1"), Some("2"), None]),
+            ("b", vec![Some("2"), Some("3")]),
+        ] {
+            let batch = RecordBatch::try_from_iter(vec![(
+                "PNR",
+                Arc::new(StringArray::from(pnrs)) as ArrayRef,
+            )])
+            .unwrap();
+            let file = File::create(dir.join(format!("{name}.parquet"))).unwrap();
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+        }
+
+        assert_eq!(approx_distinct_pnr(&dir, "PNR", true).unwrap(), 3);
+        assert_eq!(approx_distinct_pnr(&dir, "PNR", false).unwrap(), 3);
+        assert_eq!(
+            approx_distinct_pnr(&dir.join("b.parquet"), "PNR", true).unwrap(),
+            2
+        );
+        assert!(approx_distinct_pnr(&dir, "CPR", false).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,333 @@
+//! Value frequencies of categorical columns
+//!
+//! For columns such as gender, socio-economic status or family type, the
+//! counts of each value are the first thing to look at after loading.
+//! [`ValueCounter`] tallies the values of one column batch by batch, so the
+//! batches need not be held in memory together; [`value_counts`] does the
+//! same over loaded batches.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, Date32Array, Float64Array, Int64Array, LargeStringArray,
+    StringArray,
+};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use chrono::NaiveDate;
+
+use crate::error::Result;
+use crate::utils::arrow::array_utils::get_column_index;
+
+/// A single non-null value of a column
+///
+/// Integers of any width are widened to `Int`, floats to `Float`, and
+/// dictionary-encoded columns are counted by their values. Values of other
+/// types are compared by their display form.
+#[derive(Debug, Clone)]
+pub enum ScalarValue {
+    /// A boolean value
+    Boolean(bool),
+    /// An integer value
+    Int(i64),
+    /// A floating point value, equal to another only if bitwise identical
+    Float(f64),
+    /// A text value
+    Utf8(String),
+    /// A date value
+    Date(NaiveDate),
+    /// A value of another type, in its display form
+    Display(String),
+}
+
+impl ScalarValue {
+    /// Get the position of the variant, ordering values of different types
+    const fn rank(&self) -> u8 {
+        match self {
+            Self::Boolean(_) => 0,
+            Self::Int(_) => 1,
+            Self::Float(_) => 2,
+            Self::Utf8(_) => 3,
+            Self::Date(_) => 4,
+            Self::Display(_) => 5,
+        }
+    }
+}
+
+impl PartialEq for ScalarValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScalarValue {}
+
+impl PartialOrd for ScalarValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScalarValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Boolean(a), Self::Boolean(b)) => a.cmp(b),
+            (Self::Int(a), Self::Int(b)) => a.cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
+            (Self::Utf8(a), Self::Utf8(b)) | (Self::Display(a), Self::Display(b)) => a.cmp(b),
+            (Self::Date(a), Self::Date(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl Hash for ScalarValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+        match self {
+            Self::Boolean(value) => value.hash(state),
+            Self::Int(value) => value.hash(state),
+            Self::Float(value) => value.to_bits().hash(state),
+            Self::Utf8(value) | Self::Display(value) => value.hash(state),
+            Self::Date(value) => value.hash(state),
+        }
+    }
+}
+
+impl fmt::Display for ScalarValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Utf8(value) | Self::Display(value) => f.write_str(value),
+            Self::Date(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// The value frequencies of a column
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValueCounts {
+    /// Each value, or `None` for nulls, with its count, most frequent first
+    pub counts: Vec<(Option<ScalarValue>, u64)>,
+    /// The number of rows whose value was lumped into the "other" bucket
+    pub other: u64,
+}
+
+/// A tally of the values of one column, fed batch by batch
+#[derive(Debug, Clone)]
+pub struct ValueCounter {
+    column: String,
+    counts: HashMap<Option<ScalarValue>, u64>,
+}
+
+impl ValueCounter {
+    /// Create an empty tally of a column
+    #[must_use]
+    pub fn new(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Count the values of the column in a batch
+    ///
+    /// # Errors
+    /// Returns an error if the batch has no such column, or if a value
+    /// cannot be converted
+    pub fn add_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        let index = get_column_index(batch, &self.column)?;
+        let array = normalize(batch.column(index))?;
+        for row in 0..array.len() {
+            *self.counts.entry(scalar_value(&array, row)?).or_default() += 1;
+        }
+        Ok(())
+    }
+
+    /// Get the counts, most frequent first
+    ///
+    /// Values of equal count are ordered by value, with nulls last. With
+    /// `top_n`, only the `top_n` most frequent non-null values are kept and
+    /// the rows of the rest are counted in [`ValueCounts::other`]; the null
+    /// bucket is always kept.
+    #[must_use]
+    pub fn finish(mut self, top_n: Option<usize>) -> ValueCounts {
+        let null = self.counts.remove(&None);
+        let mut counts: Vec<_> = self.counts.into_iter().collect();
+        counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        let other = match top_n {
+            Some(top_n) if top_n < counts.len() => {
+                counts.drain(top_n..).map(|(_, count)| count).sum()
+            }
+            _ => 0,
+        };
+
+        if let Some(null) = null {
+            let position = counts.partition_point(|(_, count)| *count >= null);
+            counts.insert(position, (None, null));
+        }
+        ValueCounts { counts, other }
+    }
+}
+
+/// Count the values of a column across batches
+///
+/// # Arguments
+///
+/// * `batches` - The loaded batches
+/// * `column` - The column to tabulate
+///
+/// # Returns
+///
+/// Each value, or `None` for nulls, with its count, most frequent first
+///
+/// # Errors
+///
+/// Returns an error if a batch has no such column, or if a value cannot be
+/// converted
+pub fn value_counts(
+    batches: &[RecordBatch],
+    column: &str,
+) -> Result<Vec<(Option<ScalarValue>, u64)>> {
+    value_counts_top_n(batches, column, None).map(|counts| counts.counts)
+}
+
+/// Count the values of a column across batches, keeping the most frequent
+///
+/// See [`ValueCounter::finish`] for how `top_n` lumps the remaining values
+/// into an "other" bucket.
+///
+/// # Errors
+///
+/// Returns an error if a batch has no such column, or if a value cannot be
+/// converted
+pub fn value_counts_top_n(
+    batches: &[RecordBatch],
+    column: &str,
+    top_n: Option<usize>,
+) -> Result<ValueCounts> {
+    let mut counter = ValueCounter::new(column);
+    for batch in batches {
+        counter.add_batch(batch)?;
+    }
+    Ok(counter.finish(top_n))
+}
+
+/// Cast an array to the type its values are read from
+fn normalize(array: &ArrayRef) -> Result<ArrayRef> {
+    let target = match array.data_type() {
+        DataType::Dictionary(_, value_type) => Some(value_type.as_ref().clone()),
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32 => Some(DataType::Int64),
+        DataType::Float16 | DataType::Float32 => Some(DataType::Float64),
+        DataType::Date64 => Some(DataType::Date32),
+        _ => None,
+    };
+    match target {
+        Some(target) => normalize(&cast(array, &target)?),
+        None => Ok(array.clone()),
+    }
+}
+
+/// Get the value of a row of a normalized array
+fn scalar_value(array: &ArrayRef, row: usize) -> Result<Option<ScalarValue>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    let any = array.as_any();
+    let value = if let Some(array) = any.downcast_ref::<BooleanArray>() {
+        ScalarValue::Boolean(array.value(row))
+    } else if let Some(array) = any.downcast_ref::<Int64Array>() {
+        ScalarValue::Int(array.value(row))
+    } else if let Some(array) = any.downcast_ref::<Float64Array>() {
+        ScalarValue::Float(array.value(row))
+    } else if let Some(array) = any.downcast_ref::<StringArray>() {
+        ScalarValue::Utf8(array.value(row).to_string())
+    } else if let Some(array) = any.downcast_ref::<LargeStringArray>() {
+        ScalarValue::Utf8(array.value(row).to_string())
+    } else if let Some(date) = any
+        .downcast_ref::<Date32Array>()
+        .and_then(|array| array.value_as_date(row))
+    {
+        ScalarValue::Date(date)
+    } else {
+        ScalarValue::Display(array_value_to_string(array, row)?)
+    };
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{DictionaryArray, Int32Array};
+    use arrow::datatypes::Int8Type;
+    use std::sync::Arc;
+
+    fn text(value: &str) -> Option<ScalarValue> {
+        Some(ScalarValue::Utf8(value.to_string()))
+    }
+
+    #[test]
+    fn test_value_counts() {
+        let batches = vec![
+            RecordBatch::try_from_iter(vec![(
+                "KOEN",
+                Arc::new(StringArray::from(vec![
+                    Some("M"),
+                    Some("K"),
+                    None,
+                    Some("M"),
+                ])) as ArrayRef,
+            )])
+            .unwrap(),
+            RecordBatch::try_from_iter(vec![(
+                "KOEN",
+                Arc::new(
+                    vec![Some("K"), Some("M"), Some("X")]
+                        .into_iter()
+                        .collect::<DictionaryArray<Int8Type>>(),
+                ) as ArrayRef,
+            )])
+            .unwrap(),
+        ];
+
+        assert_eq!(
+            value_counts(&batches, "KOEN").unwrap(),
+            vec![(text("M"), 3), (text("K"), 2), (text("X"), 1), (None, 1)]
+        );
+
+        let top = value_counts_top_n(&batches, "KOEN", Some(1)).unwrap();
+        assert_eq!(top.counts, vec![(text("M"), 3), (None, 1)]);
+        assert_eq!(top.other, 3);
+
+        let years = RecordBatch::try_from_iter(vec![(
+            "AAR",
+            Arc::new(Int32Array::from(vec![Some(2020), Some(2021), None])) as ArrayRef,
+        )])
+        .unwrap();
+        assert_eq!(
+            value_counts(std::slice::from_ref(&years), "AAR").unwrap(),
+            vec![
+                (Some(ScalarValue::Int(2020)), 1),
+                (Some(ScalarValue::Int(2021)), 1),
+                (None, 1)
+            ]
+        );
+        assert!(value_counts(&[], "KOEN").unwrap().is_empty());
+
+        // A misspelled column is an error, not a column of nulls
+        let error = value_counts(&[years], "KOEN").unwrap_err();
+        assert!(error.to_string().contains("KOEN"), "{error}");
+    }
+}
@@ -0,0 +1,165 @@
+//! Key-to-value lookups built from record batches
+//!
+//! Several registries reach PNRs only through an identifier of another
+//! registry, such as `RECNUM` of `LPR_ADM` or `DW_EK_KONTAKT` of
+//! `LPR3_KONTAKTER`. [`build_lookup`] builds such an identifier-to-PNR map
+//! from two columns of the batches, with a [`DuplicatePolicy`] for keys seen
+//! with more than one value.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use arrow::array::{Array, StringArray};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+
+use crate::error::{ParquetReaderError, Result};
+use crate::utils::arrow::array_utils::{downcast_array, get_column};
+
+/// Which value a lookup keeps for a key seen with several values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Keep the first value, in batch and row order
+    #[default]
+    First,
+    /// Keep the last value, in batch and row order
+    Last,
+    /// Fail on a key with a second, different value
+    Error,
+}
+
+/// Build a lookup from a key column to a value column
+///
+/// Rows with a null key or value are skipped. Columns that are not `Utf8`
+/// are converted, so numeric identifiers are looked up by their string
+/// form. A key repeated with the same value is not a duplicate; the number
+/// of keys with several values is logged as a warning, since it points to
+/// problems in the source data.
+///
+/// # Arguments
+///
+/// * `batches` - The batches to read
+/// * `key_col` - The key column, such as `RECNUM`
+/// * `value_col` - The value column, such as `PNR`
+/// * `policy` - Which value to keep for a key seen with several values
+///
+/// # Returns
+///
+/// The map from keys to values
+///
+/// # Errors
+///
+/// Returns an error if a column is missing from a batch, or if a key has
+/// several values and the policy is [`DuplicatePolicy::Error`]
+pub fn build_lookup(
+    batches: &[RecordBatch],
+    key_col: &str,
+    value_col: &str,
+    policy: DuplicatePolicy,
+) -> Result<HashMap<String, String>> {
+    let mut lookup = HashMap::new();
+    let mut ambiguous = HashSet::new();
+
+    for batch in batches {
+        let key_array =
+            get_column(batch, key_col, &DataType::Utf8, true)?.expect("required column is present");
+        let value_array = get_column(batch, value_col, &DataType::Utf8, true)?
+            .expect("required column is present");
+        let keys = downcast_array::<StringArray>(&key_array, key_col, "Utf8")?;
+        let values = downcast_array::<StringArray>(&value_array, value_col, "Utf8")?;
+
+        for row in 0..batch.num_rows() {
+            if keys.is_null(row) || values.is_null(row) {
+                continue;
+            }
+            let value = values.value(row);
+
+            match lookup.entry(keys.value(row).to_string()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(value.to_string());
+                }
+                Entry::Occupied(mut entry) if entry.get() != value => match policy {
+                    DuplicatePolicy::First => {
+                        ambiguous.insert(entry.key().clone());
+                    }
+                    DuplicatePolicy::Last => {
+                        ambiguous.insert(entry.key().clone());
+                        entry.insert(value.to_string());
+                    }
+                    DuplicatePolicy::Error => {
+                        return Err(ParquetReaderError::ValidationError(format!(
+                            "Key '{}' in column '{key_col}' maps to more than one value of column '{value_col}'",
+                            entry.key()
+                        ))
+                        .into());
+                    }
+                },
+                Entry::Occupied(_) => {}
+            }
+        }
+    }
+
+    if !ambiguous.is_empty() {
+        log::warn!(
+            "{} of {} keys in column '{key_col}' map to more than one value of column \
+             '{value_col}'; keeping the {} value of each",
+            ambiguous.len(),
+            lookup.len(),
+            if policy == DuplicatePolicy::Last {
+                "last"
+            } else {
+                "first"
+            }
+        );
+    }
+
+    Ok(lookup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Int64Array};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_build_lookup_policies() {
+        let batches = vec![
+            RecordBatch::try_from_iter(vec![
+                (
+                    "RECNUM",
+                    Arc::new(Int64Array::from(vec![Some(1), Some(2), None])) as ArrayRef,
+                ),
+                (
+                    "PNR",
+                    Arc::new(StringArray::from(vec![Some("a"), Some("b"), Some("c")])) as ArrayRef,
+                ),
+            ])
+            .unwrap(),
+            RecordBatch::try_from_iter(vec![
+                ("RECNUM", Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef),
+                (
+                    "PNR",
+                    Arc::new(StringArray::from(vec!["a", "d"])) as ArrayRef,
+                ),
+            ])
+            .unwrap(),
+        ];
+
+        let first = build_lookup(&batches, "RECNUM", "PNR", DuplicatePolicy::First).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first["1"], "a");
+        assert_eq!(first["2"], "b");
+
+        let last = build_lookup(&batches, "RECNUM", "PNR", DuplicatePolicy::Last).unwrap();
+        assert_eq!(last["2"], "d");
+
+        let error = build_lookup(&batches, "RECNUM", "PNR", DuplicatePolicy::Error).unwrap_err();
+        assert!(error.to_string().contains("Key '2'"), "{error}");
+
+        // Only differing values are duplicates
+        let repeated = [batches[0].clone(), batches[0].clone()];
+        assert!(build_lookup(&repeated, "RECNUM", "PNR", DuplicatePolicy::Error).is_ok());
+        assert!(build_lookup(&batches, "RECNUM", "MISSING", DuplicatePolicy::First).is_err());
+    }
+}
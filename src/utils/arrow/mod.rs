@@ -5,16 +5,35 @@
 //! and array manipulations.
 
 pub mod array_utils;
+pub mod batch;
 pub mod conversion;
+pub mod date_validation;
+pub mod diff;
+pub mod distinct;
 pub mod extractors;
+pub mod frequency;
+pub mod lookup;
+pub mod nulls;
 
 // Re-export commonly used functions for convenience
 pub use array_utils::get_column;
+pub use batch::{concat_coerced, concat_schema_union, rechunk, take_rows, unify_schemas};
 pub use conversion::{
     arrow_array_to_bool, arrow_array_to_date, arrow_array_to_f64, arrow_array_to_i32,
     arrow_array_to_i64, arrow_array_to_string, arrow_date_to_naive_date,
 };
+pub use date_validation::{
+    DateRangeCheck, DateValidationReport, apply_date_range_checks, null_out_of_range_dates,
+    validate_date_range,
+};
+pub use diff::{
+    ChangedRow, ColumnChange, DatasetDiff, DiffKey, diff_dataset_stream, diff_datasets,
+};
+pub use distinct::{HLL_PRECISION, HyperLogLog, approx_distinct_pnr};
 pub use extractors::{
     extract_boolean, extract_date32, extract_date_from_string, extract_float64,
     extract_int32, extract_int8_as_padded_string, extract_string,
-};
\ No newline at end of file
+};
+pub use frequency::{ScalarValue, ValueCounter, ValueCounts, value_counts, value_counts_top_n};
+pub use lookup::{DuplicatePolicy, build_lookup};
+pub use nulls::{ColumnNullStats, null_report};
@@ -0,0 +1,130 @@
+//! Null counts of loaded columns
+//!
+//! A column that comes back entirely null usually means a mapping error,
+//! such as a misnamed source column, rather than missing data. Schema
+//! validation cannot see that; [`null_report`] counts the nulls of every
+//! column so it is noticed right after loading.
+
+use arrow::array::Array;
+use arrow::record_batch::RecordBatch;
+
+/// The null count of one column across batches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnNullStats {
+    /// The column name
+    pub column: String,
+    /// The number of rows across all batches
+    pub total_rows: usize,
+    /// The number of null values, including rows of batches without the column
+    pub null_count: usize,
+}
+
+impl ColumnNullStats {
+    /// Get the fraction of rows that are null
+    ///
+    /// A column without rows has a fraction of 0.
+    #[must_use]
+    pub fn null_fraction(&self) -> f64 {
+        if self.total_rows == 0 {
+            0.0
+        } else {
+            self.null_count as f64 / self.total_rows as f64
+        }
+    }
+
+    /// Check whether the column has rows and all of them are null
+    #[must_use]
+    pub const fn is_all_null(&self) -> bool {
+        self.total_rows > 0 && self.null_count == self.total_rows
+    }
+}
+
+/// Count the nulls of every column across batches
+///
+/// Columns are reported in the order they first appear. A column missing
+/// from some batches counts the rows of those batches as null, and columns
+/// of type `Null` count every row as null.
+///
+/// # Arguments
+///
+/// * `batches` - The loaded batches
+///
+/// # Returns
+///
+/// The null statistics of each column
+#[must_use]
+pub fn null_report(batches: &[RecordBatch]) -> Vec<ColumnNullStats> {
+    let total_rows = batches.iter().map(RecordBatch::num_rows).sum();
+    let mut columns: Vec<String> = Vec::new();
+    for batch in batches {
+        for field in batch.schema().fields() {
+            if !columns.contains(field.name()) {
+                columns.push(field.name().clone());
+            }
+        }
+    }
+
+    columns
+        .into_iter()
+        .map(|column| {
+            let null_count = batches
+                .iter()
+                .map(|batch| {
+                    batch
+                        .column_by_name(&column)
+                        .map_or(batch.num_rows(), |array| array.logical_null_count())
+                })
+                .sum();
+            ColumnNullStats {
+                column,
+                total_rows,
+                null_count,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Int32Array, NullArray, StringArray};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_null_report() {
+        let batches = vec![
+            RecordBatch::try_from_iter(vec![
+                (
+                    "PNR",
+                    Arc::new(StringArray::from(vec![Some("a"), None])) as ArrayRef,
+                ),
+                ("MOR_ID", Arc::new(NullArray::new(2)) as ArrayRef),
+            ])
+            .unwrap(),
+            RecordBatch::try_from_iter(vec![
+                (
+                    "PNR",
+                    Arc::new(StringArray::from(vec!["b", "c"])) as ArrayRef,
+                ),
+                (
+                    "AAR",
+                    Arc::new(Int32Array::from(vec![2020, 2021])) as ArrayRef,
+                ),
+            ])
+            .unwrap(),
+        ];
+
+        let report = null_report(&batches);
+        let columns: Vec<&str> = report.iter().map(|stats| stats.column.as_str()).collect();
+        assert_eq!(columns, ["PNR", "MOR_ID", "AAR"]);
+
+        assert_eq!(report[0].null_count, 1);
+        assert!((report[0].null_fraction() - 0.25).abs() < f64::EPSILON);
+        assert!(report[1].is_all_null());
+        assert!((report[1].null_fraction() - 1.0).abs() < f64::EPSILON);
+        assert_eq!(report[2].null_count, 2);
+        assert!(!report[2].is_all_null());
+
+        assert!(null_report(&[]).is_empty());
+    }
+}
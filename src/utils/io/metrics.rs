@@ -0,0 +1,108 @@
+//! Load metrics
+//!
+//! `LoadMetrics` accumulates counters and timings while Parquet files are
+//! read, so operators can spot a registry that is unexpectedly slow or reads
+//! far more data than its filter should require. Metrics from parallel reads
+//! are collected per file and combined with [`LoadMetrics::merge`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Counters and timings accumulated during a load
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadMetrics {
+    /// Number of files opened
+    pub files_read: usize,
+    /// Number of files whose row groups were all skipped by the PNR filter
+    pub files_skipped: usize,
+    /// Number of row groups decoded
+    pub row_groups_read: usize,
+    /// Number of row groups skipped using column statistics
+    pub row_groups_skipped: usize,
+    /// Compressed size of the column chunks decoded, in bytes
+    pub bytes_read: u64,
+    /// Number of rows decoded from the files
+    pub rows_decoded: u64,
+    /// Number of rows returned after filtering
+    pub rows_returned: u64,
+    /// Time spent opening files and reading metadata
+    pub open_time: Duration,
+    /// Time spent decoding record batches
+    pub decode_time: Duration,
+    /// Time spent filtering and adapting decoded batches
+    pub filter_time: Duration,
+    /// Wall-clock load time per registry
+    pub registry_timings: BTreeMap<String, Duration>,
+}
+
+impl LoadMetrics {
+    /// Create empty metrics
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the counters and timings of another load to these metrics
+    ///
+    /// Registry timings for the same registry are summed.
+    pub fn merge(&mut self, other: &Self) {
+        self.files_read += other.files_read;
+        self.files_skipped += other.files_skipped;
+        self.row_groups_read += other.row_groups_read;
+        self.row_groups_skipped += other.row_groups_skipped;
+        self.bytes_read += other.bytes_read;
+        self.rows_decoded += other.rows_decoded;
+        self.rows_returned += other.rows_returned;
+        self.open_time += other.open_time;
+        self.decode_time += other.decode_time;
+        self.filter_time += other.filter_time;
+        for (registry, elapsed) in &other.registry_timings {
+            self.record_registry(registry, *elapsed);
+        }
+    }
+
+    /// Record the time spent loading a registry
+    pub fn record_registry(&mut self, registry: &str, elapsed: Duration) {
+        *self
+            .registry_timings
+            .entry(registry.to_string())
+            .or_default() += elapsed;
+    }
+
+    /// Fraction of row groups skipped, or `None` if no row groups were seen
+    #[must_use]
+    pub fn row_group_skip_ratio(&self) -> Option<f64> {
+        let total = self.row_groups_read + self.row_groups_skipped;
+        (total > 0).then(|| self.row_groups_skipped as f64 / total as f64)
+    }
+}
+
+impl fmt::Display for LoadMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "files: {} read, {} skipped",
+            self.files_read, self.files_skipped
+        )?;
+        writeln!(
+            f,
+            "row groups: {} read, {} skipped",
+            self.row_groups_read, self.row_groups_skipped
+        )?;
+        writeln!(
+            f,
+            "rows: {} decoded, {} returned ({} bytes read)",
+            self.rows_decoded, self.rows_returned, self.bytes_read
+        )?;
+        writeln!(
+            f,
+            "time: open {:?}, decode {:?}, filter {:?}",
+            self.open_time, self.decode_time, self.filter_time
+        )?;
+        for (registry, elapsed) in &self.registry_timings {
+            writeln!(f, "  {registry}: {elapsed:?}")?;
+        }
+        Ok(())
+    }
+}
@@ -3,12 +3,20 @@
 //! This module provides utilities for working with files, paths,
 //! and data formats like Parquet.
 
+pub mod metrics;
 pub mod parquet;
 pub mod paths;
+pub mod sink;
 
 // Re-export commonly used functions for convenience
+pub use metrics::LoadMetrics;
 pub use parquet::{
-    find_parquet_files, load_parquet_files_parallel, read_parquet, validate_directory,
+    FileSearchOptions, WriteOptions, add_partition_columns, check_file_columns,
+    estimate_batch_rows, find_parquet_files, find_parquet_files_opts, load_parquet_files_parallel,
+    load_parquet_files_with_budget, load_parquet_files_with_config,
+    load_parquet_files_with_metrics, parse_hive_partitions, read_parquet, read_parquet_schema,
+    read_parquet_with_config, resolve_batch_size, validate_directory, write_batches,
 };
 pub use paths::general::get_available_year_files;
-pub use paths::lpr::*;
\ No newline at end of file
+pub use paths::lpr::*;
+pub use sink::{CsvSink, FileSink, IpcSink, NdJsonSink, OutputFormat, OutputSink, ParquetSink};
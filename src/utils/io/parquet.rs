@@ -4,25 +4,36 @@
 //! It includes functions for finding Parquet files, reading them into Arrow
 //! record batches, and filtering data based on various criteria.
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::fs::File;
+use std::ops::Bound;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
 
 use crate::filter::core::BatchFilter;
+use crate::filter::pnr_normalization::PnrNormalization;
 use crate::schema::{DateFormatConfig, adapt_record_batch};
-use arrow::datatypes::Schema;
+use arrow::datatypes::{Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
 use itertools::Itertools;
 use parquet::arrow::{ProjectionMask, arrow_reader::ParquetRecordBatchReaderBuilder};
+use parquet::basic::Compression;
+use parquet::file::properties::{DEFAULT_MAX_ROW_GROUP_SIZE, WriterProperties};
+use parquet::file::reader::ChunkReader;
+use parquet::file::statistics::Statistics;
 use rayon::prelude::*;
 
-use crate::error::Result;
-use crate::utils::logging::{log_operation_complete, log_operation_start, log_warning};
+use crate::error::{ParquetReaderError, Result};
+use crate::utils::io::metrics::LoadMetrics;
+use crate::utils::io::sink::{OutputSink, ParquetSink};
+use crate::utils::logging::{LoadSpan, log_operation_complete, log_operation_start, log_warning};
 
 /// Default batch size for Parquet reading
 pub const DEFAULT_BATCH_SIZE: usize = 16384;
 
+/// The name of an in-memory Parquet source in logs and errors
+pub const IN_MEMORY_SOURCE: &str = "<in-memory>";
+
 /// Helper function to get batch size from environment
 #[must_use]
 pub fn get_batch_size() -> Option<usize> {
@@ -31,6 +42,27 @@ pub fn get_batch_size() -> Option<usize> {
         .and_then(|s| s.parse::<usize>().ok())
 }
 
+/// Resolve the batch size for a single load call
+///
+/// An explicit `batch_size` takes precedence over the `PARQUET_BATCH_SIZE`
+/// environment variable, which in turn takes precedence over
+/// [`DEFAULT_BATCH_SIZE`].
+///
+/// # Errors
+/// Returns an error if an explicit batch size of zero is requested
+pub fn resolve_batch_size(batch_size: Option<usize>) -> Result<usize> {
+    match batch_size {
+        Some(0) => Err(crate::error::ParquetReaderError::ValidationError(
+            "Batch size must be greater than zero".to_string(),
+        )
+        .into()),
+        Some(size) => Ok(size),
+        None => Ok(get_batch_size()
+            .filter(|&size| size > 0)
+            .unwrap_or(DEFAULT_BATCH_SIZE)),
+    }
+}
+
 /// Validates that a directory exists and is a directory
 ///
 /// # Arguments
@@ -125,69 +157,549 @@ pub fn find_pnr_column(batch: &RecordBatch) -> Result<(String, usize)> {
 fn filter_batch_by_pnr<S: ::std::hash::BuildHasher + std::marker::Sync>(
     batch: &RecordBatch,
     pnr_filter: &HashSet<String, S>,
+    normalization: PnrNormalization,
 ) -> Result<RecordBatch> {
     // Use the centralized PnrFilter from our new filter module
-    let pnr_filter_obj = crate::filter::pnr::PnrFilter::new(pnr_filter, None);
+    let pnr_filter_obj =
+        crate::filter::pnr::PnrFilter::with_normalization(pnr_filter, None, normalization);
 
     pnr_filter_obj.filter(batch)
 }
 
+/// Read the Arrow schema of a Parquet file without decoding any data
+///
+/// # Arguments
+/// * `path` - Path to the Parquet file
+///
+/// # Returns
+/// The Arrow schema stored in the file metadata
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or if the Parquet file is invalid
+pub fn read_parquet_schema(path: &Path) -> Result<arrow::datatypes::SchemaRef> {
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open file {}: {}", path.display(), e))?;
+    let reader_builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read parquet file {}: {}", path.display(), e))?;
+
+    Ok(reader_builder.schema().clone())
+}
+
+/// Estimate how many rows fit in a decoded batch of at most `max_batch_bytes`
+///
+/// The average row size is taken from the row-group metadata as the total
+/// uncompressed size of the columns that will be read (those in `schema`, or
+/// all columns without a schema) divided by the number of rows. This is an
+/// approximation of the decoded Arrow size, which is usually of the same order.
+/// The result is at least one row; files without rows fall back to the default
+/// batch size.
+///
+/// # Arguments
+/// * `path` - Path to the Parquet file
+/// * `schema` - Optional schema restricting the columns that count towards the size
+/// * `max_batch_bytes` - Approximate upper bound on the size of each decoded batch
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or its metadata cannot be read
+pub fn estimate_batch_rows(
+    path: &Path,
+    schema: Option<&Schema>,
+    max_batch_bytes: usize,
+) -> Result<usize> {
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open file {}: {}", path.display(), e))?;
+    let reader_builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read parquet file {}: {}", path.display(), e))?;
+
+    let mut total_rows: i64 = 0;
+    let mut total_bytes: i64 = 0;
+    for row_group in reader_builder.metadata().row_groups() {
+        total_rows += row_group.num_rows();
+        total_bytes += row_group
+            .columns()
+            .iter()
+            .filter(|column| {
+                schema.is_none_or(|schema| {
+                    let name = column
+                        .column_path()
+                        .parts()
+                        .first()
+                        .map_or("", String::as_str);
+                    schema.field_with_name(name).is_ok()
+                })
+            })
+            .map(parquet::file::metadata::ColumnChunkMetaData::uncompressed_size)
+            .sum::<i64>();
+    }
+
+    if total_rows <= 0 {
+        return Ok(get_batch_size()
+            .filter(|&size| size > 0)
+            .unwrap_or(DEFAULT_BATCH_SIZE));
+    }
+
+    let avg_row_bytes = usize::try_from(total_bytes / total_rows)
+        .unwrap_or(0)
+        .max(1);
+    Ok((max_batch_bytes / avg_row_bytes).max(1))
+}
+
+/// Check a file schema against the requested schema before projecting
+///
+/// Required (non-nullable) columns of `schema` that are absent from the file
+/// are always an error. Columns present in the file but not in `schema` are
+/// dropped by the projection and logged when `ignore_extra_columns` is true,
+/// and rejected otherwise.
+///
+/// # Arguments
+/// * `schema` - The requested (declared) schema
+/// * `file_schema` - The schema of the Parquet file
+/// * `ignore_extra_columns` - Whether extra file columns are tolerated
+/// * `path` - Path of the file, used for error and log messages
+///
+/// # Returns
+/// The names of the extra columns that will be dropped
+///
+/// # Errors
+/// Returns an error if a required column is missing, or if extra columns are
+/// present and `ignore_extra_columns` is false
+pub fn check_file_columns(
+    schema: &Schema,
+    file_schema: &Schema,
+    ignore_extra_columns: bool,
+    path: &Path,
+) -> Result<Vec<String>> {
+    let missing_required = schema
+        .fields()
+        .iter()
+        .filter(|f| !f.is_nullable() && file_schema.index_of(f.name()).is_err())
+        .map(|f| f.name().as_str())
+        .collect_vec();
+    if !missing_required.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Required columns missing from {}: {}",
+            path.display(),
+            missing_required.join(", ")
+        ));
+    }
+
+    let extra = file_schema
+        .fields()
+        .iter()
+        .filter(|f| schema.index_of(f.name()).is_err())
+        .map(|f| f.name().clone())
+        .collect_vec();
+    if !extra.is_empty() {
+        if !ignore_extra_columns {
+            return Err(anyhow::anyhow!(
+                "Columns not in the declared schema found in {}: {}",
+                path.display(),
+                extra.join(", ")
+            ));
+        }
+        log::info!(
+            "Dropping {} columns not in the declared schema from {}: {}",
+            extra.len(),
+            path.display(),
+            extra.join(", ")
+        );
+    }
+
+    Ok(extra)
+}
+
 /// Read a parquet file into Arrow record batches
 ///
+/// When a schema is given the file is projected down to its columns; extra
+/// file columns are dropped (see [`check_file_columns`]), while missing
+/// required columns are an error.
+///
 /// # Arguments
 /// * `path` - Path to the Parquet file
 /// * `schema` - Optional Arrow Schema for projecting specific columns
 /// * `pnr_filter` - Optional set of PNRs to filter the data by
+/// * `batch_size` - Optional number of rows per decoded batch, overriding the
+///   default (see [`resolve_batch_size`])
+///
+/// Smaller batches lower peak memory per batch at the cost of more per-batch
+/// overhead and less parallelism when filtering; larger batches improve
+/// throughput but hold more decoded data in memory at once.
 ///
 /// # Returns
 /// A vector of `RecordBatch` objects
 ///
 /// # Errors
-/// Returns an error if the file cannot be opened or if the Parquet file is invalid
-///
-/// # Panics
-/// Panics if the projection mask is Some but is attempted to be unwrapped as None
+/// Returns an error if the file cannot be opened, if the Parquet file is invalid,
+/// if a required column of the schema is missing, or if `batch_size` is zero
 pub fn read_parquet<S: std::hash::BuildHasher + std::marker::Sync>(
     path: &Path,
     schema: Option<&Schema>,
     pnr_filter: Option<&HashSet<String, S>>,
     adapt_types: Option<bool>,
     date_format_config: Option<&crate::schema::DateFormatConfig>,
+    batch_size: Option<usize>,
+) -> Result<Vec<RecordBatch>> {
+    read_parquet_impl(
+        path,
+        schema,
+        pnr_filter,
+        adapt_types,
+        date_format_config,
+        batch_size,
+        true,
+    )
+    .map(|(batches, _)| batches)
+}
+
+/// Read a parquet file using the settings of a [`ParquetReaderConfig`]
+///
+/// Type adaptation, date formats and the handling of extra columns are taken
+/// from `config`. With `ignore_extra_columns` disabled, a file containing
+/// columns outside `schema` is rejected instead of being projected down. The
+/// `date_range_checks` of `config` are applied to the loaded batches.
+///
+/// # Errors
+/// Returns an error if the file cannot be read, fails the column checks, or a
+/// date range check targets a column that is not a date column
+pub fn read_parquet_with_config<S: std::hash::BuildHasher + std::marker::Sync>(
+    path: &Path,
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    config: &crate::config::ParquetReaderConfig,
+) -> Result<Vec<RecordBatch>> {
+    read_parquet_impl(
+        path,
+        schema,
+        pnr_filter,
+        Some(config.adapt_types),
+        Some(&config.date_format_config),
+        None,
+        config.ignore_extra_columns,
+    )
+    .and_then(|(batches, _)| {
+        crate::utils::arrow::apply_date_range_checks(batches, &config.date_range_checks)
+    })
+}
+
+/// Read the first rows of a parquet file
+///
+/// Decoding stops once `limit` rows have been read, counting across row
+/// groups, and the last batch is truncated to the limit. The schema projects
+/// the file as in [`read_parquet`].
+///
+/// # Arguments
+/// * `path` - Path to the Parquet file
+/// * `schema` - Optional Arrow Schema for projecting specific columns
+/// * `limit` - The maximum number of rows to return
+///
+/// # Errors
+/// Returns an error if the file cannot be opened, if the Parquet file is
+/// invalid, or if a required column of the schema is missing
+pub fn read_parquet_head(
+    path: &Path,
+    schema: Option<&Schema>,
+    limit: usize,
+) -> Result<Vec<RecordBatch>> {
+    read_parquet_head_impl(path, schema, limit, true)
+}
+
+fn read_parquet_head_impl(
+    path: &Path,
+    schema: Option<&Schema>,
+    limit: usize,
+    ignore_extra_columns: bool,
 ) -> Result<Vec<RecordBatch>> {
-    let start = std::time::Instant::now();
-    log_operation_start("Reading parquet file", path);
-    // Open the file
     let file = File::open(path)
         .map_err(|e| anyhow::anyhow!("Failed to open file {}: {}", path.display(), e))?;
+    let mut reader_builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read parquet file {}", e))?
+        .with_batch_size(resolve_batch_size(None)?.min(limit.max(1)))
+        .with_limit(limit);
+
+    if let Some(schema) = schema {
+        let file_schema = reader_builder.schema();
+        check_file_columns(schema, file_schema, ignore_extra_columns, path)?;
+        if let Some(projection_mask) =
+            create_projection(schema, file_schema, reader_builder.parquet_schema()).1
+        {
+            reader_builder = reader_builder.with_projection(projection_mask);
+        }
+    }
+    let reader = reader_builder
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to build parquet reader. Error: {}", e))?;
+
+    let mut batches = Vec::new();
+    let mut remaining = limit;
+    for batch_result in reader {
+        if remaining == 0 {
+            break;
+        }
+        let batch = batch_result
+            .map_err(|e| anyhow::anyhow!("Failed to read record batch. Error: {}", e))?;
+        let batch = batch.slice(0, remaining.min(batch.num_rows()));
+        remaining -= batch.num_rows();
+        if batch.num_rows() > 0 {
+            batches.push(batch);
+        }
+    }
+    Ok(batches)
+}
+
+/// Read the first rows of a sequence of parquet files
+///
+/// Files are read in order until `limit` rows have been produced, so later
+/// files are never opened. Without a PNR filter each file is read with
+/// [`read_parquet_head`]; with one, the limit counts the rows that pass the
+/// filter, and a file is read in full before its rows are counted.
+///
+/// # Arguments
+/// * `files` - The Parquet files, in the order to read them
+/// * `schema` - Optional Arrow Schema for projecting specific columns
+/// * `pnr_filter` - Optional set of PNRs to filter the data by
+/// * `limit` - The maximum number of rows to return
+///
+/// # Errors
+/// Returns an error if a file cannot be read
+pub fn read_parquet_files_head<S: std::hash::BuildHasher + std::marker::Sync>(
+    files: &[PathBuf],
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    limit: usize,
+) -> Result<Vec<RecordBatch>> {
+    let config = crate::config::ParquetReaderConfig {
+        adapt_types: false,
+        ..crate::config::ParquetReaderConfig::default()
+    };
+    read_parquet_files_head_with_config(files, schema, pnr_filter, limit, &config)
+}
+
+/// Read the first rows of a sequence of parquet files using the settings of
+/// a [`ParquetReaderConfig`]
+///
+/// Works like [`read_parquet_files_head`], reading each file as
+/// [`read_parquet_with_config`] does.
+///
+/// # Errors
+/// Returns an error if a file cannot be read, fails the column checks, or a
+/// date range check targets a column that is not a date column
+pub fn read_parquet_files_head_with_config<S: std::hash::BuildHasher + std::marker::Sync>(
+    files: &[PathBuf],
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    limit: usize,
+    config: &crate::config::ParquetReaderConfig,
+) -> Result<Vec<RecordBatch>> {
+    let mut batches = Vec::new();
+    let mut remaining = limit;
+    for file in files {
+        if remaining == 0 {
+            break;
+        }
+        let file_batches = match pnr_filter {
+            Some(pnr_filter) => read_parquet_with_config(file, schema, Some(pnr_filter), config)?,
+            None => {
+                let batches =
+                    read_parquet_head_impl(file, schema, remaining, config.ignore_extra_columns)?;
+                let batches = match schema.filter(|_| config.adapt_types) {
+                    Some(schema) => batches
+                        .into_iter()
+                        .map(|batch| {
+                            adapt_record_batch(&batch, schema, &config.date_format_config)
+                                .unwrap_or_else(|e| {
+                                    log::warn!(
+                                        "Failed to adapt record batch: {e}. Using original batch."
+                                    );
+                                    batch
+                                })
+                        })
+                        .collect(),
+                    None => batches,
+                };
+                crate::utils::arrow::apply_date_range_checks(batches, &config.date_range_checks)?
+            }
+        };
+        for batch in file_batches {
+            if remaining == 0 {
+                break;
+            }
+            let batch = batch.slice(0, remaining.min(batch.num_rows()));
+            remaining -= batch.num_rows();
+            batches.push(batch);
+        }
+    }
+    Ok(batches)
+}
+
+/// Read an in-memory Parquet file into Arrow record batches
+///
+/// The buffer is read as [`read_parquet`] reads a file, so a schema projects
+/// it and a PNR filter keeps only the matching rows. Data arriving over the
+/// network can be read without writing it to disk first.
+///
+/// # Arguments
+/// * `bytes` - The contents of a Parquet file
+/// * `schema` - Optional Arrow Schema for projecting specific columns
+/// * `pnr_filter` - Optional set of PNRs to filter the data by
+///
+/// # Errors
+/// Returns an error if the buffer is not a valid Parquet file, or if a
+/// required column of the schema is missing
+pub fn read_parquet_bytes<S: std::hash::BuildHasher + std::marker::Sync>(
+    bytes: Bytes,
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+) -> Result<Vec<RecordBatch>> {
+    read_parquet_source(
+        bytes,
+        Path::new(IN_MEMORY_SOURCE),
+        schema,
+        pnr_filter,
+        None,
+        None,
+        None,
+        true,
+    )
+    .map(|(batches, _)| batches)
+}
+
+/// Read an in-memory Parquet file using the settings of a [`ParquetReaderConfig`]
+///
+/// Works like [`read_parquet_bytes`], with the settings applied as in
+/// [`read_parquet_with_config`].
+///
+/// # Errors
+/// Returns an error if the buffer is not a valid Parquet file, fails the
+/// column checks, or a date range check targets a column that is not a date
+/// column
+pub fn read_parquet_bytes_with_config<S: std::hash::BuildHasher + std::marker::Sync>(
+    bytes: Bytes,
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    config: &crate::config::ParquetReaderConfig,
+) -> Result<Vec<RecordBatch>> {
+    read_parquet_source(
+        bytes,
+        Path::new(IN_MEMORY_SOURCE),
+        schema,
+        pnr_filter,
+        Some(config.adapt_types),
+        Some(&config.date_format_config),
+        None,
+        config.ignore_extra_columns,
+    )
+    .and_then(|(batches, _)| {
+        crate::utils::arrow::apply_date_range_checks(batches, &config.date_range_checks)
+    })
+}
+
+fn read_parquet_impl<S: std::hash::BuildHasher + std::marker::Sync>(
+    path: &Path,
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    adapt_types: Option<bool>,
+    date_format_config: Option<&crate::schema::DateFormatConfig>,
+    batch_size: Option<usize>,
+    ignore_extra_columns: bool,
+) -> Result<(Vec<RecordBatch>, LoadMetrics)> {
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open file {}: {}", path.display(), e))?;
+    read_parquet_source(
+        file,
+        path,
+        schema,
+        pnr_filter,
+        adapt_types,
+        date_format_config,
+        batch_size,
+        ignore_extra_columns,
+    )
+}
+
+/// Read a Parquet source, named by `path` in logs and errors
+#[allow(clippy::too_many_arguments)]
+fn read_parquet_source<T, S>(
+    source: T,
+    path: &Path,
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    adapt_types: Option<bool>,
+    date_format_config: Option<&crate::schema::DateFormatConfig>,
+    batch_size: Option<usize>,
+    ignore_extra_columns: bool,
+) -> Result<(Vec<RecordBatch>, LoadMetrics)>
+where
+    T: ChunkReader + 'static,
+    S: std::hash::BuildHasher + std::marker::Sync,
+{
+    let batch_size = resolve_batch_size(batch_size)?;
+    let start = std::time::Instant::now();
+    let mut metrics = LoadMetrics {
+        files_read: 1,
+        ..LoadMetrics::default()
+    };
+    let span = LoadSpan::file(path);
+    let _guard = span.enter();
+    log_operation_start("Reading parquet file", path);
 
     // Create the reader
-    let reader_builder = ParquetRecordBatchReaderBuilder::try_new(file)
-        .map_err(|e| anyhow::anyhow!("Failed to read parquet file {}", e))?;
+    let mut reader_builder = ParquetRecordBatchReaderBuilder::try_new(source)
+        .map_err(|e| anyhow::anyhow!("Failed to read parquet file {}", e))?
+        .with_batch_size(batch_size);
 
-    // Create the reader with optional projection
-    let reader = if let Some(schema) = schema {
-        // Apply schema projection
-        let file_schema = reader_builder.schema();
-        let (has_projection, projection_mask) =
-            create_projection(schema, file_schema, reader_builder.parquet_schema());
-
-        if has_projection {
-            // Build with projection
-            reader_builder
-                .with_projection(projection_mask.unwrap())
-                .build()
-                .map_err(|e| {
-                    anyhow::anyhow!(
-                        "Failed to build parquet reader with projection. Error: {}",
-                        e
-                    )
-                })?
-        } else {
-            // Build without projection (all columns)
-            reader_builder
-                .build()
-                .map_err(|e| anyhow::anyhow!("Failed to build parquet reader. Error: {}", e))?
+    let projection = match schema {
+        Some(schema) => {
+            let file_schema = reader_builder.schema();
+            check_file_columns(schema, file_schema, ignore_extra_columns, path)?;
+            create_projection(schema, file_schema, reader_builder.parquet_schema()).1
         }
+        None => None,
+    };
+
+    // Skip row groups whose PNR statistics rule out every PNR in the filter.
+    // The normalization of the row filter decides whether the statistics can.
+    let normalization = PnrNormalization::default();
+    let row_groups =
+        pnr_filter.map(|pnr_filter| prune_row_groups(&reader_builder, pnr_filter, normalization));
+    let metadata = reader_builder.metadata().clone();
+    let selected: Vec<usize> = row_groups
+        .clone()
+        .unwrap_or_else(|| (0..metadata.num_row_groups()).collect());
+    metrics.row_groups_read = selected.len();
+    metrics.row_groups_skipped = metadata.num_row_groups() - selected.len();
+    if selected.is_empty() && metadata.num_row_groups() > 0 {
+        metrics.files_skipped = 1;
+    }
+    for &index in &selected {
+        let row_group = metadata.row_group(index);
+        metrics.rows_decoded += row_group.num_rows().unsigned_abs();
+        metrics.bytes_read += row_group
+            .columns()
+            .iter()
+            .enumerate()
+            .filter(|(leaf, _)| {
+                projection
+                    .as_ref()
+                    .is_none_or(|mask| mask.leaf_included(*leaf))
+            })
+            .map(|(_, column)| column.compressed_size().unsigned_abs())
+            .sum::<u64>();
+    }
+    if let Some(row_groups) = row_groups {
+        reader_builder = reader_builder.with_row_groups(row_groups);
+    }
+
+    // Create the reader with optional projection
+    let reader = if let Some(projection_mask) = projection {
+        reader_builder
+            .with_projection(projection_mask)
+            .build()
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to build parquet reader with projection. Error: {}",
+                    e
+                )
+            })?
     } else {
         // No projection, read all columns
         reader_builder
@@ -195,8 +707,13 @@ pub fn read_parquet<S: std::hash::BuildHasher + std::marker::Sync>(
             .map_err(|e| anyhow::anyhow!("Failed to build parquet reader. Error: {}", e))?
     };
 
+    metrics.open_time = start.elapsed();
+
     // Collect the batches first to enable parallel processing
+    let decode_start = std::time::Instant::now();
     let batch_results: Vec<_> = reader.collect();
+    metrics.decode_time = decode_start.elapsed();
+    let filter_start = std::time::Instant::now();
 
     let binding = DateFormatConfig::default();
     // Use default date format config if none provided
@@ -205,77 +722,56 @@ pub fn read_parquet<S: std::hash::BuildHasher + std::marker::Sync>(
     // Determine if type adaptation is enabled
     let should_adapt_types = adapt_types.unwrap_or(false);
 
-    // Process the batches in parallel using rayon
+    // Process the batches in parallel using rayon. Indexed parallel iterators
+    // preserve input order on collect, so batches keep their order within the file.
     let batches = if let Some(pnr_filter) = pnr_filter {
-        // Create a thread-safe collector for filtered batches
-        let batches_collector = Mutex::new(Vec::with_capacity(batch_results.len()));
-
-        // Process batches in parallel
-        batch_results.par_iter().for_each(|batch_result| {
-            // Handle each batch independently
-            if let Ok(batch) = batch_result
-                .as_ref()
-                .map_err(|e| anyhow::anyhow!("Failed to read record batch. Error: {}", e))
-            {
-                // Filter the batch by PNR
-                if let Ok(filtered_batch) = filter_batch_by_pnr(batch, pnr_filter) {
-                    // Skip empty batches
-                    if filtered_batch.num_rows() == 0 {
-                        return;
-                    }
+        batch_results
+            .par_iter()
+            .filter_map(|batch_result| {
+                // Handle each batch independently
+                let batch = batch_result.as_ref().ok()?;
 
-                    // Apply type adaptation if enabled and schema is provided
-                    if should_adapt_types && schema.is_some() {
-                        match adapt_record_batch(&filtered_batch, schema.unwrap(), date_config) {
-                            Ok(adapted_batch) => {
-                                let mut batches = batches_collector.lock().unwrap();
-                                batches.push(adapted_batch);
-                            }
+                // Filter the batch by PNR, skipping empty results
+                let filtered_batch = filter_batch_by_pnr(batch, pnr_filter, normalization).ok()?;
+                if filtered_batch.num_rows() == 0 {
+                    return None;
+                }
+
+                // Apply type adaptation if enabled and schema is provided
+                match schema.filter(|_| should_adapt_types) {
+                    Some(schema) => {
+                        match adapt_record_batch(&filtered_batch, schema, date_config) {
+                            Ok(adapted_batch) => Some(adapted_batch),
                             Err(e) => {
                                 log::warn!(
                                     "Failed to adapt record batch: {e}. Using original batch."
                                 );
-                                let mut batches = batches_collector.lock().unwrap();
-                                batches.push(filtered_batch);
+                                Some(filtered_batch)
                             }
                         }
-                    } else {
-                        // No adaptation needed, use filtered batch as is
-                        let mut batches = batches_collector.lock().unwrap();
-                        batches.push(filtered_batch);
                     }
+                    // No adaptation needed, use filtered batch as is
+                    None => Some(filtered_batch),
                 }
-            }
-        });
-
-        // Get the collected batches
-        batches_collector.into_inner().unwrap()
+            })
+            .collect::<Vec<_>>()
     } else {
         // No PNR filter, process all batches
-        if should_adapt_types && schema.is_some() {
+        if let Some(schema) = schema.filter(|_| should_adapt_types) {
             // With type adaptation
-            let batches_collector = Mutex::new(Vec::with_capacity(batch_results.len()));
-
-            batch_results.par_iter().for_each(|batch_result| {
-                if let Ok(batch) = batch_result
-                    .as_ref()
-                    .map_err(|e| anyhow::anyhow!("Failed to read record batch. Error: {}", e))
-                {
-                    match adapt_record_batch(batch, schema.unwrap(), date_config) {
-                        Ok(adapted_batch) => {
-                            let mut batches = batches_collector.lock().unwrap();
-                            batches.push(adapted_batch);
-                        }
+            batch_results
+                .par_iter()
+                .filter_map(|batch_result| {
+                    let batch = batch_result.as_ref().ok()?;
+                    match adapt_record_batch(batch, schema, date_config) {
+                        Ok(adapted_batch) => Some(adapted_batch),
                         Err(e) => {
                             log::warn!("Failed to adapt record batch: {e}. Using original batch.");
-                            let mut batches = batches_collector.lock().unwrap();
-                            batches.push(batch.clone());
+                            Some(batch.clone())
                         }
                     }
-                }
-            });
-
-            batches_collector.into_inner().unwrap()
+                })
+                .collect::<Vec<_>>()
         } else {
             // No type adaptation, just process the batches
             let result: Result<Vec<RecordBatch>> = batch_results
@@ -290,52 +786,210 @@ pub fn read_parquet<S: std::hash::BuildHasher + std::marker::Sync>(
         }
     };
 
+    metrics.filter_time = filter_start.elapsed();
+    metrics.rows_returned = batches.iter().map(|batch| batch.num_rows() as u64).sum();
+    span.record("rows", metrics.rows_returned);
+    span.record("row_groups_skipped", metrics.row_groups_skipped as u64);
+
     log_operation_complete("read", path, batches.len(), Some(start.elapsed()));
-    Ok(batches)
+    Ok((batches, metrics))
+}
+
+/// Select the row groups that may contain a PNR from the filter
+///
+/// A row group is skipped when the min/max statistics of its PNR column
+/// (`PNR` or `pnr`) show that none of the filter's PNRs can occur in it.
+/// This holds only when PNRs are compared as stored
+/// ([`PnrNormalization::None`]). Under [`PnrNormalization::Canonical`] a
+/// stored value between canonical bounds, such as `123456789` between
+/// `1000000000` and `2000000000`, can still normalize to a filter PNR
+/// outside them (`0123456789`), so every row group is kept. Row groups
+/// without usable statistics are always kept.
+fn prune_row_groups<T, S: std::hash::BuildHasher>(
+    builder: &parquet::arrow::arrow_reader::ArrowReaderBuilder<T>,
+    pnr_filter: &HashSet<String, S>,
+    normalization: PnrNormalization,
+) -> Vec<usize> {
+    let metadata = builder.metadata();
+    let all: Vec<usize> = (0..metadata.num_row_groups()).collect();
+    if normalization != PnrNormalization::None {
+        return all;
+    }
+    let Some(column) = builder
+        .parquet_schema()
+        .columns()
+        .iter()
+        .position(|column| column.path().string() == "PNR")
+        .or_else(|| {
+            builder
+                .parquet_schema()
+                .columns()
+                .iter()
+                .position(|column| column.path().string() == "pnr")
+        })
+    else {
+        return all;
+    };
+
+    let sorted: BTreeSet<&str> = pnr_filter.iter().map(String::as_str).collect();
+    all.into_iter()
+        .filter(|&index| {
+            let Some(Statistics::ByteArray(stats)) =
+                metadata.row_group(index).column(column).statistics()
+            else {
+                return true;
+            };
+            let (Some(min), Some(max)) = (
+                stats.min_opt().and_then(|min| min.as_utf8().ok()),
+                stats.max_opt().and_then(|max| max.as_utf8().ok()),
+            ) else {
+                return true;
+            };
+            min > max
+                || sorted
+                    .range::<str, _>((Bound::Included(min), Bound::Included(max)))
+                    .next()
+                    .is_some()
+        })
+        .collect()
 }
 
-/// Find all Parquet files in a directory
+/// Options controlling Parquet file discovery
+///
+/// Used by [`find_parquet_files_opts`] to make the traversal behavior explicit.
+#[derive(Debug, Clone)]
+pub struct FileSearchOptions {
+    /// Whether to descend into subdirectories (e.g. Hive partitions)
+    pub recursive: bool,
+    /// Whether to descend into symlinked directories when searching recursively.
+    /// Symlinked files are always included; directory cycles are skipped.
+    pub follow_symlinks: bool,
+    /// Optional glob pattern matched against file names (e.g. `bef_*.parquet`).
+    /// Supports `*` (any sequence of characters) and `?` (any single character).
+    pub pattern: Option<String>,
+}
+
+impl Default for FileSearchOptions {
+    fn default() -> Self {
+        Self {
+            recursive: true,
+            follow_symlinks: false,
+            pattern: None,
+        }
+    }
+}
+
+/// Compile a file name glob pattern into an anchored regular expression
+fn glob_to_regex(pattern: &str) -> Result<regex::Regex> {
+    let mut regex_str = String::with_capacity(pattern.len() + 2);
+    regex_str.push('^');
+    for c in pattern.chars() {
+        match c {
+            '*' => regex_str.push_str(".*"),
+            '?' => regex_str.push('.'),
+            _ => regex_str.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex_str.push('$');
+
+    regex::Regex::new(&regex_str)
+        .map_err(|e| anyhow::anyhow!("Invalid file pattern '{pattern}': {e}"))
+}
+
+/// Find all Parquet files in a directory, recursively searching subdirectories
+///
+/// This is equivalent to [`find_parquet_files_opts`] with the default
+/// [`FileSearchOptions`], so Hive-style partitioned layouts such as
+/// `year=2019/part-0.parquet` are discovered alongside flat layouts.
 ///
 /// # Arguments
 /// * `dir` - Path to the directory to search
 ///
 /// # Returns
-/// A vector of paths to Parquet files
+/// A vector of paths to Parquet files, sorted by path
 ///
 /// # Errors
 /// Returns an error if directory reading fails
 pub fn find_parquet_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    find_parquet_files_opts(dir, &FileSearchOptions::default())
+}
+
+/// Find Parquet files in a directory using explicit search options
+///
+/// # Arguments
+/// * `dir` - Path to the directory to search
+/// * `options` - Controls recursion, symlink handling and file name matching
+///
+/// # Returns
+/// A vector of paths to Parquet files, sorted by path for determinism
+///
+/// # Errors
+/// Returns an error if directory reading fails or the pattern is invalid
+pub fn find_parquet_files_opts(dir: &Path, options: &FileSearchOptions) -> Result<Vec<PathBuf>> {
     log_operation_start("Searching for parquet files in", dir);
 
     // Validate directory
     validate_directory(dir)?;
 
-    // Find all parquet files in the directory efficiently using rayon for parallelism
-    // and itertools for improved iteration performance
-    let parquet_files = std::fs::read_dir(dir)
-        .map_err(|e| anyhow::anyhow!("Failed to read directory {}: {}", dir.display(), e))?
-        .par_bridge() // Convert to parallel iterator
-        .filter_map(|entry_result| match entry_result {
-            Ok(entry) => {
-                let path = entry.path();
-                if path.is_file() && path.extension().is_some_and(|ext| ext == "parquet") {
-                    Some(Ok(path))
-                } else {
-                    None
+    let pattern = options.pattern.as_deref().map(glob_to_regex).transpose()?;
+
+    // Recursive helper that descends into subdirectories (e.g. Hive partitions)
+    fn find_recursively(
+        dir: &Path,
+        options: &FileSearchOptions,
+        pattern: Option<&regex::Regex>,
+        visited: &mut HashSet<PathBuf>,
+        results: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| anyhow::anyhow!("Failed to read directory {}: {}", dir.display(), e))?;
+
+        for entry_result in entries {
+            let entry =
+                entry_result.map_err(|e| anyhow::anyhow!("Failed to read directory entry: {e}"))?;
+            let path = entry.path();
+            let file_type = entry.file_type().map_err(|e| {
+                anyhow::anyhow!("Failed to read file type for {}: {}", path.display(), e)
+            })?;
+
+            let is_dir = file_type.is_dir()
+                || (file_type.is_symlink() && options.follow_symlinks && path.is_dir());
+
+            if is_dir {
+                if !options.recursive {
+                    continue;
+                }
+                // Guard against symlink cycles by tracking canonical paths
+                let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+                if visited.insert(canonical) {
+                    find_recursively(&path, options, pattern, visited, results)?;
                 }
+            } else if path.is_file()
+                && path.extension().is_some_and(|ext| ext == "parquet")
+                && pattern.is_none_or(|re| {
+                    path.file_name()
+                        .is_some_and(|name| re.is_match(&name.to_string_lossy()))
+                })
+            {
+                results.push(path);
             }
-            Err(e) => Some(Err(anyhow::anyhow!("Failed to read directory entry: {e}"))),
-        })
-        .collect::<Result<Vec<_>>>()? // Collect errors during processing
-        .into_iter()
-        .sorted_by(|a, b| {
-            // Sort by modification time (newest first) for better caching behavior
-            std::fs::metadata(b)
-                .and_then(|m| m.modified())
-                .ok()
-                .cmp(&std::fs::metadata(a).and_then(|m| m.modified()).ok())
-        })
-        .collect_vec();
+        }
+
+        Ok(())
+    }
+
+    let mut visited = HashSet::new();
+    visited.insert(dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()));
+
+    let mut parquet_files = Vec::new();
+    find_recursively(
+        dir,
+        options,
+        pattern.as_ref(),
+        &mut visited,
+        &mut parquet_files,
+    )?;
+    parquet_files.sort();
 
     // If no files found, log a warning
     if parquet_files.is_empty() {
@@ -347,31 +1001,315 @@ pub fn find_parquet_files(dir: &Path) -> Result<Vec<PathBuf>> {
     Ok(parquet_files)
 }
 
+/// Parse Hive-style partition keys from the directories of a file path
+///
+/// Every directory component of the form `key=value` between `base_dir` and the
+/// file is returned in order, e.g. `base/year=2019/part-0.parquet` yields
+/// `[("year", "2019")]`. Components outside `base_dir` are ignored.
+///
+/// # Arguments
+/// * `base_dir` - The root directory of the partitioned dataset
+/// * `path` - Path to a file inside `base_dir`
+///
+/// # Returns
+/// A vector of `(key, value)` pairs
+#[must_use]
+pub fn parse_hive_partitions(base_dir: &Path, path: &Path) -> Vec<(String, String)> {
+    let relative = path.strip_prefix(base_dir).unwrap_or(path);
+    let Some(parent) = relative.parent() else {
+        return Vec::new();
+    };
+
+    parent
+        .components()
+        .filter_map(|component| {
+            let component = component.as_os_str().to_str()?;
+            let (key, value) = component.split_once('=')?;
+            if key.is_empty() {
+                None
+            } else {
+                Some((key.to_string(), value.to_string()))
+            }
+        })
+        .collect_vec()
+}
+
+/// Materialize Hive partition values as columns of a record batch
+///
+/// Columns that are already present in the batch are left untouched, so a value
+/// stored in the file always takes precedence over the directory name. When a
+/// target schema is given, only partition keys that match one of its fields
+/// (case-insensitively) are added, using the schema's field name and data type.
+/// Without a schema every partition key is added as a nullable `Utf8` column.
+///
+/// # Arguments
+/// * `batch` - The record batch read from the partition file
+/// * `partitions` - Partition key/value pairs as returned by [`parse_hive_partitions`]
+/// * `schema` - Optional target schema controlling which keys are materialized
+///
+/// # Returns
+/// The batch with the partition columns appended
+///
+/// # Errors
+/// Returns an error if a partition value cannot be cast to the target type
+pub fn add_partition_columns(
+    batch: &RecordBatch,
+    partitions: &[(String, String)],
+    schema: Option<&Schema>,
+) -> Result<RecordBatch> {
+    use arrow::array::StringArray;
+    use arrow::datatypes::{DataType, Field};
+
+    let batch_schema = batch.schema();
+    let mut fields = batch_schema.fields().iter().cloned().collect_vec();
+    let mut columns = batch.columns().to_vec();
+
+    for (key, value) in partitions {
+        // Resolve the column name and type from the target schema if provided
+        let (name, data_type) = match schema {
+            Some(schema) => match schema
+                .fields()
+                .iter()
+                .find(|f| f.name().eq_ignore_ascii_case(key))
+            {
+                Some(field) => (field.name().clone(), field.data_type().clone()),
+                None => continue,
+            },
+            None => (key.clone(), DataType::Utf8),
+        };
+
+        if fields.iter().any(|f| f.name() == &name) {
+            continue;
+        }
+
+        let values: arrow::array::ArrayRef =
+            std::sync::Arc::new(StringArray::from(vec![value.as_str(); batch.num_rows()]));
+        let column = arrow::compute::cast(&values, &data_type).map_err(|e| {
+            anyhow::anyhow!("Failed to cast partition value '{value}' for column {name}: {e}")
+        })?;
+
+        // Values that cannot be cast become nulls, so partition columns are nullable
+        fields.push(std::sync::Arc::new(Field::new(&name, data_type, true)));
+        columns.push(column);
+    }
+
+    RecordBatch::try_new(std::sync::Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| anyhow::anyhow!("Failed to add partition columns to record batch: {e}"))
+}
+
 /// Load all parquet files from a directory in parallel
 ///
+/// Files in Hive-style partition subdirectories (`key=value/`) are included.
+/// Partition keys that match a field of `schema` but are absent from the file
+/// are materialized as columns; without a schema all partition keys are added.
+///
+/// The returned batches are deterministically ordered: files are concatenated
+/// in sorted path order, and the batches of each file keep the order in which
+/// they appear in that file. Repeated loads of the same directory therefore
+/// produce identically-ordered batches.
+///
 /// # Arguments
 /// * `dir` - Path to the directory containing Parquet files
 /// * `schema` - Optional Arrow Schema for projecting specific columns
 /// * `pnr_filter` - Optional set of PNRs to filter the data by
+/// * `batch_size` - Optional number of rows per decoded batch for every file,
+///   overriding the default; see [`read_parquet`] for the tradeoff
 ///
 /// # Returns
 /// A vector of record batches from all files
 ///
 /// # Errors
-/// Returns an error if directory reading fails or any file cannot be read
+/// Returns an error if directory reading fails, any file cannot be read, or
+/// `batch_size` is zero
 pub fn load_parquet_files_parallel<S: ::std::hash::BuildHasher + std::marker::Sync>(
     dir: &Path,
     schema: Option<&Schema>,
     pnr_filter: Option<&HashSet<String, S>>,
     adapt_types: Option<bool>,
     date_format_config: Option<&DateFormatConfig>,
+    batch_size: Option<usize>,
 ) -> Result<Vec<RecordBatch>> {
+    // Validate up front so an invalid size fails even for an empty directory
+    let batch_size = resolve_batch_size(batch_size)?;
+
+    load_files_impl(
+        dir,
+        schema,
+        pnr_filter,
+        adapt_types,
+        date_format_config,
+        &|_| Ok(batch_size),
+        true,
+    )
+    .map(|(batches, _)| batches)
+}
+
+/// Load all parquet files from a directory using the settings of a
+/// [`ParquetReaderConfig`]
+///
+/// Works like [`load_parquet_files_parallel`], reading each file as
+/// [`read_parquet_with_config`] does. The `date_range_checks` of `config` are
+/// applied to the batches of every file.
+///
+/// # Errors
+/// Returns an error if directory reading fails, any file cannot be read or
+/// fails the column checks, or a date range check targets a column that is
+/// not a date column
+pub fn load_parquet_files_with_config<S: ::std::hash::BuildHasher + std::marker::Sync>(
+    dir: &Path,
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    config: &crate::config::ParquetReaderConfig,
+) -> Result<Vec<RecordBatch>> {
+    let batch_size = resolve_batch_size(None)?;
+
+    load_files_impl(
+        dir,
+        schema,
+        pnr_filter,
+        Some(config.adapt_types),
+        Some(&config.date_format_config),
+        &|_| Ok(batch_size),
+        config.ignore_extra_columns,
+    )
+    .and_then(|(batches, _)| {
+        crate::utils::arrow::apply_date_range_checks(batches, &config.date_range_checks)
+    })
+}
+
+/// Load all parquet files from a directory, returning load metrics
+///
+/// Works like [`load_parquet_files_parallel`], and additionally returns the
+/// counters and timings of the load (see [`LoadMetrics`]). PNRs are compared
+/// in canonical form, which the row group statistics cannot rule out, so
+/// every row group is read and `row_groups_skipped` stays zero.
+///
+/// # Returns
+/// The record batches from all files and the metrics of the load
+///
+/// # Errors
+/// Returns an error if directory reading fails, any file cannot be read, or
+/// `batch_size` is zero
+pub fn load_parquet_files_with_metrics<S: ::std::hash::BuildHasher + std::marker::Sync>(
+    dir: &Path,
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    adapt_types: Option<bool>,
+    date_format_config: Option<&DateFormatConfig>,
+    batch_size: Option<usize>,
+) -> Result<(Vec<RecordBatch>, LoadMetrics)> {
+    let batch_size = resolve_batch_size(batch_size)?;
+
+    load_files_impl(
+        dir,
+        schema,
+        pnr_filter,
+        adapt_types,
+        date_format_config,
+        &|_| Ok(batch_size),
+        true,
+    )
+}
+
+/// Load all parquet files from a directory, sizing batches to a memory budget
+///
+/// Works like [`load_parquet_files_parallel`], but instead of a fixed batch
+/// size each file gets its own row count, estimated from its metadata by
+/// [`estimate_batch_rows`] so that a decoded batch stays within
+/// `max_batch_bytes`. Wide files (e.g. `LPR_ADM`) get small batches while
+/// narrow files (e.g. AKM) keep large, high-throughput ones.
+///
+/// # Arguments
+/// * `dir` - Path to the directory containing Parquet files
+/// * `schema` - Optional Arrow Schema for projecting specific columns
+/// * `pnr_filter` - Optional set of PNRs to filter the data by
+/// * `adapt_types` - Whether to cast columns to the types of `schema`;
+///   defaults to false, and has no effect without a schema
+/// * `date_format_config` - Formats used to parse string dates when adapting
+///   types; defaults to [`DateFormatConfig::default`]
+/// * `max_batch_bytes` - Approximate upper bound on the size of each decoded batch
+///
+/// # Returns
+/// A vector of record batches from all files, in the same order as
+/// [`load_parquet_files_parallel`]
+///
+/// # Errors
+/// Returns an error if `max_batch_bytes` is zero, directory reading fails, or
+/// any file cannot be read
+pub fn load_parquet_files_with_budget<S: ::std::hash::BuildHasher + std::marker::Sync>(
+    dir: &Path,
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    adapt_types: Option<bool>,
+    date_format_config: Option<&DateFormatConfig>,
+    max_batch_bytes: usize,
+) -> Result<Vec<RecordBatch>> {
+    if max_batch_bytes == 0 {
+        return Err(crate::error::ParquetReaderError::ValidationError(
+            "Memory budget per batch must be greater than zero".to_string(),
+        )
+        .into());
+    }
+
+    load_files_impl(
+        dir,
+        schema,
+        pnr_filter,
+        adapt_types,
+        date_format_config,
+        &|path| {
+            let rows = estimate_batch_rows(path, schema, max_batch_bytes)?;
+            log::debug!("Using batch size {rows} for {}", path.display());
+            Ok(rows)
+        },
+        true,
+    )
+    .map(|(batches, _)| batches)
+}
+
+/// Load the Parquet files of a directory, choosing each file's batch size
+///
+/// Shared by the directory loaders. Files are read in parallel and their
+/// batches concatenated in sorted path order, with Hive partition columns
+/// added (see [`load_parquet_files_parallel`]).
+///
+/// # Arguments
+/// * `dir` - Path to the directory containing Parquet files
+/// * `schema` - Optional Arrow Schema for projecting specific columns
+/// * `pnr_filter` - Optional set of PNRs to filter the data by
+/// * `adapt_types` - Whether to cast columns to the types of `schema`
+/// * `date_format_config` - Formats used to parse string dates when adapting
+///   types
+/// * `batch_size_for` - Number of rows per decoded batch for a file path
+/// * `ignore_extra_columns` - Whether extra file columns are tolerated (see
+///   [`check_file_columns`])
+///
+/// # Returns
+/// The record batches of all files, and the metrics of the load summed
+/// over the files
+///
+/// # Errors
+/// Returns an error if directory reading fails, `batch_size_for` fails, or
+/// any file cannot be read
+fn load_files_impl<S: ::std::hash::BuildHasher + std::marker::Sync>(
+    dir: &Path,
+    schema: Option<&Schema>,
+    pnr_filter: Option<&HashSet<String, S>>,
+    adapt_types: Option<bool>,
+    date_format_config: Option<&DateFormatConfig>,
+    batch_size_for: &(dyn Fn(&Path) -> Result<usize> + Sync),
+    ignore_extra_columns: bool,
+) -> Result<(Vec<RecordBatch>, LoadMetrics)> {
+    let span = LoadSpan::directory(dir);
+    let _guard = span.enter();
+
     // Find all parquet files in the directory
     let parquet_files = find_parquet_files(dir)?;
+    span.record("files", parquet_files.len() as u64);
 
     // If no files found, return empty result
     if parquet_files.is_empty() {
-        return Ok(Vec::new());
+        return Ok((Vec::new(), LoadMetrics::default()));
     }
 
     // Clone schema and pnr_filter for sharing across threads
@@ -379,37 +1317,583 @@ pub fn load_parquet_files_parallel<S: ::std::hash::BuildHasher + std::marker::Sy
     let pnr_filter_arc = pnr_filter.map(std::sync::Arc::new);
     let date_format_config_arc = date_format_config.map(|c| std::sync::Arc::new(c.clone()));
 
-    // Process files in parallel using rayon
-    let all_batches: Vec<Result<Vec<RecordBatch>>> = parquet_files
+    // Process files in parallel using rayon. `find_parquet_files` returns paths in
+    // sorted order and indexed parallel iterators preserve that order on collect.
+    let all_batches: Vec<Result<(Vec<RecordBatch>, LoadMetrics)>> = parquet_files
         .par_iter()
         .map(|path| {
-            // Use clone of schema and pnr_filter
-            let schema_ref = schema_arc.as_ref().map(std::convert::AsRef::as_ref);
-            let pnr_filter_ref = pnr_filter_arc.as_deref();
-            let date_config_ref = date_format_config_arc.as_deref();
+            span.in_scope(|| {
+                // Use clone of schema and pnr_filter
+                let schema_ref = schema_arc.as_ref().map(std::convert::AsRef::as_ref);
+                let pnr_filter_ref = pnr_filter_arc.as_deref();
+                let date_config_ref = date_format_config_arc.as_deref();
 
-            read_parquet::<S>(
-                path,
-                schema_ref,
-                pnr_filter_ref.map(|v| &**v),
-                adapt_types,
-                date_config_ref,
-            )
+                let (batches, metrics) = read_parquet_impl::<S>(
+                    path,
+                    schema_ref,
+                    pnr_filter_ref.map(|v| &**v),
+                    adapt_types,
+                    date_config_ref,
+                    Some(batch_size_for(path)?),
+                    ignore_extra_columns,
+                )?;
+
+                // Materialize Hive partition values (e.g. `year=2019`) missing from the file
+                let partitions = parse_hive_partitions(dir, path);
+                if partitions.is_empty() {
+                    return Ok((batches, metrics));
+                }
+                let batches = batches
+                    .iter()
+                    .map(|batch| add_partition_columns(batch, &partitions, schema_ref))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok((batches, metrics))
+            })
         })
         .collect();
 
     // Combine all the results, propagating any errors
     let mut combined_batches = Vec::new();
+    let mut combined_metrics = LoadMetrics::default();
     for result in all_batches {
-        let batches = result?;
+        let (batches, metrics) = result?;
         combined_batches.extend(batches);
+        combined_metrics.merge(&metrics);
     }
 
+    span.record("rows", combined_metrics.rows_returned);
     log::info!(
         "Successfully loaded {} batches from {} Parquet files",
         combined_batches.len(),
         parquet_files.len()
     );
 
-    Ok(combined_batches)
-}
\ No newline at end of file
+    Ok((combined_batches, combined_metrics))
+}
+
+/// Options for [`write_batches`]
+#[derive(Debug, Clone)]
+pub struct WriteOptions {
+    /// Compression codec, e.g. `Compression::SNAPPY` or
+    /// `Compression::ZSTD(ZstdLevel::default())`
+    pub compression: Compression,
+    /// Maximum number of rows per row group
+    pub row_group_size: usize,
+    /// The declared schema of the output, e.g. the registry's canonical
+    /// schema. Defaults to the schema of the first batch; required to write an
+    /// empty file.
+    pub schema: Option<SchemaRef>,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            compression: Compression::SNAPPY,
+            row_group_size: DEFAULT_MAX_ROW_GROUP_SIZE,
+            schema: None,
+        }
+    }
+}
+
+/// Write record batches to a single Parquet file
+///
+/// Every batch must have the same fields, in the same order and with the same
+/// types, as the output schema, so the file keeps the declared column order
+/// and types. An empty `batches` slice produces a valid zero-row file.
+///
+/// # Arguments
+/// * `path` - Path of the output file, created or truncated
+/// * `batches` - The batches to write
+/// * `options` - Compression, row group size and optional declared schema
+///
+/// # Returns
+/// The number of rows written
+///
+/// # Errors
+/// Returns an error if a batch does not match the schema, if `batches` is
+/// empty and no schema is given, if `row_group_size` is zero, or if writing
+/// fails
+pub fn write_batches(
+    path: &Path,
+    batches: &[RecordBatch],
+    options: &WriteOptions,
+) -> Result<usize> {
+    if options.row_group_size == 0 {
+        return Err(ParquetReaderError::ValidationError(
+            "Row group size must be greater than zero".to_string(),
+        )
+        .into());
+    }
+
+    let schema = match (&options.schema, batches.first()) {
+        (Some(schema), _) => schema.clone(),
+        (None, Some(batch)) => batch.schema(),
+        (None, None) => {
+            return Err(ParquetReaderError::schema_error_with_path(
+                "Cannot write an empty file without a schema",
+                path,
+            )
+            .into());
+        }
+    };
+
+    for (index, batch) in batches.iter().enumerate() {
+        if batch.schema().fields() != schema.fields() {
+            return Err(ParquetReaderError::schema_error_with_path(
+                format!("Batch {index} does not match the output schema"),
+                path,
+            )
+            .into());
+        }
+    }
+
+    let properties = WriterProperties::builder()
+        .set_compression(options.compression)
+        .set_max_row_group_size(options.row_group_size)
+        .build();
+
+    log_operation_start("Writing parquet file", path);
+    let mut sink = ParquetSink::with_properties(path, schema, Some(properties))?;
+    for batch in batches {
+        sink.write_batch(batch)?;
+    }
+    sink.finish()?;
+
+    let rows = batches.iter().map(RecordBatch::num_rows).sum();
+    log_operation_complete("wrote", path, rows, None);
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    fn write_pnr_file(path: &Path, pnrs: &[&str]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("PNR", DataType::Utf8, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(pnrs.to_vec()))],
+        )
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(File::create(path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_parse_hive_partitions() {
+        let base = Path::new("/data/IND");
+        assert_eq!(
+            parse_hive_partitions(base, &base.join("year=2019/part-0.parquet")),
+            vec![("year".to_string(), "2019".to_string())]
+        );
+        assert_eq!(
+            parse_hive_partitions(base, &base.join("year=2019/month=03/part-0.parquet")),
+            vec![
+                ("year".to_string(), "2019".to_string()),
+                ("month".to_string(), "03".to_string())
+            ]
+        );
+        assert!(parse_hive_partitions(base, &base.join("2019.parquet")).is_empty());
+        assert!(parse_hive_partitions(base, &base.join("misc/2019.parquet")).is_empty());
+    }
+
+    #[test]
+    fn test_check_file_columns() {
+        let path = Path::new("bef.parquet");
+        let declared = Schema::new(vec![
+            Field::new("PNR", DataType::Utf8, false),
+            Field::new("KOEN", DataType::Utf8, true),
+        ]);
+        let file = Schema::new(vec![
+            Field::new("PNR", DataType::Utf8, false),
+            Field::new("NEW_COLUMN", DataType::Int32, true),
+        ]);
+
+        let dropped = check_file_columns(&declared, &file, true, path).unwrap();
+        assert_eq!(dropped, vec!["NEW_COLUMN".to_string()]);
+        assert!(check_file_columns(&declared, &file, false, path).is_err());
+
+        let without_pnr = Schema::new(vec![Field::new("KOEN", DataType::Utf8, true)]);
+        assert!(check_file_columns(&declared, &without_pnr, true, path).is_err());
+    }
+
+    #[test]
+    fn test_read_parquet_head() {
+        let dir = std::env::temp_dir().join(format!("par_reader_head_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let first = dir.join("a.parquet");
+        let second = dir.join("b.parquet");
+        write_pnr_file(&second, &["f", "g", "h"]);
+
+        // Row groups of two rows, so the limit cuts across them
+        let schema = Arc::new(Schema::new(vec![Field::new("PNR", DataType::Utf8, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(vec!["a", "b", "c", "d", "e"]))],
+        )
+        .unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&first).unwrap(), schema, Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let pnrs = |batches: Vec<RecordBatch>| {
+            batches
+                .iter()
+                .flat_map(|batch| {
+                    let pnrs = batch
+                        .column(0)
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .unwrap();
+                    (0..pnrs.len())
+                        .map(|row| pnrs.value(row).to_string())
+                        .collect_vec()
+                })
+                .collect_vec()
+        };
+
+        assert_eq!(
+            pnrs(read_parquet_head(&first, None, 3).unwrap()),
+            ["a", "b", "c"]
+        );
+        assert_eq!(pnrs(read_parquet_head(&first, None, 10).unwrap()).len(), 5);
+        assert!(read_parquet_head(&first, None, 0).unwrap().is_empty());
+
+        let files = [first, second];
+        let head = read_parquet_files_head::<std::hash::RandomState>(&files, None, None, 6);
+        assert_eq!(pnrs(head.unwrap()), ["a", "b", "c", "d", "e", "f"]);
+
+        let filter: HashSet<String> = ["b", "g", "h"].iter().map(ToString::to_string).collect();
+        let head = read_parquet_files_head(&files, None, Some(&filter), 2);
+        assert_eq!(pnrs(head.unwrap()), ["b", "g"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_parquet_bytes() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("PNR", DataType::Utf8, false),
+            Field::new("AAR", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+                Arc::new(Int32Array::from(vec![2019, 2020, 2021])),
+            ],
+        )
+        .unwrap();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let bytes = Bytes::from(buffer);
+
+        let projection = Schema::new(vec![Field::new("PNR", DataType::Utf8, false)]);
+        let filter: HashSet<String> = ["a", "c"].iter().map(ToString::to_string).collect();
+        let batches = read_parquet_bytes(bytes.clone(), Some(&projection), Some(&filter)).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_columns(), 1);
+        assert_eq!(batches[0].num_rows(), 2);
+
+        let all = read_parquet_bytes::<std::hash::RandomState>(bytes, None, None).unwrap();
+        assert_eq!(all, vec![batch]);
+
+        assert!(read_parquet_bytes::<std::hash::RandomState>(Bytes::new(), None, None).is_err());
+    }
+
+    #[test]
+    fn test_find_parquet_files_opts() {
+        let dir = std::env::temp_dir().join(format!("par_reader_search_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        write_pnr_file(&dir.join("bef_2020.parquet"), &["a"]);
+        write_pnr_file(&dir.join("bef_2019.parquet"), &["b"]);
+        write_pnr_file(&dir.join("akm_2019.parquet"), &["c"]);
+        write_pnr_file(&dir.join("nested/bef_2021.parquet"), &["d"]);
+
+        let names = |files: Vec<PathBuf>| {
+            files
+                .iter()
+                .map(|p| p.strip_prefix(&dir).unwrap().to_string_lossy().into_owned())
+                .collect_vec()
+        };
+
+        let all = find_parquet_files(&dir).unwrap();
+        assert_eq!(
+            names(all),
+            vec![
+                "akm_2019.parquet",
+                "bef_2019.parquet",
+                "bef_2020.parquet",
+                "nested/bef_2021.parquet"
+            ]
+        );
+
+        let flat = FileSearchOptions {
+            recursive: false,
+            pattern: Some("bef_*.parquet".to_string()),
+            ..FileSearchOptions::default()
+        };
+        assert_eq!(
+            names(find_parquet_files_opts(&dir, &flat).unwrap()),
+            vec!["bef_2019.parquet", "bef_2020.parquet"]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_parquet_files_parallel_is_deterministic() {
+        let dir = std::env::temp_dir().join(format!("par_reader_order_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for i in 0..8 {
+            let pnrs = (0..10).map(|j| format!("{i}-{j}")).collect_vec();
+            let pnrs = pnrs.iter().map(String::as_str).collect_vec();
+            write_pnr_file(&dir.join(format!("part-{i}.parquet")), &pnrs);
+        }
+        let filter: HashSet<String> = (0..8)
+            .flat_map(|i| [format!("{i}-1"), format!("{i}-7")])
+            .collect();
+
+        let load = || {
+            load_parquet_files_parallel(&dir, None, Some(&filter), None, None, None)
+                .unwrap()
+                .iter()
+                .flat_map(|batch| {
+                    let pnrs = batch
+                        .column(0)
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .unwrap();
+                    (0..pnrs.len())
+                        .map(|i| pnrs.value(i).to_string())
+                        .collect_vec()
+                })
+                .collect_vec()
+        };
+
+        let first = load();
+        assert_eq!(first.len(), 16);
+        assert_eq!(first[0], "0-1");
+        assert_eq!(first[15], "7-7");
+        for _ in 0..5 {
+            assert_eq!(load(), first);
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_hive_partitioned_directory() {
+        let dir = std::env::temp_dir().join(format!("par_reader_hive_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        write_pnr_file(&dir.join("year=2019/part-0.parquet"), &["a", "b"]);
+        write_pnr_file(&dir.join("year=2020/part-0.parquet"), &["c"]);
+
+        let files = find_parquet_files(&dir).unwrap();
+        assert_eq!(files.len(), 2);
+
+        let schema = Schema::new(vec![
+            Field::new("PNR", DataType::Utf8, false),
+            Field::new("YEAR", DataType::Int32, true),
+        ]);
+        let batches = load_parquet_files_parallel::<std::collections::hash_map::RandomState>(
+            &dir,
+            Some(&schema),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let mut rows = Vec::new();
+        for batch in &batches {
+            let pnrs = batch
+                .column_by_name("PNR")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            let years = batch
+                .column_by_name("YEAR")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            for i in 0..batch.num_rows() {
+                assert!(!years.is_null(i));
+                rows.push((pnrs.value(i).to_string(), years.value(i)));
+            }
+        }
+        rows.sort();
+        assert_eq!(
+            rows,
+            vec![
+                ("a".to_string(), 2019),
+                ("b".to_string(), 2019),
+                ("c".to_string(), 2020)
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_parquet_batch_size_override() {
+        let dir = std::env::temp_dir().join(format!("par_reader_batch_{}", std::process::id()));
+        let path = dir.join("pnrs.parquet");
+        let pnrs: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+        write_pnr_file(&path, &pnrs.iter().map(String::as_str).collect_vec());
+
+        let read = |batch_size| {
+            read_parquet::<std::collections::hash_map::RandomState>(
+                &path, None, None, None, None, batch_size,
+            )
+        };
+        let batches = read(Some(3)).unwrap();
+        assert_eq!(
+            batches.iter().map(RecordBatch::num_rows).collect_vec(),
+            vec![3, 3, 3, 1]
+        );
+        assert!(read(Some(0)).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_estimate_batch_rows() {
+        let dir = std::env::temp_dir().join(format!("par_reader_budget_{}", std::process::id()));
+        let path = dir.join("pnrs.parquet");
+        let pnrs: Vec<String> = (0..1000).map(|i| format!("{i:010}")).collect();
+        write_pnr_file(&path, &pnrs.iter().map(String::as_str).collect_vec());
+
+        let small = estimate_batch_rows(&path, None, 1024).unwrap();
+        let large = estimate_batch_rows(&path, None, 1024 * 1024).unwrap();
+        assert!((1..1000).contains(&small));
+        assert!(large > small);
+        assert_eq!(estimate_batch_rows(&path, None, 1).unwrap(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_batches() {
+        let dir = std::env::temp_dir().join(format!("par_reader_write_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("PNR", DataType::Utf8, false),
+            Field::new("KOEN", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["1", "2", "3"])),
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(2)])),
+            ],
+        )
+        .unwrap();
+
+        let path = dir.join("out.parquet");
+        let options = WriteOptions {
+            compression: Compression::ZSTD(parquet::basic::ZstdLevel::default()),
+            row_group_size: 2,
+            schema: None,
+        };
+        assert_eq!(
+            write_batches(&path, &[batch.clone(), batch.clone()], &options).unwrap(),
+            6
+        );
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(builder.metadata().num_row_groups(), 3);
+        assert_eq!(builder.schema().fields(), schema.fields());
+
+        // An empty slice writes a zero-row file with the declared schema
+        let empty = dir.join("empty.parquet");
+        assert!(write_batches(&empty, &[], &WriteOptions::default()).is_err());
+        let options = WriteOptions {
+            schema: Some(schema.clone()),
+            ..WriteOptions::default()
+        };
+        assert_eq!(write_batches(&empty, &[], &options).unwrap(), 0);
+        assert_eq!(
+            read_parquet_schema(&empty).unwrap().fields(),
+            schema.fields()
+        );
+
+        // Batches must match the output schema
+        let other = batch.project(&[1, 0]).unwrap();
+        assert!(write_batches(&path, &[batch, other], &WriteOptions::default()).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_metrics_row_group_pruning() {
+        let dir = std::env::temp_dir().join(format!("par_reader_metrics_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("PNR", DataType::Utf8, false)]));
+        let write = |name: &str, pnrs: Vec<String>, row_group_size| {
+            let batch =
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(StringArray::from(pnrs))])
+                    .unwrap();
+            let options = WriteOptions {
+                row_group_size,
+                ..WriteOptions::default()
+            };
+            let path = dir.join(format!("{name}.parquet"));
+            write_batches(&path, &[batch], &options).unwrap();
+            path
+        };
+        let a = write("a", (1..=6).map(|i| format!("01018{i:05}")).collect(), 2);
+        write("b", (7..=8).map(|i| format!("01018{i:05}")).collect(), 2);
+        // An unpadded PNR between canonical bounds of its row group
+        let c = write(
+            "c",
+            vec!["1000000000".into(), "123456789".into(), "2000000000".into()],
+            3,
+        );
+
+        // Canonical filter PNRs match stored values outside the statistics'
+        // bounds, so no row group is skipped
+        let filter: HashSet<String> =
+            HashSet::from(["010180-0003".to_string(), "0123456789".to_string()]);
+        let (batches, metrics) =
+            load_parquet_files_with_metrics(&dir, None, Some(&filter), None, None, None).unwrap();
+
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 2);
+        assert_eq!(metrics.files_read, 3);
+        assert_eq!(metrics.files_skipped, 0);
+        assert_eq!(metrics.row_groups_read, 5);
+        assert_eq!(metrics.row_groups_skipped, 0);
+        assert_eq!(metrics.rows_decoded, 11);
+        assert_eq!(metrics.rows_returned, 2);
+        assert!(metrics.bytes_read > 0);
+
+        // PNRs compared as stored are pruned by the statistics
+        let row_groups = |path: &Path, pnr: &str, normalization| {
+            let builder =
+                ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap();
+            prune_row_groups(&builder, &HashSet::from([pnr.to_string()]), normalization)
+        };
+        assert_eq!(row_groups(&a, "0101800003", PnrNormalization::None), [1]);
+        assert!(row_groups(&c, "0123456789", PnrNormalization::None).is_empty());
+        assert_eq!(
+            row_groups(&a, "0101800003", PnrNormalization::Canonical),
+            [0, 1, 2]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
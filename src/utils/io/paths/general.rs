@@ -3,7 +3,7 @@
 //! This module provides utilities for working with file paths and directories
 //! common to all registry types.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Get base directory for registry data
 ///
@@ -34,6 +34,29 @@ pub fn registry_dir(registry: &str) -> PathBuf {
     data_dir().join(registry)
 }
 
+/// Find the folder of a registry under a base directory
+///
+/// The folder is named after the registry as given, in lower case or in
+/// upper case, tried in that order.
+///
+/// # Arguments
+/// * `base_dir` - The directory holding one folder per registry
+/// * `registry` - The registry name (e.g., "BEF", "`LPR_ADM`")
+///
+/// # Returns
+/// The path to the registry folder, or `None` if there is none
+#[must_use]
+pub fn find_registry_dir(base_dir: &Path, registry: &str) -> Option<PathBuf> {
+    [
+        registry.to_string(),
+        registry.to_lowercase(),
+        registry.to_uppercase(),
+    ]
+    .into_iter()
+    .map(|name| base_dir.join(name))
+    .find(|path| path.exists())
+}
+
 /// Create a path to a specific file in a registry folder
 ///
 /// # Arguments
@@ -9,7 +9,7 @@ pub mod temporal;
 pub mod time_period;
 
 // Re-export commonly used functions for convenience
-pub use general::get_available_year_files;
+pub use general::{find_registry_dir, get_available_year_files};
 pub use temporal::{
     get_registry_time_period_files, 
     filter_files_by_date_range, 
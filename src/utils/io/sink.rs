@@ -0,0 +1,333 @@
+//! Output sinks for writing record batches
+//!
+//! This module provides the `OutputSink` trait, a common interface for
+//! writing record batches to Parquet, CSV, Arrow IPC or newline-delimited
+//! JSON files. Use [`FileSink::create`] to pick the format at runtime, e.g.
+//! from a command-line flag or the output file's extension.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use arrow::csv;
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::writer::FileWriter;
+use arrow::json::LineDelimitedWriter;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+
+use crate::error::{Error, Result};
+
+/// A destination that record batches can be written to
+pub trait OutputSink {
+    /// Write a record batch
+    ///
+    /// # Errors
+    /// Returns an error if the batch cannot be encoded or written
+    fn write_batch(&mut self, batch: &RecordBatch) -> Result<()>;
+
+    /// Flush buffered data and write any trailing metadata
+    ///
+    /// The output is only guaranteed to be complete once this returns.
+    ///
+    /// # Errors
+    /// Returns an error if the final write fails
+    fn finish(self) -> Result<()>;
+}
+
+/// Supported output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Apache Parquet
+    Parquet,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Arrow IPC file format
+    Ipc,
+    /// Newline-delimited JSON, one object per row
+    NdJson,
+}
+
+impl OutputFormat {
+    /// Infer the format from a file extension
+    ///
+    /// Recognizes `parquet`, `csv`, `arrow`/`ipc`/`feather` and
+    /// `ndjson`/`jsonl`/`json` (case-insensitive).
+    ///
+    /// # Returns
+    /// The format, or `None` for an unknown or missing extension
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "parquet" => Some(Self::Parquet),
+            "csv" => Some(Self::Csv),
+            "arrow" | "ipc" | "feather" => Some(Self::Ipc),
+            "ndjson" | "jsonl" | "json" => Some(Self::NdJson),
+            _ => None,
+        }
+    }
+}
+
+/// Create a buffered output file
+fn create_file(path: &Path) -> Result<BufWriter<File>> {
+    let file = File::create(path)
+        .map_err(|e| anyhow::anyhow!("Failed to create file {}: {}", path.display(), e))?;
+    Ok(BufWriter::new(file))
+}
+
+/// Writes record batches to a Parquet file
+pub struct ParquetSink {
+    writer: ArrowWriter<File>,
+}
+
+impl ParquetSink {
+    /// Create a Parquet sink with default writer properties
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created
+    pub fn try_new(path: &Path, schema: SchemaRef) -> Result<Self> {
+        Self::with_properties(path, schema, None)
+    }
+
+    /// Create a Parquet sink with explicit writer properties
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created
+    pub fn with_properties(
+        path: &Path,
+        schema: SchemaRef,
+        properties: Option<WriterProperties>,
+    ) -> Result<Self> {
+        let file = File::create(path)
+            .map_err(|e| anyhow::anyhow!("Failed to create file {}: {}", path.display(), e))?;
+        let writer = ArrowWriter::try_new(file, schema, properties)?;
+        Ok(Self { writer })
+    }
+}
+
+impl OutputSink for ParquetSink {
+    fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.writer.write(batch)?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        self.writer.close()?;
+        Ok(())
+    }
+}
+
+/// Writes record batches to a CSV file with a header row
+pub struct CsvSink {
+    writer: csv::Writer<BufWriter<File>>,
+}
+
+impl CsvSink {
+    /// Create a CSV sink
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created
+    pub fn try_new(path: &Path) -> Result<Self> {
+        let writer = csv::WriterBuilder::new()
+            .with_header(true)
+            .build(create_file(path)?);
+        Ok(Self { writer })
+    }
+}
+
+impl OutputSink for CsvSink {
+    fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.writer.write(batch)?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        self.writer.into_inner().flush()?;
+        Ok(())
+    }
+}
+
+/// Writes record batches to an Arrow IPC file
+pub struct IpcSink {
+    writer: FileWriter<BufWriter<File>>,
+}
+
+impl IpcSink {
+    /// Create an Arrow IPC sink
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created
+    pub fn try_new(path: &Path, schema: &SchemaRef) -> Result<Self> {
+        let writer = FileWriter::try_new(create_file(path)?, schema)?;
+        Ok(Self { writer })
+    }
+}
+
+impl OutputSink for IpcSink {
+    fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.writer.write(batch)?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.writer.finish()?;
+        self.writer.into_inner()?.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes record batches to a newline-delimited JSON file
+pub struct NdJsonSink {
+    writer: LineDelimitedWriter<BufWriter<File>>,
+}
+
+impl NdJsonSink {
+    /// Create a newline-delimited JSON sink
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created
+    pub fn try_new(path: &Path) -> Result<Self> {
+        let writer = LineDelimitedWriter::new(create_file(path)?);
+        Ok(Self { writer })
+    }
+}
+
+impl OutputSink for NdJsonSink {
+    fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.writer.write(batch)?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.writer.finish()?;
+        self.writer.into_inner().flush()?;
+        Ok(())
+    }
+}
+
+/// An output sink whose format is chosen at runtime
+///
+/// The writers are boxed as their sizes differ widely between formats.
+pub enum FileSink {
+    /// Parquet output
+    Parquet(Box<ParquetSink>),
+    /// CSV output
+    Csv(Box<CsvSink>),
+    /// Arrow IPC output
+    Ipc(Box<IpcSink>),
+    /// Newline-delimited JSON output
+    NdJson(Box<NdJsonSink>),
+}
+
+impl FileSink {
+    /// Create a sink of the given format
+    ///
+    /// # Arguments
+    /// * `path` - Path of the output file, created or truncated
+    /// * `format` - The output format
+    /// * `schema` - Schema of the batches to be written
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created
+    pub fn create(path: &Path, format: OutputFormat, schema: SchemaRef) -> Result<Self> {
+        Ok(match format {
+            OutputFormat::Parquet => Self::Parquet(Box::new(ParquetSink::try_new(path, schema)?)),
+            OutputFormat::Csv => Self::Csv(Box::new(CsvSink::try_new(path)?)),
+            OutputFormat::Ipc => Self::Ipc(Box::new(IpcSink::try_new(path, &schema)?)),
+            OutputFormat::NdJson => Self::NdJson(Box::new(NdJsonSink::try_new(path)?)),
+        })
+    }
+
+    /// Create a sink with the format inferred from the path's extension
+    ///
+    /// # Errors
+    /// Returns an error if the extension is not recognized or the file cannot
+    /// be created
+    pub fn from_path(path: &Path, schema: SchemaRef) -> Result<Self> {
+        let format = OutputFormat::from_path(path).ok_or_else(|| {
+            Error::ValidationError(format!(
+                "Cannot infer output format from {}",
+                path.display()
+            ))
+        })?;
+        Self::create(path, format, schema)
+    }
+}
+
+impl OutputSink for FileSink {
+    fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self {
+            Self::Parquet(sink) => sink.write_batch(batch),
+            Self::Csv(sink) => sink.write_batch(batch),
+            Self::Ipc(sink) => sink.write_batch(batch),
+            Self::NdJson(sink) => sink.write_batch(batch),
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Parquet(sink) => sink.finish(),
+            Self::Csv(sink) => sink.finish(),
+            Self::Ipc(sink) => sink.finish(),
+            Self::NdJson(sink) => sink.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Int32Array, StringArray};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_sinks_round_trip() {
+        let dir = std::env::temp_dir().join(format!("par_reader_sink_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "PNR",
+                Arc::new(StringArray::from(vec!["1", "2"])) as ArrayRef,
+            ),
+            ("KOEN", Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef),
+        ])
+        .unwrap();
+
+        for name in ["out.parquet", "out.csv", "out.arrow", "out.ndjson"] {
+            let path = dir.join(name);
+            let mut sink = FileSink::from_path(&path, batch.schema()).unwrap();
+            sink.write_batch(&batch).unwrap();
+            sink.write_batch(&batch).unwrap();
+            sink.finish().unwrap();
+        }
+
+        let parquet = File::open(dir.join("out.parquet")).unwrap();
+        let rows: usize = ParquetRecordBatchReaderBuilder::try_new(parquet)
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        assert_eq!(rows, 4);
+
+        let ipc = File::open(dir.join("out.arrow")).unwrap();
+        let rows: usize = arrow::ipc::reader::FileReader::try_new(ipc, None)
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        assert_eq!(rows, 4);
+
+        let csv = std::fs::read_to_string(dir.join("out.csv")).unwrap();
+        assert_eq!(csv, "PNR,KOEN\n1,1\n2,2\n1,1\n2,2\n");
+
+        let json = std::fs::read_to_string(dir.join("out.ndjson")).unwrap();
+        assert_eq!(json.lines().next(), Some(r#"{"PNR":"1","KOEN":1}"#));
+        assert_eq!(json.lines().count(), 4);
+
+        assert!(FileSink::from_path(&dir.join("out.txt"), batch.schema()).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -5,10 +5,14 @@
 pub mod console;
 pub mod log;
 pub mod progress;
+pub mod span;
 
 // Re-export commonly used functions for convenience
 pub use log::{log_operation_complete, log_operation_start, log_warning};
 pub use progress::{
     create_group_progress_bar, create_main_progress_bar, create_multi_progress,
     create_spinner, finish_and_clear, finish_progress_bar,
-};
\ No newline at end of file
+};
+#[cfg(feature = "spans")]
+pub use span::init_log_bridge;
+pub use span::{LoadSpan, LoadSpanGuard};
@@ -0,0 +1,168 @@
+//! Tracing spans around registry loads and file reads
+//!
+//! A [`LoadSpan`] wraps a unit of work, a registry load or a file read, in a
+//! `tracing` span with its registry, path and row count as fields. When many
+//! registries load concurrently, a tracing subscriber can group the work and
+//! the log records inside it by span, which ad-hoc `log::info!` lines cannot.
+//!
+//! Spans are only created with the `spans` feature enabled; otherwise every
+//! operation here is a no-op. Without a tracing subscriber, the spans are
+//! emitted through the `log` crate (the `log` feature of `tracing`), so
+//! existing logger setups receive them unchanged. With one,
+//! [`init_log_bridge`] forwards the crate's `log` records to it.
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::path::Path;
+
+#[cfg(feature = "spans")]
+use tracing::Instrument;
+
+/// A `tracing` span around a load, or nothing without the `spans` feature
+#[derive(Debug, Clone)]
+#[must_use = "a span is closed as soon as it is dropped"]
+pub struct LoadSpan {
+    #[cfg(feature = "spans")]
+    span: tracing::Span,
+}
+
+/// Guard of an entered [`LoadSpan`], which exits the span when dropped
+#[derive(Debug)]
+#[must_use = "the span is exited as soon as the guard is dropped"]
+pub struct LoadSpanGuard<'a> {
+    #[cfg(feature = "spans")]
+    _entered: tracing::span::Entered<'a>,
+    _span: PhantomData<&'a LoadSpan>,
+}
+
+impl LoadSpan {
+    /// Create the span of loading a registry
+    pub fn registry(name: &str) -> Self {
+        #[cfg(not(feature = "spans"))]
+        let _ = name;
+        Self {
+            #[cfg(feature = "spans")]
+            span: tracing::info_span!(
+                "load_registry",
+                registry = name,
+                rows = tracing::field::Empty
+            ),
+        }
+    }
+
+    /// Create the span of reading one Parquet file
+    pub fn file(path: &Path) -> Self {
+        #[cfg(not(feature = "spans"))]
+        let _ = path;
+        Self {
+            #[cfg(feature = "spans")]
+            span: tracing::info_span!(
+                "read_parquet_file",
+                path = %path.display(),
+                rows = tracing::field::Empty,
+                row_groups_skipped = tracing::field::Empty
+            ),
+        }
+    }
+
+    /// Create the span of reading the Parquet files of a directory
+    pub fn directory(dir: &Path) -> Self {
+        #[cfg(not(feature = "spans"))]
+        let _ = dir;
+        Self {
+            #[cfg(feature = "spans")]
+            span: tracing::info_span!(
+                "load_parquet_files",
+                dir = %dir.display(),
+                files = tracing::field::Empty,
+                rows = tracing::field::Empty
+            ),
+        }
+    }
+
+    /// Record a count field declared by the span, such as `rows`
+    pub fn record(&self, field: &'static str, value: u64) {
+        #[cfg(feature = "spans")]
+        self.span.record(field, value);
+        #[cfg(not(feature = "spans"))]
+        let _ = (field, value);
+    }
+
+    /// Enter the span on this thread until the guard is dropped
+    ///
+    /// Spans created while it is entered, e.g. of the files of a directory,
+    /// become its children. Do not hold the guard across an `.await`; use
+    /// [`LoadSpan::in_scope_async`] instead.
+    pub fn enter(&self) -> LoadSpanGuard<'_> {
+        LoadSpanGuard {
+            #[cfg(feature = "spans")]
+            _entered: self.span.enter(),
+            _span: PhantomData,
+        }
+    }
+
+    /// Run a closure inside the span
+    ///
+    /// Used to carry the span into work run on other threads, such as a
+    /// rayon pool, so spans created there get the right parent.
+    pub fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        #[cfg(feature = "spans")]
+        return self.span.in_scope(f);
+        #[cfg(not(feature = "spans"))]
+        f()
+    }
+
+    /// Await a future inside the span, wherever its task is polled
+    pub async fn in_scope_async<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "spans")]
+        return future.instrument(self.span.clone()).await;
+        #[cfg(not(feature = "spans"))]
+        future.await
+    }
+
+    /// Get the underlying `tracing` span
+    #[cfg(feature = "spans")]
+    pub const fn span(&self) -> &tracing::Span {
+        &self.span
+    }
+}
+
+/// Forward the crate's `log` records to the installed tracing subscriber
+///
+/// Call this once when using a tracing subscriber instead of a `log`
+/// logger, so the records of a load appear inside its spans.
+///
+/// # Errors
+/// Returns an error if a `log` logger has already been set
+#[cfg(feature = "spans")]
+pub fn init_log_bridge() -> Result<(), log::SetLoggerError> {
+    tracing_log::LogTracer::init()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_load_span_scopes() {
+        let span = LoadSpan::directory(Path::new("data"));
+        let _guard = span.enter();
+        let rows: u64 = (1..=3u64)
+            .into_par_iter()
+            .map(|rows| {
+                span.in_scope(|| {
+                    let file = LoadSpan::file(Path::new("data/part.parquet"));
+                    file.record("rows", rows);
+                    rows
+                })
+            })
+            .sum();
+        span.record("rows", rows);
+        assert_eq!(rows, 6);
+
+        let registry = LoadSpan::registry("BEF");
+        let loaded = futures::executor::block_on(registry.in_scope_async(async { 42 }));
+        assert_eq!(loaded, 42);
+    }
+}
@@ -13,7 +13,7 @@ pub mod test;
 // Re-export the most commonly used functions for convenience
 pub use io::parquet::{
     DEFAULT_BATCH_SIZE, find_parquet_files, get_batch_size, load_parquet_files_parallel,
-    read_parquet, validate_directory,
+    read_parquet, resolve_batch_size, validate_directory,
 };
 
 pub use logging::log::{log_operation_complete, log_operation_start, log_warning};
@@ -3,62 +3,282 @@
 //! This module provides utilities for detecting registry types from data schemas.
 //! It centralizes the registry detection logic to ensure consistency across the codebase.
 
+use std::fmt;
+use std::str::FromStr;
+
+use arrow::datatypes::Schema;
 use arrow::record_batch::RecordBatch;
 use log::debug;
 
+use crate::error::ParquetReaderError;
+use crate::registry::RegisterLoader;
+use crate::registry::direct_registry_loader::DirectRegistryLoader;
+
 /// Registry type identifier constants
+///
+/// One variant per registry with a loader. The names of [`Self::as_str`]
+/// are those used by `registry_from_name` and `DirectRegistryLoader`, and
+/// are parsed back case-insensitively by [`FromStr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RegistryType {
+    /// AKM - Employment registry
+    AKM,
     /// BEF - Population registry
     BEF,
+    /// DOD - Death registry
+    DOD,
+    /// DODSAARSAG - Cause of death registry
+    DODSAARSAG,
+    /// IDAN - Integrated employment registry
+    IDAN,
     /// IND - Income registry
     IND,
-    /// LPR - Patient registry
-    LPR,
+    /// `LPR_ADM` - LPR2 admissions
+    LprAdm,
+    /// `LPR_DIAG` - LPR2 diagnoses
+    LprDiag,
+    /// `LPR_BES` - LPR2 outpatient visits
+    LprBes,
+    /// `LPR3_KONTAKTER` - LPR3 contacts
+    Lpr3Kontakter,
+    /// `LPR3_DIAGNOSER` - LPR3 diagnoses
+    Lpr3Diagnoser,
     /// MFR - Medical birth registry
     MFR,
-    /// VNDS - Migration registry
-    VNDS,
-    /// DOD - Death registry
-    DOD,
-    /// AKM - Employment registry
-    AKM,
     /// UDDF - Education registry
     UDDF,
+    /// VNDS - Migration registry
+    VNDS,
     /// Unknown registry type
     Unknown,
 }
 
 impl RegistryType {
+    /// All known registry types
+    pub const ALL: [Self; 14] = [
+        Self::AKM,
+        Self::BEF,
+        Self::DOD,
+        Self::DODSAARSAG,
+        Self::IDAN,
+        Self::IND,
+        Self::LprAdm,
+        Self::LprDiag,
+        Self::LprBes,
+        Self::Lpr3Kontakter,
+        Self::Lpr3Diagnoser,
+        Self::MFR,
+        Self::UDDF,
+        Self::VNDS,
+    ];
+
+    /// Get the columns a file of this registry is expected to contain
+    ///
+    /// These are the columns declared in the registry's field mapping.
+    #[must_use] pub const fn expected_columns(&self) -> &'static [&'static str] {
+        match self {
+            Self::AKM => &["PNR", "SOCIO13"],
+            Self::BEF => &[
+                "PNR",
+                "AEGTE_ID",
+                "ALDER",
+                "ANTPERSF",
+                "ANTPERSH",
+                "BOP_VFRA",
+                "FAMILIE_ID",
+                "FAMILIE_TYPE",
+                "FAR_ID",
+                "FOED_DAG",
+                "IE_TYPE",
+                "KOEN",
+                "MOR_ID",
+                "PLADS",
+            ],
+            Self::DOD => &["PNR", "DODDATO", "C_AARSAG"],
+            Self::DODSAARSAG => &["PNR", "C_AARSAG", "C_TILSTAND", "D_DATO"],
+            Self::IDAN => &[
+                "PNR", "ARBGNR", "ARBNR", "CVRNR", "JOBKAT", "JOBLON", "LSTILL", "STILL",
+            ],
+            Self::IND => &["PNR", "PERINDKIALT_13", "LOENMV_13", "YEAR"],
+            Self::LprAdm => &["RECNUM", "PNR", "C_ADIAG", "C_AFD", "D_INDDTO", "D_UDDTO"],
+            Self::LprDiag => &["RECNUM", "C_DIAG", "C_DIAGTYPE"],
+            Self::LprBes => &["RECNUM", "D_AMBDTO"],
+            Self::Lpr3Kontakter => &["CPR", "DW_EK_KONTAKT", "DW_EK_FORLOEB"],
+            Self::Lpr3Diagnoser => &["DW_EK_KONTAKT", "diagnosekode", "diagnosetype"],
+            Self::MFR => &["CPR_BARN", "CPR_MODER", "CPR_FADER", "FOEDSELSDATO"],
+            Self::UDDF => &["PNR", "HFAUDD", "HF_VFRA", "HF_VTIL", "HF_KILDE", "INSTNR"],
+            Self::VNDS => &["PNR", "HAEND_DATO", "INDUD_KODE"],
+            Self::Unknown => &[],
+        }
+    }
+
+    /// Check whether a column is expected only in this registry
+    fn is_distinctive(&self, column: &str) -> bool {
+        Self::ALL
+            .iter()
+            .filter(|other| *other != self)
+            .all(|other| !other.expected_columns().contains(&column))
+    }
+
     /// Convert `RegistryType` to static string
     #[must_use] pub const fn as_str(&self) -> &'static str {
         match self {
+            Self::AKM => "AKM",
             Self::BEF => "BEF",
+            Self::DOD => "DOD",
+            Self::DODSAARSAG => "DODSAARSAG",
+            Self::IDAN => "IDAN",
             Self::IND => "IND",
-            Self::LPR => "LPR",
+            Self::LprAdm => "LPR_ADM",
+            Self::LprDiag => "LPR_DIAG",
+            Self::LprBes => "LPR_BES",
+            Self::Lpr3Kontakter => "LPR3_KONTAKTER",
+            Self::Lpr3Diagnoser => "LPR3_DIAGNOSER",
             Self::MFR => "MFR",
-            Self::VNDS => "VNDS",
-            Self::DOD => "DOD",
-            Self::AKM => "AKM",
             Self::UDDF => "UDDF",
+            Self::VNDS => "VNDS",
             Self::Unknown => "UNKNOWN",
         }
     }
+
+    /// Create the loader of this registry
+    ///
+    /// Registries are loaded with direct deserialization. `Unknown` has no
+    /// field mapping of its own and reads only the default mapping.
+    #[must_use] pub fn loader(&self) -> Box<dyn RegisterLoader> {
+        Box::new(DirectRegistryLoader::new(self.as_str()))
+    }
+}
+
+impl fmt::Display for RegistryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl From<&str> for RegistryType {
+    /// Parse a registry name as [`FromStr`] does, with `Unknown` for
+    /// unrecognized names
     fn from(s: &str) -> Self {
-        match s.trim().to_uppercase().as_str() {
-            "BEF" => Self::BEF,
-            "IND" => Self::IND,
-            "LPR" => Self::LPR,
-            "MFR" => Self::MFR,
-            "VNDS" => Self::VNDS,
-            "DOD" => Self::DOD,
-            "AKM" => Self::AKM,
-            "UDDF" => Self::UDDF,
-            _ => Self::Unknown,
+        s.parse().unwrap_or(Self::Unknown)
+    }
+}
+
+impl FromStr for RegistryType {
+    type Err = ParquetReaderError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let name = s.trim();
+        Self::ALL
+            .into_iter()
+            .find(|registry| registry.as_str().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParquetReaderError::MetadataError(format!("Unknown registry: {name}")))
+    }
+}
+
+/// Confidence below which a detection should be confirmed by the user
+pub const MIN_CONFIDENCE: f64 = 0.5;
+
+/// The outcome of detecting the registry type of a schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectionResult {
+    /// The detected registry type
+    pub registry: RegistryType,
+    /// The fraction of the registry's expected columns present, from 0 to 1
+    pub confidence: f64,
+    /// The expected columns present in the schema
+    pub matched_columns: Vec<String>,
+}
+
+impl DetectionResult {
+    /// Score a schema against a registry type
+    #[must_use] pub fn for_registry(registry: RegistryType, schema: &Schema) -> Self {
+        let expected = registry.expected_columns();
+        let matched_columns: Vec<String> = expected
+            .iter()
+            .filter(|column| schema.field_with_name(column).is_ok())
+            .map(|column| (*column).to_string())
+            .collect();
+        let confidence = if expected.is_empty() {
+            0.0
+        } else {
+            matched_columns.len() as f64 / expected.len() as f64
+        };
+        Self {
+            registry,
+            confidence,
+            matched_columns,
         }
     }
+
+    /// Check whether the confidence reaches [`MIN_CONFIDENCE`]
+    ///
+    /// A less confident detection should be confirmed before loading, e.g.
+    /// a directory matching only 2 of the 14 BEF columns.
+    #[must_use] pub fn is_confident(&self) -> bool {
+        self.confidence >= MIN_CONFIDENCE
+    }
+}
+
+/// Rank the registry types a schema may belong to
+///
+/// A registry is a candidate if the schema contains at least one column
+/// expected only in that registry (e.g. `SOCIO13` for AKM or `HFAUDD` for
+/// UDDF); shared columns such as `PNR` do not make a candidate on their own.
+/// Candidates are ordered by confidence, then by the number of matched
+/// columns.
+///
+/// # Arguments
+///
+/// * `schema` - The schema to examine
+///
+/// # Returns
+///
+/// The candidate detections, most likely first, or an empty list if no
+/// registry matches
+#[must_use] pub fn rank_registry_types(schema: &Schema) -> Vec<DetectionResult> {
+    let mut candidates: Vec<DetectionResult> = RegistryType::ALL
+        .iter()
+        .map(|registry_type| DetectionResult::for_registry(*registry_type, schema))
+        .filter(|result| {
+            result
+                .matched_columns
+                .iter()
+                .any(|column| result.registry.is_distinctive(column))
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then(b.matched_columns.len().cmp(&a.matched_columns.len()))
+    });
+    candidates
+}
+
+/// Detect registry type from a schema
+///
+/// # Arguments
+///
+/// * `schema` - The schema to examine
+///
+/// # Returns
+///
+/// The most likely registry type (see [`rank_registry_types`]), defaulting
+/// to BEF if no registry matches
+#[must_use] pub fn detect_registry_type_from_schema(schema: &Schema) -> DetectionResult {
+    let result = rank_registry_types(schema)
+        .into_iter()
+        .next()
+        // Default to BEF registry format
+        .unwrap_or_else(|| DetectionResult::for_registry(RegistryType::BEF, schema));
+
+    debug!(
+        "Detected registry type: {} (confidence {:.2})",
+        result.registry.as_str(),
+        result.confidence
+    );
+    result
 }
 
 /// Detect registry type from batch schema
@@ -72,29 +292,9 @@ impl From<&str> for RegistryType {
 ///
 /// # Returns
 ///
-/// The detected registry type as a `RegistryType` enum
-#[must_use] pub fn detect_registry_type(batch: &RecordBatch) -> RegistryType {
-    let registry_type = if batch.schema().field_with_name("RECNUM").is_ok() {
-        RegistryType::LPR
-    } else if batch.schema().field_with_name("PERINDKIALT").is_ok() {
-        RegistryType::IND
-    } else if batch.schema().field_with_name("BARSELNR").is_ok() {
-        RegistryType::MFR
-    } else if batch.schema().field_with_name("VEJ_KODE").is_ok() {
-        RegistryType::VNDS
-    } else if batch.schema().field_with_name("DODDATO").is_ok() {
-        RegistryType::DOD
-    } else if batch.schema().field_with_name("HELTID").is_ok() {
-        RegistryType::AKM
-    } else if batch.schema().field_with_name("UDD_H").is_ok() {
-        RegistryType::UDDF
-    } else {
-        // Default to BEF registry format
-        RegistryType::BEF
-    };
-
-    debug!("Detected registry type: {}", registry_type.as_str());
-    registry_type
+/// The detected registry type with its confidence and the matched columns
+#[must_use] pub fn detect_registry_type(batch: &RecordBatch) -> DetectionResult {
+    detect_registry_type_from_schema(&batch.schema())
 }
 
 /// Utility function for legacy code compatibility - returns string instead of enum
@@ -110,5 +310,74 @@ impl From<&str> for RegistryType {
 ///
 /// The detected registry type as a static string
 #[must_use] pub fn detect_registry_type_as_str(batch: &RecordBatch) -> &'static str {
-    detect_registry_type(batch).as_str()
-}
\ No newline at end of file
+    detect_registry_type(batch).registry.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field};
+
+    fn schema(columns: &[&str]) -> Schema {
+        Schema::new(
+            columns
+                .iter()
+                .map(|column| Field::new(*column, DataType::Utf8, true))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn ranked(columns: &[&str]) -> Vec<RegistryType> {
+        rank_registry_types(&schema(columns))
+            .into_iter()
+            .map(|result| result.registry)
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_registry_types() {
+        assert_eq!(ranked(&["PNR", "SOCIO13"]), vec![RegistryType::AKM]);
+        assert_eq!(
+            ranked(&["PNR", "HFAUDD", "HF_VFRA"]),
+            vec![RegistryType::UDDF]
+        );
+        // PNR alone matches no registry
+        assert!(ranked(&["PNR"]).is_empty());
+        // Columns of two registries give both, the better match first
+        assert_eq!(
+            ranked(&["PNR", "SOCIO13", "KOEN", "FOED_DAG"]),
+            vec![RegistryType::AKM, RegistryType::BEF]
+        );
+    }
+
+    #[test]
+    fn test_registry_type_names() {
+        for registry in RegistryType::ALL {
+            assert_eq!(registry.to_string().parse::<RegistryType>().unwrap(), registry);
+            assert_eq!(registry.loader().get_register_name(), registry.as_str());
+        }
+        assert_eq!(
+            " lpr3_diagnoser".parse::<RegistryType>().unwrap(),
+            RegistryType::Lpr3Diagnoser
+        );
+        assert!("LPR".parse::<RegistryType>().is_err());
+        assert!("UNKNOWN".parse::<RegistryType>().is_err());
+        assert_eq!(RegistryType::from("vnds"), RegistryType::VNDS);
+        assert_eq!(RegistryType::from("LPR"), RegistryType::Unknown);
+    }
+
+    #[test]
+    fn test_detection_confidence() {
+        let result = detect_registry_type_from_schema(&schema(&["PNR", "SOCIO13"]));
+        assert_eq!(result.registry, RegistryType::AKM);
+        assert!((result.confidence - 1.0).abs() < f64::EPSILON);
+        assert!(result.is_confident());
+
+        // 2 of the 14 BEF columns
+        let result = detect_registry_type_from_schema(&schema(&["PNR", "KOEN", "OTHER"]));
+        assert_eq!(result.registry, RegistryType::BEF);
+        assert_eq!(result.matched_columns, vec!["PNR", "KOEN"]);
+        assert!((result.confidence - 2.0 / 14.0).abs() < f64::EPSILON);
+        assert!(!result.is_confident());
+    }
+}
@@ -4,13 +4,20 @@
 //! to eliminate code duplication across registry deserializers.
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use arrow::array::{Array, Date32Array, Float64Array, StringArray};
+use arrow::array::{
+    Array, Date32Array, Float64Array, Int32Array, Int64Array, StringArray, Time32MillisecondArray,
+    Time32SecondArray, Time64MicrosecondArray, Time64NanosecondArray,
+};
 use arrow::record_batch::RecordBatch;
-use chrono::NaiveDate;
+use arrow::temporal_conversions::{
+    time32ms_to_time, time32s_to_time, time64ns_to_time, time64us_to_time,
+};
+use chrono::{NaiveDate, NaiveTime};
 
-use crate::error::Result;
+use crate::error::{ParquetReaderError, Result};
 use crate::registry::trait_deserializer::RegistryFieldExtractor;
 
 /// Re-export `ModelSetter` trait and the associated type
@@ -39,6 +46,40 @@ impl std::fmt::Debug for Setter {
     }
 }
 
+/// How [`StringExtractor`]s read empty text
+///
+/// Many registries store a missing value in a string column as `""`. By
+/// default empty or whitespace-only text is read as missing, so the field
+/// is left unset; the rare column where `""` is meaningful can be
+/// overridden to keep it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringFormat {
+    /// Read empty or whitespace-only text as missing
+    pub empty_string_as_null: bool,
+    /// Columns read differently from `empty_string_as_null`, by source name
+    pub column_overrides: HashMap<String, bool>,
+}
+
+impl Default for StringFormat {
+    fn default() -> Self {
+        Self {
+            empty_string_as_null: true,
+            column_overrides: HashMap::new(),
+        }
+    }
+}
+
+impl StringFormat {
+    /// Check whether empty text of a column is read as missing
+    #[must_use]
+    pub fn empty_string_as_null_for(&self, column: &str) -> bool {
+        self.column_overrides
+            .get(column)
+            .copied()
+            .unwrap_or(self.empty_string_as_null)
+    }
+}
+
 /// Generic field extractor for string fields
 #[derive(Debug)]
 pub struct StringExtractor {
@@ -46,6 +87,7 @@ pub struct StringExtractor {
     target_field: String,
     #[allow(missing_debug_implementations)]
     setter: Setter,
+    empty_string_as_null: bool,
 }
 
 impl StringExtractor {
@@ -55,8 +97,16 @@ impl StringExtractor {
             source_field: source_field.to_string(),
             target_field: target_field.to_string(),
             setter,
+            empty_string_as_null: true,
         }
     }
+
+    /// Read empty or whitespace-only text as missing (the default) or keep it
+    #[must_use]
+    pub const fn with_empty_string_as_null(mut self, empty_string_as_null: bool) -> Self {
+        self.empty_string_as_null = empty_string_as_null;
+        self
+    }
 }
 
 impl RegistryFieldExtractor for StringExtractor {
@@ -72,7 +122,8 @@ impl RegistryFieldExtractor for StringExtractor {
                 } else {
                     // Try to convert any other type to string
                     Some(format!("{array:?}"))
-                };
+                }
+                .filter(|value| !(self.empty_string_as_null && value.trim().is_empty()));
 
                 // Special handling for ID fields to ensure proper mapping
                 let is_id_field = match self.source_field.as_str() {
@@ -92,17 +143,25 @@ impl RegistryFieldExtractor for StringExtractor {
                     
                     // For known ID fields, ensure we also set the property with the standardized name
                     // This is important for proper ID field validation in the RegistryDeserializer
+                    // A value the setter already stored under that name is kept
                     if is_id_field {
-                        if let Some(individual) = target.downcast_mut::<crate::models::core::Individual>() {
+                        let standard_name = if self.source_field == "RECNUM" {
+                            "record_number"
+                        } else {
+                            "dw_ek_kontakt"
+                        };
+                        if let Some(individual) = target
+                            .downcast_mut::<crate::models::core::Individual>()
+                            .filter(|individual| {
+                                individual.properties().is_none_or(|properties| {
+                                    !properties.contains_key(standard_name)
+                                })
+                            })
+                        {
                             // Ensure we set the standardized property name for ID fields
                             // This is essential for proper ID field validation
-                            
-                            // Based on the source field, set the appropriate property
-                            if self.source_field == "RECNUM" {
-                                individual.set_property("record_number", Box::new(Some(string_value_clone)));
-                            } else if self.source_field == "DW_EK_KONTAKT" {
-                                individual.set_property("dw_ek_kontakt", Box::new(Some(string_value_clone)));
-                            }
+                            individual
+                                .set_property(standard_name, Box::new(Some(string_value_clone)));
                         }
                     }
                 }
@@ -197,6 +256,12 @@ impl RegistryFieldExtractor for IntegerExtractor {
                             >(array);
                             Some(array.value(row) as i32)
                         }
+                        arrow::datatypes::DataType::Utf8 => {
+                            // Codes such as INSTNR may be zero-padded or
+                            // whitespace-padded text; blank text is missing
+                            let text = arrow::array::cast::as_string_array(array).value(row);
+                            text.trim().parse::<i32>().ok()
+                        }
                         _ => None,
                     }
                 };
@@ -223,6 +288,58 @@ impl RegistryFieldExtractor for IntegerExtractor {
     }
 }
 
+/// How a [`FloatExtractor`] parses numbers stored as text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FloatFormat {
+    /// Read `,` as the decimal separator and `.` as the thousands separator,
+    /// as in Danish exports (`"1.234,56"`), instead of the other way round
+    pub decimal_comma: bool,
+    /// Fail on text that is not a number instead of leaving the field unset,
+    /// as `ParquetReaderConfig::strict_adaptation` does for type adaptation
+    pub strict_adaptation: bool,
+}
+
+impl FloatFormat {
+    /// Parse a number, ignoring whitespace and valid thousands separators
+    ///
+    /// Thousands separators must group the whole part in threes, so with a
+    /// decimal point `"1234,56"` is rejected rather than read as 123456.
+    ///
+    /// # Returns
+    ///
+    /// The number, or `None` if the text is not one
+    #[must_use]
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        let (decimal, thousands) = if self.decimal_comma {
+            (',', '.')
+        } else {
+            ('.', ',')
+        };
+        let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let (whole, fraction) = compact
+            .split_once(decimal)
+            .map_or((compact.as_str(), None), |(whole, fraction)| {
+                (whole, Some(fraction))
+            });
+        let digits = whole.trim_start_matches(['-', '+']);
+        let sign = &whole[..whole.len() - digits.len()];
+
+        if digits.contains(thousands) {
+            let mut groups = digits.split(thousands);
+            let first = groups.next().unwrap_or_default();
+            if first.is_empty() || first.len() > 3 || !groups.all(|group| group.len() == 3) {
+                return None;
+            }
+        }
+        let digits = digits.replace(thousands, "");
+        let normalized = match fraction {
+            Some(fraction) => format!("{sign}{digits}.{fraction}"),
+            None => format!("{sign}{digits}"),
+        };
+        normalized.parse().ok()
+    }
+}
+
 /// Generic field extractor for float fields
 #[derive(Debug)]
 pub struct FloatExtractor {
@@ -230,6 +347,7 @@ pub struct FloatExtractor {
     target_field: String,
     #[allow(missing_debug_implementations)]
     setter: Setter,
+    format: FloatFormat,
 }
 
 impl FloatExtractor {
@@ -239,8 +357,16 @@ impl FloatExtractor {
             source_field: source_field.to_string(),
             target_field: target_field.to_string(),
             setter,
+            format: FloatFormat::default(),
         }
     }
+
+    /// Parse numbers stored as text with the given format
+    #[must_use]
+    pub const fn with_format(mut self, format: FloatFormat) -> Self {
+        self.format = format;
+        self
+    }
 }
 
 impl RegistryFieldExtractor for FloatExtractor {
@@ -286,6 +412,21 @@ impl RegistryFieldExtractor for FloatExtractor {
                             >(array);
                             Some(array.value(row) as f64)
                         }
+                        arrow::datatypes::DataType::Utf8 => {
+                            let text = arrow::array::cast::as_string_array(array).value(row);
+                            let value = self.format.parse(text);
+                            if value.is_none()
+                                && self.format.strict_adaptation
+                                && !text.trim().is_empty()
+                            {
+                                return Err(ParquetReaderError::ValidationError(format!(
+                                    "Cannot parse '{text}' in row {row} of column {} as a number",
+                                    self.source_field
+                                ))
+                                .into());
+                            }
+                            value
+                        }
                         _ => None,
                     }
                 };
@@ -312,6 +453,32 @@ impl RegistryFieldExtractor for FloatExtractor {
     }
 }
 
+/// How a [`DateExtractor`] reads dates stored as integers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateEncoding {
+    /// Integers are not dates and leave the field unset
+    #[default]
+    Standard,
+    /// `Int32` and `Int64` values are packed `YYYYMMDD` dates, e.g. `20190315`
+    PackedInteger,
+}
+
+/// Decode a packed `YYYYMMDD` date
+///
+/// # Returns
+///
+/// The date, or `None` if the value is not a valid date, e.g. `20190230`
+#[must_use]
+pub fn decode_packed_date(value: i64) -> Option<NaiveDate> {
+    if !(0..=99_991_231).contains(&value) {
+        return None;
+    }
+    let year = i32::try_from(value / 10_000).ok()?;
+    let month = u32::try_from(value / 100 % 100).ok()?;
+    let day = u32::try_from(value % 100).ok()?;
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
 /// Generic field extractor for date fields
 #[derive(Debug)]
 pub struct DateExtractor {
@@ -319,6 +486,7 @@ pub struct DateExtractor {
     target_field: String,
     #[allow(missing_debug_implementations)]
     setter: Setter,
+    encoding: DateEncoding,
 }
 
 impl DateExtractor {
@@ -328,9 +496,17 @@ impl DateExtractor {
             source_field: source_field.to_string(),
             target_field: target_field.to_string(),
             setter,
+            encoding: DateEncoding::default(),
         }
     }
 
+    /// Read integer values with the given encoding
+    #[must_use]
+    pub const fn with_encoding(mut self, encoding: DateEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
     /// Parse date from string in various formats
     fn parse_date(&self, date_str: &str) -> Option<NaiveDate> {
         // Try various date formats
@@ -437,6 +613,15 @@ impl RegistryFieldExtractor for DateExtractor {
                     } else {
                         None
                     }
+                } else if self.encoding == DateEncoding::PackedInteger {
+                    // Impossible dates such as 20190230 leave the field unset
+                    if let Some(array) = array.as_any().downcast_ref::<Int32Array>() {
+                        decode_packed_date(i64::from(array.value(row)))
+                    } else if let Some(array) = array.as_any().downcast_ref::<Int64Array>() {
+                        decode_packed_date(array.value(row))
+                    } else {
+                        None
+                    }
                 } else {
                     None
                 };
@@ -471,4 +656,261 @@ impl RegistryFieldExtractor for DateExtractor {
     fn target_field_name(&self) -> &str {
         &self.target_field
     }
-}
\ No newline at end of file
+}
+
+/// Generic field extractor for time-of-day fields
+///
+/// Reads `Time32` and `Time64` columns of any unit, and strings such as
+/// `"14:30"` or `"14:30:15"`, into a `NaiveTime` passed to the setter, e.g.
+/// one made with `ModelSetters::time_setter`. Null and unparseable values
+/// leave the target unchanged.
+#[derive(Debug)]
+pub struct TimeExtractor {
+    source_field: String,
+    target_field: String,
+    setter: Setter,
+}
+
+impl TimeExtractor {
+    /// Create a new time field extractor
+    #[must_use]
+    pub fn new(source_field: &str, target_field: &str, setter: Setter) -> Self {
+        Self {
+            source_field: source_field.to_string(),
+            target_field: target_field.to_string(),
+            setter,
+        }
+    }
+}
+
+impl RegistryFieldExtractor for TimeExtractor {
+    fn extract_and_set(&self, batch: &RecordBatch, row: usize, target: &mut dyn Any) -> Result<()> {
+        // A missing field is skipped, as by the other extractors
+        if let Some(time) = batch
+            .column_by_name(&self.source_field)
+            .and_then(|array| extract_time(array.as_ref(), row))
+        {
+            self.setter.call(target, Box::new(time));
+        }
+        Ok(())
+    }
+
+    fn source_field_name(&self) -> &str {
+        &self.source_field
+    }
+
+    fn target_field_name(&self) -> &str {
+        &self.target_field
+    }
+}
+
+/// Read a time of day from a time or string array
+///
+/// # Returns
+///
+/// The time, or `None` if the value is null, out of range or of another type
+#[must_use]
+pub fn extract_time(array: &dyn Array, row: usize) -> Option<NaiveTime> {
+    if array.is_null(row) {
+        return None;
+    }
+
+    let any = array.as_any();
+    if let Some(array) = any.downcast_ref::<Time32SecondArray>() {
+        time32s_to_time(array.value(row))
+    } else if let Some(array) = any.downcast_ref::<Time32MillisecondArray>() {
+        time32ms_to_time(array.value(row))
+    } else if let Some(array) = any.downcast_ref::<Time64MicrosecondArray>() {
+        time64us_to_time(array.value(row))
+    } else if let Some(array) = any.downcast_ref::<Time64NanosecondArray>() {
+        time64ns_to_time(array.value(row))
+    } else if let Some(array) = any.downcast_ref::<StringArray>() {
+        let value = array.value(row).trim();
+        NaiveTime::parse_from_str(value, "%H:%M:%S%.f")
+            .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+            .ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::core::Individual;
+    use crate::schema::field_def::mapping::ModelSetters;
+    use arrow::array::ArrayRef;
+
+    #[test]
+    fn test_time_extractor_units() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "seconds",
+                Arc::new(Time32SecondArray::from(vec![Some(52_215), None])) as ArrayRef,
+            ),
+            (
+                "nanos",
+                Arc::new(Time64NanosecondArray::from(vec![52_215_500_000_000, 0])) as ArrayRef,
+            ),
+            (
+                "text",
+                Arc::new(StringArray::from(vec!["14:30", "kl. 14"])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let expected = NaiveTime::from_hms_opt(14, 30, 15).unwrap();
+        assert_eq!(extract_time(batch.column(0).as_ref(), 0), Some(expected));
+        assert_eq!(extract_time(batch.column(0).as_ref(), 1), None);
+        assert_eq!(
+            extract_time(batch.column(1).as_ref(), 0),
+            NaiveTime::from_hms_milli_opt(14, 30, 15, 500)
+        );
+        assert_eq!(
+            extract_time(batch.column(2).as_ref(), 0),
+            NaiveTime::from_hms_opt(14, 30, 0)
+        );
+        assert_eq!(extract_time(batch.column(2).as_ref(), 1), None);
+
+        let extractor = TimeExtractor::new(
+            "seconds",
+            "start_time",
+            Setter::new(ModelSetters::time_setter(|individual, time| {
+                individual.store_property("start_time", Box::new(time));
+            })),
+        );
+        let mut individual = Individual::default();
+        extractor
+            .extract_and_set(&batch, 0, &mut individual)
+            .unwrap();
+        let stored = individual.properties().unwrap()["start_time"]
+            .downcast_ref::<NaiveTime>()
+            .copied();
+        assert_eq!(stored, Some(expected));
+    }
+
+    #[test]
+    fn test_float_format_parse() {
+        let point = FloatFormat::default();
+        let comma = FloatFormat {
+            decimal_comma: true,
+            ..FloatFormat::default()
+        };
+        assert_eq!(point.parse("1,234.56"), Some(1234.56));
+        assert_eq!(point.parse(" -12 "), Some(-12.0));
+        assert_eq!(point.parse("1234,56"), None);
+        assert_eq!(comma.parse("1234,56"), Some(1234.56));
+        assert_eq!(comma.parse("1.234.567,5"), Some(1_234_567.5));
+        assert_eq!(comma.parse("1 234,5"), Some(1234.5));
+        assert_eq!(comma.parse("12.34"), None);
+        assert_eq!(comma.parse("n/a"), None);
+
+        let batch = RecordBatch::try_from_iter(vec![(
+            "PERINDKIALT_13",
+            Arc::new(StringArray::from(vec!["250.000,75", "ukendt"])) as ArrayRef,
+        )])
+        .unwrap();
+        let extractor = |strict_adaptation| {
+            FloatExtractor::new(
+                "PERINDKIALT_13",
+                "annual_income",
+                Setter::new(ModelSetters::f64_setter(|individual, value| {
+                    individual.annual_income = Some(value);
+                })),
+            )
+            .with_format(FloatFormat {
+                decimal_comma: true,
+                strict_adaptation,
+            })
+        };
+        let mut individual = Individual::default();
+        extractor(false)
+            .extract_and_set(&batch, 0, &mut individual)
+            .unwrap();
+        assert_eq!(individual.annual_income, Some(250_000.75));
+        assert!(
+            extractor(false)
+                .extract_and_set(&batch, 1, &mut individual)
+                .is_ok()
+        );
+        assert!(
+            extractor(true)
+                .extract_and_set(&batch, 1, &mut individual)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_integer_extractor_padded_codes() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "INSTNR",
+            Arc::new(StringArray::from(vec![
+                Some(" 0042 "),
+                Some(""),
+                Some("42"),
+                None,
+            ])) as ArrayRef,
+        )])
+        .unwrap();
+        let extractor = IntegerExtractor::new(
+            "INSTNR",
+            "institution_code",
+            Setter::new(ModelSetters::i32_setter(|individual, value| {
+                individual.store_property("institution_code", Box::new(value));
+            })),
+        );
+
+        let extract = |row| {
+            let mut individual = Individual::default();
+            extractor
+                .extract_and_set(&batch, row, &mut individual)
+                .unwrap();
+            individual
+                .properties()
+                .and_then(|properties| properties.get("institution_code"))
+                .and_then(|value| value.downcast_ref::<i32>())
+                .copied()
+        };
+        assert_eq!(extract(0), Some(42));
+        assert_eq!(extract(1), None);
+        assert_eq!(extract(2), Some(42));
+        assert_eq!(extract(3), None);
+    }
+
+    #[test]
+    fn test_packed_integer_dates() {
+        assert_eq!(
+            decode_packed_date(20_190_315),
+            NaiveDate::from_ymd_opt(2019, 3, 15)
+        );
+        assert_eq!(decode_packed_date(20_190_230), None);
+        assert_eq!(decode_packed_date(20_191_301), None);
+        assert_eq!(decode_packed_date(-20_190_315), None);
+
+        let batch = RecordBatch::try_from_iter(vec![(
+            "D_INDDTO",
+            Arc::new(Int32Array::from(vec![20_190_315, 20_190_230])) as ArrayRef,
+        )])
+        .unwrap();
+        let extract = |encoding, row| {
+            let extractor = DateExtractor::new(
+                "D_INDDTO",
+                "birth_date",
+                Setter::new(ModelSetters::date_setter(|individual, date| {
+                    individual.birth_date = Some(date);
+                })),
+            )
+            .with_encoding(encoding);
+            let mut individual = Individual::default();
+            extractor
+                .extract_and_set(&batch, row, &mut individual)
+                .unwrap();
+            individual.birth_date
+        };
+        assert_eq!(
+            extract(DateEncoding::PackedInteger, 0),
+            NaiveDate::from_ymd_opt(2019, 3, 15)
+        );
+        assert_eq!(extract(DateEncoding::PackedInteger, 1), None);
+        assert_eq!(extract(DateEncoding::Standard, 0), None);
+    }
+}
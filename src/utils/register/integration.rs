@@ -310,6 +310,7 @@ impl Registry for BaseRegistry {
             filter,
             None,
             None,
+            None,
         )
     }
 
@@ -11,8 +11,14 @@ pub mod longitudinal_loader;
 pub mod mapping;
 
 // Re-export commonly used functions for convenience
-pub use detection::{detect_registry_type, detect_registry_type_as_str, RegistryType};
-pub use extractors::{DateExtractor, FloatExtractor, IntegerExtractor, Setter, StringExtractor};
+pub use detection::{
+    detect_registry_type, detect_registry_type_as_str, detect_registry_type_from_schema,
+    rank_registry_types, DetectionResult, RegistryType, MIN_CONFIDENCE,
+};
+pub use extractors::{
+    DateEncoding, DateExtractor, FloatExtractor, FloatFormat, IntegerExtractor, Setter,
+    StringExtractor, StringFormat, TimeExtractor, decode_packed_date,
+};
 pub use integration::{
     DateConversionExt, DateRangeConfig, PnrLinked, Registry, RegistryFieldMapper,
     RegistryIntegrator, RegistryTransformer,
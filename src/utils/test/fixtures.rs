@@ -5,6 +5,10 @@
 use std::path::PathBuf;
 
 /// Base path for test data files
+///
+/// The data exists only on the machines it was generated on; tests that
+/// must run anywhere write their own with
+/// [`write_synthetic_registry`](super::write_synthetic_registry).
 #[must_use]
 pub fn data_dir() -> PathBuf {
     match std::env::consts::OS {
@@ -4,7 +4,9 @@
 
 pub mod fixtures;
 pub mod helpers;
+pub mod synthetic;
 
 // Re-export commonly used functions for convenience
 pub use fixtures::{data_dir, registry_dir, registry_file};
-pub use helpers::{ensure_path_exists, expr_to_filter, test_config, timed_execution};
\ No newline at end of file
+pub use helpers::{ensure_path_exists, expr_to_filter, test_config, timed_execution};
+pub use synthetic::{generate_synthetic_registry, synthetic_id, write_synthetic_registry};
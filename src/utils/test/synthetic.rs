@@ -0,0 +1,220 @@
+//! Synthetic registry data for tests
+//!
+//! The fixtures of [`data_dir`](super::data_dir) exist only on the machines
+//! holding the generated registry data. [`generate_synthetic_registry`]
+//! builds batches with the schema of a registry instead, and
+//! [`write_synthetic_registry`] writes them to a temporary directory for the
+//! loaders to read.
+//!
+//! The identifying columns of a row, its PNR and the `RECNUM` or
+//! `DW_EK_KONTAKT` linking the LPR tables, are `format!("{row:010}")`, so
+//! registries generated with the same number of rows link up row by row.
+//! The other columns are random, seeded, and null about one time in five.
+
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use arrow::array::{
+    ArrayRef, BooleanArray, Date32Array, Float64Array, Int32Array, StringArray, Time32SecondArray,
+    new_null_array,
+};
+use arrow::datatypes::{DataType, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::error::Result;
+use crate::utils::register::RegistryType;
+
+/// Maximum number of rows of a generated batch
+pub const SYNTHETIC_BATCH_SIZE: usize = 8192;
+
+/// Columns linking the rows of the LPR tables
+const LINK_COLUMNS: &[&str] = &["RECNUM", "DW_EK_KONTAKT"];
+
+/// Characters of generated text
+const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Number of distinct directories written by this process
+static WRITTEN_DIRS: AtomicUsize = AtomicUsize::new(0);
+
+/// Get the synthetic identifier of a row
+#[must_use]
+pub fn synthetic_id(row: usize) -> String {
+    format!("{row:010}")
+}
+
+/// Generate batches with the schema of a registry
+///
+/// The batches have the columns of the registry's loader schema, at most
+/// [`SYNTHETIC_BATCH_SIZE`] rows each. The same seed gives the same data.
+///
+/// # Arguments
+///
+/// * `registry_type` - The registry whose schema to generate
+/// * `num_rows` - The total number of rows
+/// * `seed` - The seed of the random values
+///
+/// # Returns
+///
+/// The batches, holding `num_rows` rows in total
+///
+/// # Errors
+///
+/// Returns an error if a batch cannot be assembled
+pub fn generate_synthetic_registry(
+    registry_type: RegistryType,
+    num_rows: usize,
+    seed: u64,
+) -> Result<Vec<RecordBatch>> {
+    let loader = registry_type.loader();
+    let schema = loader.get_schema();
+    let pnr_column = loader.get_pnr_column_name();
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    (0..num_rows)
+        .step_by(SYNTHETIC_BATCH_SIZE)
+        .map(|start| {
+            let rows = start..(start + SYNTHETIC_BATCH_SIZE).min(num_rows);
+            let columns = schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    let name = field.name().as_str();
+                    if Some(name) == pnr_column || LINK_COLUMNS.contains(&name) {
+                        Arc::new(StringArray::from_iter_values(
+                            rows.clone().map(synthetic_id),
+                        ))
+                    } else {
+                        random_column(&mut rng, field.data_type(), rows.len())
+                    }
+                })
+                .collect();
+            Ok(RecordBatch::try_new(Arc::clone(&schema), columns)?)
+        })
+        .collect()
+}
+
+/// Generate a registry and write it to a new temporary directory
+///
+/// Each batch is written as a Parquet file of its own. The directory is
+/// named after the registry, so it can also be loaded by path, and is left
+/// for the caller to remove.
+///
+/// # Arguments
+///
+/// * `registry_type` - The registry whose schema to generate
+/// * `num_rows` - The total number of rows
+/// * `seed` - The seed of the random values
+///
+/// # Returns
+///
+/// The path of the directory
+///
+/// # Errors
+///
+/// Returns an error if the data cannot be generated or written
+pub fn write_synthetic_registry(
+    registry_type: RegistryType,
+    num_rows: usize,
+    seed: u64,
+) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!(
+        "par_reader_synthetic_{}_{}_{}",
+        std::process::id(),
+        WRITTEN_DIRS.fetch_add(1, Ordering::Relaxed),
+        registry_type.as_str().to_lowercase()
+    ));
+    std::fs::create_dir_all(&dir)?;
+
+    for (index, batch) in generate_synthetic_registry(registry_type, num_rows, seed)?
+        .iter()
+        .enumerate()
+    {
+        let file = File::create(dir.join(format!("part_{index:04}.parquet")))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+        writer.write(batch)?;
+        writer.close()?;
+    }
+    Ok(dir)
+}
+
+/// Generate a column of random values, null about one time in five
+fn random_column(rng: &mut StdRng, data_type: &DataType, len: usize) -> ArrayRef {
+    let valid = |rng: &mut StdRng| rng.random_bool(0.8);
+    match data_type {
+        DataType::Utf8 => Arc::new(
+            (0..len)
+                .map(|_| valid(rng).then(|| random_text(rng)))
+                .collect::<StringArray>(),
+        ),
+        DataType::Int32 => Arc::new(
+            (0..len)
+                .map(|_| valid(rng).then(|| rng.random_range(0..1000)))
+                .collect::<Int32Array>(),
+        ),
+        DataType::Float64 => Arc::new(
+            (0..len)
+                .map(|_| valid(rng).then(|| rng.random_range(0.0..1e6)))
+                .collect::<Float64Array>(),
+        ),
+        // Days from 1980 to 2022
+        DataType::Date32 => Arc::new(
+            (0..len)
+                .map(|_| valid(rng).then(|| rng.random_range(3653..19_358)))
+                .collect::<Date32Array>(),
+        ),
+        DataType::Time32(TimeUnit::Second) => Arc::new(
+            (0..len)
+                .map(|_| valid(rng).then(|| rng.random_range(0..86_400)))
+                .collect::<Time32SecondArray>(),
+        ),
+        DataType::Boolean => Arc::new(
+            (0..len)
+                .map(|_| valid(rng).then(|| rng.random_bool(0.5)))
+                .collect::<BooleanArray>(),
+        ),
+        other => new_null_array(other, len),
+    }
+}
+
+/// Generate a code-like text of 1 to 6 characters
+fn random_text(rng: &mut StdRng) -> String {
+    let len = rng.random_range(1..=6);
+    (0..len)
+        .map(|_| char::from(CHARSET[rng.random_range(0..CHARSET.len())]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Array;
+
+    #[test]
+    fn test_generate_synthetic_registry() {
+        let batches = generate_synthetic_registry(RegistryType::BEF, 10_000, 7).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1].num_rows(), 10_000 - SYNTHETIC_BATCH_SIZE);
+
+        let pnr = batches[1].column_by_name("PNR").unwrap();
+        let pnr = pnr.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(pnr.null_count(), 0);
+        assert_eq!(pnr.value(0), synthetic_id(SYNTHETIC_BATCH_SIZE));
+
+        // The same seed gives the same data, another seed other data
+        let again = generate_synthetic_registry(RegistryType::BEF, 10_000, 7).unwrap();
+        assert_eq!(batches, again);
+        let other = generate_synthetic_registry(RegistryType::BEF, 10_000, 8).unwrap();
+        assert_ne!(batches, other);
+
+        assert!(
+            generate_synthetic_registry(RegistryType::VNDS, 0, 7)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,5 @@
+//! Integration tests for the registry deserializers
+
+mod round_trip;
+mod schema_consistency;
+mod synthetic;
@@ -0,0 +1,333 @@
+//! Round trips of random batches through each registry deserializer
+//!
+//! A batch built to a registry's schema is deserialized into its registry
+//! struct and serialized back with `serialize_batch()`. Every value,
+//! including nulls, must survive: a lost value means an extractor or setter
+//! misreads its field. The exception is empty or whitespace-only text,
+//! which is read as missing and so comes back null. Batches are generated
+//! with proptest, so a failure is shrunk to a minimal batch.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, Date32Array, Float64Array, Int32Array, StringArray, Time32SecondArray,
+};
+use arrow::datatypes::{Field, Schema};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use par_reader::registry::RegisterLoader;
+use par_reader::registry::akm::AkmRegistryDeserializer;
+use par_reader::registry::bef::BefRegistryDeserializer;
+use par_reader::registry::death::dod::DodRegistryDeserializer;
+use par_reader::registry::death::dodsaarsag::DodsaarsagRegistryDeserializer;
+use par_reader::registry::ind::IndRegistryDeserializer;
+use par_reader::registry::lpr::v2::adm::LprAdmRegistryDeserializer;
+use par_reader::registry::lpr::v2::bes::LprBesRegistryDeserializer;
+use par_reader::registry::lpr::v2::diag::LprDiagRegistryDeserializer;
+use par_reader::registry::lpr::v3::diagnoser::Lpr3DiagnoserRegistryDeserializer;
+use par_reader::registry::lpr::v3::kontakter::Lpr3KontakterRegistryDeserializer;
+use par_reader::registry::mfr::MfrRegistryDeserializer;
+use par_reader::registry::uddf::UddfRegistryDeserializer;
+use par_reader::registry::vnds::VndsRegistryDeserializer;
+use par_reader::schema::field_def::FieldType;
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestCaseError, TestRunner};
+
+/// Number of random batches per registry
+const CASES: u32 = 16;
+
+/// Maximum number of rows per batch
+const ROWS: usize = 32;
+
+/// Generated text: up to 12 characters, including the Danish letters
+const TEXT: &str = "[aZ09 /æØå-]{0,12}";
+
+/// A generated value of one cell
+#[derive(Debug, Clone)]
+enum Value {
+    Text(Option<String>),
+    Integer(Option<i32>),
+    Decimal(Option<f64>),
+    Date(Option<i32>),
+    Time(Option<i32>),
+}
+
+/// Generate a possibly null value, null about one time in five
+fn nullable<T: std::fmt::Debug + Clone + 'static>(
+    nullable: bool,
+    value: impl Strategy<Value = T> + 'static,
+) -> BoxedStrategy<Option<T>> {
+    if nullable {
+        prop::option::weighted(0.8, value).boxed()
+    } else {
+        value.prop_map(Some).boxed()
+    }
+}
+
+/// Generate the value of a cell of a declared column
+fn value_strategy(field: &Field, field_type: &FieldType) -> BoxedStrategy<Value> {
+    let is_nullable = field.is_nullable();
+    match field_type {
+        FieldType::PNR | FieldType::String => {
+            nullable(is_nullable, TEXT).prop_map(Value::Text).boxed()
+        }
+        FieldType::Integer => nullable(is_nullable, any::<i32>())
+            .prop_map(Value::Integer)
+            .boxed(),
+        FieldType::Decimal => nullable(is_nullable, -1e9..1e9)
+            .prop_map(Value::Decimal)
+            .boxed(),
+        FieldType::Date => nullable(is_nullable, -40_000..40_000)
+            .prop_map(Value::Date)
+            .boxed(),
+        FieldType::Time => nullable(is_nullable, 0..86_400)
+            .prop_map(Value::Time)
+            .boxed(),
+        other => panic!("no generator for {} of type {other}", field.name()),
+    }
+}
+
+/// Generate a batch of the declared columns of a registry
+///
+/// The non-nullable text columns identify the rows, such as `PNR` or
+/// `RECNUM`, and get unique non-empty values so no row is dropped.
+fn batch_strategy(
+    schema: &Schema,
+    field_map: &[(String, String, FieldType)],
+) -> impl Strategy<Value = RecordBatch> + use<> {
+    let fields: Vec<Field> = field_map
+        .iter()
+        .map(|(source, _, _)| schema.field_with_name(source).unwrap().clone())
+        .collect();
+    let row: Vec<BoxedStrategy<Value>> = fields
+        .iter()
+        .zip(field_map)
+        .map(|(field, (_, _, field_type))| value_strategy(field, field_type))
+        .collect();
+
+    prop::collection::vec(row, 1..=ROWS).prop_map(move |rows| {
+        let columns = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let cells = rows.iter().map(|row| &row[index]);
+                column(field, cells)
+            })
+            .collect();
+        RecordBatch::try_new(Arc::new(Schema::new(fields.clone())), columns).unwrap()
+    })
+}
+
+/// Build a column from the generated values of its cells
+fn column<'a>(field: &Field, cells: impl Iterator<Item = &'a Value>) -> ArrayRef {
+    let cells: Vec<&Value> = cells.collect();
+    match cells.first() {
+        Some(Value::Text(_)) => Arc::new(
+            cells
+                .iter()
+                .enumerate()
+                .map(|(row, cell)| match cell {
+                    _ if !field.is_nullable() => Some(format!("{row:010}")),
+                    Value::Text(text) => text.clone(),
+                    _ => unreachable!(),
+                })
+                .collect::<StringArray>(),
+        ),
+        Some(Value::Integer(_)) => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Value::Integer(value) => *value,
+                    _ => unreachable!(),
+                })
+                .collect::<Int32Array>(),
+        ),
+        Some(Value::Decimal(_)) => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Value::Decimal(value) => *value,
+                    _ => unreachable!(),
+                })
+                .collect::<Float64Array>(),
+        ),
+        Some(Value::Date(_)) => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Value::Date(value) => *value,
+                    _ => unreachable!(),
+                })
+                .collect::<Date32Array>(),
+        ),
+        Some(Value::Time(_)) => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Value::Time(value) => *value,
+                    _ => unreachable!(),
+                })
+                .collect::<Time32SecondArray>(),
+        ),
+        None => unreachable!("batches have at least one row"),
+    }
+}
+
+/// Replace empty or whitespace-only text with null, as the extractors read it
+fn blank_as_null(batch: &RecordBatch) -> RecordBatch {
+    let columns = batch
+        .columns()
+        .iter()
+        .map(
+            |column| match column.as_any().downcast_ref::<StringArray>() {
+                Some(text) => Arc::new(
+                    text.iter()
+                        .map(|value| value.filter(|value| !value.trim().is_empty()))
+                        .collect::<StringArray>(),
+                ) as ArrayRef,
+                None => column.clone(),
+            },
+        )
+        .collect();
+    RecordBatch::try_new(batch.schema(), columns).unwrap()
+}
+
+/// Check that a serialized batch has every value of the original
+fn check_same(
+    registry: &str,
+    original: &RecordBatch,
+    serialized: &RecordBatch,
+) -> Result<(), TestCaseError> {
+    prop_assert_eq!(
+        original.num_rows(),
+        serialized.num_rows(),
+        "{} dropped rows",
+        registry
+    );
+    prop_assert_eq!(
+        original.schema(),
+        serialized.schema(),
+        "{} schema",
+        registry
+    );
+
+    for (field, (expected, actual)) in original
+        .schema()
+        .fields()
+        .iter()
+        .zip(original.columns().iter().zip(serialized.columns()))
+    {
+        if let Some(row) =
+            (0..expected.len()).find(|row| expected.slice(*row, 1) != actual.slice(*row, 1))
+        {
+            return Err(TestCaseError::fail(format!(
+                "{registry}: column {} row {row} is {}, expected {}",
+                field.name(),
+                display(actual.as_ref(), row),
+                display(expected.as_ref(), row)
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Display a value, or `null`
+fn display(array: &dyn Array, row: usize) -> String {
+    if array.is_null(row) {
+        "null".to_string()
+    } else {
+        format!("{:?}", array_value_to_string(array, row).unwrap())
+    }
+}
+
+/// Round-trip random batches through a registry deserializer
+macro_rules! assert_round_trip {
+    ($deserializer:ty) => {{
+        let deserializer = <$deserializer>::new();
+        let schema = deserializer.get_schema();
+        let mut runner = TestRunner::new(Config {
+            cases: CASES,
+            failure_persistence: None,
+            ..Config::default()
+        });
+        runner
+            .run(
+                &batch_strategy(&schema, deserializer.field_map()),
+                |batch| {
+                    let records = deserializer.deserialize_batch(&batch).unwrap();
+                    let serialized = deserializer.serialize_batch(&records).unwrap();
+                    check_same(
+                        deserializer.get_register_name(),
+                        &blank_as_null(&batch),
+                        &serialized,
+                    )
+                },
+            )
+            .unwrap();
+    }};
+}
+
+#[test]
+fn test_akm_round_trip() {
+    assert_round_trip!(AkmRegistryDeserializer);
+}
+
+#[test]
+fn test_bef_round_trip() {
+    assert_round_trip!(BefRegistryDeserializer);
+}
+
+#[test]
+fn test_dod_round_trip() {
+    assert_round_trip!(DodRegistryDeserializer);
+}
+
+#[test]
+fn test_dodsaarsag_round_trip() {
+    assert_round_trip!(DodsaarsagRegistryDeserializer);
+}
+
+#[test]
+fn test_ind_round_trip() {
+    assert_round_trip!(IndRegistryDeserializer);
+}
+
+#[test]
+fn test_lpr_adm_round_trip() {
+    assert_round_trip!(LprAdmRegistryDeserializer);
+}
+
+#[test]
+fn test_lpr_bes_round_trip() {
+    assert_round_trip!(LprBesRegistryDeserializer);
+}
+
+#[test]
+fn test_lpr_diag_round_trip() {
+    assert_round_trip!(LprDiagRegistryDeserializer);
+}
+
+#[test]
+fn test_lpr3_diagnoser_round_trip() {
+    assert_round_trip!(Lpr3DiagnoserRegistryDeserializer);
+}
+
+#[test]
+fn test_lpr3_kontakter_round_trip() {
+    assert_round_trip!(Lpr3KontakterRegistryDeserializer);
+}
+
+#[test]
+fn test_mfr_round_trip() {
+    assert_round_trip!(MfrRegistryDeserializer);
+}
+
+#[test]
+fn test_uddf_round_trip() {
+    assert_round_trip!(UddfRegistryDeserializer);
+}
+
+#[test]
+fn test_vnds_round_trip() {
+    assert_round_trip!(VndsRegistryDeserializer);
+}
@@ -0,0 +1,127 @@
+//! Checks that each registry's factory loader reads the columns of its struct
+//!
+//! The schema generated by `RegistryTrait` from a registry struct decides
+//! which columns the struct deserializer reads, while the loader returned by
+//! `registry_from_name` reads the columns of its field mappings. A column in
+//! one but not the other is read by one loader and silently dropped by the
+//! other.
+
+use par_reader::SchemaRef;
+use par_reader::registry::RegisterLoader;
+use par_reader::registry::akm::AkmRegistryDeserializer;
+use par_reader::registry::bef::BefRegistryDeserializer;
+use par_reader::registry::death::dod::DodRegistryDeserializer;
+use par_reader::registry::death::dodsaarsag::DodsaarsagRegistryDeserializer;
+use par_reader::registry::factory::registry_from_name;
+use par_reader::registry::ind::IndRegistryDeserializer;
+use par_reader::registry::lpr::v2::adm::LprAdmRegistryDeserializer;
+use par_reader::registry::lpr::v2::bes::LprBesRegistryDeserializer;
+use par_reader::registry::lpr::v2::diag::LprDiagRegistryDeserializer;
+use par_reader::registry::lpr::v3::diagnoser::Lpr3DiagnoserRegistryDeserializer;
+use par_reader::registry::lpr::v3::kontakter::Lpr3KontakterRegistryDeserializer;
+use par_reader::registry::mfr::MfrRegistryDeserializer;
+use par_reader::registry::uddf::UddfRegistryDeserializer;
+use par_reader::registry::vnds::VndsRegistryDeserializer;
+
+/// The struct-derived schema of a registry and that of its factory loader
+struct Declaration {
+    name: &'static str,
+    schema: SchemaRef,
+    factory: SchemaRef,
+}
+
+impl Declaration {
+    fn new(loader: &dyn RegisterLoader) -> Self {
+        let name = loader.get_register_name();
+        Self {
+            name,
+            schema: loader.get_schema(),
+            factory: registry_from_name(name)
+                .unwrap_or_else(|e| panic!("{name}: {e}"))
+                .get_schema(),
+        }
+    }
+
+    /// Describe every mismatch between the two schemas
+    fn mismatches(&self) -> Vec<String> {
+        let mut mismatches = Vec::new();
+
+        for column in self.schema.fields() {
+            match self.factory.field_with_name(column.name()) {
+                Ok(read) => {
+                    if read.data_type() != column.data_type() {
+                        mismatches.push(format!(
+                            "{}: column {} is read as {}, but the struct declares {}",
+                            self.name,
+                            column.name(),
+                            read.data_type(),
+                            column.data_type()
+                        ));
+                    }
+                    if read.is_nullable() != column.is_nullable() {
+                        mismatches.push(format!(
+                            "{}: column {} is read as {}nullable, but the struct declares it {}nullable",
+                            self.name,
+                            column.name(),
+                            if read.is_nullable() { "" } else { "non-" },
+                            if column.is_nullable() { "" } else { "non-" }
+                        ));
+                    }
+                }
+                Err(_) => mismatches.push(format!(
+                    "{}: column {} of the struct is not read by the factory loader",
+                    self.name,
+                    column.name()
+                )),
+            }
+        }
+
+        for column in self.factory.fields() {
+            if self.schema.field_with_name(column.name()).is_err() {
+                mismatches.push(format!(
+                    "{}: column {} read by the factory loader is not in the struct",
+                    self.name,
+                    column.name()
+                ));
+            }
+        }
+
+        mismatches
+    }
+}
+
+fn declarations() -> Vec<Declaration> {
+    macro_rules! declaration {
+        ($deserializer:ty) => {{ Declaration::new(&<$deserializer>::new()) }};
+    }
+
+    vec![
+        declaration!(AkmRegistryDeserializer),
+        declaration!(BefRegistryDeserializer),
+        declaration!(DodRegistryDeserializer),
+        declaration!(DodsaarsagRegistryDeserializer),
+        declaration!(IndRegistryDeserializer),
+        declaration!(LprAdmRegistryDeserializer),
+        declaration!(LprBesRegistryDeserializer),
+        declaration!(LprDiagRegistryDeserializer),
+        declaration!(Lpr3DiagnoserRegistryDeserializer),
+        declaration!(Lpr3KontakterRegistryDeserializer),
+        declaration!(MfrRegistryDeserializer),
+        declaration!(UddfRegistryDeserializer),
+        declaration!(VndsRegistryDeserializer),
+    ]
+}
+
+#[test]
+fn test_factory_schemas_match_structs() {
+    let mismatches: Vec<String> = declarations()
+        .iter()
+        .flat_map(Declaration::mismatches)
+        .collect();
+
+    assert!(
+        mismatches.is_empty(),
+        "factory loader schemas differ from the registry structs:\n{}",
+        mismatches.join("\n")
+    );
+}
@@ -0,0 +1,87 @@
+//! Loading of generated registry data
+//!
+//! The registries are generated with `generate_synthetic_registry()` and
+//! written to temporary directories, so the loaders are exercised without
+//! the fixtures of `data_dir()`. Rows of registries generated with the same
+//! number of rows link up by their PNR, `RECNUM` or `DW_EK_KONTAKT`.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use arrow::array::{Array, StringArray};
+use arrow::record_batch::RecordBatch;
+use par_reader::utils::register::RegistryType;
+use par_reader::utils::test::{synthetic_id, write_synthetic_registry};
+
+/// Number of rows per registry, spanning more than one generated batch
+const ROWS: usize = 10_000;
+
+/// Load a generated registry and remove its directory
+fn load(
+    registry_type: RegistryType,
+    dir: &Path,
+    pnrs: Option<&HashSet<String>>,
+) -> Vec<RecordBatch> {
+    let batches = registry_type.loader().load(dir, pnrs).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+    batches
+}
+
+/// Collect the values of a text column across batches
+fn column_values(batches: &[RecordBatch], column: &str) -> Vec<String> {
+    batches
+        .iter()
+        .flat_map(|batch| {
+            let array = batch.column_by_name(column).unwrap();
+            let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+            (0..array.len())
+                .map(|row| array.value(row).to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn test_load_synthetic_bef() {
+    let dir = write_synthetic_registry(RegistryType::BEF, ROWS, 1).unwrap();
+    let batches = load(RegistryType::BEF, &dir, None);
+
+    let mut pnrs = column_values(&batches, "PNR");
+    pnrs.sort();
+    assert_eq!(pnrs, (0..ROWS).map(synthetic_id).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_load_synthetic_bef_with_pnr_filter() {
+    let wanted: HashSet<String> = [3, 4_000, 9_999].into_iter().map(synthetic_id).collect();
+    let dir = write_synthetic_registry(RegistryType::BEF, ROWS, 2).unwrap();
+    let batches = load(RegistryType::BEF, &dir, Some(&wanted));
+
+    let pnrs: HashSet<String> = column_values(&batches, "PNR").into_iter().collect();
+    assert_eq!(pnrs, wanted);
+}
+
+#[test]
+fn test_load_synthetic_lpr_links_on_recnum() {
+    let adm_dir = write_synthetic_registry(RegistryType::LprAdm, ROWS, 3).unwrap();
+    let diag_dir = write_synthetic_registry(RegistryType::LprDiag, ROWS, 4).unwrap();
+    let adm = load(RegistryType::LprAdm, &adm_dir, None);
+    let diag = load(RegistryType::LprDiag, &diag_dir, None);
+
+    let mut adm_recnums = column_values(&adm, "RECNUM");
+    let mut diag_recnums = column_values(&diag, "RECNUM");
+    adm_recnums.sort();
+    diag_recnums.sort();
+    assert_eq!(adm_recnums.len(), ROWS);
+    assert_eq!(adm_recnums, diag_recnums);
+}
+
+#[test]
+fn test_load_synthetic_lpr_adm_with_pnr_filter() {
+    let wanted: HashSet<String> = std::iter::once(synthetic_id(42)).collect();
+    let dir = write_synthetic_registry(RegistryType::LprAdm, ROWS, 5).unwrap();
+    let batches = load(RegistryType::LprAdm, &dir, Some(&wanted));
+
+    assert_eq!(column_values(&batches, "PNR"), vec![synthetic_id(42)]);
+    assert_eq!(column_values(&batches, "RECNUM"), vec![synthetic_id(42)]);
+}